@@ -0,0 +1,473 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::keymap::KEYMAP;
+
+/// Something a key does that can be bound to other keys in the `[keys]`
+/// table of the settings file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Up,
+    Down,
+    Open,
+    Back,
+    Refresh,
+    Bottom,
+    NextPage,
+    PrevPage,
+    HalfPageDown,
+    HalfPageUp,
+    Search,
+    Command,
+    Help,
+    Finder,
+    Switcher,
+    Tasks,
+    Filter,
+    Edit,
+    Copy,
+    CopyId,
+    Export,
+    Import,
+    Table,
+    Aggregate,
+    Projection,
+    Explain,
+    PageSize,
+    SaveQuery,
+    SavedQueries,
+    NewTab,
+    Maximize,
+    Drop,
+    DryRun,
+    EventLog,
+    Timings,
+    Identifiers,
+}
+
+impl Action {
+    pub const ALL: [Action; 37] = [
+        Action::Quit,
+        Action::Up,
+        Action::Down,
+        Action::Open,
+        Action::Back,
+        Action::Refresh,
+        Action::Bottom,
+        Action::NextPage,
+        Action::PrevPage,
+        Action::HalfPageDown,
+        Action::HalfPageUp,
+        Action::Search,
+        Action::Command,
+        Action::Help,
+        Action::Finder,
+        Action::Switcher,
+        Action::Tasks,
+        Action::Filter,
+        Action::Edit,
+        Action::Copy,
+        Action::CopyId,
+        Action::Export,
+        Action::Import,
+        Action::Table,
+        Action::Aggregate,
+        Action::Projection,
+        Action::Explain,
+        Action::PageSize,
+        Action::SaveQuery,
+        Action::SavedQueries,
+        Action::NewTab,
+        Action::Maximize,
+        Action::Drop,
+        Action::DryRun,
+        Action::EventLog,
+        Action::Timings,
+        Action::Identifiers,
+    ];
+
+    /// The name used in the settings file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Open => "open",
+            Action::Back => "back",
+            Action::Refresh => "refresh",
+            Action::Bottom => "bottom",
+            Action::NextPage => "next_page",
+            Action::PrevPage => "prev_page",
+            Action::HalfPageDown => "half_page_down",
+            Action::HalfPageUp => "half_page_up",
+            Action::Search => "search",
+            Action::Command => "command",
+            Action::Help => "help",
+            Action::Finder => "finder",
+            Action::Switcher => "switcher",
+            Action::Tasks => "tasks",
+            Action::Filter => "filter",
+            Action::Edit => "edit",
+            Action::Copy => "copy",
+            Action::CopyId => "copy_id",
+            Action::Export => "export",
+            Action::Import => "import",
+            Action::Table => "table",
+            Action::Aggregate => "aggregate",
+            Action::Projection => "projection",
+            Action::Explain => "explain",
+            Action::PageSize => "page_size",
+            Action::SaveQuery => "save_query",
+            Action::SavedQueries => "saved_queries",
+            Action::NewTab => "new_tab",
+            Action::Maximize => "maximize",
+            Action::Drop => "drop",
+            Action::DryRun => "dry_run",
+            Action::EventLog => "event_log",
+            Action::Timings => "timings",
+            Action::Identifiers => "identifiers",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit or go back",
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Open => "Open or confirm",
+            Action::Back => "Go back",
+            Action::Refresh => "Refresh",
+            Action::Bottom => "Last item of a list",
+            Action::NextPage => "Next page",
+            Action::PrevPage => "Previous page",
+            Action::HalfPageDown => "Half a page down",
+            Action::HalfPageUp => "Half a page up",
+            Action::Search => "Query / search",
+            Action::Command => "Command line",
+            Action::Help => "Help",
+            Action::Finder => "Fuzzy finder",
+            Action::Switcher => "Connection switcher",
+            Action::Tasks => "Background tasks",
+            Action::Filter => "Live filter",
+            Action::Edit => "Edit the document in $EDITOR",
+            Action::Copy => "Copy the document or value",
+            Action::CopyId => "Copy the document's _id",
+            Action::Export => "Export",
+            Action::Import => "Import a file",
+            Action::Table => "Table view",
+            Action::Aggregate => "Aggregation pipeline builder",
+            Action::Projection => "Projection picker",
+            Action::Explain => "Explain the query plan",
+            Action::PageSize => "Page size",
+            Action::SaveQuery => "Save the current query",
+            Action::SavedQueries => "Saved queries",
+            Action::NewTab => "Open the collection in a new tab",
+            Action::Maximize => "Maximize the content pane",
+            Action::Drop => "Drop, after typing the name",
+            Action::DryRun => "Dry run",
+            Action::EventLog => "Connection event log",
+            Action::Timings => "Query timing history",
+            Action::Identifiers => "ObjectId/UUID utilities",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    // the keys the screens handle; a rebound key stands in for the first
+    fn default_keys(self) -> Vec<Chord> {
+        let keys = match self {
+            Action::Quit => "q",
+            Action::Up => "k Up",
+            Action::Down => "j Down",
+            Action::Open => "Enter",
+            Action::Back => "Backspace",
+            Action::Refresh => "r",
+            Action::Bottom => "G",
+            Action::NextPage => "]",
+            Action::PrevPage => "[",
+            Action::HalfPageDown => "Ctrl+d",
+            Action::HalfPageUp => "Ctrl+u",
+            Action::Search => "/",
+            Action::Command => ":",
+            Action::Help => "F1 ?",
+            Action::Finder => "Ctrl+p",
+            Action::Switcher => "Ctrl+o",
+            Action::Tasks => "Ctrl+b",
+            Action::Filter => "f",
+            Action::Edit => "e",
+            Action::Copy => "y",
+            Action::CopyId => "Y",
+            Action::Export => "X",
+            Action::Import => "I",
+            Action::Table => "t",
+            Action::Aggregate => "a",
+            Action::Projection => "p",
+            Action::Explain => "P",
+            Action::PageSize => "L",
+            Action::SaveQuery => "S",
+            Action::SavedQueries => "Q",
+            Action::NewTab => "T",
+            Action::Maximize => "Z",
+            Action::Drop => "D",
+            Action::DryRun => "F6",
+            Action::EventLog => "Ctrl+e",
+            Action::Timings => "Ctrl+t",
+            Action::Identifiers => "Ctrl+g",
+        };
+        keys.split_whitespace().filter_map(Chord::parse).collect()
+    }
+}
+
+/// A key with the modifiers that matter for binding it: Ctrl and Alt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Chord {
+    /// Reads a key as written in the settings file and the help overlay:
+    /// `j`, `G`, `Enter`, `Esc`, `Space`, `PageDown`, `F2`, `Ctrl+p`,
+    /// `Alt+x`. Letters after Ctrl ignore case, as terminals do.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut rest = text.trim();
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if rest.len() > 5 && lower.starts_with("ctrl+") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if rest.len() > 4 && lower.starts_with("alt+") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            name if name.len() > 1 && name.starts_with('f') => {
+                KeyCode::F(name[1..].parse().ok().filter(|n| (1..=12).contains(n))?)
+            }
+            _ => {
+                let mut chars = rest.chars();
+                let c = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                KeyCode::Char(c)
+            }
+        };
+        Some(Self::new(code, modifiers))
+    }
+
+    #[cfg(test)]
+    pub fn code(self) -> KeyCode {
+        self.code
+    }
+
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    // Ctrl and Alt combinations and function keys do nothing in a text
+    // field, so they can be translated while typing
+    fn is_global(self) -> bool {
+        !self.modifiers.is_empty() || matches!(self.code, KeyCode::F(_))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Esc => write!(f, "Esc"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// The keys bound to each [`Action`], after the `[keys]` table of the
+/// settings file, and how pressed keys translate to the ones the screens
+/// handle.
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    /// Every action with the keys it ends up on.
+    keys: Vec<(Action, Vec<Chord>)>,
+    /// Keys that stand in for another key.
+    remap: HashMap<Chord, Chord>,
+    /// Default keys of rebound actions, which do nothing unless bound again.
+    unbound: HashSet<Chord>,
+    /// What the conflict check found, one line each.
+    pub problems: Vec<String>,
+}
+
+impl Bindings {
+    /// Binds the keys of the `[keys]` table, e.g. `down = "k"` or
+    /// `quit = "x Ctrl+q"`; actions left out keep their default keys. A key
+    /// given to two actions stays with the one listed first in
+    /// [`Action::ALL`].
+    pub fn new(table: &BTreeMap<String, String>) -> Self {
+        let mut problems = Vec::new();
+        let mut custom: HashMap<Action, Vec<Chord>> = HashMap::new();
+        for (name, keys) in table {
+            let Some(action) = Action::parse(name) else {
+                problems.push(format!("[keys] {}: not an action", name));
+                continue;
+            };
+            let mut chords = Vec::new();
+            for key in keys.split_whitespace() {
+                match Chord::parse(key) {
+                    Some(chord) if !chords.contains(&chord) => chords.push(chord),
+                    Some(_) => {}
+                    None => problems.push(format!("[keys] {}: '{}' is not a key", name, key)),
+                }
+            }
+            custom.insert(action, chords);
+        }
+
+        let mut owner: HashMap<Chord, Action> = HashMap::new();
+        let mut keys = Vec::new();
+        for action in Action::ALL {
+            let mut bound = Vec::new();
+            for chord in custom
+                .remove(&action)
+                .unwrap_or_else(|| action.default_keys())
+            {
+                match owner.get(&chord) {
+                    Some(other) => problems.push(format!(
+                        "{} is bound to both {} and {}; it stays with {}",
+                        chord,
+                        other.name(),
+                        action.name(),
+                        other.name()
+                    )),
+                    None => {
+                        owner.insert(chord, action);
+                        bound.push(chord);
+                    }
+                }
+            }
+            keys.push((action, bound));
+        }
+
+        let defaults: HashSet<Chord> = Action::ALL
+            .into_iter()
+            .flat_map(Action::default_keys)
+            .collect();
+        let mut remap = HashMap::new();
+        for (action, bound) in &keys {
+            let default_keys = action.default_keys();
+            for chord in bound.iter().filter(|c| !default_keys.contains(c)) {
+                remap.insert(*chord, default_keys[0]);
+                if !defaults.contains(chord) {
+                    let shadowed = shadowed_sections(*chord);
+                    if !shadowed.is_empty() {
+                        problems.push(format!(
+                            "{} ({}) also has a use in {}",
+                            chord,
+                            action.name(),
+                            shadowed.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+        let unbound = defaults
+            .into_iter()
+            .filter(|chord| !owner.contains_key(chord))
+            .collect();
+
+        Self {
+            keys,
+            remap,
+            unbound,
+            problems,
+        }
+    }
+
+    /// Every action with its keys, and whether they differ from the defaults.
+    pub fn actions(&self) -> impl Iterator<Item = (Action, &[Chord], bool)> {
+        self.keys
+            .iter()
+            .map(|(action, keys)| (*action, keys.as_slice(), *keys != action.default_keys()))
+    }
+
+    /// The key the screens should see for `key`, or `None` when the key was
+    /// unbound. While typing only Ctrl, Alt and function keys translate, so
+    /// text is typed as it is.
+    pub fn translate(&self, key: KeyEvent, typing: bool) -> Option<KeyEvent> {
+        let chord = Chord::new(key.code, key.modifiers);
+        if typing && !chord.is_global() {
+            return Some(key);
+        }
+        match self.remap.get(&chord) {
+            Some(target) if !typing || target.is_global() => Some(KeyEvent {
+                code: target.code,
+                modifiers: target.modifiers,
+                ..key
+            }),
+            Some(_) => Some(key),
+            None if self.unbound.contains(&chord) => None,
+            None => Some(key),
+        }
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new())
+    }
+}
+
+// the keymap sections where `chord` already does something, which a
+// rebinding takes over
+fn shadowed_sections(chord: Chord) -> Vec<&'static str> {
+    KEYMAP
+        .iter()
+        .filter(|section| {
+            section.bindings.iter().any(|(keys, _)| {
+                keys.split(" / ")
+                    .any(|key| Chord::parse(key) == Some(chord))
+            })
+        })
+        .map(|section| section.title)
+        .collect()
+}
@@ -0,0 +1,168 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// What an editing key did to a [`TextInput`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edit {
+    /// Not an editing key; the screen handles it.
+    Ignored,
+    /// Only the cursor moved.
+    Moved,
+    /// The text changed.
+    Changed,
+}
+
+/// A single-line text field with a cursor, so text can be edited in the
+/// middle and not only at the end.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextInput {
+    text: String,
+    /// Cursor position in characters, from 0 to the text's length.
+    cursor: usize,
+}
+
+impl TextInput {
+    /// A field holding `text`, with the cursor at the end.
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Replaces the text, with the cursor at the end.
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.chars().count();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// The text before and after the cursor.
+    pub fn split_at_cursor(&self) -> (&str, &str) {
+        self.text.split_at(self.byte_index(self.cursor))
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let index = self.byte_index(self.cursor);
+        self.text.insert(index, c);
+        self.cursor += 1;
+    }
+
+    pub fn insert_str(&mut self, text: &str) {
+        let index = self.byte_index(self.cursor);
+        self.text.insert_str(index, text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Applies an editing or cursor key. Plain characters are typed; Ctrl
+    /// and Alt combinations other than the ones below are left alone, so
+    /// Ctrl+E still opens the event log rather than jumping to the end.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Edit {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let length = self.text.len();
+        match key.code {
+            KeyCode::Char(c) if !ctrl && !alt => {
+                self.insert(c);
+                return Edit::Changed;
+            }
+            KeyCode::Left if ctrl || alt => self.cursor = self.word_start(),
+            KeyCode::Right if ctrl || alt => self.cursor = self.word_end(),
+            KeyCode::Char('b') if alt => self.cursor = self.word_start(),
+            KeyCode::Char('f') if alt => self.cursor = self.word_end(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            KeyCode::Backspace if ctrl || alt => self.delete_back_to(self.word_start()),
+            KeyCode::Backspace => self.delete_back_to(self.cursor.saturating_sub(1)),
+            KeyCode::Delete => self.delete_forward_to((self.cursor + 1).min(self.len())),
+            KeyCode::Char('w') if ctrl => self.delete_back_to(self.blank_start()),
+            KeyCode::Char('u') if ctrl => self.delete_back_to(0),
+            KeyCode::Char('k') if ctrl => self.delete_forward_to(self.len()),
+            _ => return Edit::Ignored,
+        }
+        if self.text.len() == length {
+            Edit::Moved
+        } else {
+            Edit::Changed
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, position: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(position)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    fn delete_back_to(&mut self, position: usize) {
+        let (start, end) = (self.byte_index(position), self.byte_index(self.cursor));
+        self.text.replace_range(start..end, "");
+        self.cursor = position;
+    }
+
+    fn delete_forward_to(&mut self, position: usize) {
+        let (start, end) = (self.byte_index(self.cursor), self.byte_index(position));
+        self.text.replace_range(start..end, "");
+    }
+
+    // where the word before the cursor starts, words being letters, digits
+    // and underscores
+    fn word_start(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = self.cursor;
+        while position > 0 && !is_word(chars[position - 1]) {
+            position -= 1;
+        }
+        while position > 0 && is_word(chars[position - 1]) {
+            position -= 1;
+        }
+        position
+    }
+
+    fn word_end(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = self.cursor;
+        while position < chars.len() && !is_word(chars[position]) {
+            position += 1;
+        }
+        while position < chars.len() && is_word(chars[position]) {
+            position += 1;
+        }
+        position
+    }
+
+    // Ctrl+W deletes back to whitespace, as in a shell
+    fn blank_start(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = self.cursor;
+        while position > 0 && chars[position - 1].is_whitespace() {
+            position -= 1;
+        }
+        while position > 0 && !chars[position - 1].is_whitespace() {
+            position -= 1;
+        }
+        position
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
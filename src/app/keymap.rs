@@ -0,0 +1,490 @@
+use super::screen::Screen;
+
+/// Keys of one screen or mode, as listed in the help overlay.
+pub struct KeySection {
+    pub title: &'static str,
+    /// Screens whose help opens at this section.
+    pub screens: &'static [Screen],
+    pub bindings: &'static [(&'static str, &'static str)],
+}
+
+/// Every keybinding, grouped by where it applies. The help overlay is drawn
+/// from this table, so a new key belongs here as well as in its handler.
+pub const KEYMAP: &[KeySection] = &[
+    KeySection {
+        title: "Global",
+        screens: &[],
+        bindings: &[
+            ("? / F1", "This help (? only outside text fields)"),
+            ("Ctrl+O", "Connection switcher"),
+            ("Ctrl+P", "Fuzzy finder: jump to any database or collection"),
+            (
+                ":",
+                "Command line: set page_size 50 changes and saves a setting",
+            ),
+            ("Ctrl+B", "Background tasks"),
+            ("Ctrl+E", "Connection event log"),
+            ("Ctrl+T", "Query timing history"),
+            (
+                "Ctrl+G",
+                "ObjectId/UUID utilities: generate, convert, read timestamps",
+            ),
+            ("F6", "Dry run: show writes instead of sending them"),
+            ("5j / 5k", "Move by a count"),
+            ("gg / G", "First / last item of a list"),
+            ("g", "Acts alone after half a second without g, t or T"),
+            ("Ctrl+d / Ctrl+u", "Half a page down / up"),
+            ("Ctrl+C", "Quit"),
+        ],
+    },
+    KeySection {
+        title: "ObjectId / UUID utilities",
+        screens: &[],
+        bindings: &[
+            ("Enter", "Copy the selected form"),
+            ("Ctrl+N", "Generate new identifiers"),
+        ],
+    },
+    KeySection {
+        title: "Fuzzy finder",
+        screens: &[],
+        bindings: &[
+            ("↑ / ↓", "Select a match"),
+            ("Enter", "Open the database or collection"),
+            ("Esc", "Close"),
+        ],
+    },
+    KeySection {
+        title: "Connection switcher",
+        screens: &[],
+        bindings: &[
+            ("Enter", "Switch to the connection"),
+            ("d", "Duplicate the current connection into a new tab"),
+            ("x", "Close the connection"),
+        ],
+    },
+    KeySection {
+        title: "Text inputs",
+        screens: &[],
+        bindings: &[
+            ("← / →", "Move the cursor"),
+            ("Ctrl+← / Ctrl+→", "Move by a word (also Alt+b / Alt+f)"),
+            ("Home / End", "Start / end of the input (also Ctrl+A)"),
+            ("Backspace / Delete", "Delete before / under the cursor"),
+            ("Ctrl+Backspace", "Delete the word before the cursor"),
+            ("Ctrl+W", "Delete back to the previous space"),
+            ("Ctrl+U / Ctrl+K", "Delete to the start / end"),
+        ],
+    },
+    KeySection {
+        title: "First-run setup",
+        screens: &[Screen::Setup],
+        bindings: &[
+            ("Enter", "Next step, then save"),
+            ("Tab / ↑ / ↓", "Move between steps"),
+            ("← / → / Space", "Change a choice"),
+            ("Esc", "Skip and keep the defaults"),
+        ],
+    },
+    KeySection {
+        title: "Connection",
+        screens: &[Screen::Connection],
+        bindings: &[
+            ("Enter", "Connect to the typed URI"),
+            ("Ctrl+V", "Paste a URI"),
+            ("Esc", "Clear the input"),
+            ("Tab", "Connection history"),
+            ("F2", "Toggle health check on connect"),
+            ("F3", "Saved profiles"),
+            ("F4", "Browse Atlas projects"),
+            ("n / e / d", "New / edit / delete profile (profile list)"),
+            ("Enter", "Use the selected profile or history entry"),
+        ],
+    },
+    KeySection {
+        title: "Databases",
+        screens: &[Screen::DatabaseList],
+        bindings: &[
+            ("Enter / l", "Open database"),
+            ("r", "Refresh"),
+            ("H", "Health check"),
+            ("S", "Replica set members and elections"),
+            ("C", "Compare collection counts against a second connection"),
+            ("A", "About: versions and features the server supports"),
+            ("P", "Check your deployment-wide privileges"),
+            ("W", "Watch every database of the deployment"),
+            ("O", "Running operations, with killOp"),
+            ("U", "Users and roles of the selected database"),
+            ("D", "Drop the database, after typing its name"),
+            ("q", "Quit"),
+        ],
+    },
+    KeySection {
+        title: "Collections",
+        screens: &[Screen::CollectionList],
+        bindings: &[
+            ("Enter / l", "Open collection"),
+            ("T", "Open the collection in a new tab"),
+            ("Backspace / h", "Back to databases"),
+            ("r", "Refresh"),
+            ("n", "Create a collection"),
+            ("t", "Tail a capped collection"),
+            ("w", "Watch the change stream"),
+            ("W", "Watch every collection of the database"),
+            ("a", "Analyze the schema of a sample"),
+            ("R", "Write a schema report"),
+            ("I", "Toggle change stream pre/post images"),
+            ("P", "Show your privileges on the collection"),
+            ("i", "Storage, index and cache statistics"),
+            ("K", "Analyze a candidate shard key"),
+            ("B", "Sharding: chunks, zones and the balancer"),
+            ("Space", "Mark the collection for export"),
+            ("X", "Export the marked collections, or the whole database"),
+            ("p", "Replay a recorded change file into the collection"),
+            ("V", "Diff the collection against an export file"),
+            ("m", "Rename the collection"),
+            ("c", "Duplicate the collection, optionally with its indexes"),
+            ("D", "Drop the collection, after typing its name"),
+            ("q", "Quit"),
+        ],
+    },
+    KeySection {
+        title: "Documents",
+        screens: &[Screen::DocumentView],
+        bindings: &[
+            ("PgUp / PgDn", "Scroll the document"),
+            ("] / [", "Next / previous page"),
+            ("Backspace / h", "Back to collections"),
+            ("r", "Refresh"),
+            ("f", "Live filter"),
+            ("/", "JSON query: filter, sort and projection"),
+            ("Ctrl+R", "Earlier queries on the collection (in a query)"),
+            ("S", "Save the current query under a name"),
+            ("Q", "Saved queries: Enter applies, e edits, d deletes"),
+            ("g", "Geo query on a location field"),
+            ("p", "Projection picker"),
+            ("P", "Explain the query plan"),
+            ("e", "Edit the document in $EDITOR"),
+            ("a", "Aggregation pipeline builder"),
+            ("s", "Atlas Search builder"),
+            ("b", "Toggle BSON sizes"),
+            ("w", "Collection scan warning"),
+            ("L", "Page size"),
+            ("E", "Run the query against another environment"),
+            ("B", "Benchmark the query: min, median and p95 latency"),
+            (
+                "X",
+                "Export every matching document: JSON, CSV, BSON or Markdown",
+            ),
+            ("I", "Import a JSON or NDJSON file"),
+            ("d", "Distinct values of a field, with counts"),
+            ("U", "Bulk update: updateMany with a preview of the matches"),
+            (
+                "v",
+                "Query builder: pick fields and operators instead of JSON",
+            ),
+            ("Tab", "Focus the document content to scroll and search it"),
+            (
+                "Ctrl+Left / Ctrl+Right",
+                "Narrow / widen the list next to the content",
+            ),
+            ("Z", "Maximize the content pane / restore the split"),
+            ("gt / gT", "Next / previous collection tab"),
+            ("3gt / Alt+3", "Go to the third tab"),
+            ("Ctrl+W", "Close the collection tab"),
+            ("c", "Canonical extended JSON / readable values"),
+            (
+                "y / Y",
+                "Copy the document (the cell in the table) / its _id",
+            ),
+            ("t", "Table view"),
+        ],
+    },
+    KeySection {
+        title: "Distinct values",
+        screens: &[],
+        bindings: &[
+            ("j / k", "Select a value"),
+            ("s", "Order by count or by value"),
+            ("Esc", "Close"),
+        ],
+    },
+    KeySection {
+        title: "Document content",
+        screens: &[],
+        bindings: &[
+            ("j / k", "Move the cursor a line"),
+            (
+                "za / Enter",
+                "Fold or unfold the subdocument or array at the cursor",
+            ),
+            ("zo / zc", "Unfold / fold it"),
+            ("zM / zR", "Fold / unfold everything"),
+            ("y", "Copy the value at the cursor"),
+            ("Y", "Copy the document's _id"),
+            ("/", "Search the text of the document"),
+            ("n / N", "Next / previous match"),
+            ("Esc", "Clear the search, then back to the list"),
+            ("Tab", "Back to the list"),
+        ],
+    },
+    KeySection {
+        title: "Query builder",
+        screens: &[],
+        bindings: &[
+            (
+                "Tab / Shift+Tab",
+                "Field, operator, value; Tab takes the first suggested field",
+            ),
+            ("← / →", "Change the operator"),
+            ("↑ / ↓", "Select a condition"),
+            ("Ctrl+N / Ctrl+X", "Add / remove a condition"),
+            ("F2", "Combine the conditions with AND or OR"),
+            ("Enter", "Run the generated filter"),
+            ("Esc", "Close"),
+        ],
+    },
+    KeySection {
+        title: "Query history",
+        screens: &[],
+        bindings: &[
+            ("Enter", "Run the query again"),
+            ("Tab", "Edit the query before running it"),
+        ],
+    },
+    KeySection {
+        title: "Table view",
+        screens: &[],
+        bindings: &[
+            ("← / → or h / l", "Select a column"),
+            ("< / >", "Move the column"),
+            ("+ / x", "Add / remove a column"),
+            ("R", "Reset columns to the inferred set"),
+            ("F", "Pin the column at the left edge"),
+            ("Enter", "Inspect the selected cell"),
+            ("o", "Sort the page by the column"),
+            ("O", "Re-query sorted by the column"),
+            ("d", "Distinct values of the column"),
+            ("X", "Export the table as shown"),
+            ("t", "Back to the JSON view"),
+        ],
+    },
+    KeySection {
+        title: "Aggregation",
+        screens: &[Screen::Aggregation],
+        bindings: &[
+            ("a", "Add a stage from a template or snippet"),
+            ("i", "Import a pipeline"),
+            ("Enter", "Edit the stage body"),
+            ("Space", "Enable / disable the stage"),
+            ("J / K", "Move the stage down / up"),
+            ("d", "Delete the stage"),
+            ("r", "Preview output up to the stage"),
+            ("R", "Run the whole pipeline as a background task"),
+            ("PgUp / PgDn", "Scroll the preview"),
+        ],
+    },
+    KeySection {
+        title: "Import",
+        screens: &[Screen::Import],
+        bindings: &[
+            ("Tab", "Complete the file path"),
+            ("← / →", "Insert or upsert by _id"),
+            ("Enter", "Preview the file, then import it"),
+            ("Esc", "Back to the documents"),
+        ],
+    },
+    KeySection {
+        title: "Bulk update",
+        screens: &[Screen::BulkUpdate],
+        bindings: &[
+            ("Tab", "Switch between the filter and the update"),
+            ("Enter", "Preview the matches, then run updateMany"),
+            ("PgUp / PgDn", "Scroll the preview"),
+            ("Esc", "Back to the documents"),
+        ],
+    },
+    KeySection {
+        title: "Atlas Search",
+        screens: &[Screen::Search],
+        bindings: &[
+            ("Tab", "Next field"),
+            ("← / →", "Change the option"),
+            ("Enter", "Run the search"),
+            ("↑ / ↓", "Select a hit"),
+            ("PgUp / PgDn", "Scroll the hit"),
+        ],
+    },
+    KeySection {
+        title: "Tail and watch",
+        screens: &[Screen::Tail],
+        bindings: &[
+            ("Space", "Pause / resume"),
+            ("G", "Follow new events"),
+            ("n", "Show one namespace of a database or deployment watch"),
+            ("c", "Clear"),
+            ("R", "Start / stop recording events to a file"),
+        ],
+    },
+    KeySection {
+        title: "Sharding",
+        screens: &[Screen::Sharding],
+        bindings: &[
+            ("Tab", "Switch between chunks and zones"),
+            ("s", "Split the chunk"),
+            ("m", "Move the chunk"),
+            ("z", "Add a zone range"),
+            ("d", "Remove the zone range"),
+            ("b", "Toggle the balancer"),
+            ("r", "Refresh"),
+        ],
+    },
+    KeySection {
+        title: "Replica set",
+        screens: &[Screen::ReplicaSet],
+        bindings: &[
+            ("s", "Step down the primary"),
+            ("r", "Refresh"),
+            ("Space", "Pause or resume the refresh"),
+        ],
+    },
+    KeySection {
+        title: "About",
+        screens: &[Screen::About],
+        bindings: &[("r", "Check again"), ("Esc", "Back to databases")],
+    },
+    KeySection {
+        title: "Schema",
+        screens: &[Screen::Schema],
+        bindings: &[
+            ("n", "Change the sample size"),
+            ("r", "Sample again"),
+            ("Esc", "Back to collections"),
+        ],
+    },
+    KeySection {
+        title: "Tasks",
+        screens: &[Screen::Tasks],
+        bindings: &[
+            ("c", "Cancel the task and kill its server operations"),
+            ("x", "Clear finished tasks"),
+        ],
+    },
+    KeySection {
+        title: "Event log",
+        screens: &[Screen::EventLog],
+        bindings: &[("G / End", "Jump to the newest event")],
+    },
+    KeySection {
+        title: "Count comparison",
+        screens: &[Screen::Compare],
+        bindings: &[("m", "Show mismatches only"), ("r", "Run again")],
+    },
+    KeySection {
+        title: "Operations",
+        screens: &[Screen::Operations],
+        bindings: &[
+            ("K", "Kill the selected operation, after typing its opid"),
+            ("l", "Show only operations running at least some seconds"),
+            ("Space", "Pause or resume the refresh"),
+            ("r", "Refresh"),
+        ],
+    },
+    KeySection {
+        title: "Users",
+        screens: &[Screen::Users],
+        bindings: &[
+            ("n", "Create a user: name, password, then roles to check"),
+            ("p", "Change the selected user's password"),
+            ("D", "Drop the selected user, after typing its name"),
+            (
+                "R",
+                "Roles browser: privileges of built-in and custom roles",
+            ),
+            ("r", "Refresh"),
+        ],
+    },
+    KeySection {
+        title: "Roles",
+        screens: &[Screen::Roles],
+        bindings: &[("r", "Refresh"), ("Esc", "Back to users")],
+    },
+    KeySection {
+        title: "Reference diff",
+        screens: &[Screen::ReferenceDiff],
+        bindings: &[
+            ("j / k", "Select a document; its field changes show below"),
+            ("r", "Run again"),
+        ],
+    },
+    KeySection {
+        title: "Query timings",
+        screens: &[Screen::Timings],
+        bindings: &[
+            ("j / k", "Select a query shape"),
+            ("c", "Clear the history"),
+        ],
+    },
+];
+
+/// Line of the help text where the section for `screen` starts; each section
+/// is a title, its bindings and a blank line.
+pub fn section_line(screen: &Screen) -> usize {
+    let mut line = 0;
+    for section in KEYMAP {
+        if section.screens.contains(screen) {
+            return line;
+        }
+        line += section.bindings.len() + 2;
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyCode;
+
+    use super::KEYMAP;
+    use crate::app::bindings::Chord;
+
+    // every source file with a key handler
+    const HANDLERS: &[&str] = &[
+        include_str!("../main.rs"),
+        include_str!("keys.rs"),
+        include_str!("input.rs"),
+    ];
+
+    // how a handler matches `code`; digits are matched as a range and the
+    // arrows are written as glyphs, so those are left out
+    fn pattern(code: KeyCode) -> Option<String> {
+        match code {
+            KeyCode::Char(c) if c.is_ascii_graphic() && !c.is_ascii_digit() => {
+                Some(format!("KeyCode::Char({:?})", c))
+            }
+            KeyCode::F(n) => Some(format!("KeyCode::F({})", n)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn every_documented_key_has_a_handler() {
+        let mut missing = Vec::new();
+        for section in KEYMAP {
+            for (keys, _) in section.bindings {
+                for key in keys.split(" / ").flat_map(|k| k.split(" or ")) {
+                    let Some(pattern) = Chord::parse(key).and_then(|c| pattern(c.code())) else {
+                        continue;
+                    };
+                    if !HANDLERS.iter().any(|source| source.contains(&pattern)) {
+                        missing.push(format!("{}: {}", section.title, key));
+                    }
+                }
+            }
+        }
+        assert!(
+            missing.is_empty(),
+            "documented but not handled: {:?}",
+            missing
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::screen::Screen;
+
+/// How long a lone `g` waits for the key completing `gg`, `gt` or `gT`
+/// before it is handled as a plain key.
+const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A cursor movement produced by a vim-style key sequence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Motion {
+    Down(usize),
+    Up(usize),
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+}
+
+impl Motion {
+    /// Where a cursor at `selected` in a list of `len` lands; `len` must be
+    /// non-zero.
+    pub fn target(self, selected: usize, len: usize, half_page: usize) -> usize {
+        let last = len - 1;
+        match self {
+            Motion::Down(n) => selected.saturating_add(n).min(last),
+            Motion::Up(n) => selected.saturating_sub(n),
+            Motion::Top => 0,
+            Motion::Bottom => last,
+            Motion::HalfPageDown => selected.saturating_add(half_page).min(last),
+            Motion::HalfPageUp => selected.saturating_sub(half_page),
+        }
+    }
+}
+
+/// A change of collection tab, from `gt`, `gT`, `{count}gt` or Alt+digit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TabMove {
+    Next,
+    Previous,
+    /// The tab with this number, counting from 1.
+    To(usize),
+}
+
+/// What the event loop should do with a key after the sequence state
+/// machine has seen it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feed {
+    /// Part of an unfinished sequence; nothing to do yet.
+    Pending,
+    Motion(Motion),
+    Tab(TabMove),
+    /// Not a motion; handle it as usual.
+    Key(KeyEvent),
+    /// A `g` that turned out not to start `gg`, followed by the key that
+    /// ended the sequence.
+    Replay(KeyEvent, KeyEvent),
+}
+
+/// Collects count prefixes (`5j`) and the `gg`, `gt` and `gT` sequences
+/// across key presses.
+#[derive(Debug, Default)]
+pub struct KeySequence {
+    count: Option<usize>,
+    pending_g: Option<(KeyEvent, Instant)>,
+}
+
+impl KeySequence {
+    pub fn feed(&mut self, key: KeyEvent) -> Feed {
+        if let Some((g, _)) = self.pending_g.take() {
+            let count = self.count.take();
+            return match key.code {
+                KeyCode::Char('g') => Feed::Motion(Motion::Top),
+                KeyCode::Char('t') => Feed::Tab(count.map_or(TabMove::Next, TabMove::To)),
+                KeyCode::Char('T') => Feed::Tab(TabMove::Previous),
+                _ => Feed::Replay(g, key),
+            };
+        }
+
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char(c @ '1'..='9') if alt => {
+                self.count = None;
+                Feed::Tab(TabMove::To(c.to_digit(10).unwrap_or(1) as usize))
+            }
+            KeyCode::Char(c @ '1'..='9') if !control => {
+                self.push_digit(c);
+                Feed::Pending
+            }
+            KeyCode::Char('0') if self.count.is_some() => {
+                self.push_digit('0');
+                Feed::Pending
+            }
+            KeyCode::Char('g') if !control => {
+                self.pending_g = Some((key, Instant::now()));
+                Feed::Pending
+            }
+            KeyCode::Char('G') => self.motion(Motion::Bottom),
+            KeyCode::Char('d') if control => self.motion(Motion::HalfPageDown),
+            KeyCode::Char('u') if control => self.motion(Motion::HalfPageUp),
+            KeyCode::Char('j') | KeyCode::Down if self.count.is_some() => {
+                let n = self.count.take().unwrap_or(1);
+                Feed::Motion(Motion::Down(n))
+            }
+            KeyCode::Char('k') | KeyCode::Up if self.count.is_some() => {
+                let n = self.count.take().unwrap_or(1);
+                Feed::Motion(Motion::Up(n))
+            }
+            _ => {
+                self.count = None;
+                Feed::Key(key)
+            }
+        }
+    }
+
+    /// A `g` left waiting past the timeout, to be handled on its own.
+    pub fn expire(&mut self) -> Option<KeyEvent> {
+        match self.pending_g {
+            Some((g, since)) if since.elapsed() >= SEQUENCE_TIMEOUT => {
+                self.pending_g = None;
+                self.count = None;
+                Some(g)
+            }
+            _ => None,
+        }
+    }
+
+    /// Drops a half-typed sequence, e.g. when a text field takes focus.
+    pub fn reset(&mut self) {
+        self.count = None;
+        self.pending_g = None;
+    }
+
+    fn push_digit(&mut self, digit: char) {
+        let value = digit.to_digit(10).unwrap_or(0) as usize;
+        self.count = Some(
+            self.count
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(value),
+        );
+    }
+
+    fn motion(&mut self, motion: Motion) -> Feed {
+        self.count = None;
+        Feed::Motion(motion)
+    }
+}
+
+/// `l` opens and `h` goes back on screens where they are not bound already.
+pub fn alias(screen: &Screen, table_view: bool, code: KeyCode) -> KeyCode {
+    match (screen, code) {
+        (Screen::DatabaseList | Screen::CollectionList, KeyCode::Char('l')) => KeyCode::Enter,
+        (Screen::CollectionList, KeyCode::Char('h')) => KeyCode::Backspace,
+        (Screen::DocumentView, KeyCode::Char('h')) if !table_view => KeyCode::Backspace,
+        _ => code,
+    }
+}
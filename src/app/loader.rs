@@ -0,0 +1,332 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use mongodb::bson::Document;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use crate::{
+    config::ConnectionProfile,
+    error::AppError,
+    models::{
+        AtlasCluster, AtlasProject, BulkUpdatePreview, CollectionInfo, CollectionStats,
+        Compatibility, CountComparison, DatabaseInfo, DatabaseTotals, DistinctValues,
+        ElectionEvent, HealthItem, OpCounters, OperationInfo, PageCursor, ReferenceDiff,
+        ReplicaSetStatus, RoleInfo, SchemaAnalysis, SearchHit, ServerInfo, ShardKeyAnalysis,
+        ShardingStatus, UserInfo, UserPrivileges, WatchScope, WouldExecute,
+    },
+    services::{connection::ConnectionService, permissions, query::QueryService, tail::Tailer},
+};
+
+/// A finished connection attempt, carrying the new service so the main loop
+/// can swap it in.
+pub struct Connected {
+    pub uri: String,
+    pub profile: Option<ConnectionProfile>,
+    pub service: ConnectionService,
+    pub server_info: ServerInfo,
+    pub privileges: Option<UserPrivileges>,
+    pub databases: Result<Vec<DatabaseInfo>, AppError>,
+}
+
+impl Connected {
+    /// Connects a fresh service and loads what the database list needs.
+    pub async fn establish(
+        uri: String,
+        profile: Option<ConnectionProfile>,
+    ) -> Result<Box<Self>, AppError> {
+        let mut service = ConnectionService::new();
+        let server_info = service.connect(&uri, profile.as_ref()).await?;
+        let client = service
+            .get_client()
+            .cloned()
+            .ok_or_else(|| AppError::Connection("Not connected".to_string()))?;
+
+        let privileges = permissions::fetch(&client).await.ok();
+        let databases = QueryService::new(client).list_databases().await;
+        Ok(Box::new(Self {
+            uri,
+            profile,
+            service,
+            server_info,
+            privileges,
+            databases,
+        }))
+    }
+}
+
+/// Results sent back to the main loop by background loads.
+pub enum Loaded {
+    Connection(Result<Box<Connected>, AppError>),
+    Databases(Result<Vec<DatabaseInfo>, AppError>),
+    Collections {
+        db: String,
+        open: bool,
+        result: Result<Vec<CollectionInfo>, AppError>,
+    },
+    /// The first page of a collection being opened from the collection list.
+    Collection {
+        db: String,
+        coll: String,
+        /// Opened with `T`, into a new tab.
+        tab: bool,
+        limit: usize,
+        /// The collection's default sort, if it opens newest first.
+        sort: Option<Document>,
+        result: Result<Vec<Document>, AppError>,
+    },
+    ShardKey(Result<ShardKeyAnalysis, AppError>),
+    SearchIndexes(Vec<String>),
+    /// Field paths sampled for the projection picker.
+    ProjectionFields {
+        db: String,
+        coll: String,
+        result: Result<Vec<String>, AppError>,
+    },
+    /// Field paths sampled for the query builder.
+    BuilderFields {
+        db: String,
+        coll: String,
+        result: Result<Vec<String>, AppError>,
+    },
+    /// A new collection and the refreshed list of its database.
+    CollectionCreated {
+        db: String,
+        name: String,
+        result: Result<Vec<CollectionInfo>, AppError>,
+    },
+    /// A dropped collection and the refreshed list of its database.
+    CollectionDropped {
+        db: String,
+        name: String,
+        result: Result<Vec<CollectionInfo>, AppError>,
+    },
+    /// A renamed collection and the refreshed list of its database.
+    CollectionRenamed {
+        db: String,
+        from: String,
+        to: String,
+        result: Result<Vec<CollectionInfo>, AppError>,
+    },
+    /// A dropped database and the refreshed database list.
+    DatabaseDropped {
+        name: String,
+        result: Result<Vec<DatabaseInfo>, AppError>,
+    },
+    SearchResults(Result<Vec<SearchHit>, AppError>),
+    /// Output of the pipeline run through stage `upto`.
+    AggregationPreview {
+        upto: usize,
+        result: Result<Vec<Document>, AppError>,
+    },
+    /// The pipeline through a stage body still being typed, on a sample.
+    DraftPreview {
+        body: String,
+        result: Result<Vec<Document>, AppError>,
+    },
+    /// A write to the open collection; the page reloads once it lands.
+    Write(Result<String, AppError>),
+    /// Sharding state, reloaded after the action that produced `message`.
+    Sharding {
+        message: Option<String>,
+        result: Result<Box<ShardingStatus>, AppError>,
+    },
+    /// Server versions and the features they support, for the about screen.
+    Compatibility(Result<Compatibility, AppError>),
+    /// Field paths and types over a sample, for the schema view.
+    Schema(Result<SchemaAnalysis, AppError>),
+    ReferenceDiff(Result<ReferenceDiff, AppError>),
+    Operations(Result<Vec<OperationInfo>, AppError>),
+    /// Matches of a bulk update, before it runs.
+    BulkUpdatePreview(Result<BulkUpdatePreview, AppError>),
+    /// Outcome of a bulk update, as a summary of the counts.
+    BulkUpdated(Result<String, AppError>),
+    /// Totals of one database for the badges on the database list.
+    DatabaseTotals {
+        name: String,
+        result: Result<DatabaseTotals, AppError>,
+    },
+    /// Distinct values of a field of the open collection.
+    DistinctValues(Result<DistinctValues, AppError>),
+    /// Every database and collection name, for the fuzzy finder.
+    Namespaces(Result<Vec<String>, AppError>),
+    /// A sample of the read and write counters of `db`'s collections.
+    OpCounters {
+        db: String,
+        result: Result<BTreeMap<String, OpCounters>, AppError>,
+    },
+    /// Storage statistics of a collection, for the panel over the list.
+    CollectionStats(Result<CollectionStats, AppError>),
+    /// Users and grantable roles of `db`, reloaded after the change that
+    /// produced `message`.
+    Users {
+        db: String,
+        message: Option<String>,
+        result: Result<(Vec<UserInfo>, Vec<RoleInfo>), AppError>,
+    },
+    /// Replica set members and recent elections, loaded again periodically.
+    ReplicaSet(Result<(ReplicaSetStatus, Vec<ElectionEvent>), AppError>),
+    OperationKilled {
+        opid: String,
+        result: Result<(), AppError>,
+    },
+    /// A write held back by dry run, with the documents it would match.
+    DryRun(WouldExecute),
+    /// The plan of the current query, explained with execution stats.
+    Explain(Result<Document, AppError>),
+    /// A page for the open collection after a query changed.
+    Documents {
+        db: String,
+        coll: String,
+        label: &'static str,
+        result: Result<Vec<Document>, AppError>,
+    },
+    /// How many documents the open namespace and filter in `key` match.
+    Count {
+        key: String,
+        result: Result<u64, AppError>,
+    },
+    /// Another page of the current query, starting at `cursor`.
+    Page {
+        db: String,
+        coll: String,
+        page: usize,
+        cursor: PageCursor,
+        result: Result<Vec<Document>, AppError>,
+    },
+    /// The current page read again, after a write or a new page size.
+    Reload {
+        db: String,
+        coll: String,
+        result: Result<Vec<Document>, AppError>,
+    },
+    /// What a page of `page_size` documents would take, when it could be
+    /// estimated.
+    PageEstimate {
+        page_size: usize,
+        bytes: Option<u64>,
+    },
+    /// Documents of the open collection matching the live filter `input`.
+    LiveFilter {
+        db: String,
+        coll: String,
+        input: String,
+        result: Result<Vec<Document>, AppError>,
+    },
+    /// The field a geo query runs on, from the collection's 2dsphere index.
+    GeoField {
+        db: String,
+        coll: String,
+        result: Result<Option<String>, AppError>,
+    },
+    AtlasProjects(Result<Vec<AtlasProject>, AppError>),
+    AtlasClusters {
+        project: AtlasProject,
+        result: Result<Vec<AtlasCluster>, AppError>,
+    },
+    /// The findings of a health check.
+    Health(Vec<HealthItem>),
+    /// The connected user's privileges, read to check them on `namespace`,
+    /// or deployment-wide when it is `None`.
+    Privileges {
+        namespace: Option<(String, String)>,
+        result: Result<UserPrivileges, AppError>,
+    },
+    /// A tail or watch of `coll` that has started, with the documents shown
+    /// before any new ones arrive.
+    Tail {
+        db: String,
+        coll: String,
+        source: &'static str,
+        label: &'static str,
+        result: Result<(Box<Tailer>, Vec<Document>), AppError>,
+    },
+    /// A watch of every collection of a database or of the deployment.
+    WideWatch {
+        scope: WatchScope,
+        result: Result<Box<Tailer>, AppError>,
+    },
+    /// Collection counts of this connection against those of `target`.
+    CountComparison {
+        target: String,
+        result: Result<Vec<CountComparison>, AppError>,
+    },
+}
+
+/// Runs MongoDB calls on tokio tasks so the UI keeps drawing while they are
+/// in flight.
+pub struct Loader {
+    tx: UnboundedSender<Loaded>,
+    rx: UnboundedReceiver<Loaded>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// Decrements the in-flight count when its task ends, even by panic.
+struct InFlight(Arc<AtomicUsize>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Loader {
+    pub fn new() -> Self {
+        let (tx, rx) = unbounded_channel();
+        Self {
+            tx,
+            rx,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn spawn<F>(&self, load: F)
+    where
+        F: Future<Output = Loaded> + Send + 'static,
+    {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlight(self.in_flight.clone());
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let _guard = guard;
+            let _ = tx.send(load.await);
+        });
+    }
+
+    /// Like [`spawn`](Self::spawn), for work nobody is waiting on: it does
+    /// not count as busy, so no spinner shows and keys held back while a
+    /// load runs stay available.
+    pub fn spawn_quiet<F>(&self, load: F)
+    where
+        F: Future<Output = Loaded> + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send(load.await);
+        });
+    }
+
+    /// Where work the loader does not run itself, such as a background
+    /// task, sends what it found for the screens.
+    pub fn sender(&self) -> UnboundedSender<Loaded> {
+        self.tx.clone()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.in_flight.load(Ordering::SeqCst) > 0
+    }
+
+    /// Results that have arrived since the last call.
+    pub fn drain(&mut self) -> Vec<Loaded> {
+        let mut loaded = Vec::new();
+        while let Ok(result) = self.rx.try_recv() {
+            loaded.push(result);
+        }
+        loaded
+    }
+}
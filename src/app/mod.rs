@@ -1,2 +1,9 @@
 pub mod state;
-pub mod screen;
\ No newline at end of file
+pub mod screen;
+pub mod prompt;
+pub mod loader;
+pub mod session;
+pub mod keys;
+pub mod keymap;
+pub mod input;
+pub mod bindings;
@@ -0,0 +1,92 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptKind {
+    SchemaReportPath,
+    CompareTarget,
+    SwitchEnvironment,
+    PageSize,
+    ConfirmLargePage,
+    ConfirmStepDown,
+    ShardKeyCandidate,
+    ConfirmBalancer,
+    ConfirmSplit,
+    MoveChunkTarget,
+    AddZoneRange,
+    ConfirmRemoveZone,
+    AddStage,
+    EditStage,
+    ConfirmPreImages,
+    ConfirmDeleteProfile,
+    AddColumn,
+    ExportTablePath,
+    ImportPipeline,
+    Benchmark,
+    SchemaSampleSize,
+    SaveQueryName,
+    RecordChangesPath,
+    ReplayChanges,
+    ConfirmDropCollection,
+    ConfirmDropDatabase,
+    RenameCollection,
+    CloneCollection,
+    ReferenceDiffPath,
+    QueryParameter,
+    OperationsMinSecs,
+    ConfirmKillOp,
+    NewUserName,
+    NewUserPassword,
+    ChangeUserPassword,
+    ConfirmDropUser,
+    ConfirmBulkUpdate,
+    DistinctField,
+    Command,
+}
+
+impl PromptKind {
+    /// Prompts whose confirmation changes data or cluster state.
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            PromptKind::ConfirmStepDown
+                | PromptKind::ConfirmBalancer
+                | PromptKind::ConfirmSplit
+                | PromptKind::MoveChunkTarget
+                | PromptKind::AddZoneRange
+                | PromptKind::ConfirmRemoveZone
+                | PromptKind::ConfirmPreImages
+                | PromptKind::ConfirmDropCollection
+                | PromptKind::ConfirmDropDatabase
+                | PromptKind::RenameCollection
+                | PromptKind::CloneCollection
+                | PromptKind::ConfirmKillOp
+                | PromptKind::ChangeUserPassword
+                | PromptKind::ConfirmDropUser
+                | PromptKind::ConfirmBulkUpdate
+        )
+    }
+
+    /// Prompts whose input is masked on screen.
+    pub fn is_secret(&self) -> bool {
+        matches!(
+            self,
+            PromptKind::NewUserPassword | PromptKind::ChangeUserPassword
+        )
+    }
+}
+
+/// A single-line text prompt shown as a popup over the current screen.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub title: String,
+    pub input: String,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind, title: &str, default: &str) -> Self {
+        Self {
+            kind,
+            title: title.to_string(),
+            input: default.to_string(),
+        }
+    }
+}
@@ -4,4 +4,24 @@ pub enum Screen{
     CollectionList,
     DocumentView,
     Connection,
+    Tail,
+    HealthCheck,
+    Atlas,
+    Compare,
+    ReferenceDiff,
+    Operations,
+    Users,
+    Roles,
+    Tasks,
+    EventLog,
+    ReplicaSet,
+    Sharding,
+    Search,
+    Aggregation,
+    Timings,
+    Import,
+    BulkUpdate,
+    About,
+    Schema,
+    Setup,
 }
\ No newline at end of file
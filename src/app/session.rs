@@ -0,0 +1,122 @@
+use mongodb::bson::Document;
+
+use super::input::TextInput;
+use super::screen::Screen;
+use super::state::ConnectionState;
+use crate::config::ConnectionProfile;
+use crate::models::{CollectionInfo, DatabaseInfo, PageCursor, UserPrivileges};
+use crate::services::connection::ConnectionService;
+
+/// Where the user is in one collection: the list it was opened from, the
+/// query, the page and the selection. The open tab's view lives in the app
+/// state; the other tabs keep theirs here.
+#[derive(Debug, Clone, Default)]
+pub struct CollectionView {
+    pub current_database: Option<String>,
+    pub current_collection: Option<String>,
+    pub collections: Vec<CollectionInfo>,
+    pub selected_coll_index: usize,
+    pub documents: Vec<Document>,
+    pub selected_doc_index: usize,
+    pub doc_scroll_offset: usize,
+    pub filter: Option<Document>,
+    pub sort: Option<Document>,
+    pub newest_first: Option<Document>,
+    pub projection: Option<Document>,
+    pub query_template: Option<String>,
+    pub filter_input: TextInput,
+    pub query_input: TextInput,
+    pub current_page: usize,
+    pub page_cursors: Vec<PageCursor>,
+    pub page_starts: Vec<u64>,
+    pub page_limit: usize,
+}
+
+impl CollectionView {
+    pub fn label(&self) -> String {
+        tab_label(
+            self.current_database.as_deref(),
+            self.current_collection.as_deref(),
+        )
+    }
+}
+
+/// The namespace of a tab, as shown in the tab bar.
+pub fn tab_label(database: Option<&str>, collection: Option<&str>) -> String {
+    match (database, collection) {
+        (Some(db), Some(coll)) => format!("{}.{}", db, coll),
+        (Some(db), None) => db.to_string(),
+        _ => "(no collection)".to_string(),
+    }
+}
+
+/// Everything needed to put a connection back exactly where the user left it.
+#[derive(Debug)]
+pub struct SessionView {
+    pub connection: Option<ConnectionState>,
+    pub active_profile: Option<ConnectionProfile>,
+    pub privileges: Option<UserPrivileges>,
+    pub screen: Screen,
+    pub databases: Vec<DatabaseInfo>,
+    pub selected_db_index: usize,
+    pub view: CollectionView,
+    /// The connection's collection tabs; the one at `active_tab` is `view`.
+    pub tabs: Vec<CollectionView>,
+    pub active_tab: usize,
+}
+
+impl SessionView {
+    pub fn label(&self) -> String {
+        label(
+            self.active_profile.as_ref(),
+            self.connection.as_ref(),
+            self.view.current_database.as_deref(),
+            self.view.current_collection.as_deref(),
+        )
+    }
+}
+
+/// Profile name or host, followed by the namespace being browsed.
+pub fn label(
+    profile: Option<&ConnectionProfile>,
+    connection: Option<&ConnectionState>,
+    database: Option<&str>,
+    collection: Option<&str>,
+) -> String {
+    let name = match (profile, connection) {
+        (Some(profile), _) => profile.name.clone(),
+        (None, Some(conn)) => conn.server_info.host.clone(),
+        (None, None) => "(not connected)".to_string(),
+    };
+    match (database, collection) {
+        (Some(db), Some(coll)) => format!("{}  {}.{}", name, db, coll),
+        (Some(db), None) => format!("{}  {}", name, db),
+        _ => name,
+    }
+}
+
+/// A live connection that is open in the background.
+pub struct ParkedSession {
+    pub service: ConnectionService,
+    pub view: SessionView,
+}
+
+/// Connections held open besides the active one, most recently used last.
+#[derive(Default)]
+pub struct Sessions {
+    parked: Vec<ParkedSession>,
+}
+
+impl Sessions {
+    pub fn labels(&self) -> Vec<String> {
+        self.parked.iter().map(|s| s.view.label()).collect()
+    }
+
+    pub fn park(&mut self, service: ConnectionService, view: SessionView) {
+        self.parked.push(ParkedSession { service, view });
+    }
+
+    pub fn take(&mut self, index: usize) -> Option<ParkedSession> {
+        (index < self.parked.len()).then(|| self.parked.remove(index))
+    }
+}
@@ -1,13 +1,57 @@
-use std::fmt::format;
-
+use super::bindings::Bindings;
+use super::input::TextInput;
+use super::keymap;
+use super::keys::Motion;
+use super::prompt::{Prompt, PromptKind};
 use super::screen::Screen;
-use crate::models::{CollectionInfo, DatabaseInfo, ServerInfo};
-use mongodb::bson::Document;
+use super::session::{self, CollectionView, SessionView};
+use crate::config::{
+    ConnectionProfile, DefaultSort, Environment, EnvironmentSet, ProfileStore, QueryHistory,
+    QueryHistoryEntry, QueryLimits, SavedQueries, SavedQuery, Settings, StageSnippet,
+};
+use crate::models::{
+    AtlasCluster, AtlasProject, BulkUpdateForm, BulkUpdatePreview, ChunkInfo, CollectionForm,
+    CollectionHeat, CollectionInfo, CollectionStats, ColorScheme, Compatibility, CountComparison,
+    DatabaseInfo, DatabaseTotals, DateFormat, DistinctValues, DraftPreview, ElectionEvent,
+    EventLogEntry, ExportDialog, HealthItem, ImportForm, NamespaceActivity, NamespaceFinder,
+    OpCounters, OperationInfo, PageCursor, PendingEdit, PermissionCheck, PipelineStage, PlanTree,
+    ProfileForm, ProjectionPicker, QueryBuilder, QueryParams, QueryTiming, ReferenceDiff,
+    ReplicaSetStatus, RoleInfo, RolePicker, ScanWarning, SchemaAnalysis, SearchForm, SearchHit,
+    ServerInfo, SetupForm, ShardKeyAnalysis, ShardingStatus, TaskInfo, Theme, TimingGroup,
+    UserInfo, UserPrivileges, UtilityPanel, ValueFormat, WatchScope, WouldExecute, ZoneRange,
+};
+use crate::services::aggregation;
+use crate::services::bson_ext::{self, number_as_f64};
+use crate::services::geo::GeoShape;
+use crate::services::heat;
+use crate::services::memory;
+use crate::services::metrics::NetworkSnapshot;
+use crate::services::paging;
+use crate::services::permissions;
+use crate::services::placeholders::{self, Placeholder};
+use crate::services::replay::Recorder;
+use crate::services::schema;
+use crate::services::shell_syntax;
+use crate::services::table;
+use crate::services::timings;
+use mongodb::bson::{Bson, Document};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
+
+const MAX_TAIL_DOCUMENTS: usize = 1000;
+/// Bounds and default of the document list's width, in percent.
+pub const MIN_SPLIT_PERCENT: u16 = 10;
+pub const MAX_SPLIT_PERCENT: u16 = 70;
+pub const DEFAULT_SPLIT_PERCENT: u16 = 30;
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
 #[derive(Debug, Clone)]
 pub struct ConnectionState {
+    #[allow(dead_code)]
     pub uri: String,
     pub server_info: ServerInfo,
+    /// Which of the profile's URIs answered, and how many it lists.
+    pub endpoint: Option<(usize, usize)>,
 }
 
 #[derive(Debug)]
@@ -17,11 +61,30 @@ pub struct AppState {
     pub current_collection: Option<String>,
     pub databases: Vec<DatabaseInfo>,
     pub collections: Vec<CollectionInfo>,
+    /// Collections marked in the collection list for a bulk export.
+    pub marked_collections: BTreeSet<String>,
     pub documents: Vec<Document>,
     pub current_page: usize,
+    /// Where each page after the first starts, in page order.
+    pub page_cursors: Vec<PageCursor>,
+    /// How many documents come before each page visited, in page order.
+    page_starts: Vec<u64>,
     pub page_size: usize,
+    /// Documents a page is read with: the page size, or fewer when the
+    /// first page was shrunk to the memory budget.
+    pub page_limit: usize,
     pub filter: Option<Document>,
+    /// Documents the filter matches in the open collection, for the status
+    /// bar; `None` until counted.
+    pub total_count: Option<u64>,
+    /// The namespace and filter `total_count` is for, or is being counted for.
+    counted: Option<String>,
+    pub sort: Option<Document>,
+    /// The open collection's default sort, newest documents first.
+    pub newest_first: Option<Document>,
+    pub projection: Option<Document>,
     pub loading: bool,
+    pub spinner_frame: usize,
     pub error: Option<String>,
     pub should_quit: bool,
     pub selected_db_index: usize,
@@ -29,15 +92,197 @@ pub struct AppState {
     pub current_screen: Screen,
     pub selected_doc_index: usize,
     pub doc_scroll_offset: usize,
-    pub connection_input: String,
+    /// Keys go to the document content pane instead of the list.
+    pub content_focus: bool,
+    /// Text searched for in the selected document, and which match is
+    /// current.
+    pub content_search: TextInput,
+    pub content_search_mode: bool,
+    pub content_match: usize,
+    pub value_format: ValueFormat,
+    /// Line of the document tree the content pane's cursor is on.
+    pub content_cursor: usize,
+    /// Folded subdocument and array paths, by document; see `fold_key`.
+    folds: BTreeMap<String, BTreeSet<String>>,
+    /// `z` was pressed and waits for the fold command after it.
+    pub fold_pending: bool,
+    /// Width of the document list, in percent of the screen; the content
+    /// pane has the rest.
+    pub split_percent: u16,
+    /// The content pane fills the screen and the list is hidden.
+    pub content_maximized: bool,
+    pub connection_input: TextInput,
     pub input_mode: bool,
-    pub filter_input: String,
+    pub filter_input: TextInput,
     pub filter_mode: bool,
     pub query_mode: bool,
-    pub query_input: String,
+    pub query_input: TextInput,
     pub connection_history: Vec<String>,
     pub selected_history_index: usize,
     pub show_history: bool,
+    pub geo_mode: bool,
+    pub geo_input: String,
+    pub geo_field: Option<String>,
+    pub geo_shape: Option<GeoShape>,
+    pub tail_documents: Vec<Document>,
+    pub tail_paused: bool,
+    pub tail_scroll_offset: usize,
+    pub tail_source: String,
+    pub tail_scope: WatchScope,
+    /// Event counts per namespace of a database or deployment watch.
+    pub tail_activity: BTreeMap<String, NamespaceActivity>,
+    /// Shows only this namespace's events of a wide watch.
+    pub tail_namespace: Option<String>,
+    /// Writes tail events to a file for a later replay.
+    pub tail_recorder: Option<Recorder>,
+    pub prompt: Option<Prompt>,
+    pub message: Option<String>,
+    pub health_items: Vec<HealthItem>,
+    pub health_check_on_connect: bool,
+    pub scan_warning: Option<ScanWarning>,
+    pub show_scan_warning: bool,
+    pub network: NetworkSnapshot,
+    pub profiles: Vec<ConnectionProfile>,
+    pub selected_profile_index: usize,
+    pub show_profiles: bool,
+    pub active_profile: Option<ConnectionProfile>,
+    pub atlas_projects: Vec<AtlasProject>,
+    pub atlas_clusters: Vec<AtlasCluster>,
+    pub atlas_project: Option<AtlasProject>,
+    pub selected_atlas_index: usize,
+    pub comparisons: Vec<CountComparison>,
+    pub compare_target: String,
+    pub compare_mismatches_only: bool,
+    pub selected_compare_index: usize,
+    pub operations: Vec<OperationInfo>,
+    pub selected_operation_index: usize,
+    /// Operations running for less than this are hidden.
+    pub operations_min_secs: u64,
+    pub operations_paused: bool,
+    /// Users and grantable roles of `users_database`.
+    pub users: Vec<UserInfo>,
+    pub roles: Vec<RoleInfo>,
+    pub users_database: Option<String>,
+    pub selected_user_index: usize,
+    pub selected_role_index: usize,
+    pub role_picker: Option<RolePicker>,
+    /// Name typed for a user being created, while the password is asked.
+    pub pending_user: Option<String>,
+    pub reference_diff: Option<ReferenceDiff>,
+    pub selected_reference_index: usize,
+    pub environment_sets: Vec<EnvironmentSet>,
+    pub privileges: Option<UserPrivileges>,
+    pub permission_checks: Vec<PermissionCheck>,
+    pub show_permissions: bool,
+    pub shard_key_analysis: Option<ShardKeyAnalysis>,
+    pub collection_stats: Option<CollectionStats>,
+    pub collection_stats_scroll: usize,
+    /// Counters of the open database's collections at the last sample.
+    op_counters: Option<(String, Instant, BTreeMap<String, OpCounters>)>,
+    /// Activity per collection between the last two samples.
+    pub collection_heat: BTreeMap<String, CollectionHeat>,
+    pub tasks: Vec<TaskInfo>,
+    /// The background task counting the open query, if one was started.
+    pub count_task: Option<u64>,
+    pub selected_task_index: usize,
+    pub panel_return_screen: Screen,
+    pub limits: QueryLimits,
+    /// User stage snippets from the settings file.
+    pub stage_snippets: Vec<StageSnippet>,
+    pub result_bytes: u64,
+    pub document_sizes: Vec<u64>,
+    pub show_document_sizes: bool,
+    pub table_view: bool,
+    pub table_columns: Vec<String>,
+    pub selected_column: usize,
+    /// Column the loaded page was sorted by locally, and whether descending.
+    pub table_sort: Option<(String, bool)>,
+    /// Column kept at the left edge while the others scroll.
+    pub frozen_column: Option<String>,
+    /// Column whose value for the selected row is open in the detail pane.
+    pub inspected_column: Option<String>,
+    pub column_sets: BTreeMap<String, Vec<String>>,
+    /// Totals per database of this connection, as they arrive.
+    pub database_totals: BTreeMap<String, DatabaseTotals>,
+    /// Databases whose totals were asked for, including refused ones.
+    totals_requested: BTreeSet<String>,
+    pub query_history: QueryHistory,
+    pub show_query_history: bool,
+    pub selected_query_history: usize,
+    pub saved_queries: SavedQueries,
+    /// The query with placeholders behind the active filter, if it had any.
+    pub query_template: Option<String>,
+    pending_template: Option<String>,
+    pending_parameters: Vec<Placeholder>,
+    /// The value last typed for each placeholder name.
+    parameter_values: BTreeMap<String, String>,
+    pub show_saved_queries: bool,
+    pub selected_saved_query: usize,
+    pub pending_page_size: Option<usize>,
+    pub event_log: Vec<EventLogEntry>,
+    pub event_log_scroll: usize,
+    pub replica_set: Option<ReplicaSetStatus>,
+    pub elections: Vec<ElectionEvent>,
+    pub replica_set_paused: bool,
+    pub sharding: Option<ShardingStatus>,
+    pub selected_chunk_index: usize,
+    pub selected_zone_index: usize,
+    pub sharding_focus_zones: bool,
+    pub pending_edit: Option<PendingEdit>,
+    pub search_form: SearchForm,
+    pub search_hits: Vec<SearchHit>,
+    pub selected_hit_index: usize,
+    pub search_scroll: usize,
+    pub pipeline: Vec<PipelineStage>,
+    pub pipeline_namespace: Option<String>,
+    pub selected_stage_index: usize,
+    pub preview_stage: Option<usize>,
+    pub aggregation_preview: Vec<Document>,
+    pub aggregation_scroll: usize,
+    /// Live preview of the stage being edited, and the body last sent for one.
+    pub draft_preview: Option<DraftPreview>,
+    pub draft_requested: Option<String>,
+    pub collection_form: Option<CollectionForm>,
+    pub export_dialog: Option<ExportDialog>,
+    pub utilities: Option<UtilityPanel>,
+    pub finder: Option<NamespaceFinder>,
+    pub projection_picker: Option<ProjectionPicker>,
+    pub distinct_values: Option<DistinctValues>,
+    pub query_builder: Option<QueryBuilder>,
+    pub plan_tree: Option<PlanTree>,
+    pub show_help: bool,
+    pub query_timings: Vec<QueryTiming>,
+    pub import_form: ImportForm,
+    pub bulk_update: BulkUpdateForm,
+    pub compatibility: Option<Compatibility>,
+    pub schema: Option<SchemaAnalysis>,
+    pub selected_schema_field: usize,
+    /// Documents sampled for the schema view.
+    pub schema_sample_size: i64,
+    pub selected_timing_index: usize,
+    pub help_scroll: usize,
+    pub profile_form: Option<ProfileForm>,
+    pub setup_form: Option<SetupForm>,
+    /// Blocks writes on every connection; set in the settings file.
+    pub read_only: bool,
+    /// Collections that open newest first; set in the settings file.
+    pub default_sort: DefaultSort,
+    /// Drops and deletions wait for a typed answer; set in the settings file.
+    pub confirm_delete: bool,
+    pub theme: Theme,
+    /// Keys as rebound in the settings file.
+    pub bindings: Bindings,
+    /// Writes are shown as what they would execute instead of being sent.
+    pub dry_run: bool,
+    pub would_execute: Option<WouldExecute>,
+    pub show_switcher: bool,
+    pub session_labels: Vec<String>,
+    pub selected_session_index: usize,
+    /// Collections open in tabs. The entry at `active_tab` is only a
+    /// placeholder; the open tab's view is in the fields above. Empty while
+    /// a single collection is open.
+    pub tabs: Vec<CollectionView>,
+    pub active_tab: usize,
 }
 
 impl AppState {
@@ -48,11 +293,21 @@ impl AppState {
             current_collection: None,
             databases: Vec::new(),
             collections: Vec::new(),
+            marked_collections: BTreeSet::new(),
             documents: Vec::new(),
             current_page: 0,
+            page_cursors: Vec::new(),
+            page_starts: vec![0],
             page_size: 20,
+            page_limit: 20,
             filter: None,
+            total_count: None,
+            counted: None,
+            sort: None,
+            newest_first: None,
+            projection: None,
             loading: false,
+            spinner_frame: 0,
             error: None,
             should_quit: false,
             selected_db_index: 0,
@@ -60,20 +315,178 @@ impl AppState {
             current_screen: Screen::Connection,
             selected_doc_index: 0,
             doc_scroll_offset: 0,
-            connection_input: String::from("mongodb://localhost:27017"),
+            content_focus: false,
+            split_percent: DEFAULT_SPLIT_PERCENT,
+            content_maximized: false,
+            content_search: TextInput::default(),
+            content_search_mode: false,
+            content_match: 0,
+            value_format: ValueFormat::default(),
+            content_cursor: 0,
+            folds: BTreeMap::new(),
+            fold_pending: false,
+            connection_input: TextInput::new("mongodb://localhost:27017"),
             input_mode: false,
-            filter_input: String::new(),
+            filter_input: TextInput::default(),
             filter_mode: false,
-            query_input: String::new(),
+            query_input: TextInput::default(),
             query_mode: false,
             connection_history: Vec::new(),
             selected_history_index: 0,
             show_history: false,
+            geo_mode: false,
+            geo_input: String::new(),
+            geo_field: None,
+            geo_shape: None,
+            tail_documents: Vec::new(),
+            tail_paused: false,
+            tail_scroll_offset: 0,
+            tail_source: String::new(),
+            tail_scope: WatchScope::default(),
+            tail_activity: BTreeMap::new(),
+            tail_namespace: None,
+            tail_recorder: None,
+            prompt: None,
+            message: None,
+            health_items: Vec::new(),
+            health_check_on_connect: false,
+            scan_warning: None,
+            show_scan_warning: false,
+            network: NetworkSnapshot::default(),
+            profiles: Vec::new(),
+            selected_profile_index: 0,
+            show_profiles: false,
+            active_profile: None,
+            atlas_projects: Vec::new(),
+            atlas_clusters: Vec::new(),
+            atlas_project: None,
+            selected_atlas_index: 0,
+            comparisons: Vec::new(),
+            compare_target: String::new(),
+            compare_mismatches_only: false,
+            selected_compare_index: 0,
+            operations: Vec::new(),
+            selected_operation_index: 0,
+            users: Vec::new(),
+            roles: Vec::new(),
+            users_database: None,
+            selected_user_index: 0,
+            selected_role_index: 0,
+            role_picker: None,
+            pending_user: None,
+            operations_min_secs: 0,
+            operations_paused: false,
+            reference_diff: None,
+            selected_reference_index: 0,
+            environment_sets: Vec::new(),
+            privileges: None,
+            permission_checks: Vec::new(),
+            show_permissions: false,
+            shard_key_analysis: None,
+            collection_stats: None,
+            collection_stats_scroll: 0,
+            op_counters: None,
+            collection_heat: BTreeMap::new(),
+            tasks: Vec::new(),
+            count_task: None,
+            selected_task_index: 0,
+            panel_return_screen: Screen::Connection,
+            limits: QueryLimits::default(),
+            stage_snippets: Vec::new(),
+            result_bytes: 0,
+            document_sizes: Vec::new(),
+            show_document_sizes: false,
+            table_view: false,
+            table_columns: Vec::new(),
+            selected_column: 0,
+            table_sort: None,
+            frozen_column: Some("_id".to_string()),
+            inspected_column: None,
+            column_sets: BTreeMap::new(),
+            database_totals: BTreeMap::new(),
+            totals_requested: BTreeSet::new(),
+            query_history: QueryHistory::default(),
+            show_query_history: false,
+            selected_query_history: 0,
+            saved_queries: SavedQueries::default(),
+            query_template: None,
+            pending_template: None,
+            pending_parameters: Vec::new(),
+            parameter_values: BTreeMap::new(),
+            show_saved_queries: false,
+            selected_saved_query: 0,
+            pending_page_size: None,
+            event_log: Vec::new(),
+            event_log_scroll: 0,
+            replica_set: None,
+            elections: Vec::new(),
+            replica_set_paused: false,
+            sharding: None,
+            selected_chunk_index: 0,
+            selected_zone_index: 0,
+            sharding_focus_zones: false,
+            pending_edit: None,
+            search_form: SearchForm::default(),
+            search_hits: Vec::new(),
+            selected_hit_index: 0,
+            search_scroll: 0,
+            pipeline: Vec::new(),
+            pipeline_namespace: None,
+            selected_stage_index: 0,
+            preview_stage: None,
+            aggregation_preview: Vec::new(),
+            draft_preview: None,
+            draft_requested: None,
+            aggregation_scroll: 0,
+            collection_form: None,
+            export_dialog: None,
+            utilities: None,
+            finder: None,
+            projection_picker: None,
+            distinct_values: None,
+            query_builder: None,
+            plan_tree: None,
+            show_help: false,
+            query_timings: Vec::new(),
+            import_form: ImportForm::default(),
+            bulk_update: BulkUpdateForm::default(),
+            compatibility: None,
+            schema: None,
+            selected_schema_field: 0,
+            schema_sample_size: schema::DEFAULT_SAMPLE_SIZE,
+            selected_timing_index: 0,
+            help_scroll: 0,
+            profile_form: None,
+            setup_form: None,
+            read_only: false,
+            default_sort: DefaultSort::default(),
+            confirm_delete: true,
+            theme: Theme::default(),
+            bindings: Bindings::default(),
+            dry_run: false,
+            would_execute: None,
+            show_switcher: false,
+            session_labels: Vec::new(),
+            selected_session_index: 0,
+            tabs: Vec::new(),
+            active_tab: 0,
         }
     }
 
     pub fn set_connection(&mut self, uri: String, server_info: ServerInfo) {
-        self.connection = Some(ConnectionState { uri, server_info });
+        self.connection = Some(ConnectionState {
+            uri,
+            server_info,
+            endpoint: None,
+        });
+        self.privileges = None;
+        self.clear_database_totals();
+    }
+
+    pub fn set_endpoint(&mut self, endpoint: Option<(usize, usize)>) {
+        if let Some(conn) = self.connection.as_mut() {
+            conn.endpoint = endpoint;
+        }
     }
 
     pub fn set_databases(&mut self, databases: Vec<DatabaseInfo>) {
@@ -81,27 +494,380 @@ impl AppState {
         self.selected_db_index = 0;
     }
 
+    /// Listed databases whose totals were not asked for yet, now marked
+    /// as asked so each is loaded once.
+    pub fn request_database_totals(&mut self) -> Vec<String> {
+        let missing: Vec<String> = self
+            .databases
+            .iter()
+            .filter(|db| !self.totals_requested.contains(&db.name))
+            .map(|db| db.name.clone())
+            .collect();
+        self.totals_requested.extend(missing.iter().cloned());
+        missing
+    }
+
+    /// Stores totals that arrived, unless the cache was cleared meanwhile.
+    pub fn set_database_totals(&mut self, name: String, totals: DatabaseTotals) {
+        if self.totals_requested.contains(&name) {
+            self.database_totals.insert(name, totals);
+        }
+    }
+
+    pub fn clear_database_totals(&mut self) {
+        self.database_totals.clear();
+        self.totals_requested.clear();
+    }
+
     pub fn set_collections(&mut self, collections: Vec<CollectionInfo>) {
+        self.marked_collections
+            .retain(|name| collections.iter().any(|c| &c.name == name));
         self.collections = collections;
         self.selected_coll_index = 0;
     }
 
+    /// Marks the selected collection for a bulk export, or unmarks it.
+    pub fn toggle_marked_collection(&mut self) {
+        if let Some(name) = self.get_selected_collection().map(|c| c.name.clone())
+            && !self.marked_collections.remove(&name)
+        {
+            self.marked_collections.insert(name);
+        }
+    }
+
+    /// Collections a bulk export covers: the marked ones, or the whole
+    /// database but its `system.` collections when none are marked.
+    pub fn bulk_export_collections(&self) -> Vec<String> {
+        if !self.marked_collections.is_empty() {
+            return self.marked_collections.iter().cloned().collect();
+        }
+        self.collections
+            .iter()
+            .map(|c| c.name.clone())
+            .filter(|name| !name.starts_with("system."))
+            .collect()
+    }
+
+    /// Shows the first page of a freshly run query, which is counted again.
     pub fn set_documents(&mut self, documents: Vec<Document>) {
+        self.current_page = 0;
+        self.page_cursors.clear();
+        self.page_starts = vec![0];
+        self.page_limit = self.page_size;
+        self.counted = None;
+        self.show_page(documents);
+    }
+
+    /// Changes the page size, which the next page read uses.
+    pub fn set_page_size(&mut self, page_size: usize) {
+        self.page_size = page_size;
+        self.page_limit = page_size;
+    }
+
+    /// Shows a page loaded from `page_params`, keeping the page cursors.
+    pub fn show_page(&mut self, documents: Vec<Document>) {
+        self.document_sizes = documents.iter().map(memory::document_size).collect();
+        self.result_bytes = self.document_sizes.iter().sum();
         self.documents = documents;
+        self.table_sort = None;
+        if self.table_view {
+            self.refresh_table_columns();
+        }
+    }
+
+    pub fn selected_document_size(&self) -> Option<u64> {
+        self.document_sizes.get(self.selected_doc_index).copied()
+    }
+
+    pub fn toggle_document_sizes(&mut self) {
+        self.show_document_sizes = !self.show_document_sizes;
+    }
+
+    pub fn current_namespace(&self) -> Option<String> {
+        Some(format!(
+            "{}.{}",
+            self.current_database.as_ref()?,
+            self.current_collection.as_ref()?
+        ))
+    }
+
+    /// The open namespace and filter when their documents have not been
+    /// counted yet; the count is then marked as asked for.
+    pub fn take_uncounted(&mut self) -> Option<String> {
+        let filter = self
+            .filter
+            .as_ref()
+            .map(|f| f.to_string())
+            .unwrap_or_default();
+        let key = format!("{} {}", self.current_namespace()?, filter);
+        if self.counted.as_ref() == Some(&key) {
+            return None;
+        }
+        self.counted = Some(key.clone());
+        self.total_count = None;
+        Some(key)
+    }
+
+    /// Keeps a count if it is still for the open namespace and filter.
+    pub fn set_total_count(&mut self, key: &str, count: u64) {
+        if self.counted.as_deref() == Some(key) {
+            self.total_count = Some(count);
+        }
+    }
+
+    /// How many pages the filter's documents fill, once they are counted.
+    pub fn page_count(&self) -> Option<usize> {
+        let total = self.total_count? as usize;
+        Some(
+            total
+                .div_ceil(self.page_limit.max(1))
+                .max(self.current_page + 1),
+        )
+    }
+
+    /// Pins the selected column, or unpins it if it already is.
+    pub fn toggle_frozen_column(&mut self) {
+        let column = self.selected_column_name().map(str::to_string);
+        self.frozen_column = if self.frozen_column == column {
+            None
+        } else {
+            column
+        };
+    }
+
+    /// Table columns left to right as drawn, frozen column first.
+    pub fn displayed_columns(&self) -> Vec<&str> {
+        table::display_order(&self.table_columns, self.frozen_column.as_deref())
+            .into_iter()
+            .map(|i| self.table_columns[i].as_str())
+            .collect()
+    }
+
+    /// Opens the selected cell's value in the detail pane.
+    pub fn inspect_cell(&mut self) {
+        self.inspected_column = self.selected_column_name().map(str::to_string);
+        self.doc_scroll_offset = 0;
+    }
+
+    pub fn close_inspector(&mut self) {
+        self.inspected_column = None;
+    }
+
+    /// The inspected value of the selected row, `None` when the field is missing.
+    pub fn inspected_value(&self) -> Option<&Bson> {
+        let column = self.inspected_column.as_deref()?;
+        table::lookup(self.get_selected_document()?, column)
+    }
+
+    /// Moves the divider between the document list and the content pane
+    /// by `step` percent, within bounds both panes stay usable at.
+    pub fn resize_split(&mut self, step: i16) {
+        self.split_percent = self
+            .split_percent
+            .saturating_add_signed(step)
+            .clamp(MIN_SPLIT_PERCENT, MAX_SPLIT_PERCENT);
+    }
+
+    pub fn toggle_content_maximized(&mut self) {
+        self.content_maximized = !self.content_maximized;
+    }
+
+    pub fn toggle_table_view(&mut self) {
+        self.inspected_column = None;
+        self.table_view = !self.table_view;
+        if self.table_view {
+            self.refresh_table_columns();
+        }
+    }
+
+    /// Uses the saved column set for this namespace, or the page's fields.
+    fn refresh_table_columns(&mut self) {
+        self.table_columns = self
+            .current_namespace()
+            .and_then(|ns| self.column_sets.get(&ns).cloned())
+            .unwrap_or_else(|| table::infer_columns(&self.documents));
+        self.selected_column = self
+            .selected_column
+            .min(self.table_columns.len().saturating_sub(1));
+    }
+
+    pub fn selected_column_name(&self) -> Option<&str> {
+        self.table_columns
+            .get(self.selected_column)
+            .map(String::as_str)
+    }
+
+    pub fn select_next_column(&mut self) {
+        if self.selected_column + 1 < self.table_columns.len() {
+            self.selected_column += 1;
+        }
+    }
+
+    pub fn select_prev_column(&mut self) {
+        self.selected_column = self.selected_column.saturating_sub(1);
+    }
+
+    /// Moves the selected column one place left or right.
+    pub fn move_column(&mut self, forward: bool) {
+        let from = self.selected_column;
+        let to = if forward {
+            from + 1
+        } else {
+            from.wrapping_sub(1)
+        };
+        if to < self.table_columns.len() {
+            self.table_columns.swap(from, to);
+            self.selected_column = to;
+            self.remember_columns();
+        }
+    }
+
+    pub fn remove_column(&mut self) {
+        if self.selected_column < self.table_columns.len() {
+            self.table_columns.remove(self.selected_column);
+            self.selected_column = self
+                .selected_column
+                .min(self.table_columns.len().saturating_sub(1));
+            self.remember_columns();
+        }
+    }
+
+    /// Inserts `path` after the selected column.
+    pub fn add_column(&mut self, path: &str) -> Result<(), String> {
+        let path = path.trim();
+        if path.is_empty() {
+            return Err("Column path is empty".to_string());
+        }
+        if self.table_columns.iter().any(|c| c == path) {
+            return Err(format!("{} is already shown", path));
+        }
+        let at = (self.selected_column + 1).min(self.table_columns.len());
+        self.table_columns.insert(at, path.to_string());
+        self.selected_column = at;
+        self.remember_columns();
+        Ok(())
+    }
+
+    /// Forgets the saved column set and infers one from the page again.
+    pub fn reset_columns(&mut self) {
+        if let Some(ns) = self.current_namespace() {
+            self.column_sets.remove(&ns);
+        }
+        self.table_sort = None;
+        self.refresh_table_columns();
+    }
+
+    fn remember_columns(&mut self) {
+        if let Some(ns) = self.current_namespace() {
+            self.column_sets.insert(ns, self.table_columns.clone());
+        }
+    }
+
+    /// Sorts the loaded page by the selected column, flipping direction on
+    /// repeat presses.
+    pub fn sort_page_by_column(&mut self) {
+        let Some(column) = self.selected_column_name().map(str::to_string) else {
+            return;
+        };
+        let descending = matches!(&self.table_sort, Some((c, false)) if *c == column);
+        let mut documents = std::mem::take(&mut self.documents);
+        table::sort_documents(&mut documents, &column, descending);
+        self.set_documents(documents);
+        self.selected_doc_index = 0;
+        self.table_sort = Some((column, descending));
+    }
+
+    /// A server sort on the selected column, flipping direction if the
+    /// active sort is already on it.
+    pub fn server_sort_by_column(&mut self) -> bool {
+        let Some(column) = self.selected_column_name().map(str::to_string) else {
+            return false;
+        };
+        let ascending = self
+            .sort
+            .as_ref()
+            .and_then(|s| s.get(&column))
+            .and_then(number_as_f64)
+            .is_some_and(|dir| dir > 0.0);
+        let mut sort = Document::new();
+        sort.insert(column, if ascending { -1 } else { 1 });
+        self.sort = Some(sort);
+        true
     }
 
     pub fn set_loading(&mut self, loading: bool) {
         self.loading = loading;
     }
 
+    pub fn tick_spinner(&mut self) {
+        if self.loading {
+            self.spinner_frame = (self.spinner_frame + 1) % SPINNER.len();
+        }
+    }
+
+    pub fn spinner(&self) -> char {
+        SPINNER[self.spinner_frame]
+    }
+
     pub fn set_error(&mut self, error: Option<String>) {
         self.error = error;
     }
 
+    pub fn set_health_items(&mut self, items: Vec<HealthItem>) {
+        self.health_items = items;
+    }
+
+    pub fn toggle_health_check_on_connect(&mut self) {
+        self.health_check_on_connect = !self.health_check_on_connect;
+    }
+
+    pub fn set_scan_warning(&mut self, warning: Option<ScanWarning>) {
+        if warning.is_none() {
+            self.show_scan_warning = false;
+        }
+        self.scan_warning = warning;
+    }
+
+    pub fn toggle_scan_warning(&mut self) {
+        self.show_scan_warning = self.scan_warning.is_some() && !self.show_scan_warning;
+    }
+
+    pub fn set_message(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+
+    pub fn open_prompt(&mut self, kind: PromptKind, title: &str, default: &str) {
+        self.prompt = Some(Prompt::new(kind, title, default));
+    }
+
+    /// Asks before a drop or deletion; with `confirm_delete` off `answer`
+    /// is filled in, so Enter alone goes ahead.
+    pub fn open_delete_prompt(&mut self, kind: PromptKind, title: &str, answer: &str) {
+        let default = if self.confirm_delete { "" } else { answer };
+        self.open_prompt(kind, title, default);
+    }
+
+    pub fn take_prompt(&mut self) -> Option<Prompt> {
+        self.prompt.take()
+    }
+
+    pub fn push_prompt_char(&mut self, c: char) {
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.input.push(c);
+        }
+    }
+
+    pub fn pop_prompt_char(&mut self) {
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.input.pop();
+        }
+    }
+
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
 
+    #[allow(dead_code)]
     pub fn is_connected(&self) -> bool {
         self.connection.is_some()
     }
@@ -128,6 +894,13 @@ impl AppState {
         }
     }
 
+    /// Selects the collection called `name`, if the list has it.
+    pub fn select_collection(&mut self, name: &str) {
+        if let Some(index) = self.collections.iter().position(|c| c.name == name) {
+            self.selected_coll_index = index;
+        }
+    }
+
     pub fn select_prev_coll(&mut self) {
         if !self.collections.is_empty() {
             if self.selected_coll_index == 0 {
@@ -176,14 +949,101 @@ impl AppState {
         }
     }
 
+    /// Switches between readable values and canonical extended JSON.
+    pub fn toggle_canonical(&mut self) {
+        self.value_format.canonical = !self.value_format.canonical;
+    }
+
+    /// Gives the keys to the content pane, with its cursor on the top line
+    /// in view.
+    pub fn focus_content(&mut self) {
+        self.content_focus = true;
+        self.content_cursor = self.doc_scroll_offset;
+    }
+
+    /// Moves the content cursor to `line`, scrolling so it stays within the
+    /// `height` lines in view.
+    pub fn move_content_cursor(&mut self, line: usize, height: usize) {
+        self.content_cursor = line;
+        if line < self.doc_scroll_offset {
+            self.doc_scroll_offset = line;
+        } else if line >= self.doc_scroll_offset + height.max(1) {
+            self.doc_scroll_offset = line + 1 - height.max(1);
+        }
+    }
+
+    // folds are remembered by namespace and _id, so they survive paging
+    // and reloads; documents without an _id are told apart by position
+    fn fold_key(&self) -> Option<String> {
+        let doc = self.get_selected_document()?;
+        let id = match doc.get("_id") {
+            Some(id) => id.clone().into_relaxed_extjson().to_string(),
+            None => format!("#{}", self.selected_doc_index),
+        };
+        Some(format!(
+            "{}.{}:{}",
+            self.current_database.as_deref().unwrap_or(""),
+            self.current_collection.as_deref().unwrap_or(""),
+            id
+        ))
+    }
+
+    /// The folded paths of the selected document.
+    pub fn selected_folds(&self) -> &BTreeSet<String> {
+        const NONE: &BTreeSet<String> = &BTreeSet::new();
+        self.fold_key()
+            .and_then(|key| self.folds.get(&key))
+            .unwrap_or(NONE)
+    }
+
+    /// Folds or unfolds `path` of the selected document; `None` toggles.
+    pub fn set_fold(&mut self, path: &str, fold: Option<bool>) {
+        let Some(key) = self.fold_key() else {
+            return;
+        };
+        let folds = self.folds.entry(key).or_default();
+        if fold.unwrap_or(!folds.contains(path)) {
+            folds.insert(path.to_string());
+        } else {
+            folds.remove(path);
+        }
+    }
+
+    /// Replaces the folded paths of the selected document.
+    pub fn set_folds(&mut self, paths: BTreeSet<String>) {
+        if let Some(key) = self.fold_key() {
+            self.folds.insert(key, paths);
+        }
+    }
+
+    /// Hands the keys back to the document list, dropping the search.
+    pub fn unfocus_content(&mut self) {
+        self.content_focus = false;
+        self.fold_pending = false;
+        self.exit_content_search();
+        self.content_search.clear();
+    }
+
+    pub fn enter_content_search(&mut self) {
+        self.content_search_mode = true;
+        self.content_search.clear();
+        self.content_match = 0;
+    }
+
+    pub fn exit_content_search(&mut self) {
+        self.content_search_mode = false;
+    }
+
     pub fn get_selected_document(&self) -> Option<&mongodb::bson::Document> {
         self.documents.get(self.selected_doc_index)
     }
 
+    #[allow(dead_code)]
     pub fn enter_input_mode(&mut self) {
         self.input_mode = true;
     }
 
+    #[allow(dead_code)]
     pub fn exit_input_mode(&mut self) {
         self.input_mode = false;
     }
@@ -192,14 +1052,6 @@ impl AppState {
         self.connection_input.clear();
     }
 
-    pub fn push_char(&mut self, c: char) {
-        self.connection_input.push(c);
-    }
-
-    pub fn pop_char(&mut self) {
-        self.connection_input.pop();
-    }
-
     pub fn enter_filter_mode(&mut self) {
         self.filter_mode = true;
     }
@@ -208,41 +1060,139 @@ impl AppState {
         self.filter_mode = false;
     }
 
+    /// Drops the query; the collection's default sort, if any, stays.
     pub fn clear_filter(&mut self) {
         self.filter_input.clear();
         self.filter = None;
-    }
-
-    pub fn push_filter_char(&mut self, c: char) {
-        self.filter_input.push(c);
-    }
-
-    pub fn pop_filter_char(&mut self) {
-        self.filter_input.pop();
+        self.sort = self.newest_first.clone();
+        self.projection = None;
+        self.query_template = None;
+        self.clear_geo();
+        self.set_scan_warning(None);
     }
 
     pub fn apply_filter(&mut self) -> Result<(), String> {
         let input = if !self.query_input.is_empty() {
-            &self.query_input
+            self.query_input.as_str().to_string()
         } else {
-            &self.filter_input
+            self.filter_input.as_str().to_string()
         };
+        self.apply_query_text(&input)
+    }
+
+    /// Sets the filter, sort and projection from a query in shell syntax,
+    /// either a plain filter or the combined `{filter, sort, projection}`.
+    /// A query without a sort keeps the collection's default one.
+    pub fn apply_query_text(&mut self, input: &str) -> Result<(), String> {
+        self.query_template = None;
+        let query = shell_syntax::parse_query(input)?;
+        self.filter = query.filter;
+        self.sort = query.sort.or_else(|| self.newest_first.clone());
+        self.projection = query.projection;
+        Ok(())
+    }
+
+    /// The query's `find` from its first document, with the active filter,
+    /// sort and projection.
+    pub fn query_params(&self) -> Option<QueryParams> {
+        Some(QueryParams {
+            database: self.current_database.clone()?,
+            collection: self.current_collection.clone()?,
+            filter: self.filter.clone(),
+            skip: 0,
+            limit: self.page_size as i64,
+            sort: self.sort.clone(),
+            projection: self.projection.clone(),
+        })
+    }
+
+    /// The `find` for the page starting at `cursor`.
+    pub fn page_params(&self, cursor: &PageCursor) -> Option<QueryParams> {
+        let mut params = self.query_params()?;
+        match cursor {
+            PageCursor::Start => {}
+            PageCursor::After(values) => {
+                params.filter = Some(paging::after_filter(
+                    params.filter.take(),
+                    self.sort.as_ref(),
+                    values,
+                ));
+            }
+            PageCursor::Skip(skip) => params.skip = *skip,
+        }
+        params.limit = self.page_limit as i64;
+        Some(params)
+    }
 
-        if input.is_empty() {
-            self.filter = None;
-            return Ok(());
+    /// Where the current page starts.
+    pub fn page_cursor(&self) -> PageCursor {
+        match self.current_page {
+            0 => PageCursor::Start,
+            page => self
+                .page_cursors
+                .get(page - 1)
+                .cloned()
+                .unwrap_or(PageCursor::Start),
         }
+    }
 
-        match serde_json::from_str::<serde_json::Value>(input) {
-            Ok(json_value) => match mongodb::bson::to_document(&json_value) {
-                Ok(doc) => {
-                    self.filter = Some(doc);
-                    Ok(())
-                }
-                Err(e) => Err(format!("Invalid filter: {}", e)),
-            },
-            Err(e) => Err(format!("Invalid JSON: {}", e)),
+    /// Where the next page starts, read off the last document shown.
+    pub fn next_page_cursor(&self) -> Result<PageCursor, String> {
+        if !self.filter_input.is_empty() {
+            return Err("Pages follow the JSON query; clear the live filter first".to_string());
+        }
+        match self.documents.last() {
+            Some(last) if self.documents.len() >= self.page_limit => {
+                let skip = self.page_start() + self.documents.len() as u64;
+                Ok(paging::cursor_after(
+                    last,
+                    self.filter.as_ref(),
+                    self.sort.as_ref(),
+                    skip,
+                ))
+            }
+            _ => Err("This is the last page".to_string()),
+        }
+    }
+
+    /// Where the previous page starts.
+    pub fn prev_page_cursor(&self) -> Result<PageCursor, String> {
+        match self.current_page {
+            0 => Err("This is the first page".to_string()),
+            1 => Ok(PageCursor::Start),
+            page => Ok(self
+                .page_cursors
+                .get(page - 2)
+                .cloned()
+                .unwrap_or(PageCursor::Start)),
+        }
+    }
+
+    // how many documents come before the current page
+    fn page_start(&self) -> u64 {
+        self.page_starts
+            .get(self.current_page)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Shows `documents` as page `page`, which starts at `cursor`.
+    pub fn enter_page(&mut self, page: usize, cursor: PageCursor, documents: Vec<Document>) {
+        let start = if page == self.current_page + 1 {
+            self.page_start() + self.documents.len() as u64
+        } else {
+            self.page_starts.get(page).copied().unwrap_or_default()
+        };
+        self.page_starts.truncate(page);
+        self.page_starts.push(start);
+        self.page_cursors.truncate(page.saturating_sub(1));
+        if page > 0 {
+            self.page_cursors.push(cursor);
         }
+        self.current_page = page;
+        self.show_page(documents);
+        self.selected_doc_index = 0;
+        self.doc_scroll_offset = 0;
     }
 
     pub fn enter_query_mode(&mut self) {
@@ -253,24 +1203,208 @@ impl AppState {
         self.query_mode = false;
     }
 
-    pub fn push_every_char(&mut self, c: char) {
-        self.query_input.push(c);
+    pub fn clear_query(&mut self) {
+        self.query_input.clear();
     }
 
-    pub fn pop_every_char(&mut self) {
-        self.query_input.pop();
+    /// Earlier queries on the open collection, newest first.
+    pub fn namespace_query_history(&self) -> &[QueryHistoryEntry] {
+        match self.current_namespace() {
+            Some(namespace) => self.query_history.entries(&namespace),
+            None => &[],
+        }
     }
 
-    pub fn clear_query(&mut self) {
-        self.query_input.clear();
+    /// Remembers a query that ran on the open collection.
+    pub fn record_query(&mut self, query: &str) {
+        if let Some(namespace) = self.current_namespace()
+            && !query.trim().is_empty()
+        {
+            self.query_history.add(&namespace, query.trim());
+        }
+    }
+
+    pub fn open_query_history(&mut self) {
+        if self.namespace_query_history().is_empty() {
+            self.set_error(Some("No queries run on this collection yet".to_string()));
+            return;
+        }
+        self.show_query_history = true;
+        self.selected_query_history = 0;
+    }
+
+    pub fn close_query_history(&mut self) {
+        self.show_query_history = false;
+    }
+
+    pub fn select_next_query_history(&mut self) {
+        let count = self.namespace_query_history().len();
+        if count > 0 {
+            self.selected_query_history = (self.selected_query_history + 1) % count;
+        }
+    }
+
+    pub fn select_prev_query_history(&mut self) {
+        let count = self.namespace_query_history().len();
+        if count > 0 {
+            self.selected_query_history = (self.selected_query_history + count - 1) % count;
+        }
+    }
+
+    /// Puts the selected earlier query into the query input.
+    pub fn recall_query(&mut self) {
+        if let Some(entry) = self
+            .namespace_query_history()
+            .get(self.selected_query_history)
+        {
+            self.query_input.set(entry.query.clone());
+        }
+        self.show_query_history = false;
+    }
+
+    /// The active filter, sort and projection in the combined form `/`
+    /// accepts, or `None` when no query is active.
+    pub fn current_query_json(&self) -> Option<String> {
+        if self.filter.is_none() && self.sort.is_none() && self.projection.is_none() {
+            return None;
+        }
+        let mut query = Document::new();
+        for (key, part) in [
+            ("filter", &self.filter),
+            ("sort", &self.sort),
+            ("projection", &self.projection),
+        ] {
+            if let Some(part) = part {
+                query.insert(key, part.clone());
+            }
+        }
+        Some(Bson::Document(query).into_relaxed_extjson().to_string())
+    }
+
+    /// Starts asking for the values of a query's placeholders, one prompt
+    /// each, offering the value each name had last time.
+    pub fn ask_query_parameters(&mut self, template: String, placeholders: Vec<Placeholder>) {
+        self.pending_template = Some(template);
+        self.pending_parameters = placeholders;
+        self.prompt_next_parameter();
+    }
+
+    fn prompt_next_parameter(&mut self) {
+        if let Some(placeholder) = self.pending_parameters.first() {
+            let title = format!(
+                "Value for {{{{{}}}}} ({})",
+                placeholder.name,
+                placeholder.kind.describe()
+            );
+            let default = self
+                .parameter_values
+                .get(&placeholder.name)
+                .cloned()
+                .unwrap_or_default();
+            self.open_prompt(PromptKind::QueryParameter, &title, &default);
+        }
+    }
+
+    /// Takes the value typed for the current placeholder. Once every one
+    /// has a value, returns the template and the query filled in from it.
+    pub fn answer_query_parameter(
+        &mut self,
+        value: &str,
+    ) -> Option<Result<(String, String), String>> {
+        if self.pending_parameters.is_empty() {
+            return None;
+        }
+        let placeholder = self.pending_parameters.remove(0);
+        self.parameter_values
+            .insert(placeholder.name, value.trim().to_string());
+        if !self.pending_parameters.is_empty() {
+            self.prompt_next_parameter();
+            return None;
+        }
+        let template = self.pending_template.take()?;
+        let filled = placeholders::fill(&template, |name| {
+            self.parameter_values.get(name).map(String::as_str)
+        });
+        Some(filled.map(|query| (template, query)))
+    }
+
+    pub fn namespace_saved_queries(&self) -> &[SavedQuery] {
+        match self.current_namespace() {
+            Some(namespace) => self.saved_queries.entries(&namespace),
+            None => &[],
+        }
+    }
+
+    /// Saves the active query under `name` for the open collection.
+    pub fn save_current_query(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("A saved query needs a name".to_string());
+        }
+        let namespace = self
+            .current_namespace()
+            .ok_or_else(|| "No collection open".to_string())?;
+        // a query run with placeholders is saved with them, not the values
+        let query = self
+            .query_template
+            .clone()
+            .or_else(|| self.current_query_json())
+            .ok_or_else(|| "No filter, sort or projection to save".to_string())?;
+        self.saved_queries.add(&namespace, name, query);
+        Ok(())
+    }
+
+    pub fn open_saved_queries(&mut self) {
+        if self.namespace_saved_queries().is_empty() {
+            self.set_error(Some(
+                "No saved queries for this collection; save one with S".to_string(),
+            ));
+            return;
+        }
+        self.show_saved_queries = true;
+        self.selected_saved_query = 0;
+    }
+
+    pub fn close_saved_queries(&mut self) {
+        self.show_saved_queries = false;
+    }
+
+    pub fn select_next_saved_query(&mut self) {
+        let count = self.namespace_saved_queries().len();
+        if count > 0 {
+            self.selected_saved_query = (self.selected_saved_query + 1) % count;
+        }
+    }
+
+    pub fn select_prev_saved_query(&mut self) {
+        let count = self.namespace_saved_queries().len();
+        if count > 0 {
+            self.selected_saved_query = (self.selected_saved_query + count - 1) % count;
+        }
     }
 
-    pub fn push_query_char(&mut self, c: char) {
-        self.query_input.push(c);
+    /// Puts the selected saved query into the query input.
+    pub fn use_saved_query(&mut self) {
+        if let Some(saved) = self
+            .namespace_saved_queries()
+            .get(self.selected_saved_query)
+        {
+            self.query_input.set(saved.query.clone());
+        }
+        self.show_saved_queries = false;
     }
 
-    pub fn pop_query_char(&mut self) {
-        self.query_input.pop();
+    pub fn delete_saved_query(&mut self) -> Option<SavedQuery> {
+        let namespace = self.current_namespace()?;
+        let removed = self
+            .saved_queries
+            .remove(&namespace, self.selected_saved_query)?;
+        let count = self.namespace_saved_queries().len();
+        self.selected_saved_query = self.selected_saved_query.min(count.saturating_sub(1));
+        if count == 0 {
+            self.show_saved_queries = false;
+        }
+        Some(removed)
     }
 
     pub fn set_connection_history(&mut self, history: Vec<String>) {
@@ -302,6 +1436,1540 @@ impl AppState {
             .get(self.selected_history_index)
             .cloned()
     }
+
+    pub fn set_profiles(&mut self, profiles: Vec<ConnectionProfile>) {
+        self.profiles = profiles;
+        self.selected_profile_index = 0;
+    }
+
+    pub fn toggle_profiles(&mut self) {
+        self.show_profiles = !self.show_profiles;
+        if self.show_profiles {
+            self.show_history = false;
+        }
+    }
+
+    pub fn select_next_profile(&mut self) {
+        if !self.profiles.is_empty() {
+            self.selected_profile_index = (self.selected_profile_index + 1) % self.profiles.len();
+        }
+    }
+
+    pub fn select_prev_profile(&mut self) {
+        if !self.profiles.is_empty() {
+            if self.selected_profile_index == 0 {
+                self.selected_profile_index = self.profiles.len() - 1;
+            } else {
+                self.selected_profile_index -= 1;
+            }
+        }
+    }
+
+    pub fn use_selected_profile(&mut self) {
+        if let Some(profile) = self.profiles.get(self.selected_profile_index).cloned() {
+            self.connection_input.set(profile.uri.clone());
+            self.active_profile = Some(profile);
+            self.show_profiles = false;
+        }
+    }
+
+    /// Takes over the defaults from the settings file. Profiles must be
+    /// loaded first, so a default connection can name one.
+    pub fn apply_settings(&mut self, settings: Settings) {
+        self.apply_preferences(&settings);
+        self.limits = settings.limits;
+        self.stage_snippets = settings.snippets;
+        self.dry_run = settings.dry_run;
+        self.default_sort = settings.default_sort;
+        self.bindings = Bindings::new(&settings.keys);
+        if let Some(problem) = self.bindings.problems.first()
+            && self.error.is_none()
+        {
+            let more = self.bindings.problems.len() - 1;
+            self.set_error(Some(if more > 0 {
+                format!("{} ({} more in the help, ?)", problem, more)
+            } else {
+                problem.clone()
+            }));
+        }
+        if let Some(connection) = settings.default_connection {
+            self.use_connection(&connection);
+        }
+    }
+
+    /// The settings `:set` changes that take effect while running; the
+    /// default connection only matters at startup.
+    pub fn apply_preferences(&mut self, settings: &Settings) {
+        if let Some(page_size) = settings.page_size {
+            self.set_page_size(page_size);
+        }
+        self.read_only = settings.read_only;
+        self.split_percent = settings.split_percent.map_or(DEFAULT_SPLIT_PERCENT, |n| {
+            n.clamp(MIN_SPLIT_PERCENT, MAX_SPLIT_PERCENT)
+        });
+        self.confirm_delete = settings.confirm_delete.unwrap_or(true);
+        let scheme = settings
+            .theme
+            .as_deref()
+            .and_then(ColorScheme::parse)
+            .unwrap_or_default();
+        let (theme, problems) = Theme::load(scheme, &settings.colors);
+        self.theme = theme;
+        if !problems.is_empty() {
+            self.set_error(Some(problems.join("; ")));
+        }
+        self.value_format.utc_offset = settings
+            .utc_offset
+            .as_deref()
+            .and_then(bson_ext::parse_utc_offset)
+            .unwrap_or(0);
+        self.value_format.date_format = settings
+            .date_format
+            .as_deref()
+            .and_then(DateFormat::parse)
+            .unwrap_or_default();
+    }
+
+    pub fn open_new_profile_form(&mut self) {
+        self.profile_form = Some(ProfileForm {
+            uri: self.connection_input.as_str().to_string(),
+            ..ProfileForm::default()
+        });
+    }
+
+    pub fn open_edit_profile_form(&mut self) {
+        if let Some(profile) = self.profiles.get(self.selected_profile_index) {
+            self.profile_form = Some(ProfileForm::edit(self.selected_profile_index, profile));
+        }
+    }
+
+    pub fn close_profile_form(&mut self) {
+        self.profile_form = None;
+    }
+
+    /// Writes the open form into the profile list; the form stays open on error.
+    pub fn save_profile_form(&mut self) -> Result<String, String> {
+        let Some(form) = &self.profile_form else {
+            return Ok(String::new());
+        };
+        let base = form.editing.and_then(|i| self.profiles.get(i).cloned());
+        let profile = form.to_profile(base, &self.profiles)?;
+        let name = profile.name.clone();
+        match form.editing {
+            Some(index) if index < self.profiles.len() => self.profiles[index] = profile,
+            _ => {
+                self.profiles.push(profile);
+                self.selected_profile_index = self.profiles.len() - 1;
+            }
+        }
+        self.profile_form = None;
+        Ok(name)
+    }
+
+    pub fn delete_selected_profile(&mut self) -> Option<ConnectionProfile> {
+        if self.selected_profile_index >= self.profiles.len() {
+            return None;
+        }
+        let profile = self.profiles.remove(self.selected_profile_index);
+        self.selected_profile_index = self
+            .selected_profile_index
+            .min(self.profiles.len().saturating_sub(1));
+        Some(profile)
+    }
+
+    /// Profiles and environment sets as they should be written back to disk.
+    pub fn profile_store(&self) -> ProfileStore {
+        ProfileStore {
+            profiles: self.profiles.clone(),
+            environment_sets: self.environment_sets.clone(),
+        }
+    }
+
+    /// The chosen profile, as long as its URI has not been edited away.
+    /// Fills in the profile named `connection`, or `connection` as a URI.
+    pub fn use_connection(&mut self, connection: &str) {
+        match self.profiles.iter().position(|p| p.name == connection) {
+            Some(index) => {
+                self.selected_profile_index = index;
+                self.use_selected_profile();
+            }
+            None => self.connection_input.set(connection),
+        }
+    }
+
+    pub fn profile_for_input(&self) -> Option<ConnectionProfile> {
+        self.active_profile
+            .clone()
+            .filter(|p| p.uri == self.connection_input.as_str())
+    }
+
+    pub fn set_atlas_projects(&mut self, projects: Vec<AtlasProject>) {
+        self.atlas_projects = projects;
+        self.atlas_clusters.clear();
+        self.atlas_project = None;
+        self.selected_atlas_index = 0;
+    }
+
+    pub fn set_atlas_clusters(&mut self, project: AtlasProject, clusters: Vec<AtlasCluster>) {
+        self.atlas_project = Some(project);
+        self.atlas_clusters = clusters;
+        self.selected_atlas_index = 0;
+    }
+
+    /// Goes back from a project's clusters to the project list.
+    pub fn leave_atlas_project(&mut self) {
+        if let Some(project) = self.atlas_project.take() {
+            self.atlas_clusters.clear();
+            self.selected_atlas_index = self
+                .atlas_projects
+                .iter()
+                .position(|p| p.id == project.id)
+                .unwrap_or(0);
+        }
+    }
+
+    fn atlas_item_count(&self) -> usize {
+        if self.atlas_project.is_some() {
+            self.atlas_clusters.len()
+        } else {
+            self.atlas_projects.len()
+        }
+    }
+
+    pub fn select_next_atlas(&mut self) {
+        let count = self.atlas_item_count();
+        if count > 0 {
+            self.selected_atlas_index = (self.selected_atlas_index + 1) % count;
+        }
+    }
+
+    pub fn select_prev_atlas(&mut self) {
+        let count = self.atlas_item_count();
+        if count > 0 {
+            if self.selected_atlas_index == 0 {
+                self.selected_atlas_index = count - 1;
+            } else {
+                self.selected_atlas_index -= 1;
+            }
+        }
+    }
+
+    pub fn get_selected_atlas_project(&self) -> Option<&AtlasProject> {
+        self.atlas_projects.get(self.selected_atlas_index)
+    }
+
+    /// Fills the connection input with the selected cluster's SRV address,
+    /// falling back to the seed list for clusters without one.
+    pub fn use_selected_atlas_cluster(&mut self) -> bool {
+        let uri = self
+            .atlas_clusters
+            .get(self.selected_atlas_index)
+            .and_then(|c| c.srv_uri.clone().or_else(|| c.standard_uri.clone()));
+        match uri {
+            Some(uri) => {
+                self.connection_input.set(uri);
+                self.active_profile = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_comparisons(&mut self, target: String, comparisons: Vec<CountComparison>) {
+        self.compare_target = target;
+        self.comparisons = comparisons;
+        self.selected_compare_index = 0;
+    }
+
+    pub fn visible_comparisons(&self) -> Vec<&CountComparison> {
+        self.comparisons
+            .iter()
+            .filter(|c| !self.compare_mismatches_only || !c.matches())
+            .collect()
+    }
+
+    pub fn toggle_compare_mismatches(&mut self) {
+        self.compare_mismatches_only = !self.compare_mismatches_only;
+        self.selected_compare_index = 0;
+    }
+
+    pub fn select_next_compare(&mut self) {
+        let count = self.visible_comparisons().len();
+        if count > 0 {
+            self.selected_compare_index = (self.selected_compare_index + 1) % count;
+        }
+    }
+
+    pub fn select_prev_compare(&mut self) {
+        let count = self.visible_comparisons().len();
+        if count > 0 {
+            if self.selected_compare_index == 0 {
+                self.selected_compare_index = count - 1;
+            } else {
+                self.selected_compare_index -= 1;
+            }
+        }
+    }
+
+    /// Replaces the operation list, keeping the selected operation selected
+    /// while it is still running.
+    pub fn set_operations(&mut self, operations: Vec<OperationInfo>) {
+        let selected = self
+            .visible_operations()
+            .get(self.selected_operation_index)
+            .map(|op| op.opid.clone());
+        self.operations = operations;
+        let visible = self.visible_operations();
+        self.selected_operation_index = selected
+            .and_then(|opid| visible.iter().position(|op| op.opid == opid))
+            .unwrap_or(self.selected_operation_index)
+            .min(visible.len().saturating_sub(1));
+    }
+
+    pub fn visible_operations(&self) -> Vec<&OperationInfo> {
+        self.operations
+            .iter()
+            .filter(|op| op.running.as_secs() >= self.operations_min_secs)
+            .collect()
+    }
+
+    pub fn get_selected_operation(&self) -> Option<&OperationInfo> {
+        self.visible_operations()
+            .get(self.selected_operation_index)
+            .copied()
+    }
+
+    pub fn set_operations_min_secs(&mut self, secs: u64) {
+        self.operations_min_secs = secs;
+        self.selected_operation_index = 0;
+    }
+
+    /// Replaces the users and roles, keeping the selections in range.
+    pub fn set_users(&mut self, db: String, users: Vec<UserInfo>, roles: Vec<RoleInfo>) {
+        if self.users_database.as_ref() != Some(&db) {
+            self.selected_user_index = 0;
+            self.selected_role_index = 0;
+        }
+        self.selected_user_index = self.selected_user_index.min(users.len().saturating_sub(1));
+        self.selected_role_index = self.selected_role_index.min(roles.len().saturating_sub(1));
+        self.users_database = Some(db);
+        self.users = users;
+        self.roles = roles;
+    }
+
+    pub fn get_selected_user(&self) -> Option<&UserInfo> {
+        self.users.get(self.selected_user_index)
+    }
+
+    pub fn get_selected_role(&self) -> Option<&RoleInfo> {
+        self.roles.get(self.selected_role_index)
+    }
+
+    pub fn set_reference_diff(&mut self, diff: ReferenceDiff) {
+        self.reference_diff = Some(diff);
+        self.selected_reference_index = 0;
+    }
+
+    fn reference_entry_count(&self) -> usize {
+        self.reference_diff.as_ref().map_or(0, |d| d.entries.len())
+    }
+
+    /// Parses a page size such as `50` or `20000!`, where the trailing `!` is
+    /// the explicit override for sizes above the configured cap.
+    pub fn parse_page_size(&self, input: &str) -> Result<usize, String> {
+        let input = input.trim();
+        let (number, force) = match input.strip_suffix('!') {
+            Some(number) => (number.trim(), true),
+            None => (input, false),
+        };
+        let requested = number
+            .parse::<usize>()
+            .map_err(|_| format!("Not a page size: {}", input))?;
+        self.limits.check_page_size(requested, force)
+    }
+
+    pub fn set_query_timings(&mut self, timings: Vec<QueryTiming>) {
+        self.query_timings = timings;
+        let groups = self.timing_groups().len();
+        if self.selected_timing_index >= groups {
+            self.selected_timing_index = groups.saturating_sub(1);
+        }
+    }
+
+    pub fn timing_groups(&self) -> Vec<TimingGroup> {
+        timings::group(&self.query_timings)
+    }
+
+    pub fn select_next_timing(&mut self) {
+        let count = self.timing_groups().len();
+        if count > 0 {
+            self.selected_timing_index = (self.selected_timing_index + 1) % count;
+        }
+    }
+
+    pub fn select_prev_timing(&mut self) {
+        let count = self.timing_groups().len();
+        if count > 0 {
+            self.selected_timing_index = (self.selected_timing_index + count - 1) % count;
+        }
+    }
+
+    pub fn set_tasks(&mut self, tasks: Vec<TaskInfo>) {
+        self.tasks = tasks;
+        if self.selected_task_index >= self.tasks.len() {
+            self.selected_task_index = self.tasks.len().saturating_sub(1);
+        }
+    }
+
+    /// Shows a global panel (tasks, event log), or returns to the screen it
+    /// was first opened from.
+    /// Whether keys are going into a text field, so `?` is typed rather than
+    /// opening help.
+    pub fn is_typing(&self) -> bool {
+        if self.export_dialog.is_some() || self.utilities.is_some() || self.finder.is_some() {
+            return true;
+        }
+        match self.current_screen {
+            Screen::Connection => {
+                self.profile_form.is_some() || !(self.show_profiles || self.show_history)
+            }
+            Screen::CollectionList => self.collection_form.is_some(),
+            Screen::DocumentView => {
+                self.filter_mode
+                    || self.query_mode
+                    || self.geo_mode
+                    || self.content_search_mode
+                    || self.query_builder.is_some()
+            }
+            Screen::Search | Screen::Import | Screen::BulkUpdate | Screen::Setup => true,
+            _ => false,
+        }
+    }
+
+    /// Opens the help overlay at the current screen's section.
+    pub fn open_help(&mut self) {
+        self.show_help = true;
+        self.help_scroll = keymap::section_line(&self.current_screen);
+    }
+
+    pub fn close_help(&mut self) {
+        self.show_help = false;
+    }
+
+    pub fn scroll_help_down(&mut self, lines: usize) {
+        self.help_scroll += lines;
+    }
+
+    pub fn scroll_help_up(&mut self, lines: usize) {
+        self.help_scroll = self.help_scroll.saturating_sub(lines);
+    }
+
+    /// Whether vim-style counts and motions apply: a list has focus and no
+    /// text field is taking keys.
+    pub fn accepts_motions(&self) -> bool {
+        if self.prompt.is_some() || self.show_switcher || self.show_help || self.finder.is_some() {
+            return false;
+        }
+        match self.current_screen {
+            Screen::Connection => {
+                self.profile_form.is_none() && (self.show_profiles || self.show_history)
+            }
+            Screen::CollectionList => {
+                self.collection_form.is_none() && self.collection_stats.is_none()
+            }
+            Screen::DocumentView => {
+                !self.filter_mode
+                    && !self.query_mode
+                    && !self.geo_mode
+                    && self.pending_edit.is_none()
+                    && self.projection_picker.is_none()
+                    && self.distinct_values.is_none()
+                    && self.query_builder.is_none()
+                    && !self.content_focus
+                    && self.plan_tree.is_none()
+            }
+            Screen::Users => self.role_picker.is_none(),
+            _ => self.list_cursor().is_some(),
+        }
+    }
+
+    /// Moves the focused list's selection; in the document view half-page
+    /// motions scroll the document instead.
+    pub fn apply_motion(&mut self, motion: Motion, half_page: usize) {
+        if self.current_screen == Screen::DocumentView
+            && matches!(motion, Motion::HalfPageDown | Motion::HalfPageUp)
+        {
+            self.doc_scroll_offset = if motion == Motion::HalfPageDown {
+                self.doc_scroll_offset + half_page
+            } else {
+                self.doc_scroll_offset.saturating_sub(half_page)
+            };
+            return;
+        }
+        if let Some((selected, len)) = self.list_cursor()
+            && len > 0
+        {
+            let target = motion.target(selected, len, half_page);
+            self.set_list_cursor(target);
+        }
+    }
+
+    /// Pulls the focused list's selection back inside the list, so a redraw
+    /// after a resize never highlights a row past its end.
+    pub fn clamp_cursors(&mut self) {
+        if let Some((selected, len)) = self.list_cursor()
+            && selected >= len
+        {
+            self.set_list_cursor(len.saturating_sub(1));
+        }
+    }
+
+    // selected index and length of the list the current screen navigates
+    fn list_cursor(&self) -> Option<(usize, usize)> {
+        match self.current_screen {
+            Screen::Connection if self.show_profiles => {
+                Some((self.selected_profile_index, self.profiles.len()))
+            }
+            Screen::Connection if self.show_history => {
+                Some((self.selected_history_index, self.connection_history.len()))
+            }
+            Screen::DatabaseList => Some((self.selected_db_index, self.databases.len())),
+            Screen::CollectionList => Some((self.selected_coll_index, self.collections.len())),
+            Screen::DocumentView => Some((self.selected_doc_index, self.documents.len())),
+            Screen::Aggregation => Some((self.selected_stage_index, self.pipeline.len())),
+            Screen::Tasks => Some((self.selected_task_index, self.tasks.len())),
+            Screen::Compare => Some((
+                self.selected_compare_index,
+                self.visible_comparisons().len(),
+            )),
+            Screen::ReferenceDiff => {
+                Some((self.selected_reference_index, self.reference_entry_count()))
+            }
+            Screen::Operations => Some((
+                self.selected_operation_index,
+                self.visible_operations().len(),
+            )),
+            Screen::Users => Some((self.selected_user_index, self.users.len())),
+            Screen::Roles => Some((self.selected_role_index, self.roles.len())),
+            Screen::Timings => Some((self.selected_timing_index, self.timing_groups().len())),
+            Screen::Schema => Some((
+                self.selected_schema_field,
+                self.schema.as_ref().map_or(0, |s| s.fields.len()),
+            )),
+            _ => None,
+        }
+    }
+
+    fn set_list_cursor(&mut self, index: usize) {
+        match self.current_screen {
+            Screen::Connection if self.show_profiles => self.selected_profile_index = index,
+            Screen::Connection => self.selected_history_index = index,
+            Screen::DatabaseList => self.selected_db_index = index,
+            Screen::CollectionList => self.selected_coll_index = index,
+            Screen::DocumentView => self.selected_doc_index = index,
+            Screen::Aggregation => self.selected_stage_index = index,
+            Screen::Tasks => self.selected_task_index = index,
+            Screen::Compare => self.selected_compare_index = index,
+            Screen::ReferenceDiff => self.selected_reference_index = index,
+            Screen::Operations => self.selected_operation_index = index,
+            Screen::Users => self.selected_user_index = index,
+            Screen::Roles => self.selected_role_index = index,
+            Screen::Timings => self.selected_timing_index = index,
+            Screen::Schema => self.selected_schema_field = index,
+            _ => {}
+        }
+    }
+
+    pub fn toggle_panel(&mut self, panel: Screen) {
+        if self.current_screen == panel {
+            self.current_screen = self.panel_return_screen.clone();
+        } else {
+            if !matches!(
+                self.current_screen,
+                Screen::Tasks | Screen::EventLog | Screen::Timings
+            ) {
+                self.panel_return_screen = self.current_screen.clone();
+            }
+            self.current_screen = panel;
+        }
+    }
+
+    pub fn set_replica_set(&mut self, status: ReplicaSetStatus, elections: Vec<ElectionEvent>) {
+        self.replica_set = Some(status);
+        self.elections = elections;
+    }
+
+    /// Starts the search builder for the open collection, keeping the last
+    /// query when the index list is unchanged.
+    /// Opens the import screen, keeping the last path and mode.
+    pub fn open_import(&mut self) {
+        self.import_form.completions.clear();
+        self.set_screen(Screen::Import);
+    }
+
+    /// Opens the bulk update screen with the applied filter filled in.
+    pub fn open_bulk_update(&mut self) {
+        self.bulk_update = BulkUpdateForm::with_filter(self.filter.as_ref());
+        self.set_screen(Screen::BulkUpdate);
+    }
+
+    pub fn set_bulk_update_preview(&mut self, preview: BulkUpdatePreview) {
+        self.bulk_update.preview = Some(preview);
+        self.bulk_update.scroll = 0;
+    }
+
+    pub fn open_search(&mut self, indexes: Vec<String>) {
+        if self.search_form.indexes != indexes {
+            self.search_form = SearchForm {
+                indexes,
+                ..SearchForm::default()
+            };
+        }
+        self.search_hits.clear();
+        self.selected_hit_index = 0;
+        self.search_scroll = 0;
+    }
+
+    pub fn set_search_hits(&mut self, hits: Vec<SearchHit>) {
+        self.search_hits = hits;
+        self.selected_hit_index = 0;
+        self.search_scroll = 0;
+    }
+
+    pub fn select_next_hit(&mut self) {
+        if !self.search_hits.is_empty() {
+            self.selected_hit_index = (self.selected_hit_index + 1) % self.search_hits.len();
+            self.search_scroll = 0;
+        }
+    }
+
+    pub fn select_prev_hit(&mut self) {
+        if !self.search_hits.is_empty() {
+            if self.selected_hit_index == 0 {
+                self.selected_hit_index = self.search_hits.len() - 1;
+            } else {
+                self.selected_hit_index -= 1;
+            }
+            self.search_scroll = 0;
+        }
+    }
+
+    pub fn get_selected_hit(&self) -> Option<&SearchHit> {
+        self.search_hits.get(self.selected_hit_index)
+    }
+
+    pub fn scroll_search_down(&mut self) {
+        self.search_scroll += 10;
+    }
+
+    pub fn scroll_search_up(&mut self) {
+        self.search_scroll = self.search_scroll.saturating_sub(10);
+    }
+
+    /// Opens the pipeline editor, starting a fresh pipeline when the
+    /// collection changed since it was last used.
+    pub fn open_aggregation(&mut self, namespace: String) {
+        if self.pipeline_namespace.as_ref() != Some(&namespace) {
+            self.pipeline = vec![PipelineStage::new("$match")];
+            self.pipeline_namespace = Some(namespace);
+            self.selected_stage_index = 0;
+            self.preview_stage = None;
+            self.aggregation_preview.clear();
+        }
+        self.current_screen = Screen::Aggregation;
+    }
+
+    /// Inserts a stage after the selected one and selects it.
+    /// Replaces the whole pipeline, e.g. with an imported one.
+    pub fn set_pipeline(&mut self, stages: Vec<PipelineStage>) {
+        self.pipeline = stages;
+        self.selected_stage_index = 0;
+        self.preview_stage = None;
+        self.aggregation_preview.clear();
+    }
+
+    pub fn add_stage(&mut self, stage: PipelineStage) {
+        let index = if self.pipeline.is_empty() {
+            0
+        } else {
+            self.selected_stage_index + 1
+        };
+        self.pipeline.insert(index, stage);
+        self.selected_stage_index = index;
+    }
+
+    pub fn remove_selected_stage(&mut self) {
+        if self.selected_stage_index < self.pipeline.len() {
+            self.pipeline.remove(self.selected_stage_index);
+            if self.selected_stage_index >= self.pipeline.len() {
+                self.selected_stage_index = self.pipeline.len().saturating_sub(1);
+            }
+            self.preview_stage = None;
+        }
+    }
+
+    pub fn toggle_selected_stage(&mut self) {
+        if let Some(stage) = self.pipeline.get_mut(self.selected_stage_index) {
+            stage.enabled = !stage.enabled;
+        }
+    }
+
+    /// Swaps the selected stage with its neighbour, keeping it selected.
+    pub fn move_selected_stage(&mut self, down: bool) {
+        let index = self.selected_stage_index;
+        let target = if down {
+            index + 1
+        } else {
+            index.wrapping_sub(1)
+        };
+        if target < self.pipeline.len() {
+            self.pipeline.swap(index, target);
+            self.selected_stage_index = target;
+        }
+    }
+
+    pub fn select_next_stage(&mut self) {
+        if !self.pipeline.is_empty() {
+            self.selected_stage_index = (self.selected_stage_index + 1) % self.pipeline.len();
+        }
+    }
+
+    pub fn select_prev_stage(&mut self) {
+        if !self.pipeline.is_empty() {
+            if self.selected_stage_index == 0 {
+                self.selected_stage_index = self.pipeline.len() - 1;
+            } else {
+                self.selected_stage_index -= 1;
+            }
+        }
+    }
+
+    pub fn get_selected_stage(&self) -> Option<&PipelineStage> {
+        self.pipeline.get(self.selected_stage_index)
+    }
+
+    pub fn set_selected_stage_body(&mut self, body: String) {
+        if let Some(stage) = self.pipeline.get_mut(self.selected_stage_index) {
+            stage.body = body;
+        }
+    }
+
+    pub fn set_aggregation_preview(&mut self, upto: usize, documents: Vec<Document>) {
+        self.preview_stage = Some(upto);
+        self.aggregation_preview = documents;
+        self.aggregation_scroll = 0;
+    }
+
+    /// Whether the selected stage's body is open in the prompt.
+    pub fn is_editing_stage(&self) -> bool {
+        self.prompt
+            .as_ref()
+            .is_some_and(|p| p.kind == PromptKind::EditStage)
+    }
+
+    /// The pipeline through the selected stage with `body` in its place,
+    /// run even if the stage is disabled.
+    pub fn draft_pipeline(&self, body: &str) -> Result<Vec<Document>, String> {
+        let mut stages = self.pipeline.clone();
+        if let Some(stage) = stages.get_mut(self.selected_stage_index) {
+            stage.body = body.to_string();
+            stage.enabled = true;
+        }
+        aggregation::build_pipeline(&stages, self.selected_stage_index)
+    }
+
+    pub fn scroll_aggregation_down(&mut self) {
+        self.aggregation_scroll += 10;
+    }
+
+    pub fn scroll_aggregation_up(&mut self) {
+        self.aggregation_scroll = self.aggregation_scroll.saturating_sub(10);
+    }
+
+    pub fn review_edit(&mut self, edit: PendingEdit) {
+        self.pending_edit = Some(edit);
+    }
+
+    pub fn take_pending_edit(&mut self) -> Option<PendingEdit> {
+        self.pending_edit.take()
+    }
+
+    pub fn set_sharding(&mut self, status: ShardingStatus) {
+        if self.selected_chunk_index >= status.chunks.len() {
+            self.selected_chunk_index = status.chunks.len().saturating_sub(1);
+        }
+        if self.selected_zone_index >= status.zones.len() {
+            self.selected_zone_index = status.zones.len().saturating_sub(1);
+        }
+        self.sharding = Some(status);
+    }
+
+    pub fn toggle_sharding_focus(&mut self) {
+        self.sharding_focus_zones = !self.sharding_focus_zones;
+    }
+
+    /// Moves through chunks or zone ranges, whichever list has focus.
+    pub fn select_next_sharding(&mut self) {
+        let (count, index) = self.sharding_selection();
+        if count > 0 {
+            *index = (*index + 1) % count;
+        }
+    }
+
+    pub fn select_prev_sharding(&mut self) {
+        let (count, index) = self.sharding_selection();
+        if count > 0 {
+            if *index == 0 {
+                *index = count - 1;
+            } else {
+                *index -= 1;
+            }
+        }
+    }
+
+    fn sharding_selection(&mut self) -> (usize, &mut usize) {
+        let status = self.sharding.as_ref();
+        if self.sharding_focus_zones {
+            (
+                status.map_or(0, |s| s.zones.len()),
+                &mut self.selected_zone_index,
+            )
+        } else {
+            (
+                status.map_or(0, |s| s.chunks.len()),
+                &mut self.selected_chunk_index,
+            )
+        }
+    }
+
+    pub fn get_selected_zone(&self) -> Option<&ZoneRange> {
+        self.sharding
+            .as_ref()
+            .and_then(|s| s.zones.get(self.selected_zone_index))
+    }
+
+    pub fn get_selected_chunk(&self) -> Option<&ChunkInfo> {
+        self.sharding
+            .as_ref()
+            .and_then(|s| s.chunks.get(self.selected_chunk_index))
+    }
+
+    pub fn set_event_log(&mut self, entries: Vec<EventLogEntry>) {
+        // keep the viewport anchored when scrolled back
+        if self.event_log_scroll > 0 {
+            self.event_log_scroll += entries.len().saturating_sub(self.event_log.len());
+        }
+        self.event_log = entries;
+        self.event_log_scroll = self
+            .event_log_scroll
+            .min(self.event_log.len().saturating_sub(1));
+    }
+
+    pub fn scroll_event_log_up(&mut self) {
+        if self.event_log_scroll + 1 < self.event_log.len() {
+            self.event_log_scroll += 1;
+        }
+    }
+
+    pub fn scroll_event_log_down(&mut self) {
+        self.event_log_scroll = self.event_log_scroll.saturating_sub(1);
+    }
+
+    pub fn select_next_task(&mut self) {
+        if !self.tasks.is_empty() {
+            self.selected_task_index = (self.selected_task_index + 1) % self.tasks.len();
+        }
+    }
+
+    pub fn select_prev_task(&mut self) {
+        if !self.tasks.is_empty() {
+            if self.selected_task_index == 0 {
+                self.selected_task_index = self.tasks.len() - 1;
+            } else {
+                self.selected_task_index -= 1;
+            }
+        }
+    }
+
+    pub fn get_selected_task(&self) -> Option<&TaskInfo> {
+        self.tasks.get(self.selected_task_index)
+    }
+
+    pub fn set_privileges(&mut self, privileges: Option<UserPrivileges>) {
+        self.privileges = privileges;
+    }
+
+    pub fn open_permissions(&mut self, checks: Vec<PermissionCheck>) {
+        self.permission_checks = checks;
+        self.show_permissions = true;
+    }
+
+    pub fn close_permissions(&mut self) {
+        self.show_permissions = false;
+    }
+
+    pub fn open_collection_form(&mut self) {
+        self.collection_form = Some(CollectionForm::default());
+    }
+
+    pub fn close_collection_form(&mut self) {
+        self.collection_form = None;
+    }
+
+    pub fn open_export_dialog(&mut self, dialog: ExportDialog) {
+        self.export_dialog = Some(dialog);
+    }
+
+    pub fn close_export_dialog(&mut self) {
+        self.export_dialog = None;
+    }
+
+    pub fn toggle_dry_run(&mut self) {
+        self.dry_run = !self.dry_run;
+        self.set_message(Some(if self.dry_run {
+            "Dry run on: writes are shown, not sent [F6] Off".to_string()
+        } else {
+            "Dry run off: writes are sent again".to_string()
+        }));
+    }
+
+    pub fn toggle_utilities(&mut self) {
+        self.utilities = match self.utilities {
+            Some(_) => None,
+            None => Some(UtilityPanel::new()),
+        };
+    }
+
+    /// Opens the fuzzy finder over the listed databases; their collections
+    /// follow with [`NamespaceFinder::set_namespaces`].
+    pub fn open_finder(&mut self) {
+        let databases = self.databases.iter().map(|db| db.name.clone()).collect();
+        self.finder = Some(NamespaceFinder::new(databases));
+    }
+
+    pub fn close_finder(&mut self) {
+        self.finder = None;
+    }
+
+    pub fn open_projection_picker(&mut self, fields: Vec<String>) {
+        self.projection_picker = Some(ProjectionPicker::new(fields, self.projection.as_ref()));
+    }
+
+    pub fn open_plan_tree(&mut self, tree: PlanTree) {
+        self.plan_tree = Some(tree);
+    }
+
+    pub fn close_plan_tree(&mut self) {
+        self.plan_tree = None;
+    }
+
+    pub fn close_projection_picker(&mut self) {
+        self.projection_picker = None;
+    }
+
+    pub fn open_query_builder(&mut self, fields: Vec<String>) {
+        self.query_builder = Some(QueryBuilder::new(fields));
+    }
+
+    pub fn close_query_builder(&mut self) {
+        self.query_builder = None;
+    }
+
+    /// Opens the connection switcher; entry 0 is the active connection,
+    /// followed by `labels` for the parked ones and a final "new connection".
+    pub fn open_switcher(&mut self, labels: Vec<String>) {
+        self.session_labels = labels;
+        self.selected_session_index = 0;
+        self.show_switcher = true;
+    }
+
+    pub fn session_label(&self) -> String {
+        session::label(
+            self.active_profile.as_ref(),
+            self.connection.as_ref(),
+            self.current_database.as_deref(),
+            self.current_collection.as_deref(),
+        )
+    }
+
+    pub fn close_switcher(&mut self) {
+        self.show_switcher = false;
+    }
+
+    pub fn select_next_session(&mut self) {
+        self.selected_session_index = (self.selected_session_index + 1) % self.switcher_len();
+    }
+
+    pub fn select_prev_session(&mut self) {
+        self.selected_session_index = self
+            .selected_session_index
+            .checked_sub(1)
+            .unwrap_or(self.switcher_len() - 1);
+    }
+
+    fn switcher_len(&self) -> usize {
+        self.session_labels.len() + 2
+    }
+
+    /// Which parked session the selected switcher entry refers to.
+    pub fn selected_parked_session(&self) -> Option<usize> {
+        let index = self.selected_session_index.checked_sub(1)?;
+        (index < self.session_labels.len()).then_some(index)
+    }
+
+    pub fn is_new_session_selected(&self) -> bool {
+        self.selected_session_index == self.switcher_len() - 1
+    }
+
+    /// Moves the active connection's navigation state out, leaving the
+    /// connection screen ready for another URI.
+    pub fn take_session_view(&mut self) -> SessionView {
+        let screen = self.resumable_screen();
+        let view = SessionView {
+            connection: self.connection.take(),
+            active_profile: self.active_profile.take(),
+            privileges: self.privileges.take(),
+            screen,
+            databases: std::mem::take(&mut self.databases),
+            selected_db_index: std::mem::take(&mut self.selected_db_index),
+            view: self.take_collection_view(),
+            tabs: std::mem::take(&mut self.tabs),
+            active_tab: std::mem::take(&mut self.active_tab),
+        };
+        self.current_screen = Screen::Connection;
+        view
+    }
+
+    /// A copy of the open collection's view.
+    fn collection_view(&self) -> CollectionView {
+        CollectionView {
+            current_database: self.current_database.clone(),
+            current_collection: self.current_collection.clone(),
+            collections: self.collections.clone(),
+            selected_coll_index: self.selected_coll_index,
+            documents: self.documents.clone(),
+            selected_doc_index: self.selected_doc_index,
+            doc_scroll_offset: self.doc_scroll_offset,
+            filter: self.filter.clone(),
+            sort: self.sort.clone(),
+            newest_first: self.newest_first.clone(),
+            projection: self.projection.clone(),
+            query_template: self.query_template.clone(),
+            filter_input: self.filter_input.clone(),
+            query_input: self.query_input.clone(),
+            current_page: self.current_page,
+            page_cursors: self.page_cursors.clone(),
+            page_starts: self.page_starts.clone(),
+            page_limit: self.page_limit,
+        }
+    }
+
+    /// Moves the open collection's view out, leaving no collection open.
+    fn take_collection_view(&mut self) -> CollectionView {
+        let view = CollectionView {
+            current_database: self.current_database.take(),
+            current_collection: self.current_collection.take(),
+            collections: std::mem::take(&mut self.collections),
+            selected_coll_index: std::mem::take(&mut self.selected_coll_index),
+            documents: std::mem::take(&mut self.documents),
+            selected_doc_index: std::mem::take(&mut self.selected_doc_index),
+            doc_scroll_offset: std::mem::take(&mut self.doc_scroll_offset),
+            filter: self.filter.take(),
+            sort: self.sort.take(),
+            newest_first: self.newest_first.take(),
+            projection: self.projection.take(),
+            query_template: self.query_template.take(),
+            filter_input: std::mem::take(&mut self.filter_input),
+            query_input: std::mem::take(&mut self.query_input),
+            current_page: self.current_page,
+            page_cursors: std::mem::take(&mut self.page_cursors),
+            page_starts: std::mem::take(&mut self.page_starts),
+            page_limit: self.page_limit,
+        };
+        self.clear_filter();
+        self.set_documents(Vec::new());
+        view
+    }
+
+    fn restore_collection_view(&mut self, view: CollectionView) {
+        self.clear_filter();
+        self.unfocus_content();
+        self.current_database = view.current_database;
+        self.current_collection = view.current_collection;
+        self.collections = view.collections;
+        self.selected_coll_index = view.selected_coll_index;
+        self.set_documents(view.documents);
+        self.current_page = view.current_page;
+        self.page_cursors = view.page_cursors;
+        // a new tab's view has visited no pages and keeps the fresh ones
+        if !view.page_starts.is_empty() {
+            self.page_starts = view.page_starts;
+            self.page_limit = view.page_limit;
+        }
+        self.selected_doc_index = view.selected_doc_index;
+        self.doc_scroll_offset = view.doc_scroll_offset;
+        self.filter = view.filter;
+        self.query_template = view.query_template;
+        self.sort = view.sort;
+        self.newest_first = view.newest_first;
+        self.projection = view.projection;
+        self.filter_input = view.filter_input;
+        self.query_input = view.query_input;
+    }
+
+    // screens holding data for one-off views fall back to where they were opened from
+    fn resumable_screen(&self) -> Screen {
+        match self.current_screen {
+            Screen::Connection
+            | Screen::DatabaseList
+            | Screen::CollectionList
+            | Screen::DocumentView => self.current_screen.clone(),
+            _ if self.current_collection.is_some() => Screen::DocumentView,
+            _ if self.current_database.is_some() => Screen::CollectionList,
+            _ if self.connection.is_some() => Screen::DatabaseList,
+            _ => Screen::Connection,
+        }
+    }
+
+    /// A copy of the active connection's navigation state, for a duplicate
+    /// that starts out where this one is.
+    pub fn session_view(&self) -> SessionView {
+        SessionView {
+            connection: self.connection.clone(),
+            active_profile: self.active_profile.clone(),
+            privileges: self.privileges.clone(),
+            screen: self.resumable_screen(),
+            databases: self.databases.clone(),
+            selected_db_index: self.selected_db_index,
+            view: self.collection_view(),
+            tabs: self.tabs.clone(),
+            active_tab: self.active_tab,
+        }
+    }
+
+    pub fn restore_session_view(&mut self, view: SessionView) {
+        self.clear_database_totals();
+        self.connection = view.connection;
+        self.active_profile = view.active_profile;
+        self.privileges = view.privileges;
+        self.current_screen = view.screen;
+        self.databases = view.databases;
+        self.selected_db_index = view.selected_db_index;
+        self.restore_collection_view(view.view);
+        self.tabs = view.tabs;
+        self.active_tab = view.active_tab;
+    }
+
+    /// Labels of the open tabs, in order; empty with a single collection.
+    pub fn tab_labels(&self) -> Vec<String> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                if index == self.active_tab {
+                    session::tab_label(
+                        self.current_database.as_deref(),
+                        self.current_collection.as_deref(),
+                    )
+                } else {
+                    tab.label()
+                }
+            })
+            .collect()
+    }
+
+    /// Keeps the open collection in its tab, so the collection opened next
+    /// goes to a tab of its own that starts from the same collection list.
+    /// Nothing happens while no collection is open.
+    pub fn open_tab(&mut self) {
+        if self.current_collection.is_none() {
+            return;
+        }
+        if self.tabs.is_empty() {
+            self.tabs.push(CollectionView::default());
+            self.active_tab = 0;
+        }
+        self.tabs[self.active_tab] = self.collection_view();
+        self.tabs.push(CollectionView::default());
+        self.active_tab = self.tabs.len() - 1;
+    }
+
+    /// Brings the tab at `index` to the front, parking the open one.
+    pub fn switch_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+        let parked = self.take_collection_view();
+        self.tabs[self.active_tab] = parked;
+        let view = std::mem::take(&mut self.tabs[index]);
+        self.active_tab = index;
+        self.restore_collection_view(view);
+        self.current_screen = Screen::DocumentView;
+    }
+
+    /// The tab `step` places after the open one, wrapping around; negative
+    /// steps go back.
+    pub fn cycle_tab(&mut self, step: isize) {
+        if self.tabs.len() > 1 {
+            let count = self.tabs.len() as isize;
+            let index = (self.active_tab as isize + step).rem_euclid(count);
+            self.switch_tab(index as usize);
+        }
+    }
+
+    /// Closes the open tab and brings its right neighbour, or the last
+    /// tab, to the front. Returns false when there is no other tab.
+    pub fn close_tab(&mut self) -> bool {
+        if self.tabs.len() < 2 {
+            return false;
+        }
+        self.tabs.remove(self.active_tab);
+        let index = self.active_tab.min(self.tabs.len() - 1);
+        let view = std::mem::take(&mut self.tabs[index]);
+        self.active_tab = index;
+        self.restore_collection_view(view);
+        self.current_screen = Screen::DocumentView;
+        if self.tabs.len() == 1 {
+            self.tabs.clear();
+            self.active_tab = 0;
+        }
+        true
+    }
+
+    pub fn set_shard_key_analysis(&mut self, analysis: ShardKeyAnalysis) {
+        self.shard_key_analysis = Some(analysis);
+    }
+
+    pub fn close_shard_key_analysis(&mut self) {
+        self.shard_key_analysis = None;
+    }
+
+    /// Shows statistics, keeping the scroll when they are for the same
+    /// collection, as after a refresh.
+    pub fn set_collection_stats(&mut self, stats: CollectionStats) {
+        if self.collection_stats.as_ref().map(|s| &s.namespace) != Some(&stats.namespace) {
+            self.collection_stats_scroll = 0;
+        }
+        self.collection_stats = Some(stats);
+    }
+
+    pub fn close_collection_stats(&mut self) {
+        self.collection_stats = None;
+    }
+
+    /// Takes a sample of the collections' counters, turning the change
+    /// since the previous sample of the same database into their heat.
+    pub fn record_op_counters(&mut self, db: String, counters: BTreeMap<String, OpCounters>) {
+        let now = Instant::now();
+        match &self.op_counters {
+            Some((previous_db, at, previous)) if *previous_db == db => {
+                let secs = now.duration_since(*at).as_secs_f64().max(1.0);
+                self.collection_heat = heat::rates(previous, &counters, secs);
+            }
+            _ => self.collection_heat.clear(),
+        }
+        self.op_counters = Some((db, now, counters));
+    }
+
+    pub fn scroll_collection_stats_down(&mut self, lines: usize) {
+        self.collection_stats_scroll += lines;
+    }
+
+    pub fn scroll_collection_stats_up(&mut self, lines: usize) {
+        self.collection_stats_scroll = self.collection_stats_scroll.saturating_sub(lines);
+    }
+
+    /// Shows a fresh analysis, keeping the selected field when it still exists.
+    pub fn set_schema(&mut self, analysis: SchemaAnalysis) {
+        let selected = self
+            .schema
+            .as_ref()
+            .and_then(|s| s.fields.get(self.selected_schema_field))
+            .map(|f| f.path.clone());
+        self.selected_schema_field = selected
+            .and_then(|path| analysis.fields.iter().position(|f| f.path == path))
+            .unwrap_or(0);
+        self.schema = Some(analysis);
+    }
+
+    pub fn select_next_schema_field(&mut self) {
+        let count = self.schema.as_ref().map_or(0, |s| s.fields.len());
+        if count > 0 {
+            self.selected_schema_field = (self.selected_schema_field + 1) % count;
+        }
+    }
+
+    pub fn select_prev_schema_field(&mut self) {
+        let count = self.schema.as_ref().map_or(0, |s| s.fields.len());
+        if count > 0 {
+            self.selected_schema_field = (self.selected_schema_field + count - 1) % count;
+        }
+    }
+
+    /// A sample size typed at the prompt; past the document cap it needs a
+    /// trailing `!`, as page sizes do.
+    pub fn parse_sample_size(&self, input: &str) -> Result<i64, String> {
+        let input = input.trim();
+        let (number, force) = match input.strip_suffix('!') {
+            Some(number) => (number.trim(), true),
+            None => (input, false),
+        };
+        let requested = number
+            .parse::<u64>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("Not a sample size: {}", input))?;
+        self.limits
+            .check_operation(Some(requested), force)
+            .map(|()| requested as i64)
+            .map_err(|e| format!("{}; append ! to override", e))
+    }
+
+    /// Explains why `feature` is unavailable when the settings or the
+    /// connected profile are read-only.
+    pub fn read_only_denial(&self, feature: &str) -> Option<String> {
+        if self.read_only {
+            return Some(format!(
+                "{} is disabled: read-only mode is on in the settings",
+                feature
+            ));
+        }
+        let profile = self.active_profile.as_ref().filter(|p| p.read_only)?;
+        Some(format!(
+            "{} is disabled: profile {} is read-only",
+            feature, profile.name
+        ))
+    }
+
+    /// Explains why `feature` is unavailable on a namespace, if the known
+    /// privileges rule it out. Unknown privileges never block anything.
+    pub fn permission_denial(
+        &self,
+        db: &str,
+        collection: &str,
+        action: &str,
+        feature: &str,
+    ) -> Option<String> {
+        if !permissions::is_read_action(action)
+            && let Some(reason) = self.read_only_denial(feature)
+        {
+            return Some(reason);
+        }
+        let privileges = self.privileges.as_ref()?;
+        if privileges.allows(db, collection, action) {
+            None
+        } else {
+            let resource = match (db, collection) {
+                ("", _) => "every database".to_string(),
+                (db, "") => format!("every collection of {}", db),
+                (db, collection) => format!("{}.{}", db, collection),
+            };
+            Some(format!(
+                "{} needs the `{}` action on {}, which your roles do not grant",
+                feature, action, resource
+            ))
+        }
+    }
+
+    pub fn set_environment_sets(&mut self, sets: Vec<EnvironmentSet>) {
+        self.environment_sets = sets;
+    }
+
+    /// The environment set and environment the connected profile belongs to.
+    pub fn current_environment(&self) -> Option<(&EnvironmentSet, &Environment)> {
+        let profile = self.active_profile.as_ref()?;
+        self.environment_sets.iter().find_map(|set| {
+            set.environments
+                .iter()
+                .find(|env| env.profile == profile.name)
+                .map(|env| (set, env))
+        })
+    }
+
+    /// The environment after the current one, wrapping around, used as the
+    /// default when switching.
+    pub fn next_environment(&self) -> Option<&Environment> {
+        let (set, current) = self.current_environment()?;
+        let index = set.environments.iter().position(|e| e == current)?;
+        set.environments.get((index + 1) % set.environments.len())
+    }
+
+    /// Looks up a profile by name so prompts can take either a URI or a profile.
+    pub fn find_profile(&self, name: &str) -> Option<ConnectionProfile> {
+        self.profiles.iter().find(|p| p.name == name).cloned()
+    }
+
+    pub fn enter_geo_mode(&mut self, field: String) {
+        self.geo_field = Some(field);
+        self.geo_mode = true;
+    }
+
+    pub fn exit_geo_mode(&mut self) {
+        self.geo_mode = false;
+    }
+
+    pub fn push_geo_char(&mut self, c: char) {
+        self.geo_input.push(c);
+    }
+
+    pub fn pop_geo_char(&mut self) {
+        self.geo_input.pop();
+    }
+
+    pub fn apply_geo_query(&mut self) -> Result<(), String> {
+        let field = self
+            .geo_field
+            .clone()
+            .ok_or_else(|| "No 2dsphere field selected".to_string())?;
+        let shape = GeoShape::parse(&self.geo_input)?;
+        self.filter = Some(shape.to_filter(&field));
+        self.query_template = None;
+        self.geo_shape = Some(shape);
+        Ok(())
+    }
+
+    pub fn start_tail(&mut self, documents: Vec<Document>, source: &str) {
+        self.tail_documents = documents;
+        self.tail_paused = false;
+        self.tail_scroll_offset = 0;
+        self.tail_source = source.to_string();
+        self.tail_scope = WatchScope::Collection;
+        self.tail_activity.clear();
+        self.tail_namespace = None;
+    }
+
+    /// Starts an empty tail screen for a database or deployment watch.
+    pub fn start_wide_watch(&mut self, scope: WatchScope) {
+        self.start_tail(Vec::new(), "change stream, all operations");
+        self.tail_scope = scope;
+    }
+
+    pub fn clear_tail(&mut self) {
+        self.tail_documents.clear();
+        self.tail_activity.clear();
+        self.follow_tail();
+    }
+
+    /// Namespaces of a wide watch, busiest first.
+    pub fn tail_namespaces(&self) -> Vec<(&String, &NamespaceActivity)> {
+        let mut namespaces: Vec<_> = self.tail_activity.iter().collect();
+        namespaces.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then(a.0.cmp(b.0)));
+        namespaces
+    }
+
+    /// Narrows the stream to the next namespace in activity order, then back
+    /// to all of them.
+    pub fn cycle_tail_namespace(&mut self) {
+        let namespaces: Vec<String> = self
+            .tail_namespaces()
+            .into_iter()
+            .map(|(ns, _)| ns.clone())
+            .collect();
+        let next = match &self.tail_namespace {
+            None => 0,
+            Some(current) => namespaces
+                .iter()
+                .position(|ns| ns == current)
+                .map_or(namespaces.len(), |i| i + 1),
+        };
+        self.tail_namespace = namespaces.get(next).cloned();
+        self.follow_tail();
+    }
+
+    /// Tail documents after the namespace filter.
+    pub fn visible_tail_documents(&self) -> Vec<&Document> {
+        self.tail_documents
+            .iter()
+            .filter(|doc| match &self.tail_namespace {
+                Some(ns) => doc.get_str("ns").is_ok_and(|d| d == ns),
+                None => true,
+            })
+            .collect()
+    }
+
+    pub fn append_tail_documents(&mut self, documents: Vec<Document>) {
+        for doc in &documents {
+            if let Ok(ns) = doc.get_str("ns") {
+                let activity = self.tail_activity.entry(ns.to_string()).or_default();
+                match doc.get_str("operationType") {
+                    Ok("insert") => activity.inserts += 1,
+                    Ok("delete") => activity.deletes += 1,
+                    _ => activity.updates += 1,
+                }
+            }
+        }
+        // keep the viewport anchored when scrolled back through history
+        if self.tail_scroll_offset > 0 {
+            self.tail_scroll_offset += documents.len();
+        }
+        self.tail_documents.extend(documents);
+        if self.tail_documents.len() > MAX_TAIL_DOCUMENTS {
+            let overflow = self.tail_documents.len() - MAX_TAIL_DOCUMENTS;
+            self.tail_documents.drain(..overflow);
+        }
+        self.tail_scroll_offset = self
+            .tail_scroll_offset
+            .min(self.tail_documents.len().saturating_sub(1));
+    }
+
+    pub fn toggle_tail_pause(&mut self) {
+        self.tail_paused = !self.tail_paused;
+    }
+
+    pub fn scroll_tail_up(&mut self) {
+        if self.tail_scroll_offset + 1 < self.visible_tail_documents().len() {
+            self.tail_scroll_offset += 1;
+        }
+    }
+
+    pub fn scroll_tail_down(&mut self) {
+        self.tail_scroll_offset = self.tail_scroll_offset.saturating_sub(1);
+    }
+
+    pub fn follow_tail(&mut self) {
+        self.tail_scroll_offset = 0;
+    }
+
+    pub fn start_tail_recording(&mut self, path: &str) -> Result<(), String> {
+        let path = path.trim();
+        if path.is_empty() {
+            return Err("Enter a file to record to".to_string());
+        }
+        let recorder = Recorder::create(std::path::Path::new(path))
+            .map_err(|e| format!("Failed to create {}: {}", path, e))?;
+        self.tail_recorder = Some(recorder);
+        Ok(())
+    }
+
+    /// Ends the recording, describing what was written.
+    pub fn stop_tail_recording(&mut self) -> Option<String> {
+        let recorder = self.tail_recorder.take()?;
+        Some(format!(
+            "Recorded {} event(s) to {}",
+            recorder.count(),
+            recorder.path().display()
+        ))
+    }
+
+    /// Writes newly tailed documents to the recording, if one is running.
+    /// A write error ends the recording.
+    pub fn record_tail(&mut self, documents: &[Document], events: bool) {
+        if let Some(recorder) = self.tail_recorder.as_mut()
+            && let Err(e) = recorder.write(documents, events)
+        {
+            self.tail_recorder = None;
+            self.set_error(Some(format!("Recording stopped: {}", e)));
+        }
+    }
+
+    pub fn is_following_tail(&self) -> bool {
+        self.tail_scroll_offset == 0
+    }
+
+    pub fn clear_geo(&mut self) {
+        self.geo_input.clear();
+        self.geo_shape = None;
+    }
 }
 
 impl Default for AppState {
@@ -0,0 +1,118 @@
+pub const USAGE: &str = "\
+Usage: monjo-kompass [OPTIONS]
+       monjo-kompass --exec --uri <URI> --db <NAME> --coll <NAME> [--query <QUERY> | --pipeline <PIPELINE>] [--limit <N>] [--force]
+
+Options:
+  --uri <URI>            Connect at startup, to a URI or a profile by name
+  --db <NAME>            Open this database once connected
+  --coll <NAME>          Open this collection of the database
+  --query <QUERY>        Apply this query, as typed at / in the document view
+  --exec                 Print the query's documents to stdout as NDJSON instead of starting the app
+  --pipeline <PIPELINE>  With --exec, run this aggregation pipeline instead of a query
+  --limit <N>            With --exec, stop after N documents
+  --force                With --exec, read past the max_documents cap of the settings
+  -h, --help             Show this help
+  -V, --version          Show the version";
+
+/// What the command line asks for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Start the app, going straight to what the arguments name.
+    Run(Args),
+    /// Run the query or pipeline without the app and print its documents.
+    Exec(Args),
+    Help,
+    Version,
+}
+
+/// Where to land at startup. Each option needs the one before it: a
+/// collection needs a database, a database needs a connection.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    /// A URI or a profile name, as `default_connection` in the settings.
+    pub uri: Option<String>,
+    pub db: Option<String>,
+    pub coll: Option<String>,
+    /// A filter or `{filter, sort, projection}` in shell syntax.
+    pub query: Option<String>,
+    /// An aggregation pipeline in shell syntax or JSON, for `--exec`.
+    pub pipeline: Option<String>,
+    /// Documents `--exec` prints at most.
+    pub limit: Option<u64>,
+    /// Lets `--exec` read past the `max_documents` cap.
+    pub force: bool,
+}
+
+/// Reads the arguments after the program name. Options take their value
+/// as the next argument or after `=`, e.g. `--db=shop`.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut parsed = Args::default();
+    let mut exec = false;
+    let mut limit = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg, None),
+        };
+        let slot = match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--exec" => {
+                exec = true;
+                continue;
+            }
+            "--force" => {
+                parsed.force = true;
+                continue;
+            }
+            "--uri" => &mut parsed.uri,
+            "--db" => &mut parsed.db,
+            "--coll" => &mut parsed.coll,
+            "--query" => &mut parsed.query,
+            "--pipeline" => &mut parsed.pipeline,
+            "--limit" => &mut limit,
+            _ => return Err(format!("Unknown argument: {}", flag)),
+        };
+        let value = match inline.or_else(|| args.next()) {
+            Some(value) if !value.is_empty() => value,
+            _ => return Err(format!("{} needs a value", flag)),
+        };
+        *slot = Some(value);
+    }
+
+    if let Some(limit) = limit {
+        parsed.limit = Some(
+            limit
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("--limit takes a positive number, not '{}'", limit))?,
+        );
+    }
+    if !exec && (parsed.pipeline.is_some() || parsed.limit.is_some() || parsed.force) {
+        return Err("--pipeline, --limit and --force need --exec".to_string());
+    }
+    if parsed.query.is_some() && parsed.pipeline.is_some() {
+        return Err("Give either --query or --pipeline".to_string());
+    }
+    if exec && parsed.coll.is_none() {
+        return Err("--exec needs --uri, --db and --coll".to_string());
+    }
+    if parsed.query.is_some() && parsed.coll.is_none() {
+        return Err("--query needs --coll".to_string());
+    }
+    if parsed.coll.is_some() && parsed.db.is_none() {
+        return Err("--coll needs --db".to_string());
+    }
+    if parsed.db.is_some() && parsed.uri.is_none() {
+        return Err("--db needs --uri".to_string());
+    }
+    Ok(if exec {
+        Command::Exec(parsed)
+    } else {
+        Command::Run(parsed)
+    })
+}
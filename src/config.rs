@@ -1,7 +1,12 @@
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
+use mongodb::bson::{DateTime, serde_helpers::bson_datetime_as_rfc3339_string};
 use serde::{Deserialize, Serialize};
 
+use crate::app::state::{MAX_SPLIT_PERCENT, MIN_SPLIT_PERCENT};
+use crate::models::{ColorScheme, DateFormat};
+use crate::services::bson_ext;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionHistory {
     pub uris: Vec<String>,
@@ -25,12 +30,11 @@ impl ConnectionHistory {
     }
 
     pub fn load() -> Self {
-        if let Some(path) = Self::config_path() {
-            if let Ok(contents) = fs::read_to_string(&path) {
-                if let Ok(history) = serde_json::from_str(&contents) {
-                    return history;
-                }
-            }
+        if let Some(path) = Self::config_path()
+            && let Ok(contents) = fs::read_to_string(&path)
+            && let Ok(history) = serde_json::from_str(&contents)
+        {
+            return history;
         }
         Self::new()
     }
@@ -50,3 +54,635 @@ impl ConnectionHistory {
         Ok(())
     }
 }
+
+/// Table view columns chosen per `db.collection`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColumnStore {
+    #[serde(default)]
+    pub namespaces: BTreeMap<String, Vec<String>>,
+}
+
+impl ColumnStore {
+    pub fn load() -> Self {
+        if let Some(path) = Self::config_path()
+            && let Ok(contents) = fs::read_to_string(&path)
+            && let Ok(store) = serde_json::from_str(&contents)
+        {
+            return store;
+        }
+        Self::default()
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".mongonaut").join("columns.json"))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = Self::config_path() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(self)?;
+            fs::write(path, json)?;
+        }
+        Ok(())
+    }
+}
+
+/// A query run from the document view, kept for recall.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryHistoryEntry {
+    /// The query as typed: a filter, or `{"filter", "sort", "projection"}`.
+    pub query: String,
+    #[serde(with = "bson_datetime_as_rfc3339_string")]
+    pub run_at: DateTime,
+}
+
+/// Queries run per `db.collection`, newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryHistory {
+    #[serde(default)]
+    pub namespaces: BTreeMap<String, Vec<QueryHistoryEntry>>,
+}
+
+impl QueryHistory {
+    const MAX_PER_NAMESPACE: usize = 50;
+
+    /// Records a run, moving an earlier run of the same query to the top.
+    pub fn add(&mut self, namespace: &str, query: &str) {
+        let entries = self.namespaces.entry(namespace.to_string()).or_default();
+        entries.retain(|e| e.query != query);
+        entries.insert(
+            0,
+            QueryHistoryEntry {
+                query: query.to_string(),
+                run_at: DateTime::now(),
+            },
+        );
+        entries.truncate(Self::MAX_PER_NAMESPACE);
+    }
+
+    pub fn entries(&self, namespace: &str) -> &[QueryHistoryEntry] {
+        self.namespaces
+            .get(namespace)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn load() -> Self {
+        if let Some(path) = Self::config_path()
+            && let Ok(contents) = fs::read_to_string(&path)
+            && let Ok(history) = serde_json::from_str(&contents)
+        {
+            return history;
+        }
+        Self::default()
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".mongonaut").join("query_history.json"))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = Self::config_path() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(self)?;
+            fs::write(path, json)?;
+        }
+        Ok(())
+    }
+}
+
+/// A query kept under a name for one namespace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub name: String,
+    /// `{"filter", "sort", "projection"}` in extended JSON, as typed at `/`.
+    pub query: String,
+}
+
+/// Named queries per `db.collection`, stored next to `config.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedQueries {
+    #[serde(default)]
+    pub namespaces: BTreeMap<String, Vec<SavedQuery>>,
+}
+
+impl SavedQueries {
+    /// Saves `query` under `name`, replacing a query of the same name.
+    pub fn add(&mut self, namespace: &str, name: &str, query: String) {
+        let queries = self.namespaces.entry(namespace.to_string()).or_default();
+        let saved = SavedQuery {
+            name: name.to_string(),
+            query,
+        };
+        match queries.iter_mut().find(|q| q.name == name) {
+            Some(existing) => *existing = saved,
+            None => {
+                queries.push(saved);
+                queries.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, namespace: &str, index: usize) -> Option<SavedQuery> {
+        let queries = self.namespaces.get_mut(namespace)?;
+        if index >= queries.len() {
+            return None;
+        }
+        let removed = queries.remove(index);
+        if queries.is_empty() {
+            self.namespaces.remove(namespace);
+        }
+        Some(removed)
+    }
+
+    pub fn entries(&self, namespace: &str) -> &[SavedQuery] {
+        self.namespaces
+            .get(namespace)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn load() -> Self {
+        if let Some(path) = Self::config_path()
+            && let Ok(contents) = fs::read_to_string(&path)
+            && let Ok(queries) = serde_json::from_str(&contents)
+        {
+            return queries;
+        }
+        Self::default()
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("monjo-kompass").join("queries.json"))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = Self::config_path() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(self)?;
+            fs::write(path, json)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AwsIamConfig {
+    /// Named profile from the shared AWS config; the default chain otherwise.
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortForwardConfig {
+    /// Anything `kubectl port-forward` accepts, e.g. `svc/mongodb` or `pod/mongo-0`.
+    pub target: String,
+    #[serde(default = "default_mongo_port")]
+    pub remote_port: u16,
+    #[serde(default)]
+    pub local_port: Option<u16>,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+}
+
+fn default_mongo_port() -> u16 {
+    27017
+}
+
+/// Upper bound for bulk operations (exports, copies, mass updates) so they
+/// cannot saturate a production primary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    #[serde(default)]
+    pub docs_per_sec: Option<f64>,
+    #[serde(default)]
+    pub batches_per_sec: Option<f64>,
+}
+
+impl std::fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = [
+            self.docs_per_sec.map(|r| format!("{} docs/s", r)),
+            self.batches_per_sec.map(|r| format!("{} batches/s", r)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if parts.is_empty() {
+            write!(f, "unlimited")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub uri: String,
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+    #[serde(default)]
+    pub aws_iam: Option<AwsIamConfig>,
+    #[serde(default)]
+    pub port_forward: Option<PortForwardConfig>,
+    /// Tried in order when `uri` cannot be reached, e.g. a DR site.
+    #[serde(default)]
+    pub fallback_uris: Vec<String>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+    /// Database opened straight after connecting.
+    #[serde(default)]
+    pub default_database: Option<String>,
+    /// Blocks every write the app can issue through this profile.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// One stage of a service, pointing at the profile that reaches it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    pub profile: String,
+}
+
+/// The same service deployed to several environments (dev/staging/prod)
+/// that share database and collection names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentSet {
+    pub name: String,
+    pub environments: Vec<Environment>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileStore {
+    #[serde(default)]
+    pub profiles: Vec<ConnectionProfile>,
+    #[serde(default)]
+    pub environment_sets: Vec<EnvironmentSet>,
+}
+
+impl ProfileStore {
+    pub fn load() -> Self {
+        if let Some(path) = Self::config_path()
+            && let Ok(contents) = fs::read_to_string(&path)
+            && let Ok(store) = serde_json::from_str(&contents)
+        {
+            return store;
+        }
+        Self::default()
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".mongonaut").join("profiles.json"))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = Self::config_path() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(self)?;
+            fs::write(path, json)?;
+        }
+        Ok(())
+    }
+}
+
+/// Atlas Admin API key pair, read from the Atlas CLI environment variables
+/// or `~/.mongonaut/atlas.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtlasConfig {
+    pub public_key: String,
+    pub private_key: String,
+}
+
+impl AtlasConfig {
+    pub fn load() -> Option<Self> {
+        if let (Ok(public_key), Ok(private_key)) = (
+            std::env::var("MONGODB_ATLAS_PUBLIC_API_KEY"),
+            std::env::var("MONGODB_ATLAS_PRIVATE_API_KEY"),
+        ) {
+            return Some(Self {
+                public_key,
+                private_key,
+            });
+        }
+
+        let path = dirs::home_dir()?.join(".mongonaut").join("atlas.json");
+        let contents = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+}
+
+/// Guard rails for how much data a single page or operation may pull.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryLimits {
+    /// Largest page size accepted without an explicit `!` override.
+    pub max_page_size: usize,
+    /// Largest number of documents one operation (e.g. an export) may read
+    /// without an explicit override.
+    pub max_documents: u64,
+    /// Memory a loaded page may take, in MiB of BSON.
+    pub memory_budget_mb: u64,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        Self {
+            max_page_size: 1000,
+            max_documents: 10_000,
+            memory_budget_mb: 256,
+        }
+    }
+}
+
+impl QueryLimits {
+    pub fn check_page_size(&self, requested: usize, force: bool) -> Result<usize, String> {
+        if requested == 0 {
+            return Err("Page size must be at least 1".to_string());
+        }
+        if requested > self.max_page_size && !force {
+            return Err(format!(
+                "{} documents per page exceeds the cap of {}; append ! to override",
+                requested, self.max_page_size
+            ));
+        }
+        Ok(requested)
+    }
+
+    pub fn memory_budget_bytes(&self) -> u64 {
+        self.memory_budget_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// `documents` is the expected size of the operation, `None` when it is
+    /// unbounded. The error leaves naming the override to the caller.
+    pub fn check_operation(&self, documents: Option<u64>, force: bool) -> Result<(), String> {
+        if force {
+            return Ok(());
+        }
+        match documents {
+            Some(n) if n <= self.max_documents => Ok(()),
+            Some(n) => Err(format!(
+                "{} documents exceeds the cap of {}",
+                n, self.max_documents
+            )),
+            None => Err("Unbounded operation".to_string()),
+        }
+    }
+}
+
+/// Application settings from `~/.config/monjo-kompass/config.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub limits: QueryLimits,
+    pub snippets: Vec<StageSnippet>,
+    /// Language pack for UI labels, e.g. `de`; English when unset.
+    pub language: Option<String>,
+    /// A profile name or URI to fill in on the connection screen.
+    pub default_connection: Option<String>,
+    /// Documents per page when a collection opens.
+    pub page_size: Option<usize>,
+    /// Blocks writes on every connection, whatever its profile says.
+    pub read_only: bool,
+    /// Starts with dry run on: writes are shown, not sent.
+    pub dry_run: bool,
+    pub default_sort: DefaultSort,
+    /// Offset from UTC dates are shown at, e.g. `+02:00`; UTC when unset.
+    pub utc_offset: Option<String>,
+    /// How dates are written: `iso`, `short` or `epoch`; `iso` when unset.
+    pub date_format: Option<String>,
+    /// `dark`, `light` for light terminal backgrounds, `high-contrast` or
+    /// `mono`.
+    pub theme: Option<String>,
+    /// Colors per role on top of the theme, e.g. `selection = "#3a3a3a"`.
+    pub colors: BTreeMap<String, String>,
+    /// Asks before drops and deletions unless `false`, which fills the
+    /// answer in so Enter alone goes ahead.
+    pub confirm_delete: Option<bool>,
+    /// Width of the document list next to the content pane, in percent;
+    /// saved when it is resized with Ctrl+Left and Ctrl+Right.
+    pub split_percent: Option<u16>,
+    /// Keys per action, e.g. `down = "k"`; several keys are separated by
+    /// spaces. Actions left out keep their default keys.
+    pub keys: BTreeMap<String, String>,
+}
+
+/// The order a collection opens in, newest documents first where asked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultSort {
+    /// Opens every collection newest first, on a detected field.
+    pub newest_first: bool,
+    /// Field to sort descending by per `db.collection`: a field name,
+    /// `auto` to detect one, or `none` to keep the server's order.
+    pub collections: BTreeMap<String, String>,
+}
+
+impl DefaultSort {
+    pub const AUTO: &str = "auto";
+
+    /// The field `namespace` opens sorted descending by, or [`Self::AUTO`];
+    /// `None` when it opens in the server's order.
+    pub fn field(&self, namespace: &str) -> Option<&str> {
+        match self.collections.get(namespace).map(String::as_str) {
+            Some("none") => None,
+            Some(field) => Some(field),
+            None if self.newest_first => Some(Self::AUTO),
+            None => None,
+        }
+    }
+}
+
+/// A named pipeline stage offered alongside the built-in templates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageSnippet {
+    pub name: String,
+    pub operator: String,
+    /// Extended JSON for the stage's argument.
+    pub body: String,
+}
+
+impl Settings {
+    /// Settings that `:set` changes, in the order it lists them.
+    pub const KEYS: [&str; 8] = [
+        "page_size",
+        "default_connection",
+        "theme",
+        "confirm_delete",
+        "date_format",
+        "read_only",
+        "utc_offset",
+        "split_percent",
+    ];
+
+    pub fn load() -> Self {
+        Self::try_load().unwrap_or_default()
+    }
+
+    /// Like [`load`](Self::load), but a file that does not parse is an
+    /// error rather than the defaults, so it is not saved over.
+    pub fn try_load() -> Result<Self, String> {
+        let Some(path) = Self::config_path() else {
+            return Ok(Self::default());
+        };
+        ::config::Config::builder()
+            .add_source(::config::File::from(path.clone()).required(false))
+            .build()
+            .and_then(|c| c.try_deserialize())
+            .map_err(|e| format!("{} does not parse: {}", path.display(), e))
+    }
+
+    /// One of [`KEYS`](Self::KEYS) as `key=value`.
+    pub fn get(&self, key: &str) -> Option<String> {
+        let value = match key {
+            "page_size" => self.page_size.map(|n| n.to_string()),
+            "default_connection" => self.default_connection.clone(),
+            "theme" => self.theme.clone(),
+            "confirm_delete" => Some(self.confirm_delete.unwrap_or(true).to_string()),
+            "date_format" => self.date_format.clone(),
+            "read_only" => Some(self.read_only.to_string()),
+            "utc_offset" => self.utc_offset.clone(),
+            "split_percent" => self.split_percent.map(|n| n.to_string()),
+            _ => return None,
+        };
+        Some(format!("{}={}", key, value.as_deref().unwrap_or("none")))
+    }
+
+    /// Changes one of [`KEYS`](Self::KEYS) from text, checking the value;
+    /// `none` clears the optional ones back to their default.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        let text =
+            (!value.is_empty() && !value.eq_ignore_ascii_case("none")).then(|| value.to_string());
+        let invalid = |expected: &str| format!("{} takes {}, not '{}'", key, expected, value);
+        match key {
+            "page_size" => {
+                self.page_size = match text {
+                    Some(text) => Some(
+                        text.parse()
+                            .ok()
+                            .filter(|n: &usize| *n > 0)
+                            .ok_or_else(|| invalid("a positive number"))?,
+                    ),
+                    None => None,
+                };
+            }
+            "default_connection" => self.default_connection = text,
+            "theme" => {
+                if text
+                    .as_deref()
+                    .is_some_and(|t| ColorScheme::parse(t).is_none())
+                {
+                    return Err(invalid(&ColorScheme::NAMES.join(", ")));
+                }
+                self.theme = text;
+            }
+            "confirm_delete" => {
+                self.confirm_delete = match text {
+                    Some(text) => Some(parse_bool(&text).ok_or_else(|| invalid("true or false"))?),
+                    None => None,
+                };
+            }
+            "date_format" => {
+                if text
+                    .as_deref()
+                    .is_some_and(|t| DateFormat::parse(t).is_none())
+                {
+                    return Err(invalid(&DateFormat::NAMES.join(", ")));
+                }
+                self.date_format = text;
+            }
+            "read_only" => {
+                self.read_only = match text {
+                    Some(text) => parse_bool(&text).ok_or_else(|| invalid("true or false"))?,
+                    None => false,
+                };
+            }
+            "utc_offset" => {
+                if text
+                    .as_deref()
+                    .is_some_and(|t| bson_ext::parse_utc_offset(t).is_none())
+                {
+                    return Err(invalid("an offset such as +02:00"));
+                }
+                self.utc_offset = text;
+            }
+            "split_percent" => {
+                self.split_percent = match text {
+                    Some(text) => Some(
+                        text.parse()
+                            .ok()
+                            .filter(|n| (MIN_SPLIT_PERCENT..=MAX_SPLIT_PERCENT).contains(n))
+                            .ok_or_else(|| {
+                                invalid(&format!(
+                                    "a percentage from {} to {}",
+                                    MIN_SPLIT_PERCENT, MAX_SPLIT_PERCENT
+                                ))
+                            })?,
+                    ),
+                    None => None,
+                };
+            }
+            _ => {
+                return Err(format!(
+                    "Unknown setting {}; try {}",
+                    key,
+                    Self::KEYS.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("monjo-kompass").join("config.toml"))
+    }
+
+    /// Whether a settings file has been written yet; the setup wizard runs
+    /// until one has.
+    pub fn exists() -> bool {
+        Self::config_path().is_some_and(|path| path.exists())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = Self::config_path() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, toml::to_string_pretty(self)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
@@ -21,4 +21,5 @@ pub enum AppError{
     InvalidInput(String),
 }
 
+#[allow(dead_code)]
 pub type Result<T> = std::result::Result<T,AppError>;
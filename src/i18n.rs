@@ -0,0 +1,83 @@
+//! Translations for UI labels. English is the source language: a label is
+//! looked up by its English text and shown as-is when the selected pack has
+//! no entry for it.
+
+use std::{collections::HashMap, fs, sync::OnceLock};
+
+/// Packs compiled into the binary, by language code.
+const BUILT_IN: &[(&str, &str)] = &[("de", include_str!("../lang/de.json"))];
+
+/// Language codes offered by the setup wizard, English first.
+pub fn languages() -> Vec<&'static str> {
+    let mut codes = vec!["en"];
+    codes.extend(BUILT_IN.iter().map(|(code, _)| *code));
+    codes
+}
+
+static PACK: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Selects the language for the rest of the run. A pack file at
+/// `~/.config/monjo-kompass/lang/<code>.json` overrides or extends the
+/// built-in pack of the same code.
+pub fn init(language: Option<&str>) {
+    let Some(code) = language.map(str::trim).filter(|c| !c.is_empty()) else {
+        return;
+    };
+    let mut pack = HashMap::new();
+    if let Some((_, text)) = BUILT_IN.iter().find(|(c, _)| c.eq_ignore_ascii_case(code)) {
+        pack.extend(parse(text));
+    }
+    if let Some(path) = dirs::config_dir().map(|dir| {
+        dir.join("monjo-kompass")
+            .join("lang")
+            .join(format!("{}.json", code))
+    }) && let Ok(text) = fs::read_to_string(path)
+    {
+        pack.extend(parse(&text));
+    }
+    let _ = PACK.set(pack);
+}
+
+// a pack is a flat JSON object from English text to its translation
+fn parse(text: &str) -> HashMap<String, String> {
+    serde_json::from_str(text).unwrap_or_default()
+}
+
+/// The translation of `text`, or `text` itself when there is none.
+pub fn tr(text: &str) -> &str {
+    PACK.get()
+        .and_then(|pack| pack.get(text))
+        .map_or(text, String::as_str)
+}
+
+/// Translates a footer hint line such as ` [Enter] Select  [Esc] Back `,
+/// leaving the bracketed keys alone. A label runs from its key to the
+/// next double space, so a trailing value like `: on` stays part of it.
+pub fn hints(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        if rest.starts_with('[') {
+            let end = rest.find(']').map_or(rest.len(), |i| i + 1);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        let end = rest.find('[').unwrap_or(rest.len());
+        let segment = &rest[..end];
+        let label_end = segment.find("  ").unwrap_or(segment.len());
+        let (label, tail) = segment.split_at(label_end);
+        let trimmed = label.trim();
+        if trimmed.is_empty() {
+            out.push_str(label);
+        } else {
+            let start = label.find(trimmed).unwrap_or(0);
+            out.push_str(&label[..start]);
+            out.push_str(tr(trimmed));
+            out.push_str(&label[start + trimmed.len()..]);
+        }
+        out.push_str(tail);
+        rest = &rest[end..];
+    }
+    out
+}
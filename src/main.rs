@@ -1,42 +1,183 @@
 mod app;
+mod cli;
 mod config;
 mod error;
+mod i18n;
 mod models;
 mod services;
 mod ui;
 
 use arboard::Clipboard;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyModifiers,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{Terminal, backend::CrosstermBackend};
-use std::io;
+use ratatui::{
+    Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout},
+};
+use std::{
+    collections::BTreeSet,
+    io,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
+use app::input::Edit;
+use app::keys::{self, Feed, KeySequence, TabMove};
+use app::loader::{Connected, Loaded, Loader};
+use app::prompt::{Prompt, PromptKind};
+use app::session::Sessions;
 use app::state::AppState;
+use models::{
+    BuilderField, DraftPreview, ExportDialog, ExportField, ImportMode, PageCursor, PendingEdit,
+    PipelineStage, RolePicker, SetupForm, TaskStatus, WatchScope, WouldExecute, ZoneRange,
+};
+use services::aggregation;
+use services::atlas::AtlasService;
+use services::benchmark;
+use services::bulk_update;
+use services::clipboard;
+use services::finder;
+use services::collections;
+use services::compare;
+use services::compatibility;
+use services::distinct;
 use services::connection::ConnectionService;
+use services::crash;
+use services::dry_run;
+use services::editor;
+use services::explain;
+use services::export;
+use services::geo;
+use services::health::HealthService;
+use services::heat;
+use services::import;
+use services::memory;
+use services::metrics::format_bytes;
+use services::operations;
+use services::permissions;
+use services::placeholders;
+use services::projection;
+use services::reference;
 use services::query::QueryService;
+use services::replay;
+use services::replset::ReplicaSetService;
+use services::report;
+use services::schema;
+use services::search;
+use services::shard_key;
+use services::stats;
+use services::sharding::{ShardingAction, ShardingService};
+use services::table;
+use services::tail::Tailer;
+use services::tasks::TaskRegistry;
+use services::users::{self, UserChange};
+
+use crate::config::{
+    AtlasConfig, ColumnStore, ConnectionHistory, DefaultSort, ProfileStore, QueryHistory,
+    SavedQueries, Settings,
+};
 
-use crate::config::ConnectionHistory;
+const TICK_RATE: Duration = Duration::from_millis(250);
+const TAIL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const STEPDOWN_SECS: i64 = 60;
+const OPERATIONS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const REPLICA_SET_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// How often the collection list samples activity for its heat indicator.
+const HEAT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Pause in typing a stage body before its live preview runs.
+const DRAFT_PREVIEW_DELAY: Duration = Duration::from_millis(500);
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(cli::Command::Run(args)) => args,
+        Ok(cli::Command::Exec(args)) => {
+            if let Err(e) = services::headless::exec(args).await {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Ok(cli::Command::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(cli::Command::Version) => {
+            println!("monjo-kompass {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     // setup terminal
+    crash::install_hook();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // appstate
     let mut state = AppState::new();
-    let mut conn_service = ConnectionService::new();
     let mut history = ConnectionHistory::load();
     state.set_connection_history(history.uris.clone());
+    let profile_store = ProfileStore::load();
+    state.set_profiles(profile_store.profiles);
+    state.set_environment_sets(profile_store.environment_sets);
+    let settings = Settings::load();
+    i18n::init(settings.language.as_deref());
+    state.apply_settings(settings);
+    if !Settings::exists() && args.uri.is_none() {
+        state.setup_form = Some(SetupForm::default());
+        state.set_screen(app::screen::Screen::Setup);
+    }
+    state.column_sets = ColumnStore::load().namespaces;
+    state.query_history = QueryHistory::load();
+    state.saved_queries = SavedQueries::load();
 
     // connecting to mongo
     let mut conn_service = ConnectionService::new();
+    // second connection used only by the count comparison
+    let mut compare_service = ConnectionService::new();
+    let mut tailer: Option<Tailer> = None;
+    let mut last_tail_poll = Instant::now();
+    let mut last_operations_poll = Instant::now();
+    let mut last_replica_set_poll = Instant::now();
+    // database whose collections' activity was last sampled, and when
+    let mut last_heat_poll: Option<(String, Instant)> = None;
+    // the stage body being typed and when it last changed
+    let mut draft_typed = (String::new(), Instant::now());
+    let tasks = TaskRegistry::new();
+    let mut loader = Loader::new();
+    // connections kept open in the background, switched with Ctrl+O
+    let mut sessions = Sessions::default();
+    let mut key_sequence = KeySequence::default();
+    let mut replay: Option<KeyEvent> = None;
+    // where the command line asked to land, reached as the connection and
+    // then the collection finish loading
+    let mut launch = None;
+    if let Some(uri) = &args.uri {
+        state.use_connection(uri);
+        let uri = state.connection_input.as_str().to_string();
+        let profile = state.profile_for_input();
+        loader.spawn(async move { Loaded::Connection(Connected::establish(uri, profile).await) });
+        launch = Some(args);
+    }
     // let uri = "mongodb://localhost:27017";
 
     // match conn_service.connect(uri).await {
@@ -63,25 +204,520 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // main loop
     loop {
-        terminal.draw(|f| match state.current_screen {
-            app::screen::Screen::DatabaseList => {
-                ui::database_list::render(f, f.area(), &state);
+        terminal.draw(|f| {
+            if !ui::too_small::fits(f.area().as_size()) {
+                ui::too_small::render(f, f.area(), &state.theme);
+                return;
+            }
+            // every screen sits above the status bar
+            let [area, status_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(f.area());
+            match state.current_screen {
+                app::screen::Screen::DatabaseList => {
+                    ui::database_list::render(f, area, &state);
+                }
+                app::screen::Screen::Connection => {
+                    ui::connection::render(f, area, &state);
+                }
+                app::screen::Screen::CollectionList => {
+                    ui::collection_list::render(f, area, &state);
+                }
+                app::screen::Screen::DocumentView => {
+                    ui::document_view::render(f, area, &state);
+                }
+                app::screen::Screen::Tail => {
+                    ui::tail::render(f, area, &state);
+                }
+                app::screen::Screen::HealthCheck => {
+                    ui::health::render(f, area, &state);
+                }
+                app::screen::Screen::Atlas => {
+                    ui::atlas::render(f, area, &state);
+                }
+                app::screen::Screen::Compare => {
+                    ui::compare::render(f, area, &state);
+                }
+                app::screen::Screen::ReferenceDiff => {
+                    ui::reference_diff::render(f, area, &state);
+                }
+                app::screen::Screen::Operations => {
+                    ui::operations::render(f, area, &state);
+                }
+                app::screen::Screen::Tasks => {
+                    ui::tasks::render(f, area, &state);
+                }
+                app::screen::Screen::EventLog => {
+                    ui::event_log::render(f, area, &state);
+                }
+                app::screen::Screen::ReplicaSet => {
+                    ui::replica_set::render(f, area, &state);
+                }
+                app::screen::Screen::Users => {
+                    ui::users::render(f, area, &state);
+                }
+                app::screen::Screen::Roles => {
+                    ui::users::render_roles(f, area, &state);
+                }
+                app::screen::Screen::Sharding => {
+                    ui::sharding::render(f, area, &state);
+                }
+                app::screen::Screen::Search => {
+                    ui::search::render(f, area, &state);
+                }
+                app::screen::Screen::Aggregation => {
+                    ui::aggregation::render(f, area, &state);
+                }
+                app::screen::Screen::Timings => {
+                    ui::timings::render(f, area, &state);
+                }
+                app::screen::Screen::Import => {
+                    ui::import::render(f, area, &state);
+                }
+                app::screen::Screen::BulkUpdate => {
+                    ui::bulk_update::render(f, area, &state);
+                }
+                app::screen::Screen::About => {
+                    ui::about::render(f, area, &state);
+                }
+                app::screen::Screen::Schema => {
+                    ui::schema::render(f, area, &state);
+                }
+                app::screen::Screen::Setup => {
+                    ui::setup::render(f, area, &state);
+                }
+            }
+            ui::status_bar::render(f, status_area, &state);
+
+            if state.show_switcher {
+                ui::switcher::render(f, f.area(), &state);
             }
-            app::screen::Screen::Connection => {
-                ui::connection::render(f, f.area(), &state);
+            if state.show_help {
+                ui::help::render(f, f.area(), &state);
             }
-            app::screen::Screen::CollectionList => {
-                ui::collection_list::render(f, f.area(), &state);
+            if let Some(dialog) = &state.export_dialog {
+                ui::export::render(f, f.area(), dialog, state.error.as_deref(), &state.theme);
             }
-            app::screen::Screen::DocumentView => {
-                ui::document_view::render(f, f.area(), &state);
+            if let Some(panel) = &state.utilities {
+                ui::utilities::render(f, f.area(), panel, &state.theme);
+            }
+            if let Some(finder) = &state.finder {
+                ui::finder::render(f, f.area(), finder, &state.theme);
+            }
+            if let Some(prompt) = &state.prompt {
+                ui::popup::render_prompt(f, f.area(), prompt, &state.theme);
+            }
+            if let Some(summary) = &state.would_execute {
+                ui::dry_run::render(f, f.area(), summary, &state.theme);
             }
         })?;
 
-        if let Event::Key(key) = event::read()? {
+        // poll so that live views (tail) keep updating without key presses;
+        // a lone `g` whose sequence timed out is passed on untouched
+        let (event, fresh) = if let Some(key) = replay.take() {
+            (Some(Event::Key(key)), true)
+        } else if event::poll(TICK_RATE)? {
+            // keys rebound in the settings file become the keys screens handle
+            let event = match event::read()? {
+                Event::Key(key) => {
+                    let typing = state.is_typing() || state.prompt.is_some();
+                    state.bindings.translate(key, typing).map(Event::Key)
+                }
+                other => Some(other),
+            };
+            (event, true)
+        } else {
+            (key_sequence.expire().map(Event::Key), false)
+        };
+
+        // below the minimum size nothing is visible to act on, so only quitting works
+        let event = match event {
+            Some(Event::Resize(_, _)) => {
+                state.clamp_cursors();
+                None
+            }
+            Some(Event::Key(key)) if !ui::too_small::fits(terminal.size()?) => {
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    state.quit();
+                }
+                None
+            }
+            other => other,
+        };
+
+        // vim-style counts and motions resolve before keys reach the screens
+        let event = match event {
+            Some(Event::Key(key)) if fresh && state.accepts_motions() => {
+                match key_sequence.feed(key) {
+                    Feed::Pending => None,
+                    Feed::Motion(motion) => {
+                        let half_page = (terminal.size()?.height / 2) as usize;
+                        state.apply_motion(motion, half_page);
+                        None
+                    }
+                    Feed::Tab(step) => {
+                        switch_tab(&mut state, &loader, step);
+                        None
+                    }
+                    Feed::Key(key) => Some(Event::Key(KeyEvent {
+                        code: keys::alias(&state.current_screen, state.table_view, key.code),
+                        ..key
+                    })),
+                    Feed::Replay(g, next) => {
+                        replay = Some(next);
+                        Some(Event::Key(g))
+                    }
+                }
+            }
+            Some(Event::Key(key)) => {
+                if fresh {
+                    key_sequence.reset();
+                }
+                Some(Event::Key(key))
+            }
+            other => other,
+        };
+
+        if let Some(Event::Paste(text)) = &event
+            && state.prompt.is_some()
+        {
+            // prompts are one line, so pasted line breaks become spaces
+            for c in text.chars() {
+                state.push_prompt_char(if c.is_control() { ' ' } else { c });
+            }
+        } else if let Some(Event::Paste(text)) = &event
+            && let Some(dialog) = state.export_dialog.as_mut()
+        {
+            for c in text.chars().filter(|c| !c.is_control()) {
+                dialog.push_char(c);
+            }
+        } else if let Some(Event::Key(key)) = event
+            && let Some(dialog) = state.export_dialog.as_mut()
+        {
+            match key.code {
+                KeyCode::Tab | KeyCode::Down => dialog.next_field(),
+                KeyCode::BackTab | KeyCode::Up => dialog.prev_field(),
+                KeyCode::Left | KeyCode::Right if dialog.focused_field() == ExportField::Format => {
+                    dialog.cycle_format(key.code == KeyCode::Right);
+                }
+                KeyCode::Char(c) => dialog.push_char(c),
+                KeyCode::Backspace => dialog.pop_char(),
+                KeyCode::Esc => {
+                    state.close_export_dialog();
+                    state.set_error(None);
+                }
+                KeyCode::Enter => {
+                    if let Some(dialog) = state.export_dialog.clone() {
+                        start_export(&mut state, &conn_service, &tasks, &dialog);
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(Event::Paste(text)) = &event
+            && let Some(panel) = state.utilities.as_mut()
+        {
+            for c in text.chars().filter(|c| !c.is_control()) {
+                panel.push_char(c);
+            }
+        } else if let Some(Event::Key(key)) = event
+            && let Some(panel) = state.utilities.as_mut()
+            && state.prompt.is_none()
+        {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.quit();
+                }
+                KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.toggle_utilities();
+                }
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    panel.regenerate();
+                }
+                KeyCode::Char(c) => panel.push_char(c),
+                KeyCode::Backspace => panel.pop_char(),
+                KeyCode::Down | KeyCode::Tab => panel.select_next(),
+                KeyCode::Up | KeyCode::BackTab => panel.select_prev(),
+                KeyCode::Esc => state.toggle_utilities(),
+                KeyCode::Enter => {
+                    if let Some(value) = panel.selected_value().map(String::from) {
+                        copy_to_clipboard(&mut state, &value, &value);
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(Event::Paste(text)) = &event
+            && let Some(finder) = state.finder.as_mut()
+        {
+            for c in text.chars().filter(|c| !c.is_control()) {
+                finder.push_char(c);
+            }
+        } else if let Some(Event::Key(key)) = event
+            && let Some(finder) = state.finder.as_mut()
+            && state.prompt.is_none()
+        {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.quit();
+                }
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.close_finder();
+                }
+                KeyCode::Char(c) => finder.push_char(c),
+                KeyCode::Backspace => finder.pop_char(),
+                KeyCode::Down | KeyCode::Tab => finder.select_next(),
+                KeyCode::Up | KeyCode::BackTab => finder.select_prev(),
+                KeyCode::Esc => state.close_finder(),
+                // a load still in flight would land in the wrong namespace
+                KeyCode::Enter if !loader.is_busy() => {
+                    if let Some((db_name, coll_name)) = finder
+                        .selected()
+                        .map(|(db, coll)| (db.to_string(), coll.map(String::from)))
+                    {
+                        state.close_finder();
+                        tailer = None;
+                        jump_to_namespace(&mut state, &conn_service, &loader, db_name, coll_name)
+                            .await;
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::Char('p')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            open_finder(&mut state, &conn_service, &loader);
+        } else if let Some(Event::Key(key)) = event
+            && state.would_execute.is_some()
+        {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.quit();
+                }
+                KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => {
+                    state.would_execute = None;
+                }
+                KeyCode::F(6) => {
+                    state.would_execute = None;
+                    state.toggle_dry_run();
+                }
+                _ => {}
+            }
+        } else if let Some(Event::Key(key)) = event
+            && state.prompt.is_some()
+        {
+            match key.code {
+                KeyCode::Char(c) => {
+                    state.push_prompt_char(c);
+                }
+                KeyCode::Backspace => {
+                    state.pop_prompt_char();
+                }
+                KeyCode::Esc => {
+                    state.take_prompt();
+                }
+                KeyCode::Enter => {
+                    if let Some(prompt) = state.take_prompt() {
+                        submit_prompt(
+                            prompt,
+                            &mut state,
+                            &mut conn_service,
+                            &mut compare_service,
+                            &tasks,
+                            &loader,
+                        )
+                        .await;
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::Char('o')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            if state.show_switcher {
+                state.close_switcher();
+            } else {
+                state.open_switcher(sessions.labels());
+            }
+        } else if let Some(Event::Key(key)) = event
+            && state.show_switcher
+        {
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    state.select_prev_session();
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    state.select_next_session();
+                }
+                KeyCode::Esc => {
+                    state.close_switcher();
+                }
+                // a load still in flight would land in the wrong connection
+                KeyCode::Enter if !loader.is_busy() => {
+                    state.close_switcher();
+                    if state.is_new_session_selected() || state.selected_parked_session().is_some()
+                    {
+                        switch_session(&mut state, &mut conn_service, &mut sessions);
+                        tailer = None;
+                    }
+                }
+                KeyCode::Char('x') => {
+                    if let Some(index) = state.selected_parked_session() {
+                        sessions.take(index);
+                        state.open_switcher(sessions.labels());
+                    }
+                }
+                KeyCode::Char('d') if state.connection.is_some() => {
+                    sessions.park(conn_service.duplicate(), state.session_view());
+                    state.open_switcher(sessions.labels());
+                    state.set_message(Some(format!(
+                        "Duplicated {}; switch between the two here",
+                        state.session_label()
+                    )));
+                }
+                _ => {}
+            }
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::Char('b')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            state.toggle_panel(app::screen::Screen::Tasks);
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::Char('e')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            state.toggle_panel(app::screen::Screen::EventLog);
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::Char('t')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            state.toggle_panel(app::screen::Screen::Timings);
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::Char('g')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            state.toggle_utilities();
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::F(6)
+        {
+            state.toggle_dry_run();
+        } else if let Some(Event::Key(key)) = event
+            && state.show_help
+        {
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') => {
+                    state.scroll_help_down(1);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    state.scroll_help_up(1);
+                }
+                KeyCode::PageDown => {
+                    state.scroll_help_down(10);
+                }
+                KeyCode::PageUp => {
+                    state.scroll_help_up(10);
+                }
+                KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::F(1) => {
+                    state.close_help();
+                }
+                _ => {}
+            }
+        } else if let Some(Event::Key(key)) = event
+            && (key.code == KeyCode::F(1) || (key.code == KeyCode::Char('?') && !state.is_typing()))
+        {
+            state.open_help();
+        } else if let Some(Event::Key(key)) = event
+            && key.code == KeyCode::Char(':')
+            && !state.is_typing()
+        {
+            state.open_prompt(PromptKind::Command, "Command, e.g. set page_size 50", "set ");
+        } else if let Some(Event::Key(key)) = event {
+            // typed text stays out of crash reports; it may be a password
+            let action = match key.code {
+                KeyCode::Char(_) if state.is_typing() => "typing".to_string(),
+                _ => format!("{:?} {:?}", key.modifiers, key.code),
+            };
+            crash::record_action(format!("{:?}: {}", state.current_screen, action));
+            state.set_message(None);
             match state.current_screen {
                 app::screen::Screen::Connection => {
-                    if state.show_history {
+                    if let Some(form) = state.profile_form.as_mut() {
+                        match key.code {
+                            KeyCode::Tab | KeyCode::Down => {
+                                form.next_field();
+                            }
+                            KeyCode::BackTab | KeyCode::Up => {
+                                form.prev_field();
+                            }
+                            KeyCode::Left | KeyCode::Right => {
+                                form.toggle_read_only();
+                            }
+                            KeyCode::Char(c) => {
+                                form.push_char(c);
+                            }
+                            KeyCode::Backspace => {
+                                form.pop_char();
+                            }
+                            KeyCode::Enter => match state.save_profile_form() {
+                                Ok(name) => match state.profile_store().save() {
+                                    Ok(()) => {
+                                        state.set_error(None);
+                                        state.set_message(Some(format!("Saved profile {}", name)));
+                                    }
+                                    Err(e) => {
+                                        state.set_error(Some(format!(
+                                            "Failed to save profiles: {}",
+                                            e
+                                        )));
+                                    }
+                                },
+                                Err(e) => {
+                                    state.set_error(Some(e));
+                                }
+                            },
+                            KeyCode::Esc => {
+                                state.set_error(None);
+                                state.close_profile_form();
+                            }
+                            _ => {}
+                        }
+                    } else if state.show_profiles {
+                        match key.code {
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                state.quit();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                state.select_prev_profile();
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                state.select_next_profile();
+                            }
+                            KeyCode::Enter => {
+                                state.use_selected_profile();
+                            }
+                            KeyCode::Char('n') => {
+                                state.open_new_profile_form();
+                            }
+                            KeyCode::Char('e') => {
+                                state.open_edit_profile_form();
+                            }
+                            KeyCode::Char('d') => {
+                                if let Some(profile) =
+                                    state.profiles.get(state.selected_profile_index)
+                                {
+                                    let label = format!("Delete profile {}? (y/N)", profile.name);
+                                    state.open_delete_prompt(
+                                        PromptKind::ConfirmDeleteProfile,
+                                        &label,
+                                        "y",
+                                    );
+                                }
+                            }
+                            KeyCode::F(3) | KeyCode::Esc => {
+                                state.toggle_profiles();
+                            }
+                            _ => {}
+                        }
+                    } else if state.show_history {
                         match key.code {
                             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                                 state.quit();
@@ -97,7 +733,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             }
                             KeyCode::Enter => {
                                 if let Some(uri) = state.get_selected_history_uri() {
-                                    state.connection_input = uri.clone();
+                                    state.connection_input.set(uri.clone());
                                     state.toggle_history();
                                 }
                             }
@@ -117,64 +753,49 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             KeyCode::Tab => {
                                 state.toggle_history();
                             }
-                            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                if let Ok(mut clipboard) = Clipboard::new() {
-                                    if let Ok(text) = clipboard.get_text() {
-                                        for c in text.chars() {
-                                            state.push_char(c);
-                                        }
-                                    }
-                                }
+                            KeyCode::F(2) => {
+                                state.toggle_health_check_on_connect();
                             }
-                            KeyCode::Char(c) => {
-                                state.push_char(c);
+                            KeyCode::F(3) => {
+                                state.toggle_profiles();
                             }
-                            KeyCode::Backspace => {
-                                state.pop_char();
+                            KeyCode::F(4) if !loader.is_busy() => match AtlasConfig::load() {
+                                Some(config) => {
+                                    loader.spawn(async move {
+                                        Loaded::AtlasProjects(
+                                            AtlasService::new(config).list_projects().await,
+                                        )
+                                    });
+                                }
+                                None => {
+                                    state.set_error(Some(
+                                        "Set MONGODB_ATLAS_PUBLIC_API_KEY/MONGODB_ATLAS_PRIVATE_API_KEY or ~/.mongonaut/atlas.json".to_string(),
+                                    ));
+                                }
+                            },
+                            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                if let Ok(mut clipboard) = Clipboard::new()
+                                    && let Ok(text) = clipboard.get_text()
+                                {
+                                    state.connection_input.insert_str(&text);
+                                }
                             }
                             KeyCode::Esc => {
                                 state.clear_input();
                                 state.set_error(None);
                             }
-                            KeyCode::Enter => {
-                                let uri = state.connection_input.clone();
-                                state.set_loading(true);
+                            KeyCode::Enter if !loader.is_busy() => {
+                                let uri = state.connection_input.as_str().to_string();
+                                let profile = state.profile_for_input();
                                 state.set_error(None);
 
-                                match conn_service.connect(&uri).await {
-                                    Ok(server_info) => {
-                                        // Save to history
-                                        history.add_uri(uri.clone());
-                                        let _ = history.save();
-                                        state.set_connection_history(history.uris.clone());
-
-                                        state.set_connection(uri, server_info);
-
-                                        if let Some(client) = conn_service.get_client() {
-                                            let query_service = QueryService::new(client.clone());
-                                            match query_service.list_databases().await {
-                                                Ok(databases) => {
-                                                    state.set_databases(databases);
-                                                    state.set_screen(
-                                                        app::screen::Screen::DatabaseList,
-                                                    );
-                                                }
-                                                Err(e) => {
-                                                    state.set_error(Some(format!(
-                                                        "Failed to load databases: {}",
-                                                        e
-                                                    )));
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        state.set_error(Some(format!("Connection failed: {}", e)));
-                                    }
-                                }
-                                state.set_loading(false);
+                                loader.spawn(async move {
+                                    Loaded::Connection(Connected::establish(uri, profile).await)
+                                });
+                            }
+                            _ => {
+                                state.connection_input.handle_key(key);
                             }
-                            _ => {}
                         }
                     }
                 }
@@ -183,365 +804,5111 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         KeyCode::Char('q') => {
                             state.quit();
                         }
+                        KeyCode::Char('W') if !loader.is_busy() => {
+                            start_wide_watch(&mut state, &conn_service, &loader, WatchScope::Deployment);
+                        }
                         KeyCode::Down | KeyCode::Char('j') => {
                             state.select_next_db();
                         }
                         KeyCode::Up | KeyCode::Char('k') => {
                             state.select_prev_db();
                         }
-                        KeyCode::Enter => {
+                        KeyCode::Enter if !loader.is_busy() => {
                             // Get database name first (clone to avoid borrow issues)
                             let db_name = state.get_selected_database().map(|db| db.name.clone());
 
-                            if let Some(db_name) = db_name {
-                                state.set_loading(true);
-                                if let Some(client) = conn_service.get_client() {
-                                    let query_service = QueryService::new(client.clone());
-                                    match query_service.list_collections(&db_name).await {
-                                        Ok(collections) => {
-                                            state.current_database = Some(db_name);
-                                            state.set_collections(collections);
-                                            state.set_screen(app::screen::Screen::CollectionList);
-                                        }
-                                        Err(e) => {
-                                            state.set_error(Some(format!(
-                                                "Failed to load collections: {}",
-                                                e
-                                            )));
-                                        }
+                            if let Some(db_name) = db_name
+                                && let Some(client) = conn_service.get_client()
+                            {
+                                let query_service = QueryService::new(client.clone());
+                                loader.spawn(async move {
+                                    let result = query_service.list_collections(&db_name).await;
+                                    Loaded::Collections {
+                                        db: db_name,
+                                        open: true,
+                                        result,
                                     }
+                                });
+                            }
+                        }
+                        KeyCode::Char('H') if !loader.is_busy() => {
+                            run_health_check(&conn_service, &loader);
+                        }
+                        KeyCode::Char('D') => {
+                            if let Some(db_name) =
+                                state.get_selected_database().map(|db| db.name.clone())
+                            {
+                                if let Some(reason) = state.permission_denial(
+                                    &db_name,
+                                    "",
+                                    "dropDatabase",
+                                    "Dropping the database",
+                                ) {
+                                    state.set_error(Some(reason));
+                                } else {
+                                    state.open_delete_prompt(
+                                        PromptKind::ConfirmDropDatabase,
+                                        &format!(
+                                            "Drop database {} and every collection in it? Type its name to confirm",
+                                            db_name
+                                        ),
+                                        &db_name,
+                                    );
                                 }
-                                state.set_loading(false);
                             }
                         }
-                        KeyCode::Char('r') => {
-                            // Refresh databases
-                            state.set_loading(true);
-                            if let Some(client) = conn_service.get_client() {
-                                let query_service = QueryService::new(client.clone());
-                                match query_service.list_databases().await {
-                                    Ok(databases) => {
-                                        state.set_databases(databases);
-                                    }
-                                    Err(e) => {
-                                        state.set_error(Some(format!(
-                                            "Failed to refresh databases: {}",
-                                            e
-                                        )));
-                                    }
+                        KeyCode::Char('U') if !loader.is_busy() => {
+                            if let Some(db_name) =
+                                state.get_selected_database().map(|db| db.name.clone())
+                            {
+                                if let Some(reason) = state.permission_denial(
+                                    &db_name,
+                                    "",
+                                    "viewUser",
+                                    "Listing users",
+                                ) {
+                                    state.set_error(Some(reason));
+                                } else {
+                                    load_users(&conn_service, &loader, db_name, None);
                                 }
                             }
-                            state.set_loading(false);
                         }
-
-                        _ => {}
-                    }
-                }
-                app::screen::Screen::CollectionList => {
-                    match key.code {
-                        KeyCode::Char('q') => {
-                            state.quit();
+                        KeyCode::Char('S') if !loader.is_busy() => {
+                            state.replica_set_paused = false;
+                            load_replica_set(&conn_service, &loader);
+                            last_replica_set_poll = Instant::now();
                         }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            state.select_next_coll();
+                        KeyCode::Char('O') => {
+                            state.operations.clear();
+                            state.operations_paused = false;
+                            state.set_screen(app::screen::Screen::Operations);
+                            load_operations(&conn_service, &loader);
+                            last_operations_poll = Instant::now();
                         }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            state.select_prev_coll();
+                        KeyCode::Char('A') if !loader.is_busy() => {
+                            load_compatibility(&state, &conn_service, &loader);
                         }
-                        KeyCode::Enter => {
-                            // Load documents for selected collection
-                            let coll_name = state.get_selected_collection().map(|c| c.name.clone());
-                            let db_name = state.current_database.clone();
-
-                            if let (Some(db_name), Some(coll_name)) = (db_name, coll_name) {
-                                state.set_loading(true);
-                                if let Some(client) = conn_service.get_client() {
-                                    let query_service = QueryService::new(client.clone());
-                                    match query_service
-                                        .find_documents(&db_name, &coll_name, None, 0, 20)
-                                        .await
-                                    {
-                                        Ok(documents) => {
-                                            state.current_collection = Some(coll_name);
-                                            state.set_documents(documents);
-                                            state.set_screen(app::screen::Screen::DocumentView);
-                                        }
-                                        Err(e) => {
-                                            state.set_error(Some(format!(
-                                                "Failed to load documents: {}",
-                                                e
-                                            )));
-                                        }
-                                    }
-                                }
-                                state.set_loading(false);
-                            }
+                        KeyCode::Esc | KeyCode::Char('P') if state.show_permissions => {
+                            state.close_permissions();
                         }
-                        KeyCode::Backspace => {
-                            state.set_screen(app::screen::Screen::DatabaseList);
+                        KeyCode::Char('P') if !loader.is_busy() => {
+                            check_permissions(&mut state, &conn_service, &loader, None);
+                        }
+                        KeyCode::Char('C') => {
+                            state.open_prompt(
+                                PromptKind::CompareTarget,
+                                "Compare counts against (URI or profile name)",
+                                &state.compare_target.clone(),
+                            );
                         }
                         KeyCode::Char('r') => {
-                            // Refresh collections
-                            if let Some(db_name) = state.current_database.clone() {
-                                state.set_loading(true);
-                                if let Some(client) = conn_service.get_client() {
-                                    let query_service = QueryService::new(client.clone());
-                                    match query_service.list_collections(&db_name).await {
-                                        Ok(collections) => {
-                                            state.set_collections(collections);
-                                        }
-                                        Err(e) => {
-                                            state.set_error(Some(format!(
-                                                "Failed to refresh collections: {}",
-                                                e
-                                            )));
-                                        }
-                                    }
-                                }
-                                state.set_loading(false);
+                            // Refresh databases
+                            state.clear_database_totals();
+                            if let Some(client) = conn_service.get_client() {
+                                let query_service = QueryService::new(client.clone());
+                                loader.spawn(async move {
+                                    Loaded::Databases(query_service.list_databases().await)
+                                });
                             }
                         }
 
                         _ => {}
                     }
                 }
-                app::screen::Screen::DocumentView => {
-                    if state.query_mode {
-                        // Advanced query mode - JSON input
+                app::screen::Screen::CollectionList => {
+                    if let Some(form) = state.collection_form.as_mut() {
                         match key.code {
+                            KeyCode::Esc => {
+                                state.close_collection_form();
+                            }
+                            KeyCode::Tab | KeyCode::Down => {
+                                form.next_field();
+                            }
+                            KeyCode::BackTab | KeyCode::Up => {
+                                form.prev_field();
+                            }
+                            KeyCode::Left => {
+                                form.cycle_option(false);
+                            }
+                            KeyCode::Right => {
+                                form.cycle_option(true);
+                            }
                             KeyCode::Char(c) => {
-                                state.push_query_char(c);
+                                form.push_char(c);
                             }
                             KeyCode::Backspace => {
-                                state.pop_query_char();
+                                form.pop_char();
                             }
-                            KeyCode::Esc => {
-                                state.exit_query_mode();
-                                state.clear_query();
-                            }
-                            KeyCode::Enter => {
-                                // Apply the JSON query
-                                match state.apply_filter() {
-                                    Ok(_) => {
-                                        state.exit_query_mode();
-                                        let db_name = state.current_database.clone();
-                                        let coll_name = state.current_collection.clone();
-                                        let filter = state.filter.clone();
-
-                                        if let (Some(db_name), Some(coll_name)) =
-                                            (db_name, coll_name)
-                                        {
-                                            state.set_loading(true);
-                                            if let Some(client) = conn_service.get_client() {
+                            KeyCode::Enter if !loader.is_busy() => {
+                                let name = form.name.trim().to_string();
+                                let command = form.to_command();
+                                if let Some(db_name) = state.current_database.clone()
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &name,
+                                        "createCollection",
+                                        "Creating a collection",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        match command {
+                                            Ok(command) if state.dry_run => {
+                                                let namespace = format!("{}.{}", db_name, name);
+                                                state.would_execute = Some(
+                                                    WouldExecute::command(&namespace, &command),
+                                                );
+                                            }
+                                            Ok(command) => {
                                                 let query_service =
                                                     QueryService::new(client.clone());
-                                                match query_service
-                                                    .find_documents(
-                                                        &db_name, &coll_name, filter, 0, 20,
-                                                    )
-                                                    .await
-                                                {
-                                                    Ok(documents) => {
-                                                        state.set_documents(documents);
-                                                        state.set_error(None);
+                                                let client = client.clone();
+                                                state.set_error(None);
+                                                loader.spawn(async move {
+                                                    let result = async {
+                                                        collections::create_collection(
+                                                            &client, &db_name, command,
+                                                        )
+                                                        .await?;
+                                                        query_service
+                                                            .list_collections(&db_name)
+                                                            .await
                                                     }
-                                                    Err(e) => {
-                                                        state.set_error(Some(format!(
-                                                            "Query failed: {}",
-                                                            e
-                                                        )));
+                                                    .await;
+                                                    Loaded::CollectionCreated {
+                                                        db: db_name,
+                                                        name,
+                                                        result,
                                                     }
-                                                }
+                                                });
+                                            }
+                                            Err(e) => {
+                                                state.set_error(Some(e));
                                             }
-                                            state.set_loading(false);
                                         }
                                     }
-                                    Err(e) => {
-                                        state.set_error(Some(e));
-                                    }
                                 }
                             }
                             _ => {}
                         }
-                    } else if state.filter_mode {
-                        // Simple search mode - live filtering
+                    } else {
                         match key.code {
-                            KeyCode::Char(c) => {
-                                state.push_filter_char(c);
-                                apply_dynamic_filter(&mut state, &conn_service).await;
+                            KeyCode::Char('q') => {
+                                state.quit();
                             }
-                            KeyCode::Backspace => {
-                                state.pop_filter_char();
-                                apply_dynamic_filter(&mut state, &conn_service).await;
+                            KeyCode::Esc | KeyCode::Char('P') if state.show_permissions => {
+                                state.close_permissions();
                             }
-                            KeyCode::Esc => {
-                                state.exit_filter_mode();
-                                state.clear_filter();
-                                reload_documents_without_filter(&mut state, &conn_service).await;
+                            KeyCode::Esc | KeyCode::Char('K')
+                                if state.shard_key_analysis.is_some() =>
+                            {
+                                state.close_shard_key_analysis();
                             }
-                            KeyCode::Enter => {
-                                state.exit_filter_mode();
+                            KeyCode::Esc | KeyCode::Char('i')
+                                if state.collection_stats.is_some() =>
+                            {
+                                state.close_collection_stats();
+                            }
+                            KeyCode::Down | KeyCode::Char('j')
+                                if state.collection_stats.is_some() =>
+                            {
+                                state.scroll_collection_stats_down(1);
+                            }
+                            KeyCode::Up | KeyCode::Char('k')
+                                if state.collection_stats.is_some() =>
+                            {
+                                state.scroll_collection_stats_up(1);
+                            }
+                            KeyCode::PageDown if state.collection_stats.is_some() => {
+                                state.scroll_collection_stats_down(10);
+                            }
+                            KeyCode::PageUp if state.collection_stats.is_some() => {
+                                state.scroll_collection_stats_up(10);
+                            }
+                            KeyCode::Char('r') if state.collection_stats.is_some() => {
+                                let namespace = state
+                                    .collection_stats
+                                    .as_ref()
+                                    .and_then(|s| s.namespace.split_once('.'))
+                                    .map(|(db, coll)| (db.to_string(), coll.to_string()));
+                                if let Some((db_name, coll_name)) = namespace {
+                                    load_collection_stats(
+                                        &conn_service,
+                                        &loader,
+                                        db_name,
+                                        coll_name,
+                                    );
+                                }
+                            }
+                            KeyCode::Char('i') => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                if let (Some(db_name), Some(coll_name)) =
+                                    (state.current_database.clone(), coll_name)
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "collStats",
+                                        "Collection statistics",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        load_collection_stats(
+                                            &conn_service,
+                                            &loader,
+                                            db_name,
+                                            coll_name,
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('n') => {
+                                state.open_collection_form();
+                            }
+                            KeyCode::Char('I') => {
+                                if let (Some(db_name), Some(coll)) = (
+                                    state.current_database.clone(),
+                                    state.get_selected_collection(),
+                                ) {
+                                    let action = if coll.pre_and_post_images {
+                                        "Disable"
+                                    } else {
+                                        "Enable"
+                                    };
+                                    let label = format!(
+                                        "{} change stream pre/post images on {}? (y/N)",
+                                        action, coll.name
+                                    );
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll.name,
+                                        "collMod",
+                                        "Changing pre/post images",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        state.open_prompt(PromptKind::ConfirmPreImages, &label, "");
+                                    }
+                                }
+                            }
+                            KeyCode::Char('B') => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                if let (Some(db_name), Some(coll_name)) =
+                                    (state.current_database.clone(), coll_name)
+                                {
+                                    refresh_sharding(
+                                        &conn_service,
+                                        &loader,
+                                        (db_name, coll_name),
+                                        None,
+                                    );
+                                }
+                            }
+                            KeyCode::Char('a') => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                if let (Some(db_name), Some(coll_name)) =
+                                    (state.current_database.clone(), coll_name)
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "find",
+                                        "Schema analysis",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        let size = state.schema_sample_size.to_string();
+                                        state.open_prompt(
+                                            PromptKind::SchemaSampleSize,
+                                            "Documents to sample for the schema",
+                                            &size,
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('K') => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                if let (Some(db_name), Some(coll_name)) =
+                                    (state.current_database.clone(), coll_name)
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "find",
+                                        "Shard key analysis",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        state.open_prompt(
+                                        PromptKind::ShardKeyCandidate,
+                                        "Candidate shard key (e.g. customer_id, created_at or user_id: hashed)",
+                                        "",
+                                    );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('P') if !loader.is_busy() => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                if let (Some(db_name), Some(coll_name)) =
+                                    (state.current_database.clone(), coll_name)
+                                {
+                                    let namespace = Some((db_name, coll_name));
+                                    check_permissions(&mut state, &conn_service, &loader, namespace);
+                                }
+                            }
+                            KeyCode::Char(' ') => {
+                                state.toggle_marked_collection();
+                                state.select_next_coll();
+                            }
+                            KeyCode::Char('X') => {
+                                let collections = state.bulk_export_collections();
+                                let db_name = state.current_database.clone().unwrap_or_default();
+                                let denial = collections.iter().find_map(|coll_name| {
+                                    state.permission_denial(
+                                        &db_name,
+                                        coll_name,
+                                        "find",
+                                        "Exporting",
+                                    )
+                                });
+                                if let Some(reason) = denial {
+                                    state.set_error(Some(reason));
+                                } else if collections.is_empty() {
+                                    state.set_error(Some(
+                                        "There are no collections to export".to_string(),
+                                    ));
+                                } else {
+                                    state.open_export_dialog(ExportDialog::for_collections(
+                                        &db_name,
+                                        collections,
+                                    ));
+                                }
+                            }
+                            KeyCode::Char('D') => {
+                                if let Some(db_name) = state.current_database.clone()
+                                    && let Some(coll_name) =
+                                        state.get_selected_collection().map(|c| c.name.clone())
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "dropCollection",
+                                        "Dropping the collection",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        state.open_delete_prompt(
+                                            PromptKind::ConfirmDropCollection,
+                                            &format!(
+                                                "Drop {}.{} with its documents and indexes? Type its name to confirm",
+                                                db_name, coll_name
+                                            ),
+                                            &coll_name,
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('m') => {
+                                if let Some(db_name) = state.current_database.clone()
+                                    && let Some(coll_name) =
+                                        state.get_selected_collection().map(|c| c.name.clone())
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "renameCollectionSameDB",
+                                        "Renaming the collection",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        state.open_prompt(
+                                            PromptKind::RenameCollection,
+                                            &format!("Rename {}.{} to", db_name, coll_name),
+                                            &coll_name,
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('c') => {
+                                if let Some(db_name) = state.current_database.clone()
+                                    && let Some(coll_name) =
+                                        state.get_selected_collection().map(|c| c.name.clone())
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        "",
+                                        "createCollection",
+                                        "Duplicating the collection",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        state.open_prompt(
+                                            PromptKind::CloneCollection,
+                                            &format!(
+                                                "Duplicate {}.{} as: name [indexes]",
+                                                db_name, coll_name
+                                            ),
+                                            &format!("{}_copy indexes", coll_name),
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('V') => {
+                                if let Some(db_name) = state.current_database.clone()
+                                    && let Some(coll_name) =
+                                        state.get_selected_collection().map(|c| c.name.clone())
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "find",
+                                        "Diffing against a reference",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        let default = format!("{}.{}.jsonl", db_name, coll_name);
+                                        state.open_prompt(
+                                            PromptKind::ReferenceDiffPath,
+                                            "Reference export to diff against (JSON or NDJSON)",
+                                            &default,
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('p') => {
+                                if let Some(db_name) = state.current_database.clone()
+                                    && let Some(coll) = state.get_selected_collection()
+                                {
+                                    let default =
+                                        format!("changes.jsonl {}.{} 1x", db_name, coll.name);
+                                    state.open_prompt(
+                                        PromptKind::ReplayChanges,
+                                        "Replay a recording: file db[.coll] [1x|10x|max] [profile or URI]",
+                                        &default,
+                                    );
+                                }
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                state.select_next_coll();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                state.select_prev_coll();
+                            }
+                            KeyCode::Enter | KeyCode::Char('T') if !loader.is_busy() => {
+                                let tab = key.code == KeyCode::Char('T');
+                                open_collection(&state, &conn_service, &loader, tab);
+                            }
+                            KeyCode::Backspace => {
+                                state.set_screen(app::screen::Screen::DatabaseList);
+                            }
+                            KeyCode::Char('R') => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                if let (Some(db_name), Some(coll_name)) =
+                                    (state.current_database.clone(), coll_name)
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "find",
+                                        "Schema report",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        state.open_prompt(
+                                            PromptKind::SchemaReportPath,
+                                            "Schema report path (.md or .html)",
+                                            &format!("{}.{}.schema.md", db_name, coll_name),
+                                        );
+                                    }
+                                }
+                            }
+                            KeyCode::Char('t') if !loader.is_busy() => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                let db_name = state.current_database.clone();
+
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    if let Some(reason) = state
+                                        .permission_denial(&db_name, &coll_name, "find", "Tail")
+                                    {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        let client = client.clone();
+                                        loader.spawn(async move {
+                                            let result =
+                                                Tailer::start(&client, &db_name, &coll_name).await;
+                                            let source = match &result {
+                                                Ok((tailer, _)) if !tailer.uses_change_stream() => {
+                                                    "polling _id"
+                                                }
+                                                _ => "change stream",
+                                            };
+                                            Loaded::Tail {
+                                                db: db_name,
+                                                coll: coll_name,
+                                                source,
+                                                label: "Failed to start tail",
+                                                result: result.map(|(tailer, initial)| {
+                                                    (Box::new(tailer), initial)
+                                                }),
+                                            }
+                                        });
+                                    }
+                                }
+                            }
+                            KeyCode::Char('w') if !loader.is_busy() => {
+                                let coll_name =
+                                    state.get_selected_collection().map(|c| c.name.clone());
+                                let db_name = state.current_database.clone();
+
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "changeStream",
+                                        "Watch",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        // the filter belongs to the collection it was typed on
+                                        let filter = state.filter.clone().filter(|_| {
+                                            state.current_collection.as_ref() == Some(&coll_name)
+                                        });
+                                        let source = if filter.is_some() {
+                                            "change stream, all operations, filtered"
+                                        } else {
+                                            "change stream, all operations"
+                                        };
+                                        let client = client.clone();
+                                        loader.spawn(async move {
+                                            let result = Tailer::watch(
+                                                &client,
+                                                &db_name,
+                                                &coll_name,
+                                                filter.as_ref(),
+                                            )
+                                            .await;
+                                            Loaded::Tail {
+                                                db: db_name,
+                                                coll: coll_name,
+                                                source,
+                                                label: "Failed to start watch",
+                                                result: result
+                                                    .map(|tailer| (Box::new(tailer), Vec::new())),
+                                            }
+                                        });
+                                    }
+                                }
+                            }
+                            KeyCode::Char('W') if !loader.is_busy() => {
+                                if let Some(db_name) = state.current_database.clone() {
+                                    let scope = WatchScope::Database(db_name);
+                                    start_wide_watch(&mut state, &conn_service, &loader, scope);
+                                }
+                            }
+                            KeyCode::Char('r') => {
+                                // Refresh collections
+                                if let Some(db_name) = state.current_database.clone()
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    loader.spawn(async move {
+                                        let result = query_service.list_collections(&db_name).await;
+                                        Loaded::Collections {
+                                            db: db_name,
+                                            open: false,
+                                            result,
+                                        }
+                                    });
+                                }
+                            }
+
+                            _ => {}
+                        }
+                    }
+                }
+                app::screen::Screen::HealthCheck => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        run_health_check(&conn_service, &loader);
+                    }
+                    KeyCode::Enter | KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_screen(app::screen::Screen::DatabaseList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Atlas => match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        state.quit();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.select_prev_atlas();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.select_next_atlas();
+                    }
+                    KeyCode::Enter => {
+                        if state.atlas_project.is_some() {
+                            if state.use_selected_atlas_cluster() {
+                                state.set_error(None);
+                                state.set_screen(app::screen::Screen::Connection);
+                            } else {
+                                state.set_error(Some(
+                                    "Cluster has no connection string yet".to_string(),
+                                ));
+                            }
+                        } else if let Some(project) = state.get_selected_atlas_project().cloned()
+                            && let Some(config) = AtlasConfig::load()
+                            && !loader.is_busy()
+                        {
+                            loader.spawn(async move {
+                                let result =
+                                    AtlasService::new(config).list_clusters(&project.id).await;
+                                Loaded::AtlasClusters { project, result }
+                            });
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        if state.atlas_project.is_some() {
+                            state.leave_atlas_project();
+                        } else {
+                            state.set_screen(app::screen::Screen::Connection);
+                        }
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Aggregation => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.select_next_stage();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.select_prev_stage();
+                    }
+                    KeyCode::Char('J') => {
+                        state.move_selected_stage(true);
+                    }
+                    KeyCode::Char('K') => {
+                        state.move_selected_stage(false);
+                    }
+                    KeyCode::Char('a') => {
+                        let operators: Vec<&str> = aggregation::STAGE_TEMPLATES
+                            .iter()
+                            .map(|(name, _)| *name)
+                            .collect();
+                        let snippets: Vec<&str> = state
+                            .stage_snippets
+                            .iter()
+                            .map(|s| s.name.as_str())
+                            .collect();
+                        let title = if snippets.is_empty() {
+                            format!("Stage operator ({})", operators.join(" "))
+                        } else {
+                            format!(
+                                "Stage operator ({}) or snippet ({})",
+                                operators.join(" "),
+                                snippets.join(" ")
+                            )
+                        };
+                        state.open_prompt(PromptKind::AddStage, &title, "$match");
+                    }
+                    KeyCode::Char('i') => {
+                        state.open_prompt(
+                            PromptKind::ImportPipeline,
+                            "Import pipeline (paste mongosh/JSON, or a file path)",
+                            "",
+                        );
+                    }
+                    KeyCode::Enter => {
+                        if let Some(stage) = state.get_selected_stage() {
+                            let title = format!("{} stage body (JSON)", stage.operator);
+                            let body = stage.body.clone();
+                            state.open_prompt(PromptKind::EditStage, &title, &body);
+                            state.draft_preview = None;
+                            state.draft_requested = None;
+                        }
+                    }
+                    KeyCode::Char('d') => {
+                        state.remove_selected_stage();
+                    }
+                    KeyCode::Char(' ') => {
+                        state.toggle_selected_stage();
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        let upto = state.selected_stage_index;
+                        let db_name = state.current_database.clone();
+                        let coll_name = state.current_collection.clone();
+                        match aggregation::build_pipeline(&state.pipeline, upto) {
+                            Ok(pipeline) => {
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    state.set_error(None);
+                                    loader.spawn(async move {
+                                        let result = aggregation::preview(
+                                            &query_service,
+                                            &db_name,
+                                            &coll_name,
+                                            pipeline,
+                                        )
+                                        .await;
+                                        Loaded::AggregationPreview { upto, result }
+                                    });
+                                }
+                            }
+                            Err(e) => {
+                                state.set_error(Some(e));
+                            }
+                        }
+                    }
+                    KeyCode::Char('R') => {
+                        start_pipeline(&mut state, &conn_service, &tasks);
+                    }
+                    KeyCode::PageDown => {
+                        state.scroll_aggregation_down();
+                    }
+                    KeyCode::PageUp => {
+                        state.scroll_aggregation_up();
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DocumentView);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::About => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        load_compatibility(&state, &conn_service, &loader);
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_screen(app::screen::Screen::DatabaseList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Setup => {
+                    if let Some(form) = state.setup_form.as_mut() {
+                        match key.code {
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                state.quit();
+                            }
+                            KeyCode::Tab | KeyCode::Down => {
+                                form.next_field();
+                            }
+                            KeyCode::BackTab | KeyCode::Up => {
+                                form.prev_field();
+                            }
+                            KeyCode::Left => {
+                                form.cycle(-1);
+                            }
+                            KeyCode::Right => {
+                                form.cycle(1);
+                            }
+                            KeyCode::Char(c) => {
+                                form.push_char(c);
+                            }
+                            KeyCode::Backspace => {
+                                form.pop_char();
+                            }
+                            KeyCode::Enter if !form.is_last_step() => {
+                                form.next_field();
+                            }
+                            KeyCode::Enter => {
+                                finish_setup(&mut state, false);
+                            }
+                            KeyCode::Esc => {
+                                finish_setup(&mut state, true);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                app::screen::Screen::Schema => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.select_next_schema_field();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.select_prev_schema_field();
+                    }
+                    KeyCode::Char('n') => {
+                        let size = state.schema_sample_size.to_string();
+                        state.open_prompt(
+                            PromptKind::SchemaSampleSize,
+                            "Documents to sample for the schema",
+                            &size,
+                        );
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        load_schema(&state, &conn_service, &loader);
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_screen(app::screen::Screen::CollectionList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Import => match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        state.quit();
+                    }
+                    KeyCode::Esc => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DocumentView);
+                    }
+                    KeyCode::Tab => {
+                        state.import_form.complete();
+                    }
+                    KeyCode::Left | KeyCode::Right => {
+                        state.import_form.toggle_mode();
+                    }
+                    KeyCode::Enter if !state.import_form.is_previewed() => {
+                        state.import_form.load_preview();
+                    }
+                    KeyCode::Enter => {
+                        start_import(&mut state, &conn_service, &tasks);
+                    }
+                    KeyCode::Char(c) => {
+                        state.import_form.push_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        state.import_form.pop_char();
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::BulkUpdate => match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        state.quit();
+                    }
+                    KeyCode::Esc => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DocumentView);
+                    }
+                    KeyCode::Tab | KeyCode::BackTab => {
+                        state.bulk_update.next_field();
+                    }
+                    KeyCode::PageDown => {
+                        state.bulk_update.scroll += 10;
+                    }
+                    KeyCode::PageUp => {
+                        state.bulk_update.scroll = state.bulk_update.scroll.saturating_sub(10);
+                    }
+                    KeyCode::Enter if state.bulk_update.is_previewed() => {
+                        let matched = state.bulk_update.preview.as_ref().map_or(0, |p| p.matched);
+                        if matched == 0 {
+                            state.set_message(Some("No documents match the filter".to_string()));
+                        } else {
+                            let label = format!(
+                                "Update {} document(s) of {}? (y/N)",
+                                matched,
+                                state.current_collection.as_deref().unwrap_or("")
+                            );
+                            state.open_prompt(PromptKind::ConfirmBulkUpdate, &label, "");
+                        }
+                    }
+                    KeyCode::Enter if !loader.is_busy() => {
+                        let db_name = state.current_database.clone();
+                        let coll_name = state.current_collection.clone();
+                        match state.bulk_update.parse() {
+                            Ok((filter, update)) => {
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let client = client.clone();
+                                    let form = &state.bulk_update;
+                                    let inputs = (form.filter.clone(), form.update.clone());
+                                    state.set_error(None);
+                                    loader.spawn(async move {
+                                        Loaded::BulkUpdatePreview(
+                                            bulk_update::preview(
+                                                &client, &db_name, &coll_name, inputs, filter,
+                                                update,
+                                            )
+                                            .await,
+                                        )
+                                    });
+                                }
+                            }
+                            Err(e) => {
+                                state.set_error(Some(e));
+                            }
+                        }
+                    }
+                    KeyCode::Char(c) => {
+                        state.bulk_update.push_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        state.bulk_update.pop_char();
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Search => match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        state.quit();
+                    }
+                    KeyCode::Esc => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DocumentView);
+                    }
+                    KeyCode::Tab => {
+                        state.search_form.next_field();
+                    }
+                    KeyCode::Left => {
+                        state.search_form.cycle_option(false);
+                    }
+                    KeyCode::Right => {
+                        state.search_form.cycle_option(true);
+                    }
+                    KeyCode::Up => {
+                        state.select_prev_hit();
+                    }
+                    KeyCode::Down => {
+                        state.select_next_hit();
+                    }
+                    KeyCode::PageDown => {
+                        state.scroll_search_down();
+                    }
+                    KeyCode::PageUp => {
+                        state.scroll_search_up();
+                    }
+                    KeyCode::Enter if !loader.is_busy() => {
+                        let db_name = state.current_database.clone();
+                        let coll_name = state.current_collection.clone();
+                        if state.search_form.query.trim().is_empty() {
+                            state.set_error(Some("Enter a search query".to_string()));
+                        } else if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                            && let Some(client) = conn_service.get_client()
+                        {
+                            let query_service = QueryService::new(client.clone());
+                            let stage = search::build_stage(&state.search_form);
+                            state.set_error(None);
+                            loader.spawn(async move {
+                                Loaded::SearchResults(
+                                    search::run(&query_service, &db_name, &coll_name, stage).await,
+                                )
+                            });
+                        }
+                    }
+                    KeyCode::Char(c) => {
+                        state.search_form.push_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        state.search_form.pop_char();
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Sharding => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.select_next_sharding();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.select_prev_sharding();
+                    }
+                    KeyCode::Tab => {
+                        state.toggle_sharding_focus();
+                    }
+                    KeyCode::Char('z') => {
+                        if let Some(status) = &state.sharding {
+                            let template = status.zone_template();
+                            state.open_prompt(
+                                PromptKind::AddZoneRange,
+                                "Zone range: ZONE {min} {max} over the shard key",
+                                &template,
+                            );
+                        }
+                    }
+                    KeyCode::Char('d') if state.sharding_focus_zones => {
+                        if let Some(range) = state.get_selected_zone() {
+                            let label =
+                                format!("Remove {} from zone {}? (y/N)", range.range(), range.zone);
+                            state.open_prompt(PromptKind::ConfirmRemoveZone, &label, "");
+                        }
+                    }
+                    KeyCode::Char('r') => {
+                        if let Some(status) = &state.sharding {
+                            refresh_sharding(
+                                &conn_service,
+                                &loader,
+                                status.db_and_collection(),
+                                None,
+                            );
+                        }
+                    }
+                    KeyCode::Char('b') => {
+                        if let Some(status) = &state.sharding {
+                            let label = if status.balancer_enabled() {
+                                "Stop the balancer for the whole cluster? (y/N)"
+                            } else {
+                                "Start the balancer? (y/N)"
+                            };
+                            state.open_prompt(PromptKind::ConfirmBalancer, label, "");
+                        }
+                    }
+                    KeyCode::Char('s') if !state.sharding_focus_zones => {
+                        if let Some(chunk) = state.get_selected_chunk() {
+                            let label =
+                                format!("Split chunk {} at its median? (y/N)", chunk.range());
+                            state.open_prompt(PromptKind::ConfirmSplit, &label, "");
+                        }
+                    }
+                    KeyCode::Char('m') if !state.sharding_focus_zones => {
+                        if let (Some(status), Some(chunk)) =
+                            (&state.sharding, state.get_selected_chunk())
+                        {
+                            let targets: Vec<&str> = status
+                                .shards
+                                .iter()
+                                .map(String::as_str)
+                                .filter(|s| *s != chunk.shard)
+                                .collect();
+                            let label = format!(
+                                "Move chunk {} from {} to shard ({})",
+                                chunk.range(),
+                                chunk.shard,
+                                targets.join(", ")
+                            );
+                            state.open_prompt(PromptKind::MoveChunkTarget, &label, "");
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::CollectionList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::ReplicaSet => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        state.set_error(None);
+                        load_replica_set(&conn_service, &loader);
+                        last_replica_set_poll = Instant::now();
+                    }
+                    KeyCode::Char(' ') => {
+                        state.replica_set_paused = !state.replica_set_paused;
+                        last_replica_set_poll = Instant::now();
+                    }
+                    KeyCode::Char('s') => {
+                        if let Some(primary) = state
+                            .replica_set
+                            .as_ref()
+                            .and_then(|rs| rs.primary())
+                            .map(|p| p.name.clone())
+                        {
+                            state.open_prompt(
+                                PromptKind::ConfirmStepDown,
+                                &format!(
+                                    "Step down {} for {}s? Type its host:port to confirm",
+                                    primary, STEPDOWN_SECS
+                                ),
+                                "",
+                            );
+                        } else {
+                            state.set_error(Some("No primary to step down".to_string()));
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DatabaseList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::EventLog => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.scroll_event_log_up();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.scroll_event_log_down();
+                    }
+                    KeyCode::Char('G') | KeyCode::End => {
+                        state.event_log_scroll = 0;
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.toggle_panel(app::screen::Screen::EventLog);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Timings => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.select_next_timing();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.select_prev_timing();
+                    }
+                    KeyCode::Char('c') => {
+                        conn_service.query_timings().clear();
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.toggle_panel(app::screen::Screen::Timings);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Tasks => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.select_next_task();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.select_prev_task();
+                    }
+                    KeyCode::Char('c') => {
+                        if let Some(task) = state.get_selected_task() {
+                            tasks.cancel(task.id);
+                        }
+                    }
+                    KeyCode::Char('x') => {
+                        tasks.clear_finished();
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.toggle_panel(app::screen::Screen::Tasks);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Compare => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.select_next_compare();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.select_prev_compare();
+                    }
+                    KeyCode::Char('m') => {
+                        state.toggle_compare_mismatches();
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        run_count_comparison(&state, &conn_service, &compare_service, &loader);
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DatabaseList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Users if state.role_picker.is_some() => {
+                    if let Some(picker) = state.role_picker.as_mut() {
+                        match key.code {
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                state.quit();
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => picker.select_next(),
+                            KeyCode::Up | KeyCode::Char('k') => picker.select_prev(),
+                            KeyCode::Char(' ') => picker.toggle(),
+                            KeyCode::Esc => {
+                                state.role_picker = None;
+                            }
+                            KeyCode::Enter if !loader.is_busy() => {
+                                if let Some(picker) = state.role_picker.take() {
+                                    let change = UserChange::Create(picker);
+                                    run_user_change(&mut state, &conn_service, &loader, change);
+                                }
                             }
                             _ => {}
                         }
+                    }
+                }
+                app::screen::Screen::Users => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.apply_motion(keys::Motion::Down(1), 0);
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.apply_motion(keys::Motion::Up(1), 0);
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        if let Some(db_name) = state.users_database.clone() {
+                            state.set_error(None);
+                            load_users(&conn_service, &loader, db_name, None);
+                        }
+                    }
+                    KeyCode::Char('n') => {
+                        let db_name = state.users_database.clone().unwrap_or_default();
+                        if let Some(reason) =
+                            state.permission_denial(&db_name, "", "createUser", "Creating users")
+                        {
+                            state.set_error(Some(reason));
+                        } else {
+                            state.open_prompt(PromptKind::NewUserName, "New user name", "");
+                        }
+                    }
+                    KeyCode::Char('p') => {
+                        if let Some(user) = state.get_selected_user().map(|u| u.user.clone()) {
+                            state.open_prompt(
+                                PromptKind::ChangeUserPassword,
+                                &format!("New password for {}", user),
+                                "",
+                            );
+                        }
+                    }
+                    KeyCode::Char('D') => {
+                        let db_name = state.users_database.clone().unwrap_or_default();
+                        if let Some(reason) =
+                            state.permission_denial(&db_name, "", "dropUser", "Dropping users")
+                        {
+                            state.set_error(Some(reason));
+                        } else if let Some(user) =
+                            state.get_selected_user().map(|u| u.user.clone())
+                        {
+                            state.open_delete_prompt(
+                                PromptKind::ConfirmDropUser,
+                                &format!("Drop user {}? Type its name to confirm", user),
+                                &user,
+                            );
+                        }
+                    }
+                    KeyCode::Char('R') => {
+                        let db_name = state.users_database.clone().unwrap_or_default();
+                        if let Some(reason) =
+                            state.permission_denial(&db_name, "", "viewRole", "Browsing roles")
+                        {
+                            state.set_error(Some(reason));
+                        } else {
+                            state.set_screen(app::screen::Screen::Roles);
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DatabaseList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Roles => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.apply_motion(keys::Motion::Down(1), 0);
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.apply_motion(keys::Motion::Up(1), 0);
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        if let Some(db_name) = state.users_database.clone() {
+                            state.set_error(None);
+                            load_users(&conn_service, &loader, db_name, None);
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_screen(app::screen::Screen::Users);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Operations => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.apply_motion(keys::Motion::Down(1), 0);
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.apply_motion(keys::Motion::Up(1), 0);
+                    }
+                    KeyCode::Char(' ') => {
+                        state.operations_paused = !state.operations_paused;
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        load_operations(&conn_service, &loader);
+                        last_operations_poll = Instant::now();
+                    }
+                    KeyCode::Char('l') => {
+                        let current = state.operations_min_secs.to_string();
+                        state.open_prompt(
+                            PromptKind::OperationsMinSecs,
+                            "Show operations running at least this many seconds (0 for all)",
+                            if current == "0" { "5" } else { &current },
+                        );
+                    }
+                    KeyCode::Char('K') => {
+                        if let Some(reason) = state.read_only_denial("Killing operations") {
+                            state.set_error(Some(reason));
+                        } else if let Some(op) = state.get_selected_operation() {
+                            let title = format!(
+                                "Kill {} on {} running {}? Type its opid {} to confirm",
+                                op.op,
+                                op.namespace,
+                                ui::tasks::format_elapsed(op.running),
+                                op.opid_label()
+                            );
+                            state.open_prompt(PromptKind::ConfirmKillOp, &title, "");
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::DatabaseList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::ReferenceDiff => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.apply_motion(keys::Motion::Down(1), 0);
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.apply_motion(keys::Motion::Up(1), 0);
+                    }
+                    KeyCode::Char('r') if !loader.is_busy() => {
+                        if let Some(path) = state.reference_diff.as_ref().map(|d| d.path.clone()) {
+                            start_reference_diff(&mut state, &conn_service, &loader, &path);
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Backspace => {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::CollectionList);
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::Tail => match key.code {
+                    KeyCode::Char('q') => {
+                        state.quit();
+                    }
+                    KeyCode::Char(' ') => {
+                        state.toggle_tail_pause();
+                        state.set_error(None);
+                        if let Some(active) = tailer.as_mut() {
+                            if state.tail_paused {
+                                active.pause();
+                            } else if let Err(e) = active.resume().await {
+                                state.set_error(Some(e.to_string()));
+                                state.toggle_tail_pause();
+                            }
+                        }
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        state.scroll_tail_up();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        state.scroll_tail_down();
+                    }
+                    KeyCode::Char('G') => {
+                        state.follow_tail();
+                    }
+                    KeyCode::Char('c') => {
+                        state.clear_tail();
+                    }
+                    KeyCode::Char('R') => {
+                        if let Some(message) = state.stop_tail_recording() {
+                            state.set_message(Some(message));
+                        } else {
+                            let name = match &state.tail_scope {
+                                WatchScope::Collection => {
+                                    state.current_namespace().unwrap_or_default()
+                                }
+                                WatchScope::Database(db) => db.clone(),
+                                WatchScope::Deployment => "deployment".to_string(),
+                            };
+                            state.open_prompt(
+                                PromptKind::RecordChangesPath,
+                                "Record events to (one extended JSON event per line)",
+                                &format!("{}-changes.jsonl", name),
+                            );
+                        }
+                    }
+                    KeyCode::Char('n') if state.tail_scope != WatchScope::Collection => {
+                        state.cycle_tail_namespace();
+                    }
+                    KeyCode::Backspace | KeyCode::Esc => {
+                        tailer = None;
+                        state.set_error(None);
+                        if let Some(message) = state.stop_tail_recording() {
+                            state.set_message(Some(message));
+                        }
+                        if state.tail_scope == WatchScope::Deployment {
+                            state.set_screen(app::screen::Screen::DatabaseList);
+                        } else {
+                            state.set_screen(app::screen::Screen::CollectionList);
+                        }
+                    }
+                    _ => {}
+                },
+                app::screen::Screen::DocumentView => {
+                    if state.pending_edit.is_some() {
+                        match key.code {
+                            KeyCode::Char('y') | KeyCode::Enter => {
+                                let db_name = state.current_database.clone();
+                                let coll_name = state.current_collection.clone();
+                                if let Some(edit) = state.take_pending_edit()
+                                    && let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    if state.dry_run {
+                                        let namespace = format!("{}.{}", db_name, coll_name);
+                                        let filter = mongodb::bson::doc! {
+                                            "_id": edit.edited.get("_id").cloned(),
+                                        };
+                                        let summary = WouldExecute::write(
+                                            &namespace,
+                                            "replaceOne",
+                                            &filter,
+                                            dry_run::extended_json(&edit.edited),
+                                        );
+                                        let count = Some((db_name, coll_name, Some(filter)));
+                                        show_dry_run(
+                                            &mut state,
+                                            &conn_service,
+                                            &loader,
+                                            summary,
+                                            count,
+                                        );
+                                    } else {
+                                        let query_service = QueryService::new(client.clone());
+                                        let count = edit.changes.len();
+                                        loader.spawn(async move {
+                                            let result = query_service
+                                                .replace_document(&db_name, &coll_name, edit.edited)
+                                                .await
+                                                .map(|_| {
+                                                    format!("Saved {} changed field(s)", count)
+                                                });
+                                            Loaded::Write(result)
+                                        });
+                                    }
+                                }
+                            }
+                            KeyCode::Char('n') | KeyCode::Esc => {
+                                state.take_pending_edit();
+                                state.set_message(Some("Edit discarded".to_string()));
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(tree) = state.plan_tree.as_mut() {
+                        match key.code {
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                tree.select_next();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                tree.select_prev();
+                            }
+                            KeyCode::Enter | KeyCode::Char(' ') => {
+                                tree.toggle_collapsed();
+                            }
+                            KeyCode::Esc | KeyCode::Char('P') => {
+                                state.close_plan_tree();
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(picker) = state.projection_picker.as_mut() {
+                        match key.code {
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                picker.select_next();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                picker.select_prev();
+                            }
+                            KeyCode::Char(' ') => {
+                                picker.toggle();
+                            }
+                            KeyCode::Char('m') => {
+                                picker.toggle_mode();
+                            }
+                            KeyCode::Char('c') => {
+                                picker.clear();
+                            }
+                            KeyCode::Enter if !loader.is_busy() => {
+                                state.projection = picker.to_projection();
+                                state.close_projection_picker();
+                                if let Some(params) = state.query_params()
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    loader.spawn(async move {
+                                        let (db, coll) =
+                                            (params.database.clone(), params.collection.clone());
+                                        let result = query_service.find(params).await;
+                                        Loaded::Documents {
+                                            db,
+                                            coll,
+                                            label: "Projection failed",
+                                            result,
+                                        }
+                                    });
+                                }
+                            }
+                            KeyCode::Esc => {
+                                state.close_projection_picker();
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(panel) = state.distinct_values.as_mut() {
+                        match key.code {
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                panel.select_next();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                panel.select_prev();
+                            }
+                            KeyCode::Char('s') => {
+                                panel.toggle_sort();
+                            }
+                            KeyCode::Esc | KeyCode::Char('d') => {
+                                state.distinct_values = None;
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(builder) = state.query_builder.as_mut() {
+                        match key.code {
+                            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                builder.add_condition();
+                            }
+                            KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                builder.remove_condition();
+                            }
+                            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                builder.push_char(c);
+                            }
+                            KeyCode::Backspace => {
+                                builder.pop_char();
+                            }
+                            KeyCode::Tab => {
+                                builder.next_field();
+                            }
+                            KeyCode::BackTab => {
+                                builder.prev_field();
+                            }
+                            KeyCode::Right if builder.focus == BuilderField::Operator => {
+                                builder.cycle_operator(true);
+                            }
+                            KeyCode::Left if builder.focus == BuilderField::Operator => {
+                                builder.cycle_operator(false);
+                            }
+                            KeyCode::Down => {
+                                builder.select_next();
+                            }
+                            KeyCode::Up => {
+                                builder.select_prev();
+                            }
+                            KeyCode::F(2) => {
+                                builder.toggle_any();
+                            }
+                            KeyCode::Enter if !loader.is_busy() => match builder.filter() {
+                                Ok(filter) => {
+                                    state.close_query_builder();
+                                    state.query_input.set(filter);
+                                    run_query(&mut state, &conn_service, &loader);
+                                }
+                                Err(e) => {
+                                    state.set_error(Some(e));
+                                }
+                            },
+                            KeyCode::Esc => {
+                                state.close_query_builder();
+                            }
+                            _ => {}
+                        }
+                    } else if state.geo_mode {
+                        match key.code {
+                            KeyCode::Char(c) => {
+                                state.push_geo_char(c);
+                            }
+                            KeyCode::Backspace => {
+                                state.pop_geo_char();
+                            }
+                            KeyCode::Esc => {
+                                state.exit_geo_mode();
+                            }
+                            KeyCode::Enter => match state.apply_geo_query() {
+                                Ok(_) => {
+                                    state.exit_geo_mode();
+                                    if let Some(params) = state.query_params()
+                                        && let Some(client) = conn_service.get_client()
+                                    {
+                                        let query_service = QueryService::new(client.clone());
+                                        loader.spawn(async move {
+                                            let (db, coll) = (
+                                                params.database.clone(),
+                                                params.collection.clone(),
+                                            );
+                                            let result = query_service.find(params).await;
+                                            Loaded::Documents {
+                                                db,
+                                                coll,
+                                                label: "Geo query failed",
+                                                result,
+                                            }
+                                        });
+                                    }
+                                }
+                                Err(e) => {
+                                    state.set_error(Some(e));
+                                }
+                            },
+                            _ => {}
+                        }
+                    } else if state.show_saved_queries {
+                        match key.code {
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                state.select_next_saved_query();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                state.select_prev_saved_query();
+                            }
+                            KeyCode::Enter => {
+                                state.use_saved_query();
+                                run_query(&mut state, &conn_service, &loader);
+                            }
+                            KeyCode::Char('e') => {
+                                state.use_saved_query();
+                                state.enter_query_mode();
+                            }
+                            KeyCode::Char('d') => {
+                                if let Some(saved) = state.delete_saved_query() {
+                                    match state.saved_queries.save() {
+                                        Ok(()) => state.set_message(Some(format!(
+                                            "Deleted saved query {}",
+                                            saved.name
+                                        ))),
+                                        Err(e) => state.set_error(Some(format!(
+                                            "Failed to save queries: {}",
+                                            e
+                                        ))),
+                                    }
+                                }
+                            }
+                            KeyCode::Esc => {
+                                state.close_saved_queries();
+                            }
+                            _ => {}
+                        }
+                    } else if state.query_mode && state.show_query_history {
+                        match key.code {
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                state.select_next_query_history();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                state.select_prev_query_history();
+                            }
+                            KeyCode::Tab => {
+                                state.recall_query();
+                            }
+                            KeyCode::Enter => {
+                                state.recall_query();
+                                run_query(&mut state, &conn_service, &loader);
+                            }
+                            KeyCode::Esc => {
+                                state.close_query_history();
+                            }
+                            _ => {}
+                        }
+                    } else if state.query_mode {
+                        // Advanced query mode - JSON input
+                        match key.code {
+                            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                state.open_query_history();
+                            }
+                            KeyCode::Esc => {
+                                state.exit_query_mode();
+                                state.clear_query();
+                            }
+                            KeyCode::Enter => {
+                                run_query(&mut state, &conn_service, &loader);
+                            }
+                            _ => {
+                                state.query_input.handle_key(key);
+                            }
+                        }
+                    } else if state.filter_mode {
+                        // Simple search mode - live filtering
+                        match key.code {
+                            KeyCode::Esc => {
+                                state.exit_filter_mode();
+                                state.clear_filter();
+                                let label = "Failed to reload";
+                                reload_documents_without_filter(&state, &conn_service, &loader, label);
+                            }
+                            KeyCode::Enter => {
+                                state.exit_filter_mode();
+                            }
+                            _ => {
+                                if state.filter_input.handle_key(key) == Edit::Changed {
+                                    apply_dynamic_filter(&state, &conn_service, &loader);
+                                }
+                            }
+                        }
+                    } else if state.content_search_mode {
+                        // Searching the text of the selected document
+                        match key.code {
+                            KeyCode::Esc => {
+                                state.exit_content_search();
+                                state.content_search.clear();
+                            }
+                            KeyCode::Enter => {
+                                state.exit_content_search();
+                            }
+                            _ => {
+                                if state.content_search.handle_key(key) == Edit::Changed {
+                                    let height = content_height(&state, terminal.size()?.height);
+                                    state.content_match = 0;
+                                    jump_to_match(&mut state, 0, height);
+                                }
+                            }
+                        }
+                    } else if state.content_focus && state.fold_pending {
+                        // fold commands after z, as in vim
+                        state.fold_pending = false;
+                        let height = content_height(&state, terminal.size()?.height);
+                        match key.code {
+                            KeyCode::Char('a') => {
+                                fold_at_cursor(&mut state, None, height);
+                            }
+                            KeyCode::Char('o') => {
+                                fold_at_cursor(&mut state, Some(false), height);
+                            }
+                            KeyCode::Char('c') => {
+                                fold_at_cursor(&mut state, Some(true), height);
+                            }
+                            KeyCode::Char('M') => {
+                                if let Some(doc) = state.get_selected_document() {
+                                    let tree = ui::highlight::document_tree(
+                                        doc,
+                                        &BTreeSet::new(),
+                                        state.value_format,
+                                        &state.theme,
+                                    );
+                                    state.set_folds(tree.folds.into_iter().flatten().collect());
+                                    let last = content_tree(&state).folds.len().saturating_sub(1);
+                                    let cursor = state.content_cursor.min(last);
+                                    state.move_content_cursor(cursor, height);
+                                }
+                            }
+                            KeyCode::Char('R') => {
+                                state.set_folds(BTreeSet::new());
+                            }
+                            _ => {}
+                        }
+                    } else if !state.table_view
+                        && matches!(key.code, KeyCode::Left | KeyCode::Right)
+                        && key.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        // Ctrl+Left/Right move the divider between the panes
+                        let step = if key.code == KeyCode::Right { 5 } else { -5 };
+                        state.resize_split(step);
+                        save_split(&mut state);
+                    } else if !state.table_view && key.code == KeyCode::Char('Z') {
+                        state.toggle_content_maximized();
+                    } else if state.content_focus {
+                        // The content pane has the keys
+                        let height = content_height(&state, terminal.size()?.height);
+                        let last = content_tree(&state).folds.len().saturating_sub(1);
+                        let cursor = state.content_cursor.min(last);
+                        match key.code {
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                state.move_content_cursor((cursor + 1).min(last), height);
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                state.move_content_cursor(cursor.saturating_sub(1), height);
+                            }
+                            KeyCode::PageDown => {
+                                state.move_content_cursor((cursor + height).min(last), height);
+                            }
+                            KeyCode::PageUp => {
+                                state.move_content_cursor(cursor.saturating_sub(height), height);
+                            }
+                            KeyCode::Char('z') => {
+                                state.fold_pending = true;
+                            }
+                            KeyCode::Char('c') => {
+                                state.toggle_canonical();
+                            }
+                            KeyCode::Char('y') => {
+                                let fields = content_tree(&state).fields;
+                                match fields.get(cursor).cloned().flatten() {
+                                    Some(path) => copy_field(&mut state, &path),
+                                    None => copy_document(&mut state),
+                                }
+                            }
+                            KeyCode::Char('Y') => {
+                                copy_field(&mut state, "_id");
+                            }
+                            KeyCode::Enter => {
+                                fold_at_cursor(&mut state, None, height);
+                            }
+                            KeyCode::Char('/') => {
+                                state.enter_content_search();
+                            }
+                            KeyCode::Char('n') => {
+                                jump_to_match(&mut state, 1, height);
+                            }
+                            KeyCode::Char('N') => {
+                                jump_to_match(&mut state, -1, height);
+                            }
+                            KeyCode::Esc if !state.content_search.is_empty() => {
+                                state.content_search.clear();
+                            }
+                            KeyCode::Esc | KeyCode::Tab => {
+                                state.unfocus_content();
+                            }
+                            _ => {}
+                        }
+                    } else {
+                        // Normal navigation mode
+                        match key.code {
+                            KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                state.quit();
+                            }
+                            KeyCode::Tab if !state.table_view => {
+                                state.focus_content();
+                            }
+                            KeyCode::Char('f') => {
+                                state.enter_filter_mode();
+                            }
+                            KeyCode::Char('/') => {
+                                state.enter_query_mode();
+                            }
+                            KeyCode::Char('g') if !loader.is_busy() => {
+                                let db_name = state.current_database.clone();
+                                let coll_name = state.current_collection.clone();
+
+                                if let (Some(db), Some(coll)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    loader.spawn(async move {
+                                        let result = query_service
+                                            .list_indexes(&db, &coll)
+                                            .await
+                                            .map(|indexes| geo::find_2dsphere_field(&indexes));
+                                        Loaded::GeoField { db, coll, result }
+                                    });
+                                }
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                state.select_next_doc();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                state.select_prev_doc();
+                            }
+                            KeyCode::PageDown => {
+                                state.scroll_doc_down();
+                            }
+                            KeyCode::PageUp => {
+                                state.scroll_doc_up();
+                            }
+                            KeyCode::Backspace => {
+                                state.set_screen(app::screen::Screen::CollectionList);
+                            }
+                            KeyCode::Esc if state.show_scan_warning => {
+                                state.toggle_scan_warning();
+                            }
+                            // a page still loading would land in the wrong tab
+                            KeyCode::Char('w')
+                                if key.modifiers.contains(KeyModifiers::CONTROL)
+                                    && !loader.is_busy() =>
+                            {
+                                let closed = state.current_namespace();
+                                if state.close_tab() {
+                                    state.set_message(closed.map(|ns| format!("Closed {}", ns)));
+                                } else {
+                                    state.set_message(Some("This is the only tab".to_string()));
+                                }
+                            }
+                            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {}
+                            KeyCode::Char('w') => {
+                                state.toggle_scan_warning();
+                            }
+                            KeyCode::Char('b') => {
+                                state.toggle_document_sizes();
+                            }
+                            KeyCode::Char('t') => {
+                                state.toggle_table_view();
+                            }
+                            KeyCode::Char('c') => {
+                                state.toggle_canonical();
+                            }
+                            KeyCode::Char('y') if state.table_view => {
+                                if let Some(column) = state.selected_column_name() {
+                                    let column = column.to_string();
+                                    copy_field(&mut state, &column);
+                                }
+                            }
+                            KeyCode::Char('y') => {
+                                copy_document(&mut state);
+                            }
+                            KeyCode::Char('Y') => {
+                                copy_field(&mut state, "_id");
+                            }
+                            KeyCode::Left | KeyCode::Char('h') if state.table_view => {
+                                state.select_prev_column();
+                            }
+                            KeyCode::Right | KeyCode::Char('l') if state.table_view => {
+                                state.select_next_column();
+                            }
+                            KeyCode::Char('<') | KeyCode::Char('>') if state.table_view => {
+                                state.move_column(key.code == KeyCode::Char('>'));
+                                save_columns(&mut state);
+                            }
+                            KeyCode::Char('x') if state.table_view => {
+                                state.remove_column();
+                                save_columns(&mut state);
+                            }
+                            KeyCode::Char('+') if state.table_view => {
+                                state.open_prompt(
+                                    PromptKind::AddColumn,
+                                    "Add column (field path, e.g. address.city)",
+                                    "",
+                                );
+                            }
+                            KeyCode::Enter if state.table_view => {
+                                state.inspect_cell();
+                            }
+                            KeyCode::Esc if state.inspected_column.is_some() => {
+                                state.close_inspector();
+                            }
+                            KeyCode::Char('F') if state.table_view => {
+                                state.toggle_frozen_column();
+                            }
+                            KeyCode::Char('R') if state.table_view => {
+                                state.reset_columns();
+                                save_columns(&mut state);
+                                state.set_message(Some(format!(
+                                    "Columns inferred from the first {} document(s)",
+                                    state.documents.len().min(table::COLUMN_SAMPLE_SIZE)
+                                )));
+                            }
+                            KeyCode::Char('X') if state.table_view => {
+                                let namespace = state.current_namespace().unwrap_or_default();
+                                state.open_prompt(
+                                    PromptKind::ExportTablePath,
+                                    "Export table to (.csv, .tsv or .md)",
+                                    &format!("{}.csv", namespace),
+                                );
+                            }
+                            KeyCode::Char('S') => {
+                                if state.current_query_json().is_some() {
+                                    state.open_prompt(
+                                        PromptKind::SaveQueryName,
+                                        "Save the current query as",
+                                        "",
+                                    );
+                                } else {
+                                    state.set_error(Some(
+                                        "No filter, sort or projection to save".to_string(),
+                                    ));
+                                }
+                            }
+                            KeyCode::Char('Q') => {
+                                state.open_saved_queries();
+                            }
+                            KeyCode::Char('X') => {
+                                let namespace = state.current_namespace().unwrap_or_default();
+                                state.open_export_dialog(ExportDialog::for_documents(&namespace));
+                            }
+                            KeyCode::Char('o') if state.table_view => {
+                                state.sort_page_by_column();
+                            }
+                            KeyCode::Char(']') if !loader.is_busy() => {
+                                match state.next_page_cursor() {
+                                    Ok(cursor) => {
+                                        let page = state.current_page + 1;
+                                        load_page(&mut state, &conn_service, &loader, page, cursor);
+                                    }
+                                    Err(e) => state.set_message(Some(e)),
+                                }
+                            }
+                            KeyCode::Char('[') if !loader.is_busy() => {
+                                match state.prev_page_cursor() {
+                                    Ok(cursor) => {
+                                        let page = state.current_page - 1;
+                                        load_page(&mut state, &conn_service, &loader, page, cursor);
+                                    }
+                                    Err(e) => state.set_message(Some(e)),
+                                }
+                            }
+                            KeyCode::Char('O') if state.table_view && !loader.is_busy() => {
+                                if state.server_sort_by_column()
+                                    && let Some(params) = state.query_params()
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    loader.spawn(async move {
+                                        let (db, coll) =
+                                            (params.database.clone(), params.collection.clone());
+                                        let result = query_service.find(params).await;
+                                        Loaded::Documents {
+                                            db,
+                                            coll,
+                                            label: "Sort failed",
+                                            result,
+                                        }
+                                    });
+                                }
+                            }
+                            KeyCode::Char('p') if !loader.is_busy() => {
+                                let db_name = state.current_database.clone();
+                                let coll_name = state.current_collection.clone();
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    loader.spawn(async move {
+                                        let result = query_service
+                                            .sample_documents(
+                                                &db_name,
+                                                &coll_name,
+                                                schema::DEFAULT_SAMPLE_SIZE,
+                                            )
+                                            .await
+                                            .map(|documents| {
+                                                projection::sampled_fields(&documents)
+                                            });
+                                        Loaded::ProjectionFields {
+                                            db: db_name,
+                                            coll: coll_name,
+                                            result,
+                                        }
+                                    });
+                                }
+                            }
+                            KeyCode::Char('v') if !loader.is_busy() => {
+                                let db_name = state.current_database.clone();
+                                let coll_name = state.current_collection.clone();
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    loader.spawn(async move {
+                                        let result = query_service
+                                            .sample_documents(
+                                                &db_name,
+                                                &coll_name,
+                                                schema::DEFAULT_SAMPLE_SIZE,
+                                            )
+                                            .await
+                                            .map(|documents| {
+                                                projection::sampled_fields(&documents)
+                                            });
+                                        Loaded::BuilderFields {
+                                            db: db_name,
+                                            coll: coll_name,
+                                            result,
+                                        }
+                                    });
+                                }
+                            }
+                            KeyCode::Char('P') if !loader.is_busy() => {
+                                if let Some(params) = state.page_params(&state.page_cursor())
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let query_service = QueryService::new(client.clone());
+                                    loader.spawn(async move {
+                                        Loaded::Explain(query_service.explain_query(&params).await)
+                                    });
+                                }
+                            }
+                            KeyCode::Char('a') => {
+                                if let (Some(db_name), Some(coll_name)) =
+                                    (&state.current_database, &state.current_collection)
+                                {
+                                    let namespace = format!("{}.{}", db_name, coll_name);
+                                    state.open_aggregation(namespace);
+                                }
+                            }
+                            KeyCode::Char('s') => {
+                                let db_name = state.current_database.clone();
+                                let coll_name = state.current_collection.clone();
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                                    && let Some(client) = conn_service.get_client()
+                                {
+                                    let client = client.clone();
+                                    loader.spawn(async move {
+                                        Loaded::SearchIndexes(
+                                            search::list_indexes(&client, &db_name, &coll_name)
+                                                .await,
+                                        )
+                                    });
+                                }
+                            }
+                            KeyCode::Char('e') => {
+                                let db_name = state.current_database.clone().unwrap_or_default();
+                                let coll_name =
+                                    state.current_collection.clone().unwrap_or_default();
+                                if let Some(reason) = state
+                                    .permission_denial(&db_name, &coll_name, "update", "Editing")
+                                {
+                                    state.set_error(Some(reason));
+                                } else if let Some(original) =
+                                    state.get_selected_document().cloned()
+                                {
+                                    // hand the terminal to the editor until it exits
+                                    disable_raw_mode()?;
+                                    execute!(
+                                        terminal.backend_mut(),
+                                        LeaveAlternateScreen,
+                                        DisableMouseCapture,
+                                        DisableBracketedPaste
+                                    )?;
+                                    let edited = editor::edit_document(&original);
+                                    enable_raw_mode()?;
+                                    execute!(
+                                        terminal.backend_mut(),
+                                        EnterAlternateScreen,
+                                        EnableMouseCapture,
+                                        EnableBracketedPaste
+                                    )?;
+                                    terminal.clear()?;
+
+                                    match edited {
+                                        Ok(edited) if edited.get("_id") != original.get("_id") => {
+                                            state.set_error(Some(
+                                                "_id cannot be changed; edit discarded".to_string(),
+                                            ));
+                                        }
+                                        Ok(edited) => {
+                                            let changes = editor::diff(&original, &edited);
+                                            if changes.is_empty() {
+                                                state.set_message(Some("No changes".to_string()));
+                                            } else {
+                                                state.review_edit(PendingEdit { edited, changes });
+                                            }
+                                        }
+                                        Err(e) => {
+                                            state.set_error(Some(e.to_string()));
+                                        }
+                                    }
+                                }
+                            }
+                            KeyCode::Char('L') => {
+                                let title = format!(
+                                    "Documents per page (cap {}, append ! to override)",
+                                    state.limits.max_page_size
+                                );
+                                let default = state.page_size.to_string();
+                                state.open_prompt(PromptKind::PageSize, &title, &default);
+                            }
+                            KeyCode::Char('I') => {
+                                state.open_import();
+                            }
+                            KeyCode::Char('d') if state.table_view => {
+                                if let Some(field) = state.selected_column_name() {
+                                    let field = field.to_string();
+                                    load_distinct_values(&mut state, &conn_service, &loader, field);
+                                }
+                            }
+                            KeyCode::Char('d') => {
+                                state.open_prompt(
+                                    PromptKind::DistinctField,
+                                    "Field for distinct values (e.g. status or address.city)",
+                                    "",
+                                );
+                            }
+                            KeyCode::Char('U') => {
+                                let db_name = state.current_database.clone();
+                                let coll_name = state.current_collection.clone();
+                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name) {
+                                    if let Some(reason) = state.permission_denial(
+                                        &db_name,
+                                        &coll_name,
+                                        "update",
+                                        "Bulk update",
+                                    ) {
+                                        state.set_error(Some(reason));
+                                    } else {
+                                        state.open_bulk_update();
+                                    }
+                                }
+                            }
+                            KeyCode::Char('B') => {
+                                let default = benchmark::DEFAULT_RUNS.to_string();
+                                state.open_prompt(
+                                    PromptKind::Benchmark,
+                                    "Benchmark runs (add \"vary\" to read a different page each run)",
+                                    &default,
+                                );
+                            }
+                            KeyCode::Char('E') => match state.current_environment() {
+                                Some((set, _)) => {
+                                    let names = set
+                                        .environments
+                                        .iter()
+                                        .map(|e| e.name.as_str())
+                                        .collect::<Vec<_>>()
+                                        .join("/");
+                                    let title =
+                                        format!("Open in {} environment ({})", set.name, names);
+                                    let default = state
+                                        .next_environment()
+                                        .map(|e| e.name.clone())
+                                        .unwrap_or_default();
+                                    state.open_prompt(
+                                        PromptKind::SwitchEnvironment,
+                                        &title,
+                                        &default,
+                                    );
+                                }
+                                None => {
+                                    state.set_error(Some(
+                                        "Connected profile is not part of an environment set"
+                                            .to_string(),
+                                    ));
+                                }
+                            },
+                            KeyCode::Esc => {
+                                state.clear_filter();
+                                let label = "Failed to reload";
+                                reload_documents_without_filter(&state, &conn_service, &loader, label);
+                            }
+                            KeyCode::Char('r') if !loader.is_busy() => {
+                                let label = "Failed to refresh";
+                                reload_documents_without_filter(&state, &conn_service, &loader, label);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        if state.current_screen == app::screen::Screen::Tail
+            && !state.tail_paused
+            && last_tail_poll.elapsed() >= TAIL_POLL_INTERVAL
+            && let Some(active) = tailer.as_mut()
+        {
+            last_tail_poll = Instant::now();
+            match active.poll().await {
+                Ok(documents) => {
+                    if !documents.is_empty() {
+                        state.record_tail(&documents, active.reports_events());
+                        state.append_tail_documents(documents);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Tail failed: {}", e)));
+                    state.toggle_tail_pause();
+                    active.pause();
+                }
+            }
+        }
+
+        if state.current_screen == app::screen::Screen::Operations
+            && !state.operations_paused
+            && state.prompt.is_none()
+            && last_operations_poll.elapsed() >= OPERATIONS_POLL_INTERVAL
+            && !loader.is_busy()
+        {
+            last_operations_poll = Instant::now();
+            load_operations(&conn_service, &loader);
+        }
+        if let Some(prompt) = state.prompt.as_ref().filter(|_| state.is_editing_stage()) {
+            if prompt.input != draft_typed.0 {
+                draft_typed = (prompt.input.clone(), Instant::now());
+            } else if draft_typed.1.elapsed() >= DRAFT_PREVIEW_DELAY
+                && state.draft_requested.as_ref() != Some(&draft_typed.0)
+            {
+                let body = draft_typed.0.clone();
+                run_draft_preview(&mut state, &conn_service, &loader, body);
+            }
+        }
+        if state.current_screen == app::screen::Screen::CollectionList
+            && let Some(db_name) = state.current_database.clone()
+            && last_heat_poll
+                .as_ref()
+                .is_none_or(|(db, at)| *db != db_name || at.elapsed() >= HEAT_POLL_INTERVAL)
+        {
+            last_heat_poll = Some((db_name.clone(), Instant::now()));
+            load_op_counters(&state, &conn_service, &loader, db_name);
+        }
+        if state.current_screen == app::screen::Screen::DatabaseList {
+            for name in state.request_database_totals() {
+                load_database_totals(&conn_service, &loader, name);
+            }
+        }
+        if state.current_screen == app::screen::Screen::ReplicaSet
+            && !state.replica_set_paused
+            && state.prompt.is_none()
+            && last_replica_set_poll.elapsed() >= REPLICA_SET_POLL_INTERVAL
+            && !loader.is_busy()
+        {
+            last_replica_set_poll = Instant::now();
+            load_replica_set(&conn_service, &loader);
+        }
+
+        for loaded in loader.drain() {
+            let step = match &loaded {
+                Loaded::Connection(result) => Some(result.is_ok()),
+                Loaded::Collection { result, .. } => Some(result.is_ok()),
+                _ => None,
+            };
+            apply_loaded(
+                loaded,
+                &mut state,
+                &mut conn_service,
+                &mut history,
+                &loader,
+                &mut tailer,
+            )
+            .await;
+            if let Some(ok) = step {
+                follow_launch(&mut state, &conn_service, &loader, &mut launch, ok).await;
+            }
+        }
+        count_documents(&mut state, &conn_service, &loader, &tasks);
+        state.set_loading(loader.is_busy());
+        state.tick_spinner();
+
+        state.network = conn_service.network_stats().snapshot();
+        state.set_tasks(tasks.snapshot());
+        state.set_query_timings(conn_service.query_timings().snapshot());
+        if state.current_screen == app::screen::Screen::EventLog {
+            state.set_event_log(conn_service.event_log().snapshot());
+        }
+        crash::watch_events(conn_service.event_log());
+        for task in tasks.take_finished() {
+            match task.status {
+                // the status bar shows counts as they arrive
+                TaskStatus::Completed(_) if task.kind == "count" => {}
+                TaskStatus::Completed(message) => state.set_message(Some(message)),
+                TaskStatus::Failed(e) => {
+                    state.set_error(Some(format!("{} failed: {}", task.kind, e)));
+                }
+                _ => {}
+            }
+        }
+
+        if state.should_quit {
+            break;
+        }
+    }
+
+    // helper function to run the health sweep in the background; its report opens when done
+    fn run_health_check(conn_service: &ConnectionService, loader: &Loader) {
+        if let Some(client) = conn_service.get_client() {
+            let health_service = HealthService::new(client.clone());
+            loader.spawn(async move { Loaded::Health(health_service.run().await) });
+        }
+    }
+
+    // helper function to fold a background load's result into the state
+    async fn apply_loaded(
+        loaded: Loaded,
+        state: &mut AppState,
+        conn_service: &mut ConnectionService,
+        history: &mut ConnectionHistory,
+        loader: &Loader,
+        tailer: &mut Option<Tailer>,
+    ) {
+        match loaded {
+            Loaded::Connection(result) => match result {
+                Ok(connected) => {
+                    let connected = *connected;
+                    // Save to history
+                    history.add_uri(connected.uri.clone());
+                    let _ = history.save();
+                    state.set_connection_history(history.uris.clone());
+
+                    *conn_service = connected.service;
+                    state.set_connection(connected.uri, connected.server_info);
+                    state.set_endpoint(conn_service.endpoint());
+                    state.active_profile = connected.profile;
+                    state.set_privileges(connected.privileges);
+
+                    match connected.databases {
+                        Ok(databases) => {
+                            state.set_databases(databases);
+                            if state.health_check_on_connect {
+                                run_health_check(conn_service, loader);
+                            } else {
+                                state.set_screen(app::screen::Screen::DatabaseList);
+                                open_default_database(state, conn_service).await;
+                            }
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!("Failed to load databases: {}", e)));
+                        }
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Connection failed: {}", e)));
+                }
+            },
+            Loaded::Databases(result) => match result {
+                Ok(databases) => {
+                    state.set_databases(databases);
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Failed to refresh databases: {}", e)));
+                }
+            },
+            Loaded::Collections { db, open, result } => match result {
+                // the user may have moved on while the list was loading
+                Ok(collections) if open => {
+                    if state.current_screen == app::screen::Screen::DatabaseList {
+                        // the collection open before belongs to the other database
+                        if state.current_database.as_deref() != Some(db.as_str()) {
+                            state.current_collection = None;
+                        }
+                        state.current_database = Some(db);
+                        state.marked_collections.clear();
+                        state.set_collections(collections);
+                        state.set_screen(app::screen::Screen::CollectionList);
+                    }
+                }
+                Ok(collections) => {
+                    if state.current_database.as_deref() == Some(db.as_str()) {
+                        state.set_collections(collections);
+                    }
+                }
+                Err(e) => {
+                    let action = if open { "load" } else { "refresh" };
+                    state.set_error(Some(format!("Failed to {} collections: {}", action, e)));
+                }
+            },
+            Loaded::Collection {
+                db,
+                coll,
+                tab,
+                limit,
+                sort,
+                result,
+            } => match result {
+                Ok(documents) => {
+                    if state.current_screen == app::screen::Screen::CollectionList
+                        && state.current_database.as_deref() == Some(db.as_str())
+                    {
+                        if limit < state.page_size {
+                            state.set_message(Some(format!(
+                                "Loaded {} of {} per page to stay within the {} memory budget; use a projection for more",
+                                limit,
+                                state.page_size,
+                                format_bytes(state.limits.memory_budget_bytes())
+                            )));
+                        }
+                        if tab {
+                            state.open_tab();
+                        }
+                        state.newest_first = sort;
+                        state.clear_filter();
+                        state.current_collection = Some(coll);
+                        state.set_documents(documents);
+                        state.page_limit = limit;
+                        state.set_screen(app::screen::Screen::DocumentView);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Failed to load documents: {}", e)));
+                }
+            },
+            Loaded::Sharding { message, result } => match result {
+                Ok(status) => {
+                    state.set_sharding(*status);
+                    if state.current_screen == app::screen::Screen::CollectionList {
+                        state.set_screen(app::screen::Screen::Sharding);
+                    }
+                    if message.is_some() {
+                        state.set_message(message);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::Write(result) => match result {
+                Ok(message) => {
+                    reload_documents(state, conn_service, loader);
+                    state.set_message(Some(message));
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::CollectionCreated { db, name, result } => match result {
+                Ok(collections) => {
+                    state.close_collection_form();
+                    if state.current_database.as_deref() == Some(db.as_str()) {
+                        state.set_collections(collections);
+                        state.select_collection(&name);
+                    }
+                    state.set_message(Some(format!("Created collection {}.{}", db, name)));
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::CollectionDropped { db, name, result } => match result {
+                Ok(collections) => {
+                    if state.current_database.as_deref() == Some(db.as_str()) {
+                        state.set_collections(collections);
+                    }
+                    state.set_message(Some(format!("Dropped collection {}.{}", db, name)));
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::CollectionRenamed {
+                db,
+                from,
+                to,
+                result,
+            } => match result {
+                Ok(collections) => {
+                    if state.current_database.as_deref() == Some(db.as_str()) {
+                        state.set_collections(collections);
+                        state.select_collection(&to);
+                    }
+                    state.set_message(Some(format!("Renamed {}.{} to {}", db, from, to)));
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::DatabaseDropped { name, result } => match result {
+                Ok(databases) => {
+                    state.set_databases(databases);
+                    state.set_message(Some(format!("Dropped database {}", name)));
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::DraftPreview { body, result } => {
+                if state.is_editing_stage() && state.draft_requested.as_ref() == Some(&body) {
+                    let result = result.map_err(|e| e.to_string());
+                    state.draft_preview = Some(DraftPreview { body, result });
+                }
+            }
+            Loaded::AggregationPreview { upto, result } => match result {
+                Ok(documents) => {
+                    state.set_aggregation_preview(upto, documents);
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::Compatibility(result) => match result {
+                Ok(compatibility) => {
+                    state.compatibility = Some(compatibility);
+                    if state.current_screen == app::screen::Screen::DatabaseList {
+                        state.set_screen(app::screen::Screen::About);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::Schema(result) => match result {
+                Ok(analysis) => {
+                    state.set_schema(analysis);
+                    if state.current_screen == app::screen::Screen::CollectionList {
+                        state.set_screen(app::screen::Screen::Schema);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::Operations(result) => match result {
+                Ok(operations) => {
+                    state.set_operations(operations);
+                }
+                Err(e) => {
+                    // a failing poll would repeat every two seconds
+                    state.operations_paused = true;
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::Users {
+                db,
+                message,
+                result,
+            } => match result {
+                Ok((users, roles)) => {
+                    state.set_users(db, users, roles);
+                    if let Some(message) = message {
+                        state.set_message(Some(message));
+                    }
+                    if state.current_screen == app::screen::Screen::DatabaseList {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::Users);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::ReplicaSet(result) => match result {
+                Ok((status, elections)) => {
+                    state.set_replica_set(status, elections);
+                    if state.current_screen == app::screen::Screen::DatabaseList {
+                        state.set_error(None);
+                        state.set_screen(app::screen::Screen::ReplicaSet);
+                    }
+                }
+                Err(e) => {
+                    // a failing refresh would repeat every few seconds
+                    state.replica_set_paused = true;
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::DryRun(summary) => {
+                state.would_execute = Some(summary);
+            }
+            Loaded::OperationKilled { opid, result } => match result {
+                Ok(()) => {
+                    state.set_message(Some(format!("Asked the server to kill operation {}", opid)));
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::ReferenceDiff(result) => match result {
+                Ok(diff) => {
+                    state.set_reference_diff(diff);
+                    state.set_error(None);
+                    if state.current_screen == app::screen::Screen::CollectionList {
+                        state.set_screen(app::screen::Screen::ReferenceDiff);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Reference diff failed: {}", e)));
+                }
+            },
+            Loaded::Explain(result) => match result {
+                Ok(explained) => {
+                    if state.current_screen == app::screen::Screen::DocumentView {
+                        state.open_plan_tree(explain::plan_tree(&explained));
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::ProjectionFields { db, coll, result } => match result {
+                Ok(fields) => {
+                    if state.current_screen == app::screen::Screen::DocumentView
+                        && state.current_database.as_deref() == Some(db.as_str())
+                        && state.current_collection.as_deref() == Some(coll.as_str())
+                    {
+                        state.open_projection_picker(fields);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Failed to sample fields: {}", e)));
+                }
+            },
+            Loaded::BuilderFields { db, coll, result } => match result {
+                Ok(fields) => {
+                    if state.current_screen == app::screen::Screen::DocumentView
+                        && state.current_database.as_deref() == Some(db.as_str())
+                        && state.current_collection.as_deref() == Some(coll.as_str())
+                    {
+                        state.open_query_builder(fields);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Failed to sample fields: {}", e)));
+                }
+            },
+            Loaded::SearchIndexes(indexes) => {
+                if state.current_screen == app::screen::Screen::DocumentView {
+                    state.open_search(indexes);
+                    state.set_screen(app::screen::Screen::Search);
+                }
+            }
+            Loaded::SearchResults(result) => match result {
+                Ok(hits) => {
+                    if hits.is_empty() {
+                        state.set_message(Some("No matches".to_string()));
+                    }
+                    state.set_search_hits(hits);
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::BulkUpdatePreview(result) => match result {
+                Ok(preview) => {
+                    state.set_bulk_update_preview(preview);
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::BulkUpdated(result) => match result {
+                Ok(message) => {
+                    state.bulk_update.preview = None;
+                    if state.current_screen == app::screen::Screen::BulkUpdate {
+                        state.set_screen(app::screen::Screen::DocumentView);
+                    }
+                    reload_documents(state, conn_service, loader);
+                    state.set_message(Some(message));
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::DatabaseTotals { name, result } => {
+                // databases the server gives no totals for just show none
+                if let Ok(totals) = result {
+                    state.set_database_totals(name, totals);
+                }
+            }
+            Loaded::Namespaces(result) => {
+                if let Some(finder) = state.finder.as_mut() {
+                    match result {
+                        Ok(namespaces) => finder.set_namespaces(namespaces),
+                        Err(e) => {
+                            finder.loading = false;
+                            state.set_error(Some(e.to_string()));
+                        }
+                    }
+                }
+            }
+            Loaded::Count { key, result } => {
+                // the status bar goes without a total if counting fails
+                if let Ok(count) = result {
+                    state.set_total_count(&key, count);
+                }
+            }
+            Loaded::DistinctValues(result) => match result {
+                Ok(values) => {
+                    if state.current_screen == app::screen::Screen::DocumentView {
+                        state.distinct_values = Some(values);
+                    }
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::OpCounters { db, result } => {
+                // without top or collStats rights the list just shows no heat
+                if let Ok(counters) = result {
+                    state.record_op_counters(db, counters);
+                }
+            }
+            Loaded::CollectionStats(result) => match result {
+                Ok(stats) => {
+                    state.set_collection_stats(stats);
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::ShardKey(result) => match result {
+                Ok(analysis) => {
+                    state.set_shard_key_analysis(analysis);
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::Documents {
+                db,
+                coll,
+                label,
+                result,
+            } => {
+                if state.current_database.as_deref() == Some(db.as_str())
+                    && state.current_collection.as_deref() == Some(coll.as_str())
+                {
+                    match result {
+                        Ok(documents) => {
+                            state.set_documents(documents);
+                            state.set_error(None);
+                            check_query_plan(state, conn_service).await;
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!("{}: {}", label, e)));
+                        }
+                    }
+                }
+            }
+            Loaded::Reload { db, coll, result } => {
+                if state.current_database.as_deref() == Some(db.as_str())
+                    && state.current_collection.as_deref() == Some(coll.as_str())
+                {
+                    match result {
+                        Ok(documents) => {
+                            state.show_page(documents);
+                            state.set_error(None);
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!("Failed to reload: {}", e)));
+                        }
+                    }
+                }
+            }
+            Loaded::PageEstimate { page_size, bytes } => {
+                let budget = state.limits.memory_budget_bytes();
+                match bytes {
+                    Some(bytes) if bytes > budget => {
+                        state.pending_page_size = Some(page_size);
+                        let title = format!(
+                            "{} docs ≈ {} exceeds the {} budget; project fewer fields or type y to load",
+                            page_size,
+                            format_bytes(bytes),
+                            format_bytes(budget)
+                        );
+                        state.open_prompt(PromptKind::ConfirmLargePage, &title, "");
+                    }
+                    _ => {
+                        state.set_page_size(page_size);
+                        reload_documents(state, conn_service, loader);
+                    }
+                }
+            }
+            Loaded::LiveFilter {
+                db,
+                coll,
+                input,
+                result,
+            } => {
+                // a later keystroke has already asked for a newer search
+                if state.current_database.as_deref() == Some(db.as_str())
+                    && state.current_collection.as_deref() == Some(coll.as_str())
+                    && state.filter_input.as_str() == input
+                {
+                    match result {
+                        Ok(documents) => {
+                            state.set_documents(documents);
+                            state.set_error(None);
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!("Search error: {}", e)));
+                        }
+                    }
+                }
+            }
+            Loaded::GeoField { db, coll, result } => {
+                if state.current_database.as_deref() == Some(db.as_str())
+                    && state.current_collection.as_deref() == Some(coll.as_str())
+                {
+                    match result {
+                        Ok(Some(field)) => state.enter_geo_mode(field),
+                        Ok(None) => {
+                            state.set_error(Some("No 2dsphere index on this collection".to_string()));
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!("Failed to load indexes: {}", e)));
+                        }
+                    }
+                }
+            }
+            Loaded::AtlasProjects(result) => match result {
+                Ok(projects) => {
+                    state.set_error(None);
+                    state.set_atlas_projects(projects);
+                    state.set_screen(app::screen::Screen::Atlas);
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::AtlasClusters { project, result } => match result {
+                Ok(clusters) => {
+                    state.set_atlas_clusters(project, clusters);
+                }
+                Err(e) => {
+                    state.set_error(Some(e.to_string()));
+                }
+            },
+            Loaded::Health(items) => {
+                state.set_health_items(items);
+                state.set_screen(app::screen::Screen::HealthCheck);
+            }
+            Loaded::Privileges { namespace, result } => {
+                state.set_privileges(result.ok());
+                show_permissions(state, namespace);
+            }
+            Loaded::Tail {
+                db,
+                coll,
+                source,
+                label,
+                result,
+            } => {
+                if state.current_database.as_deref() == Some(db.as_str()) {
+                    match result {
+                        Ok((started, initial)) => {
+                            state.current_collection = Some(coll);
+                            state.start_tail(initial, source);
+                            state.set_error(None);
+                            *tailer = Some(*started);
+                            state.set_screen(app::screen::Screen::Tail);
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!("{}: {}", label, e)));
+                        }
+                    }
+                }
+            }
+            Loaded::WideWatch { scope, result } => match result {
+                Ok(started) => {
+                    state.start_wide_watch(scope);
+                    *tailer = Some(*started);
+                    state.set_screen(app::screen::Screen::Tail);
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Failed to start watch: {}", e)));
+                }
+            },
+            Loaded::CountComparison { target, result } => match result {
+                Ok(comparisons) => {
+                    state.set_comparisons(target, comparisons);
+                    state.set_error(None);
+                    state.set_screen(app::screen::Screen::Compare);
+                }
+                Err(e) => {
+                    state.set_error(Some(format!("Count comparison failed: {}", e)));
+                }
+            },
+            Loaded::Page {
+                db,
+                coll,
+                page,
+                cursor,
+                result,
+            } => {
+                if state.current_database.as_deref() == Some(db.as_str())
+                    && state.current_collection.as_deref() == Some(coll.as_str())
+                {
+                    match result {
+                        // a full last page looks like it has a next one
+                        Ok(documents) if documents.is_empty() && page > 0 => {
+                            state.set_message(Some("This is the last page".to_string()));
+                        }
+                        Ok(documents) => {
+                            state.enter_page(page, cursor, documents);
+                            state.set_error(None);
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!("Failed to load page: {}", e)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // helper function to run a sharding action, if any, then reload the chunk view
+    fn refresh_sharding(
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        (db_name, coll_name): (String, String),
+        action: Option<ShardingAction>,
+    ) {
+        if let Some(client) = conn_service.get_client() {
+            let service = ShardingService::new(client.clone());
+            loader.spawn(async move {
+                let mut message = None;
+                if let Some(action) = action {
+                    let namespace = format!("{}.{}", db_name, coll_name);
+                    match service.run(&namespace, action).await {
+                        Ok(done) => message = Some(done),
+                        Err(e) => {
+                            return Loaded::Sharding {
+                                message: None,
+                                result: Err(e),
+                            };
+                        }
+                    }
+                }
+                let result = service.status(&db_name, &coll_name).await.map(Box::new);
+                Loaded::Sharding { message, result }
+            });
+        }
+    }
+
+    // helper function to run a sharding action on the open chunk view, or
+    // show it when dry run is on
+    fn run_sharding_action(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        action: ShardingAction,
+    ) {
+        let Some((db_name, coll_name)) = state.sharding.as_ref().map(|s| s.db_and_collection())
+        else {
+            return;
+        };
+        if state.dry_run {
+            let namespace = format!("{}.{}", db_name, coll_name);
+            let command = action.command(&namespace);
+            state.would_execute = Some(WouldExecute::command(&namespace, &command));
+            return;
+        }
+        refresh_sharding(conn_service, loader, (db_name, coll_name), Some(action));
+    }
+
+    // helper function to show what a write would do instead of sending it,
+    // counting the documents its filter matches first when given one
+    fn show_dry_run(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        summary: WouldExecute,
+        count: Option<(String, String, Option<mongodb::bson::Document>)>,
+    ) {
+        match (count, conn_service.get_client()) {
+            (Some((db_name, coll_name, filter)), Some(client)) => {
+                let client = client.clone();
+                loader.spawn(async move {
+                    let matched = dry_run::matched(&client, &db_name, &coll_name, filter).await;
+                    Loaded::DryRun(WouldExecute { matched, ..summary })
+                });
+            }
+            _ => state.would_execute = Some(summary),
+        }
+    }
+
+    // helper function to run the pipeline through the stage being edited on
+    // a sample; a body that does not parse yet shows its error instead
+    fn run_draft_preview(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        body: String,
+    ) {
+        state.draft_requested = Some(body.clone());
+        let pipeline = match state.draft_pipeline(&body) {
+            Ok(pipeline) => pipeline,
+            Err(e) => {
+                state.draft_preview = Some(DraftPreview {
+                    body,
+                    result: Err(e),
+                });
+                return;
+            }
+        };
+        if let (Some(db_name), Some(coll_name), Some(client)) = (
+            state.current_database.clone(),
+            state.current_collection.clone(),
+            conn_service.get_client(),
+        ) {
+            let query_service = QueryService::new(client.clone());
+            loader.spawn(async move {
+                let result =
+                    aggregation::draft_preview(&query_service, &db_name, &coll_name, pipeline)
+                        .await;
+                Loaded::DraftPreview { body, result }
+            });
+        }
+    }
+
+    // helper function to make a change to the users of a database, if any,
+    // then reload its users and roles
+    fn load_users(
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        db_name: String,
+        change: Option<UserChange>,
+    ) {
+        if let Some(client) = conn_service.get_client() {
+            let client = client.clone();
+            loader.spawn(async move {
+                let mut message = None;
+                if let Some(change) = change {
+                    match change.apply(&client, &db_name).await {
+                        Ok(done) => message = Some(done),
+                        Err(e) => {
+                            return Loaded::Users {
+                                db: db_name,
+                                message: None,
+                                result: Err(e),
+                            };
+                        }
+                    }
+                }
+                let result = async {
+                    let users = users::list_users(&client, &db_name).await?;
+                    // roles may be hidden from users allowed to list users
+                    let roles = users::list_roles(&client, &db_name)
+                        .await
+                        .unwrap_or_default();
+                    Ok((users, roles))
+                }
+                .await;
+                Loaded::Users {
+                    db: db_name,
+                    message,
+                    result,
+                }
+            });
+        }
+    }
+
+    // helper function to change the users of the open database, or show the
+    // command with its password hidden when dry run is on
+    fn run_user_change(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        change: UserChange,
+    ) {
+        let Some(db_name) = state.users_database.clone() else {
+            return;
+        };
+        if state.dry_run {
+            state.would_execute = Some(WouldExecute::command(&db_name, &change.redacted()));
+            return;
+        }
+        state.set_error(None);
+        load_users(conn_service, loader, db_name, Some(change));
+    }
+
+    // helper function to load replica set members and recent elections
+    fn load_replica_set(conn_service: &ConnectionService, loader: &Loader) {
+        if let Some(client) = conn_service.get_client() {
+            let service = ReplicaSetService::new(client.clone());
+            loader.spawn(async move {
+                let result = match service.status().await {
+                    // the oplog may be unreadable for restricted users
+                    Ok(status) => Ok((status, service.elections().await.unwrap_or_default())),
+                    Err(e) => Err(e),
+                };
+                Loaded::ReplicaSet(result)
+            });
+        }
+    }
+
+    // helper function to open a change stream on a database or the deployment
+    fn start_wide_watch(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        scope: WatchScope,
+    ) {
+        let Some(client) = conn_service.get_client().cloned() else {
+            return;
+        };
+        let (db_name, feature) = match &scope {
+            WatchScope::Database(db) => (db.as_str(), "Watching the database"),
+            _ => ("", "Watching the deployment"),
+        };
+        if let Some(reason) = state.permission_denial(db_name, "", "changeStream", feature) {
+            state.set_error(Some(reason));
+            return;
+        }
+        loader.spawn(async move {
+            let result = Tailer::watch_scope(&client, &scope).await.map(Box::new);
+            Loaded::WideWatch { scope, result }
+        });
+    }
+
+    // helper function to start the export the dialog describes; the dialog
+    // stays open with the error if the input is incomplete
+    fn start_export(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        tasks: &TaskRegistry,
+        dialog: &ExportDialog,
+    ) {
+        let Some(client) = conn_service.get_client().cloned() else {
+            return;
+        };
+        let throttle = conn_service.throttle();
+        if dialog.is_bulk() {
+            let Some(db_name) = state.current_database.clone() else {
+                return;
+            };
+            let target = match dialog.bulk_target(&state.limits) {
+                Ok(target) => target,
+                Err(e) => {
+                    state.set_error(Some(e));
+                    return;
+                }
+            };
+            let collections = dialog.collections.clone();
+            let label = format!(
+                "{} ({} collections) → {}",
+                db_name,
+                collections.len(),
+                target.dir.display()
+            );
+            tasks.spawn("export", label, move |progress| {
+                export::export_collections(client, db_name, collections, target, throttle, progress)
+            });
+            state.marked_collections.clear();
+        } else {
+            let Some(params) = state.query_params() else {
+                return;
+            };
+            let target = match dialog.document_target(&state.limits) {
+                Ok(target) => target,
+                Err(e) => {
+                    state.set_error(Some(e));
+                    return;
+                }
+            };
+            let label = format!(
+                "{}.{} → {}",
+                params.database,
+                params.collection,
+                target.path.display()
+            );
+            tasks.spawn("export", label, move |progress| {
+                export::export_documents(client, params, target, throttle, progress)
+            });
+        }
+        state.close_export_dialog();
+        state.set_error(None);
+        state.set_message(Some(
+            "Export started in the background [Ctrl+B] Tasks".to_string(),
+        ));
+    }
+
+    // helper function to run the whole pipeline as a background task
+    fn start_pipeline(state: &mut AppState, conn_service: &ConnectionService, tasks: &TaskRegistry) {
+        let (Some(db_name), Some(coll_name), Some(client)) = (
+            state.current_database.clone(),
+            state.current_collection.clone(),
+            conn_service.get_client().cloned(),
+        ) else {
+            return;
+        };
+        let pipeline = match aggregation::build_pipeline(&state.pipeline, state.pipeline.len()) {
+            Ok(pipeline) => pipeline,
+            Err(e) => {
+                state.set_error(Some(e));
+                return;
+            }
+        };
+        if let Some(operator) = aggregation::write_stage(&pipeline) {
+            let feature = format!("Running a pipeline with {}", operator);
+            if let Some(reason) = state.read_only_denial(&feature) {
+                state.set_error(Some(reason));
+                return;
+            }
+            if state.dry_run {
+                let namespace = format!("{}.{}", db_name, coll_name);
+                let command = mongodb::bson::doc! { "aggregate": &coll_name, "pipeline": pipeline };
+                state.would_execute = Some(WouldExecute::command(&namespace, &command));
+                return;
+            }
+        }
+        let label = format!("{}.{} ({} stage(s))", db_name, coll_name, pipeline.len());
+        tasks.spawn("aggregation", label, move |progress| {
+            aggregation::run(client, db_name, coll_name, pipeline, progress)
+        });
+        state.set_error(None);
+        state.set_message(Some(
+            "Pipeline started in the background [Ctrl+B] Tasks".to_string(),
+        ));
+    }
+
+    // helper function to replay a recording in the background, into this
+    // connection or the one the plan names
+    async fn start_replay(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        tasks: &TaskRegistry,
+        plan: replay::ReplayPlan,
+    ) {
+        let (client, service, label) = match &plan.connection {
+            None => {
+                let collection = plan.collection.as_deref().unwrap_or("");
+                if let Some(reason) =
+                    state.permission_denial(&plan.database, collection, "insert", "Replaying")
+                {
+                    state.set_error(Some(reason));
+                    return;
+                }
+                let Some(client) = conn_service.get_client() else {
+                    return;
+                };
+                (client.clone(), None, plan.target())
+            }
+            Some(connection) => {
+                // the current profile being read-only does not protect another server
+                let profile = state.find_profile(connection);
+                let denial = if state.read_only {
+                    state.read_only_denial("Replaying")
+                } else {
+                    profile
+                        .as_ref()
+                        .filter(|p| p.read_only)
+                        .map(|p| format!("Replaying is disabled: profile {} is read-only", p.name))
+                };
+                if let Some(reason) = denial {
+                    state.set_error(Some(reason));
+                    return;
+                }
+
+                let uri = profile
+                    .as_ref()
+                    .map_or(connection.clone(), |p| p.uri.clone());
+                let mut service = ConnectionService::new();
+                state.set_loading(true);
+                let result = service.connect(&uri, profile.as_ref()).await;
+                state.set_loading(false);
+                let client = match result {
+                    Ok(_) => service.get_client().cloned(),
+                    Err(e) => {
+                        state.set_error(Some(format!("Replay connection failed: {}", e)));
+                        return;
+                    }
+                };
+                let Some(client) = client else {
+                    return;
+                };
+                // a typed URI may carry a password, so only profiles are named
+                let on = profile.map_or("another connection".to_string(), |p| p.name);
+                (
+                    client,
+                    Some(service),
+                    format!("{} on {}", plan.target(), on),
+                )
+            }
+        };
+
+        if state.dry_run {
+            state.would_execute = Some(WouldExecute {
+                target: label,
+                operation: "replay".to_string(),
+                payload: Some(format!(
+                    "The inserts, updates, replaces and deletes recorded in {}",
+                    plan.path.display()
+                )),
+                ..WouldExecute::default()
+            });
+            return;
+        }
+        let label = format!("{} → {}", plan.path.display(), label);
+        tasks.spawn("replay", label, move |progress| async move {
+            // the connection, and any tunnel it opened, lasts as long as the replay
+            let _service = service;
+            replay::replay(client, plan, progress).await
+        });
+        state.set_error(None);
+        state.set_message(Some(
+            "Replay started in the background [Ctrl+B] Tasks".to_string(),
+        ));
+    }
+
+    // helper function to load the page starting at `cursor` in the background
+    fn load_page(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        page: usize,
+        cursor: PageCursor,
+    ) {
+        if let Some(params) = state.page_params(&cursor)
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            loader.spawn(async move {
+                let (db, coll) = (params.database.clone(), params.collection.clone());
+                let result = query_service.find(params).await;
+                Loaded::Page {
+                    db,
+                    coll,
+                    page,
+                    cursor,
+                    result,
+                }
+            });
+        }
+    }
+
+    // helper function to apply the typed JSON query and remember it for the collection
+    fn run_query(state: &mut AppState, conn_service: &ConnectionService, loader: &Loader) {
+        let query = state.query_input.as_str().to_string();
+        let found = placeholders::find(&query);
+        if !found.is_empty() {
+            state.exit_query_mode();
+            state.ask_query_parameters(query, found);
+            return;
+        }
+        if let Err(e) = state.apply_filter() {
+            state.set_error(Some(e));
+            return;
+        }
+        state.exit_query_mode();
+        find_with_query(state, conn_service, loader, &query);
+    }
+
+    // helper function to put text on the clipboard and say where it went
+    fn copy_to_clipboard(state: &mut AppState, text: &str, what: &str) {
+        match clipboard::copy(text) {
+            Ok(clipboard::Copied::System) => {
+                state.set_message(Some(format!("Copied {}", what)));
+            }
+            Ok(clipboard::Copied::Terminal) => {
+                state.set_message(Some(format!("Copied {} through the terminal (OSC 52)", what)));
+            }
+            Err(e) => {
+                state.set_error(Some(e));
+            }
+        }
+    }
+
+    // helper function to copy the selected document as extended JSON
+    fn copy_document(state: &mut AppState) {
+        let Some(doc) = state.get_selected_document() else {
+            return;
+        };
+        let text = clipboard::value_text(
+            &mongodb::bson::Bson::Document(doc.clone()),
+            state.value_format.canonical,
+        );
+        copy_to_clipboard(state, &text, "the document");
+    }
+
+    // helper function to copy one value of the selected document
+    fn copy_field(state: &mut AppState, path: &str) {
+        let Some(doc) = state.get_selected_document() else {
+            return;
+        };
+        match services::bson_ext::value_at(doc, path) {
+            Some(value) => {
+                let text = clipboard::value_text(value, state.value_format.canonical);
+                copy_to_clipboard(state, &text, path);
+            }
+            None => {
+                state.set_error(Some(format!("The document has no {}", path)));
+            }
+        }
+    }
+
+    // helper function to lay out the selected document as the content pane
+    // shows it: a tree with the document's folds, and each line's path
+    fn content_tree(state: &AppState) -> ui::highlight::DocumentTree {
+        match state.get_selected_document() {
+            Some(doc) => {
+                ui::highlight::document_tree(
+                    doc,
+                    state.selected_folds(),
+                    state.value_format,
+                    &state.theme,
+                )
+            }
+            None => ui::highlight::DocumentTree::default(),
+        }
+    }
+
+    // helper function for the lines in view in the content pane: the footer
+    // and the pane's title take one each, a geo plot half of the rest
+    fn content_height(state: &AppState, terminal_height: u16) -> usize {
+        // the footer and the status bar, and a maximized pane's header
+        let chrome = if state.content_maximized { 4 } else { 2 };
+        let height = terminal_height.saturating_sub(chrome) as usize;
+        let height = if state.geo_shape.is_some() {
+            height / 2
+        } else {
+            height
+        };
+        height.saturating_sub(1)
+    }
+
+    // helper function to keep a resized split for the next start
+    fn save_split(state: &mut AppState) {
+        let saved = Settings::try_load().and_then(|mut settings| {
+            settings.split_percent = Some(state.split_percent);
+            settings.save().map_err(|e| e.to_string())
+        });
+        if let Err(e) = saved {
+            state.set_error(Some(format!("Failed to save settings: {}", e)));
+        }
+    }
+
+    // helper function to fold or unfold the subdocument or array the content
+    // cursor is in, leaving the cursor on the line that opens it
+    fn fold_at_cursor(state: &mut AppState, fold: Option<bool>, height: usize) {
+        let folds = content_tree(state).folds;
+        let Some(path) = folds.get(state.content_cursor).cloned().flatten() else {
+            return;
+        };
+        state.set_fold(&path, fold);
+        if let Some(line) = content_tree(state)
+            .folds
+            .iter()
+            .position(|p| p.as_deref() == Some(path.as_str()))
+        {
+            state.move_content_cursor(line, height);
+        }
+    }
+
+    // helper function to move the content cursor to the in-document search's
+    // match `step` matches away from the current one, wrapping around
+    fn jump_to_match(state: &mut AppState, step: isize, height: usize) {
+        let lines = content_tree(state).lines;
+        let matches = ui::highlight::find_matches(&lines, state.content_search.as_str());
+        if matches.is_empty() {
+            return;
+        }
+        let current = (state.content_match as isize + step).rem_euclid(matches.len() as isize);
+        state.content_match = current as usize;
+        state.move_content_cursor(matches[state.content_match].0, height);
+    }
+
+    // helper function to run a query once its placeholders have values; the
+    // template is what history and saving keep
+    fn run_filled_query(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        template: &str,
+        query: &str,
+    ) {
+        if let Err(e) = state.apply_query_text(query) {
+            state.set_error(Some(e));
+            return;
+        }
+        state.query_template = Some(template.to_string());
+        find_with_query(state, conn_service, loader, template);
+    }
+
+    // helper function to record the applied query and load its first page
+    fn find_with_query(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        query: &str,
+    ) {
+        state.record_query(query);
+        if let Err(e) = state.query_history.save() {
+            state.set_error(Some(format!("Failed to save query history: {}", e)));
+        }
+        if let Some(params) = state.query_params()
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            loader.spawn(async move {
+                let (db, coll) = (params.database.clone(), params.collection.clone());
+                let result = query_service.find(params).await;
+                Loaded::Documents {
+                    db,
+                    coll,
+                    label: "Query failed",
+                    result,
+                }
+            });
+        }
+    }
+
+    // helper function to persist the table columns chosen per namespace
+    fn save_columns(state: &mut AppState) {
+        let store = ColumnStore {
+            namespaces: state.column_sets.clone(),
+        };
+        if let Err(e) = store.save() {
+            state.set_error(Some(format!("Failed to save columns: {}", e)));
+        }
+    }
+
+    // helper function to load the first page of the selected collection
+    fn open_collection(
+        state: &AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        tab: bool,
+    ) {
+        let coll_name = state.get_selected_collection().map(|c| c.name.clone());
+        let db_name = state.current_database.clone();
+
+        if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            let budget = state.limits.memory_budget_bytes();
+            let page_size = state.page_size;
+            let newest_first = state
+                .default_sort
+                .field(&format!("{}.{}", db_name, coll_name))
+                .map(String::from);
+
+            loader.spawn(async move {
+                // shrink the first page of very large documents to the budget
+                let mut limit = page_size;
+                if let Ok(Some(avg)) = query_service.avg_document_size(&db_name, &coll_name).await
+                {
+                    limit = limit.min(memory::documents_within_budget(avg, budget));
+                }
+                let sort = match newest_first.as_deref() {
+                    Some(DefaultSort::AUTO) => {
+                        Some(query_service.newest_first_sort(&db_name, &coll_name).await)
+                    }
+                    Some(field) => Some(mongodb::bson::doc! { field: -1 }),
+                    None => None,
+                };
+                let result = query_service
+                    .find_documents(&db_name, &coll_name, None, sort.clone(), 0, limit as i64)
+                    .await;
+                Loaded::Collection {
+                    db: db_name,
+                    coll: coll_name,
+                    tab,
+                    limit,
+                    sort,
+                    result,
+                }
+            });
+        }
+    }
+
+    // helper function to open the connected profile's default database
+    async fn open_default_database(state: &mut AppState, conn_service: &ConnectionService) {
+        let db_name = state
+            .active_profile
+            .as_ref()
+            .and_then(|p| p.default_database.clone());
+        if let Some(db_name) = db_name {
+            open_database(state, conn_service, db_name).await;
+        }
+    }
+
+    // helper function to list a database's collections and show them
+    async fn open_database(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        db_name: String,
+    ) -> bool {
+        let Some(client) = conn_service.get_client() else {
+            return false;
+        };
+        if let Some(index) = state.databases.iter().position(|db| db.name == db_name) {
+            state.selected_db_index = index;
+        }
+        match QueryService::new(client.clone())
+            .list_collections(&db_name)
+            .await
+        {
+            Ok(collections) => {
+                // the collection open before belongs to the other database
+                if state.current_database.as_deref() != Some(db_name.as_str()) {
+                    state.current_collection = None;
+                }
+                state.current_database = Some(db_name);
+                state.marked_collections.clear();
+                state.set_collections(collections);
+                state.set_screen(app::screen::Screen::CollectionList);
+                true
+            }
+            Err(e) => {
+                state.set_error(Some(format!("Failed to open {}: {}", db_name, e)));
+                false
+            }
+        }
+    }
+
+    // helper function to ask the server which features it supports
+    fn load_compatibility(state: &AppState, conn_service: &ConnectionService, loader: &Loader) {
+        if let Some(conn) = &state.connection
+            && let Some(client) = conn_service.get_client()
+        {
+            let client = client.clone();
+            let host = conn.server_info.host.clone();
+            let version = conn.server_info.version.clone();
+            loader.spawn(async move {
+                Loaded::Compatibility(compatibility::check(&client, &host, &version).await)
+            });
+        }
+    }
+
+    // helper function to write the settings file from the setup wizard;
+    // skipping writes the defaults so the wizard does not come back
+    fn finish_setup(state: &mut AppState, skip: bool) {
+        let Some(form) = state.setup_form.clone() else {
+            return;
+        };
+        let form = if skip { SetupForm::default() } else { form };
+        let settings = match form.to_settings(&state.profiles, &state.limits) {
+            Ok(settings) => settings,
+            Err(e) => {
+                state.set_error(Some(e));
+                return;
+            }
+        };
+
+        i18n::init(settings.language.as_deref());
+        match settings.save() {
+            Ok(()) => {
+                state.set_error(None);
+                if let Some(path) = Settings::config_path() {
+                    state.set_message(Some(format!("Settings written to {}", path.display())));
+                }
+            }
+            Err(e) => {
+                state.set_error(Some(format!("Failed to save settings: {}", e)));
+            }
+        }
+        state.apply_settings(settings);
+        state.setup_form = None;
+        state.set_screen(app::screen::Screen::Connection);
+    }
+
+    // helper function to sample the selected collection for the schema view
+    fn load_schema(state: &AppState, conn_service: &ConnectionService, loader: &Loader) {
+        let coll_name = match state.current_screen {
+            app::screen::Screen::Schema => state.schema.as_ref().map(|s| s.collection.clone()),
+            _ => state.get_selected_collection().map(|c| c.name.clone()),
+        };
+        if let (Some(db_name), Some(coll_name)) = (state.current_database.clone(), coll_name)
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            let size = state.schema_sample_size;
+            loader.spawn(async move {
+                let result = query_service
+                    .sample_documents(&db_name, &coll_name, size)
+                    .await
+                    .map(|documents| schema::analyze(&db_name, &coll_name, &documents));
+                Loaded::Schema(result)
+            });
+        }
+    }
+
+    // helper function to list the running operations in the background
+    fn load_operations(conn_service: &ConnectionService, loader: &Loader) {
+        if let Some(client) = conn_service.get_client() {
+            let client = client.clone();
+            loader.spawn(async move { Loaded::Operations(operations::current(&client).await) });
+        }
+    }
+
+    // helper function to load a database's totals without holding up the list
+    fn load_database_totals(conn_service: &ConnectionService, loader: &Loader, name: String) {
+        if let Some(client) = conn_service.get_client() {
+            let client = client.clone();
+            loader.spawn_quiet(async move {
+                let result = stats::database_totals(&client, &name).await;
+                Loaded::DatabaseTotals { name, result }
+            });
+        }
+    }
+
+    // helper function to count the distinct values of a field among the
+    // documents the applied filter matches
+    fn load_distinct_values(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        field: String,
+    ) {
+        let db_name = state.current_database.clone();
+        let coll_name = state.current_collection.clone();
+        if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+            && let Some(client) = conn_service.get_client()
+        {
+            let client = client.clone();
+            let filter = state.filter.clone();
+            state.set_error(None);
+            loader.spawn(async move {
+                let result =
+                    distinct::distinct_values(&client, &db_name, &coll_name, filter, &field).await;
+                Loaded::DistinctValues(result)
+            });
+        }
+    }
+
+    // helper function to run a `:` command; `set` changes and saves a setting,
+    // and lists them all without one
+    fn run_command(state: &mut AppState, input: &str) {
+        let Some(rest) = input
+            .strip_prefix("set")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        else {
+            state.set_error(Some(format!("Unknown command: {}; try set", input)));
+            return;
+        };
+        let mut settings = match Settings::try_load() {
+            Ok(settings) => settings,
+            Err(e) => {
+                state.set_error(Some(e));
+                return;
+            }
+        };
+        let rest = rest.trim();
+        let (key, value) = rest
+            .split_once(['=', ' '])
+            .map_or((rest, ""), |(key, value)| (key.trim(), value.trim()));
+        if value.is_empty() {
+            let listed = if key.is_empty() {
+                Settings::KEYS.iter().filter_map(|key| settings.get(key)).collect()
+            } else {
+                settings.get(key).into_iter().collect::<Vec<_>>()
+            };
+            if listed.is_empty() {
+                state.set_error(Some(format!(
+                    "Unknown setting {}; try {}",
+                    key,
+                    Settings::KEYS.join(", ")
+                )));
+            } else {
+                state.set_message(Some(listed.join("  ")));
+            }
+            return;
+        }
+        if let Err(e) = settings.set(key, value) {
+            state.set_error(Some(e));
+            return;
+        }
+        match settings.save() {
+            Ok(()) => {
+                state.apply_preferences(&settings);
+                state.set_error(None);
+                state.set_message(settings.get(key).map(|set| format!("Saved {}", set)));
+            }
+            Err(e) => state.set_error(Some(format!("Failed to save settings: {}", e))),
+        }
+    }
+
+    // helper function to count what the open collection's filter matches,
+    // once per query, for the status bar
+    fn count_documents(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        tasks: &TaskRegistry,
+    ) {
+        if state.current_screen != app::screen::Screen::DocumentView {
+            return;
+        }
+        let (Some(db), Some(coll), Some(client)) = (
+            state.current_database.clone(),
+            state.current_collection.clone(),
+            conn_service.get_client(),
+        ) else {
+            return;
+        };
+        let Some(key) = state.take_uncounted() else {
+            return;
+        };
+        // the count of a query that has since changed is of no use
+        if let Some(id) = state.count_task.take() {
+            tasks.cancel(id);
+        }
+        // the server refuses `$near` in a count, so the total stays unknown
+        if state.filter.as_ref().is_some_and(services::paging::has_near) {
+            return;
+        }
+        let query_service = QueryService::new(client.clone());
+        match state.filter.clone().filter(|f| !f.is_empty()) {
+            // a filter is counted on the server, which can take long on a big
+            // collection, so the count is a task that can be cancelled
+            Some(filter) => {
+                let (client, sender) = (client.clone(), loader.sender());
+                let label = format!("{}.{} {}", db, coll, filter);
+                let id = tasks.spawn("count", label, move |progress| async move {
+                    let comment = progress.tag_operations(&client);
+                    let count = query_service
+                        .count_matching(&db, &coll, filter, comment)
+                        .await?;
+                    let _ = sender.send(Loaded::Count {
+                        key,
+                        result: Ok(count),
+                    });
+                    Ok(format!("{} document(s) of {}.{} match", count, db, coll))
+                });
+                state.count_task = Some(id);
+            }
+            None => loader.spawn_quiet(async move {
+                let result = query_service.estimated_count(&db, &coll).await;
+                Loaded::Count { key, result }
+            }),
+        }
+    }
+
+    // helper function to open the fuzzy finder and list every namespace for it
+    fn open_finder(state: &mut AppState, conn_service: &ConnectionService, loader: &Loader) {
+        let Some(client) = conn_service.get_client() else {
+            state.set_message(Some("Connect first to find databases and collections".to_string()));
+            return;
+        };
+        state.open_finder();
+        let client = client.clone();
+        let known = state.databases.iter().map(|db| db.name.clone()).collect();
+        loader.spawn_quiet(async move {
+            Loaded::Namespaces(finder::list_namespaces(&client, known).await)
+        });
+    }
+
+    // helper function to open the database, and its collection, picked in the finder
+    async fn jump_to_namespace(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        db_name: String,
+        coll_name: Option<String>,
+    ) {
+        state.set_error(None);
+        if !open_database(state, conn_service, db_name).await {
+            return;
+        }
+        if let Some(coll_name) = coll_name {
+            state.select_collection(&coll_name);
+            if state
+                .get_selected_collection()
+                .is_some_and(|c| c.name == coll_name)
+            {
+                open_collection(state, conn_service, loader, false);
+            }
+        }
+    }
+
+    // helper function to take the next step towards the namespace and query
+    // given on the command line, once the step before it loaded
+    async fn follow_launch(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        launch: &mut Option<cli::Args>,
+        ok: bool,
+    ) {
+        let Some(mut args) = launch.take() else {
+            return;
+        };
+        if !ok {
+            return;
+        }
+        if let Some(db_name) = args.db.take() {
+            jump_to_namespace(state, conn_service, loader, db_name.clone(), args.coll.clone())
+                .await;
+            if let Some(coll_name) = &args.coll
+                && state.current_screen == app::screen::Screen::CollectionList
+                && state
+                    .get_selected_collection()
+                    .is_none_or(|c| &c.name != coll_name)
+            {
+                state.set_error(Some(format!("No collection {}.{}", db_name, coll_name)));
+                return;
+            }
+            // the query waits for the collection's first page
+            if args.query.is_some() && loader.is_busy() {
+                *launch = Some(args);
+            }
+        } else if let Some(query) = args.query.take()
+            && state.current_screen == app::screen::Screen::DocumentView
+        {
+            state.query_input.set(query);
+            run_query(state, conn_service, loader);
+        }
+    }
+
+    // helper function to sample the read and write counters behind the heat
+    // indicator, quietly since nothing waits on it
+    fn load_op_counters(
+        state: &AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        db_name: String,
+    ) {
+        if let Some(client) = conn_service.get_client() {
+            let client = client.clone();
+            let collections: Vec<String> =
+                state.collections.iter().map(|c| c.name.clone()).collect();
+            loader.spawn_quiet(async move {
+                let result = heat::op_counters(&client, &db_name, &collections).await;
+                Loaded::OpCounters {
+                    db: db_name,
+                    result,
+                }
+            });
+        }
+    }
+
+    // helper function to load a collection's storage statistics in the background
+    fn load_collection_stats(
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        db_name: String,
+        coll_name: String,
+    ) {
+        if let Some(client) = conn_service.get_client() {
+            let client = client.clone();
+            loader.spawn(async move {
+                let result = stats::collection_stats(&client, &db_name, &coll_name).await;
+                Loaded::CollectionStats(result)
+            });
+        }
+    }
+
+    // helper function to diff the selected collection, or the one on screen,
+    // against an export file
+    fn start_reference_diff(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        path: &str,
+    ) {
+        let coll_name = match state.current_screen {
+            app::screen::Screen::ReferenceDiff => state
+                .reference_diff
+                .as_ref()
+                .and_then(|d| d.namespace.split_once('.'))
+                .map(|(_, coll)| coll.to_string()),
+            _ => state.get_selected_collection().map(|c| c.name.clone()),
+        };
+        if let (Some(db_name), Some(coll_name)) = (state.current_database.clone(), coll_name)
+            && let Some(client) = conn_service.get_client()
+        {
+            let client = client.clone();
+            let throttle = conn_service.throttle();
+            let path = PathBuf::from(path);
+            state.set_error(None);
+            loader.spawn(async move {
+                Loaded::ReferenceDiff(
+                    reference::diff_against(&client, &db_name, &coll_name, &path, throttle).await,
+                )
+            });
+        }
+    }
+
+    // helper function to stream the previewed file into the current collection
+    fn start_import(state: &mut AppState, conn_service: &ConnectionService, tasks: &TaskRegistry) {
+        let form = &state.import_form;
+        if form.preview.is_empty() {
+            state.set_error(Some("Nothing to import from this file".to_string()));
+            return;
+        }
+        let (Some(db_name), Some(coll_name)) = (
+            state.current_database.clone(),
+            state.current_collection.clone(),
+        ) else {
+            return;
+        };
+        let action = match form.mode {
+            ImportMode::Insert => "insert",
+            ImportMode::Upsert => "update",
+        };
+        if let Some(reason) = state.permission_denial(&db_name, &coll_name, action, "Importing") {
+            state.set_error(Some(reason));
+            return;
+        }
+        if state.dry_run {
+            let form = &state.import_form;
+            state.would_execute = Some(WouldExecute {
+                target: format!("{}.{}", db_name, coll_name),
+                operation: match form.mode {
+                    ImportMode::Insert => "insertMany",
+                    ImportMode::Upsert => "replaceOne by _id, upsert",
+                }
+                .to_string(),
+                payload: Some(format!(
+                    "Every document of {}, starting with {}",
+                    form.path.trim(),
+                    dry_run::extended_json(&form.preview[0])
+                )),
+                ..WouldExecute::default()
+            });
+            return;
+        }
+        let Some(client) = conn_service.get_client() else {
+            return;
+        };
+
+        let form = &state.import_form;
+        let client = client.clone();
+        let throttle = conn_service.throttle();
+        let path = form.path.trim().to_string();
+        let mode = form.mode;
+        let label = format!("{} → {}.{}", path, db_name, coll_name);
+        tasks.spawn("import", label, move |progress| {
+            import::import_documents(client, db_name, coll_name, path, mode, throttle, progress)
+        });
+        state.set_error(None);
+        state.set_screen(app::screen::Screen::DocumentView);
+        state.set_message(Some(
+            "Import started in the background [Ctrl+B] Tasks; [r] refreshes the page".to_string(),
+        ));
+    }
+
+    // helper function to bring another collection tab to the front
+    fn switch_tab(state: &mut AppState, loader: &Loader, step: TabMove) {
+        // a page still loading would land in the wrong tab
+        if loader.is_busy()
+            || !matches!(
+                state.current_screen,
+                app::screen::Screen::DocumentView | app::screen::Screen::CollectionList
+            )
+        {
+            return;
+        }
+        if state.tabs.is_empty() {
+            state.set_message(Some(
+                "Only one collection is open; T in the collection list opens another in a tab"
+                    .to_string(),
+            ));
+            return;
+        }
+        match step {
+            TabMove::Next => state.cycle_tab(1),
+            TabMove::Previous => state.cycle_tab(-1),
+            TabMove::To(number) => state.switch_tab(number - 1),
+        }
+        state.set_error(None);
+    }
+
+    // helper function to swap the active connection for the one picked in the switcher
+    fn switch_session(
+        state: &mut AppState,
+        conn_service: &mut ConnectionService,
+        sessions: &mut Sessions,
+    ) {
+        let parked = state
+            .selected_parked_session()
+            .and_then(|index| sessions.take(index));
+        // an unconnected connection screen is not worth keeping
+        if state.connection.is_some() {
+            let view = state.take_session_view();
+            let service = std::mem::replace(conn_service, ConnectionService::new());
+            sessions.park(service, view);
+        } else {
+            state.take_session_view();
+        }
+        state.set_error(None);
+        if let Some(parked) = parked {
+            *conn_service = parked.service;
+            state.restore_session_view(parked.view);
+            state.set_message(Some(format!("Switched to {}", state.session_label())));
+        }
+    }
+
+    // helper function to flag filters that fall back to a collection scan
+    async fn check_query_plan(state: &mut AppState, conn_service: &ConnectionService) {
+        let db_name = state.current_database.clone();
+        let coll_name = state.current_collection.clone();
+        let filter = state.filter.clone();
+
+        if let (Some(db_name), Some(coll_name), Some(filter)) = (db_name, coll_name, filter)
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            // explain is best-effort; a failure simply leaves no warning
+            let warning = query_service
+                .explain_find(&db_name, &coll_name, filter.clone())
+                .await
+                .ok()
+                .and_then(|plan| {
+                    explain::scan_warning(&plan, &filter, explain::COLLSCAN_WARNING_THRESHOLD)
+                });
+            state.set_scan_warning(warning);
+        } else {
+            state.set_scan_warning(None);
+        }
+    }
+
+    // helper function to act on a confirmed prompt
+    // failures leave privileges unknown, which never blocks an action
+    async fn load_privileges(state: &mut AppState, conn_service: &ConnectionService) {
+        if let Some(client) = conn_service.get_client() {
+            state.set_privileges(permissions::fetch(client).await.ok());
+        }
+    }
+
+    // helper function to check the privileges on a namespace, or deployment-wide,
+    // reading them in the background first when they are not known yet
+    fn check_permissions(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        namespace: Option<(String, String)>,
+    ) {
+        if state.privileges.is_some() {
+            show_permissions(state, namespace);
+        } else if let Some(client) = conn_service.get_client().cloned() {
+            loader.spawn(async move {
+                let result = permissions::fetch(&client).await;
+                Loaded::Privileges { namespace, result }
+            });
+        }
+    }
+
+    // helper function to open the privilege checks, or say the privileges are unknown
+    fn show_permissions(state: &mut AppState, namespace: Option<(String, String)>) {
+        let checks = match (&state.privileges, namespace) {
+            (Some(privileges), Some((db_name, coll_name))) => {
+                permissions::probe(privileges, &db_name, &coll_name)
+            }
+            (Some(privileges), None) => permissions::probe_cluster(privileges),
+            (None, _) => {
+                state.set_error(Some(
+                    "Could not read privileges for this connection".to_string(),
+                ));
+                return;
+            }
+        };
+        state.open_permissions(checks);
+    }
+
+    // helper function to count every collection on both connections in the background
+    fn run_count_comparison(
+        state: &AppState,
+        conn_service: &ConnectionService,
+        compare_service: &ConnectionService,
+        loader: &Loader,
+    ) {
+        let (Some(left), Some(right)) = (
+            conn_service.get_client().cloned(),
+            compare_service.get_client().cloned(),
+        ) else {
+            return;
+        };
+        let throttles = (conn_service.throttle(), compare_service.throttle());
+        let target = state.compare_target.clone();
+        loader.spawn(async move {
+            let result = tokio::try_join!(
+                compare::collection_counts(&left, throttles.0),
+                compare::collection_counts(&right, throttles.1)
+            )
+            .map(|(left_counts, right_counts)| compare::compare(&left_counts, &right_counts));
+            Loaded::CountComparison { target, result }
+        });
+    }
+
+    /// Reconnects with another environment's profile and re-opens the current
+    /// namespace with the current filter.
+    async fn switch_environment(
+        state: &mut AppState,
+        conn_service: &mut ConnectionService,
+        env_name: &str,
+    ) {
+        let target = state.current_environment().and_then(|(set, _)| {
+            set.environments
+                .iter()
+                .find(|e| e.name.eq_ignore_ascii_case(env_name))
+                .cloned()
+        });
+        let Some(target) = target else {
+            state.set_error(Some(format!("Unknown environment: {}", env_name)));
+            return;
+        };
+        let Some(profile) = state.find_profile(&target.profile) else {
+            state.set_error(Some(format!("Profile not found: {}", target.profile)));
+            return;
+        };
+        let (Some(db_name), Some(coll_name)) = (
+            state.current_database.clone(),
+            state.current_collection.clone(),
+        ) else {
+            return;
+        };
+
+        state.set_loading(true);
+        match conn_service.connect(&profile.uri, Some(&profile)).await {
+            Ok(server_info) => {
+                state.connection_input.set(profile.uri.clone());
+                state.set_connection(profile.uri.clone(), server_info);
+                state.set_endpoint(conn_service.endpoint());
+                state.active_profile = Some(profile);
+                load_privileges(state, conn_service).await;
+
+                if let Some(client) = conn_service.get_client() {
+                    let query_service = QueryService::new(client.clone());
+                    if let Ok(databases) = query_service.list_databases().await {
+                        state.set_databases(databases);
+                    }
+                    if let Ok(collections) = query_service.list_collections(&db_name).await {
+                        state.set_collections(collections);
+                        if let Some(index) =
+                            state.collections.iter().position(|c| c.name == coll_name)
+                        {
+                            state.selected_coll_index = index;
+                        }
+                    }
+
+                    let result = match state.query_params() {
+                        Some(params) => query_service.find(params).await,
+                        None => Ok(Vec::new()),
+                    };
+                    match result {
+                        Ok(documents) => {
+                            state.set_documents(documents);
+                            state.selected_doc_index = 0;
+                            state.doc_scroll_offset = 0;
+                            state.set_error(None);
+                            state.set_message(Some(format!(
+                                "Switched to {} ({})",
+                                target.name, target.profile
+                            )));
+                            check_query_plan(state, conn_service).await;
+                        }
+                        Err(e) => {
+                            state.set_error(Some(format!(
+                                "Failed to load {}.{} in {}: {}",
+                                db_name, coll_name, target.name, e
+                            )));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                state.set_error(Some(format!("Failed to connect to {}: {}", target.name, e)));
+            }
+        }
+        state.set_loading(false);
+    }
+
+    async fn submit_prompt(
+        prompt: Prompt,
+        state: &mut AppState,
+        conn_service: &mut ConnectionService,
+        compare_service: &mut ConnectionService,
+        tasks: &TaskRegistry,
+        loader: &Loader,
+    ) {
+        if prompt.kind.is_write()
+            && let Some(reason) = state.read_only_denial("This action")
+        {
+            state.set_error(Some(reason));
+            return;
+        }
+        match prompt.kind {
+            PromptKind::PageSize => match state.parse_page_size(&prompt.input) {
+                Ok(page_size) => {
+                    estimate_page_bytes(state, conn_service, loader, page_size);
+                }
+                Err(e) => {
+                    state.set_error(Some(e));
+                }
+            },
+            PromptKind::AddStage => {
+                let operator = prompt.input.trim();
+                if operator.trim_start_matches('$').is_empty() {
+                    state.set_error(Some("Enter a stage operator such as $match".to_string()));
+                } else {
+                    state.add_stage(aggregation::stage_for(operator, &state.stage_snippets));
+                }
+            }
+            PromptKind::ImportPipeline => {
+                let input = prompt.input.trim();
+                let text = match std::fs::read_to_string(input) {
+                    Ok(contents) => contents,
+                    Err(_) => input.to_string(),
+                };
+                match aggregation::import_pipeline(&text) {
+                    Ok(stages) => {
+                        let count = stages.len();
+                        state.set_pipeline(stages);
+                        state.set_error(None);
+                        state.set_message(Some(format!("Imported {} stage(s)", count)));
+                    }
+                    Err(e) => state.set_error(Some(e)),
+                }
+            }
+            PromptKind::EditStage => {
+                state.set_selected_stage_body(prompt.input.trim().to_string());
+                if let Some(Err(e)) = state.get_selected_stage().map(PipelineStage::to_document) {
+                    state.set_error(Some(e));
+                } else {
+                    state.set_error(None);
+                }
+            }
+            PromptKind::AddColumn => match state.add_column(&prompt.input) {
+                Ok(()) => save_columns(state),
+                Err(e) => state.set_error(Some(e)),
+            },
+            PromptKind::ConfirmDeleteProfile => {
+                if prompt.input.trim().eq_ignore_ascii_case("y")
+                    && let Some(profile) = state.delete_selected_profile()
+                {
+                    match state.profile_store().save() {
+                        Ok(()) => {
+                            state.set_message(Some(format!("Deleted profile {}", profile.name)))
+                        }
+                        Err(e) => state.set_error(Some(format!("Failed to save profiles: {}", e))),
+                    }
+                }
+            }
+            PromptKind::Command => {
+                run_command(state, prompt.input.trim());
+            }
+            PromptKind::DistinctField => {
+                let field = prompt.input.trim().to_string();
+                if !field.is_empty() {
+                    load_distinct_values(state, conn_service, loader, field);
+                }
+            }
+            PromptKind::ConfirmBulkUpdate => {
+                if prompt.input.trim().eq_ignore_ascii_case("y")
+                    && let Some(preview) = state.bulk_update.preview.clone()
+                    && let (Some(db_name), Some(coll_name)) = (
+                        state.current_database.clone(),
+                        state.current_collection.clone(),
+                    )
+                    && let Some(client) = conn_service.get_client()
+                {
+                    if state.dry_run {
+                        let namespace = format!("{}.{}", db_name, coll_name);
+                        let summary = WouldExecute::write(
+                            &namespace,
+                            "updateMany",
+                            &preview.filter,
+                            dry_run::extended_json(&preview.update),
+                        );
+                        state.would_execute = Some(WouldExecute {
+                            matched: Some(preview.matched),
+                            ..summary
+                        });
+                        return;
+                    }
+                    let client = client.clone();
+                    loader.spawn(async move {
+                        let result = bulk_update::apply(
+                            &client,
+                            &db_name,
+                            &coll_name,
+                            preview.filter,
+                            preview.update,
+                        )
+                        .await;
+                        Loaded::BulkUpdated(result)
+                    });
+                }
+            }
+            PromptKind::ConfirmPreImages => {
+                if prompt.input.trim().eq_ignore_ascii_case("y")
+                    && let (Some(db_name), Some(coll)) = (
+                        state.current_database.clone(),
+                        state.get_selected_collection(),
+                    )
+                    && let Some(client) = conn_service.get_client()
+                {
+                    let (coll_name, enabled) = (coll.name.clone(), !coll.pre_and_post_images);
+                    if state.dry_run {
+                        let command = mongodb::bson::doc! {
+                            "collMod": &coll_name,
+                            "changeStreamPreAndPostImages": { "enabled": enabled },
+                        };
+                        let namespace = format!("{}.{}", db_name, coll_name);
+                        state.would_execute = Some(WouldExecute::command(&namespace, &command));
+                        return;
+                    }
+                    let query_service = QueryService::new(client.clone());
+                    let client = client.clone();
+                    loader.spawn(async move {
+                        let result = async {
+                            collections::set_pre_and_post_images(
+                                &client, &db_name, &coll_name, enabled,
+                            )
+                            .await?;
+                            query_service.list_collections(&db_name).await
+                        }
+                        .await;
+                        Loaded::Collections {
+                            db: db_name,
+                            open: false,
+                            result,
+                        }
+                    });
+                }
+            }
+            PromptKind::ConfirmBalancer => {
+                if prompt.input.trim().eq_ignore_ascii_case("y")
+                    && let Some(status) = &state.sharding
+                {
+                    let action = ShardingAction::SetBalancer(!status.balancer_enabled());
+                    run_sharding_action(state, conn_service, loader, action);
+                }
+            }
+            PromptKind::AddZoneRange => match ZoneRange::parse(&prompt.input) {
+                Ok(range) => {
+                    run_sharding_action(
+                        state,
+                        conn_service,
+                        loader,
+                        ShardingAction::AddZoneRange(range),
+                    );
+                }
+                Err(e) => {
+                    state.set_error(Some(e));
+                }
+            },
+            PromptKind::ConfirmRemoveZone => {
+                if prompt.input.trim().eq_ignore_ascii_case("y")
+                    && let Some(range) = state.get_selected_zone()
+                {
+                    let action = ShardingAction::RemoveZoneRange(range.clone());
+                    run_sharding_action(state, conn_service, loader, action);
+                }
+            }
+            PromptKind::ConfirmSplit => {
+                if prompt.input.trim().eq_ignore_ascii_case("y")
+                    && let Some(chunk) = state.get_selected_chunk()
+                {
+                    let action = ShardingAction::Split(chunk.clone());
+                    run_sharding_action(state, conn_service, loader, action);
+                }
+            }
+            PromptKind::MoveChunkTarget => {
+                let target = prompt.input.trim();
+                if let (Some(status), Some(chunk)) = (&state.sharding, state.get_selected_chunk()) {
+                    if !status.shards.iter().any(|s| s == target) || target == chunk.shard {
+                        state.set_error(Some(format!("'{}' is not another shard", target)));
                     } else {
-                        // Normal navigation mode
-                        match key.code {
-                            KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                state.quit();
-                            }
-                            KeyCode::Char('f') => {
-                                state.enter_filter_mode();
+                        let action = ShardingAction::Move(chunk.clone(), target.to_string());
+                        if !state.dry_run {
+                            state.set_message(Some(format!("Moving chunk to {}...", target)));
+                        }
+                        run_sharding_action(state, conn_service, loader, action);
+                    }
+                }
+            }
+            PromptKind::ConfirmStepDown => {
+                let primary = state
+                    .replica_set
+                    .as_ref()
+                    .and_then(|rs| rs.primary())
+                    .map(|p| p.name.clone());
+                match (primary, conn_service.get_client()) {
+                    (Some(primary), _) if state.dry_run && prompt.input.trim() == primary => {
+                        let command = mongodb::bson::doc! { "replSetStepDown": STEPDOWN_SECS };
+                        state.would_execute = Some(WouldExecute::command(&primary, &command));
+                    }
+                    (Some(primary), Some(client)) if prompt.input.trim() == primary => {
+                        state.set_loading(true);
+                        match ReplicaSetService::new(client.clone())
+                            .step_down(STEPDOWN_SECS)
+                            .await
+                        {
+                            Ok(()) => {
+                                state.set_message(Some(format!(
+                                    "{} asked to step down; an election follows",
+                                    primary
+                                )));
                             }
-                            KeyCode::Char('/') => {
-                                state.enter_query_mode();
+                            Err(e) => {
+                                state.set_error(Some(e.to_string()));
                             }
-                            KeyCode::Down | KeyCode::Char('j') => {
-                                state.select_next_doc();
+                        }
+                        state.set_loading(false);
+                    }
+                    _ => {
+                        state
+                            .set_error(Some("Step down cancelled: name did not match".to_string()));
+                    }
+                }
+            }
+            PromptKind::ConfirmDropCollection => {
+                let db_name = state.current_database.clone();
+                let coll_name = state.get_selected_collection().map(|c| c.name.clone());
+                match (db_name, coll_name, conn_service.get_client()) {
+                    (Some(db_name), Some(coll_name), _)
+                        if state.dry_run && prompt.input.trim() == coll_name =>
+                    {
+                        let namespace = format!("{}.{}", db_name, coll_name);
+                        let command = mongodb::bson::doc! { "drop": &coll_name };
+                        let summary = WouldExecute::command(&namespace, &command);
+                        let count = Some((db_name, coll_name, None));
+                        show_dry_run(state, conn_service, loader, summary, count);
+                    }
+                    (Some(db_name), Some(coll_name), Some(client))
+                        if prompt.input.trim() == coll_name =>
+                    {
+                        let query_service = QueryService::new(client.clone());
+                        let client = client.clone();
+                        state.set_error(None);
+                        loader.spawn(async move {
+                            let result = async {
+                                collections::drop_collection(&client, &db_name, &coll_name).await?;
+                                query_service.list_collections(&db_name).await
                             }
-                            KeyCode::Up | KeyCode::Char('k') => {
-                                state.select_prev_doc();
+                            .await;
+                            Loaded::CollectionDropped {
+                                db: db_name,
+                                name: coll_name,
+                                result,
                             }
-                            KeyCode::PageDown => {
-                                state.scroll_doc_down();
+                        });
+                    }
+                    _ => {
+                        state.set_error(Some("Drop cancelled: name did not match".to_string()));
+                    }
+                }
+            }
+            PromptKind::RenameCollection => {
+                let db_name = state.current_database.clone();
+                let coll_name = state.get_selected_collection().map(|c| c.name.clone());
+                let new_name = prompt.input.trim().to_string();
+                match (db_name, coll_name, conn_service.get_client()) {
+                    (Some(_), Some(coll_name), _)
+                        if new_name.is_empty() || new_name == coll_name =>
+                    {
+                        state.set_error(Some(
+                            "Rename cancelled: enter a different name".to_string(),
+                        ));
+                    }
+                    (Some(db_name), Some(coll_name), _) if state.dry_run => {
+                        let command = mongodb::bson::doc! {
+                            "renameCollection": format!("{}.{}", db_name, coll_name),
+                            "to": format!("{}.{}", db_name, new_name),
+                            "dropTarget": false,
+                        };
+                        let summary = WouldExecute::command("admin", &command);
+                        let count = Some((db_name, coll_name, None));
+                        show_dry_run(state, conn_service, loader, summary, count);
+                    }
+                    (Some(db_name), Some(coll_name), Some(client)) => {
+                        let query_service = QueryService::new(client.clone());
+                        let client = client.clone();
+                        state.set_error(None);
+                        loader.spawn(async move {
+                            let result = async {
+                                collections::rename_collection(
+                                    &client, &db_name, &coll_name, &new_name,
+                                )
+                                .await?;
+                                query_service.list_collections(&db_name).await
                             }
-                            KeyCode::PageUp => {
-                                state.scroll_doc_up();
+                            .await;
+                            Loaded::CollectionRenamed {
+                                db: db_name,
+                                from: coll_name,
+                                to: new_name,
+                                result,
                             }
-                            KeyCode::Backspace => {
-                                state.set_screen(app::screen::Screen::CollectionList);
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            PromptKind::CloneCollection => match collections::ClonePlan::parse(&prompt.input) {
+                Ok(plan) => {
+                    let db_name = state.current_database.clone();
+                    let coll_name = state.get_selected_collection().map(|c| c.name.clone());
+                    if state.collections.iter().any(|c| c.name == plan.name) {
+                        state.set_error(Some(format!("{} already exists", plan.name)));
+                    } else if state.dry_run
+                        && let (Some(db_name), Some(coll_name)) =
+                            (db_name.clone(), coll_name.clone())
+                    {
+                        let summary = WouldExecute {
+                            target: format!("{}.{}", db_name, plan.name),
+                            operation: "create + insertMany".to_string(),
+                            payload: Some(format!(
+                                "Every document of {}.{}{}",
+                                db_name,
+                                coll_name,
+                                if plan.indexes { ", then its indexes" } else { "" }
+                            )),
+                            ..WouldExecute::default()
+                        };
+                        let count = Some((db_name, coll_name, None));
+                        show_dry_run(state, conn_service, loader, summary, count);
+                    } else if let (Some(db_name), Some(coll_name), Some(client)) =
+                        (db_name, coll_name, conn_service.get_client())
+                    {
+                        let client = client.clone();
+                        let throttle = conn_service.throttle();
+                        let label = format!("{}.{} → {}", db_name, coll_name, plan.name);
+                        tasks.spawn("duplicate", label, move |progress| {
+                            collections::clone_collection(
+                                client, db_name, coll_name, plan, throttle, progress,
+                            )
+                        });
+                        state.set_error(None);
+                        state.set_message(Some(
+                            "Duplicate started in the background [Ctrl+B] Tasks, [r] refreshes"
+                                .to_string(),
+                        ));
+                    }
+                }
+                Err(e) => state.set_error(Some(e)),
+            },
+            PromptKind::ConfirmDropDatabase => {
+                let db_name = state.get_selected_database().map(|db| db.name.clone());
+                match (db_name, conn_service.get_client()) {
+                    (Some(db_name), _) if state.dry_run && prompt.input.trim() == db_name => {
+                        let command = mongodb::bson::doc! { "dropDatabase": 1 };
+                        state.would_execute = Some(WouldExecute::command(&db_name, &command));
+                    }
+                    (Some(db_name), Some(client)) if prompt.input.trim() == db_name => {
+                        let query_service = QueryService::new(client.clone());
+                        let client = client.clone();
+                        state.set_error(None);
+                        loader.spawn(async move {
+                            let result = async {
+                                collections::drop_database(&client, &db_name).await?;
+                                query_service.list_databases().await
                             }
-                            KeyCode::Esc => {
-                                state.clear_filter();
-                                reload_documents_without_filter(&mut state, &conn_service).await;
+                            .await;
+                            Loaded::DatabaseDropped {
+                                name: db_name,
+                                result,
                             }
-                            KeyCode::Char('r') => {
-                                let db_name = state.current_database.clone();
-                                let coll_name = state.current_collection.clone();
+                        });
+                    }
+                    _ => {
+                        state.set_error(Some("Drop cancelled: name did not match".to_string()));
+                    }
+                }
+            }
+            PromptKind::ConfirmLargePage => {
+                if let Some(page_size) = state.pending_page_size.take()
+                    && prompt.input.trim().eq_ignore_ascii_case("y")
+                {
+                    state.set_page_size(page_size);
+                    reload_documents(state, conn_service, loader);
+                }
+            }
+            PromptKind::SwitchEnvironment => {
+                switch_environment(state, conn_service, prompt.input.trim()).await;
+            }
+            PromptKind::CompareTarget => {
+                let target = prompt.input.trim().to_string();
+                let (uri, profile) = match state.find_profile(&target) {
+                    Some(profile) => (profile.uri.clone(), Some(profile)),
+                    None => (target.clone(), None),
+                };
 
-                                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name) {
-                                    state.set_loading(true);
-                                    if let Some(client) = conn_service.get_client() {
-                                        let query_service = QueryService::new(client.clone());
-                                        match query_service
-                                            .find_documents(&db_name, &coll_name, None, 0, 20)
-                                            .await
-                                        {
-                                            Ok(documents) => {
-                                                state.set_documents(documents);
-                                            }
-                                            Err(e) => {
-                                                state.set_error(Some(format!(
-                                                    "Failed to refresh: {}",
-                                                    e
-                                                )));
-                                            }
-                                        }
-                                    }
-                                    state.set_loading(false);
-                                }
-                            }
-                            _ => {}
+                state.set_loading(true);
+                match compare_service.connect(&uri, profile.as_ref()).await {
+                    Ok(_) => {
+                        state.compare_target = target;
+                        run_count_comparison(state, conn_service, compare_service, loader);
+                    }
+                    Err(e) => {
+                        state.set_error(Some(format!("Comparison connection failed: {}", e)));
+                    }
+                }
+                state.set_loading(false);
+            }
+            PromptKind::ShardKeyCandidate => {
+                let coll_name = state.get_selected_collection().map(|c| c.name.clone());
+                let db_name = state.current_database.clone();
+
+                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                    && let Some(client) = conn_service.get_client()
+                {
+                    match shard_key::parse_candidate(&prompt.input) {
+                        Ok(candidate) => {
+                            let client = client.clone();
+                            state.set_error(None);
+                            loader.spawn(async move {
+                                Loaded::ShardKey(
+                                    shard_key::analyze(&client, &db_name, &coll_name, candidate)
+                                        .await,
+                                )
+                            });
+                        }
+                        Err(e) => {
+                            state.set_error(Some(e));
                         }
                     }
                 }
             }
-        }
+            PromptKind::ExportTablePath => {
+                let path = PathBuf::from(prompt.input.trim());
+                let columns = state.displayed_columns();
+                match table::write_table(&columns, &state.documents, &path) {
+                    Ok(()) => {
+                        let message = format!(
+                            "Exported {} row(s) × {} column(s) to {}",
+                            state.documents.len(),
+                            columns.len(),
+                            path.display()
+                        );
+                        state.set_error(None);
+                        state.set_message(Some(message));
+                    }
+                    Err(e) => state.set_error(Some(format!("Export failed: {}", e))),
+                }
+            }
+            PromptKind::Benchmark => match benchmark::BenchmarkPlan::parse(&prompt.input) {
+                Ok(plan) => {
+                    if let Some(params) = state.query_params()
+                        && let Some(client) = conn_service.get_client()
+                    {
+                        let query_service = QueryService::new(client.clone());
+                        let label =
+                            format!("{}.{} × {}", params.database, params.collection, plan.runs);
+                        tasks.spawn("benchmark", label, move |progress| {
+                            benchmark::run(query_service, params, plan, progress)
+                        });
+                        state.set_error(None);
+                        state.set_message(Some(
+                            "Benchmark started in the background [Ctrl+B] Tasks".to_string(),
+                        ));
+                    }
+                }
+                Err(e) => state.set_error(Some(e)),
+            },
+            PromptKind::SaveQueryName => match state.save_current_query(&prompt.input) {
+                Ok(()) => match state.saved_queries.save() {
+                    Ok(()) => {
+                        state.set_error(None);
+                        state.set_message(Some(format!(
+                            "Saved query {} [Q] Saved queries",
+                            prompt.input.trim()
+                        )));
+                    }
+                    Err(e) => state.set_error(Some(format!("Failed to save queries: {}", e))),
+                },
+                Err(e) => state.set_error(Some(e)),
+            },
+            PromptKind::RecordChangesPath => match state.start_tail_recording(&prompt.input) {
+                Ok(()) => {
+                    state.set_error(None);
+                    state.set_message(Some(format!(
+                        "Recording to {} [R] Stop",
+                        prompt.input.trim()
+                    )));
+                }
+                Err(e) => state.set_error(Some(e)),
+            },
+            PromptKind::ReplayChanges => match replay::ReplayPlan::parse(&prompt.input) {
+                Ok(plan) => start_replay(state, conn_service, tasks, plan).await,
+                Err(e) => state.set_error(Some(e)),
+            },
+            PromptKind::SchemaSampleSize => match state.parse_sample_size(&prompt.input) {
+                Ok(size) => {
+                    state.schema_sample_size = size;
+                    state.set_error(None);
+                    load_schema(state, conn_service, loader);
+                }
+                Err(e) => state.set_error(Some(e)),
+            },
+            PromptKind::QueryParameter => match state.answer_query_parameter(&prompt.input) {
+                Some(Ok((template, query))) => {
+                    state.set_error(None);
+                    run_filled_query(state, conn_service, loader, &template, &query);
+                }
+                Some(Err(e)) => state.set_error(Some(e)),
+                None => {}
+            },
+            PromptKind::OperationsMinSecs => match prompt.input.trim().parse::<u64>() {
+                Ok(secs) => {
+                    state.set_operations_min_secs(secs);
+                    state.set_error(None);
+                }
+                Err(_) => state.set_error(Some("Enter a number of seconds".to_string())),
+            },
+            PromptKind::NewUserName => {
+                let name = prompt.input.trim().to_string();
+                if name.is_empty() {
+                    state.set_error(Some("Enter a user name".to_string()));
+                } else {
+                    let title = format!("Password for {}", name);
+                    state.pending_user = Some(name);
+                    state.open_prompt(PromptKind::NewUserPassword, &title, "");
+                }
+            }
+            PromptKind::NewUserPassword => match state.pending_user.take() {
+                Some(_) if prompt.input.is_empty() => {
+                    state.set_error(Some("User not created: the password is empty".to_string()));
+                }
+                Some(name) => {
+                    let picker = RolePicker::new(name, prompt.input, &state.roles);
+                    state.role_picker = Some(picker);
+                }
+                None => {}
+            },
+            PromptKind::ChangeUserPassword => {
+                let user = state.get_selected_user().map(|u| u.user.clone());
+                match user {
+                    Some(_) if prompt.input.is_empty() => {
+                        state.set_error(Some(
+                            "Password not changed: the new one is empty".to_string(),
+                        ));
+                    }
+                    Some(user) => {
+                        let password = prompt.input;
+                        let change = UserChange::ChangePassword { user, password };
+                        run_user_change(state, conn_service, loader, change);
+                    }
+                    None => {}
+                }
+            }
+            PromptKind::ConfirmDropUser => {
+                match state.get_selected_user().map(|u| u.user.clone()) {
+                    Some(user) if prompt.input.trim() == user => {
+                        run_user_change(state, conn_service, loader, UserChange::Drop(user));
+                    }
+                    _ => {
+                        state.set_error(Some("Drop cancelled: name did not match".to_string()));
+                    }
+                }
+            }
+            PromptKind::ConfirmKillOp => {
+                let opid = prompt.input.trim();
+                let target = state
+                    .operations
+                    .iter()
+                    .find(|op| op.opid_label() == opid)
+                    .map(|op| op.opid.clone());
+                match (target, conn_service.get_client()) {
+                    (Some(target), _) if state.dry_run && !opid.is_empty() => {
+                        let command = mongodb::bson::doc! { "killOp": 1, "op": target };
+                        state.would_execute = Some(WouldExecute::command("admin", &command));
+                    }
+                    (Some(target), Some(client)) if !opid.is_empty() => {
+                        let client = client.clone();
+                        let opid = opid.to_string();
+                        state.set_error(None);
+                        loader.spawn(async move {
+                            Loaded::OperationKilled {
+                                result: operations::kill(&client, target).await,
+                                opid,
+                            }
+                        });
+                    }
+                    _ => {
+                        state.set_error(Some(
+                            "Kill cancelled: no running operation has that opid".to_string(),
+                        ));
+                    }
+                }
+            }
+            PromptKind::ReferenceDiffPath => {
+                start_reference_diff(state, conn_service, loader, prompt.input.trim());
+            }
+            PromptKind::SchemaReportPath => {
+                let coll_name = state.get_selected_collection().map(|c| c.name.clone());
+                let db_name = state.current_database.clone();
 
-        if state.should_quit {
-            break;
+                if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+                    && let Some(client) = conn_service.get_client()
+                {
+                    let query_service = QueryService::new(client.clone());
+                    let path = PathBuf::from(prompt.input.trim());
+                    let label = format!("{}.{} → {}", db_name, coll_name, path.display());
+                    tasks.spawn("schema report", label, move |progress| async move {
+                        let documents = query_service
+                            .sample_documents(&db_name, &coll_name, schema::DEFAULT_SAMPLE_SIZE)
+                            .await?;
+                        progress.set(documents.len() as u64, Some(documents.len() as u64));
+                        let analysis = schema::analyze(&db_name, &coll_name, &documents);
+                        report::write_schema_report(&analysis, &path)?;
+                        Ok(format!("Schema report written to {}", path.display()))
+                    });
+                    state.set_error(None);
+                    state.set_message(Some(
+                        "Schema report started in the background [Ctrl+B] Tasks".to_string(),
+                    ));
+                }
+            }
         }
     }
 
-    // helper function for dynamic filtering
-    async fn apply_dynamic_filter(state: &mut AppState, conn_service: &ConnectionService) {
+    // helper function for dynamic filtering; matches for stale input are dropped
+    fn apply_dynamic_filter(state: &AppState, conn_service: &ConnectionService, loader: &Loader) {
         if state.filter_input.is_empty() {
-            reload_documents_without_filter(state, conn_service).await;
+            reload_documents_without_filter(state, conn_service, loader, "Failed to reload");
             return;
         }
 
         let db_name = state.current_database.clone();
         let coll_name = state.current_collection.clone();
 
-        if let (Some(db_name), Some(coll_name)) = (db_name, coll_name) {
-            if let Some(client) = conn_service.get_client() {
-                let query_service = QueryService::new(client.clone());
-
-                // Get a sample document to extract field names
-                match query_service
-                    .find_documents(&db_name, &coll_name, None, 0, 1)
-                    .await
-                {
-                    Ok(sample_docs) => {
-                        if let Some(sample_doc) = sample_docs.first() {
-                            // Build $or array with regex for each field
-                            let mut or_conditions = Vec::new();
-
-                            for (key, _) in sample_doc.iter() {
-                                if key != "_id" {
-                                    or_conditions.push(mongodb::bson::doc! {
-                                        key: {"$regex": &state.filter_input, "$options": "i"}
-                                    });
-                                }
-                            }
-
-                            if or_conditions.is_empty() {
-                                reload_documents_without_filter(state, conn_service).await;
-                                return;
-                            }
-
-                            let filter = mongodb::bson::doc! {
-                                "$or": or_conditions
-                            };
-
-                            match query_service
-                                .find_documents(&db_name, &coll_name, Some(filter), 0, 20)
-                                .await
-                            {
-                                Ok(documents) => {
-                                    state.set_documents(documents);
-                                    state.set_error(None);
-                                }
-                                Err(e) => {
-                                    state.set_error(Some(format!("Search error: {}", e)));
-                                }
-                            }
-                        } else {
-                            state.set_documents(Vec::new());
-                        }
-                    }
-                    Err(e) => {
-                        state.set_error(Some(format!("Failed to analyze fields: {}", e)));
-                    }
+        if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            let input = state.filter_input.as_str().to_string();
+            let sort = state.newest_first.clone();
+            let limit = state.page_size as i64;
+            loader.spawn(async move {
+                let result = query_service
+                    .find_in_any_field(&db_name, &coll_name, &input, sort, limit)
+                    .await;
+                Loaded::LiveFilter {
+                    db: db_name,
+                    coll: coll_name,
+                    input,
+                    result,
                 }
+            });
+        }
+    }
+
+    // helper function to estimate a page's size from the collection's average document
+    // in the background; the page size prompt goes on once it is known
+    fn estimate_page_bytes(
+        state: &mut AppState,
+        conn_service: &ConnectionService,
+        loader: &Loader,
+        page_size: usize,
+    ) {
+        let (Some(db_name), Some(coll_name), Some(client)) = (
+            state.current_database.clone(),
+            state.current_collection.clone(),
+            conn_service.get_client(),
+        ) else {
+            state.set_page_size(page_size);
+            return;
+        };
+        // fall back to what is already loaded
+        let loaded_avg = (!state.documents.is_empty())
+            .then(|| state.result_bytes / state.documents.len() as u64);
+        let query_service = QueryService::new(client.clone());
+        loader.spawn(async move {
+            let avg = match query_service.avg_document_size(&db_name, &coll_name).await {
+                Ok(Some(avg)) => Some(avg),
+                _ => loaded_avg,
+            };
+            Loaded::PageEstimate {
+                page_size,
+                bytes: avg.map(|avg| memory::estimate_page_bytes(avg, page_size)),
             }
+        });
+    }
+
+    // helper function to reload the current page with the active filter
+    fn reload_documents(state: &AppState, conn_service: &ConnectionService, loader: &Loader) {
+        if let Some(params) = state.page_params(&state.page_cursor())
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            loader.spawn(async move {
+                let (db, coll) = (params.database.clone(), params.collection.clone());
+                let result = query_service.find(params).await;
+                Loaded::Reload { db, coll, result }
+            });
         }
     }
 
     // helper function to reload without filter
-    async fn reload_documents_without_filter(
-        state: &mut AppState,
+    fn reload_documents_without_filter(
+        state: &AppState,
         conn_service: &ConnectionService,
+        loader: &Loader,
+        label: &'static str,
     ) {
         let db_name = state.current_database.clone();
         let coll_name = state.current_collection.clone();
 
-        if let (Some(db_name), Some(coll_name)) = (db_name, coll_name) {
-            if let Some(client) = conn_service.get_client() {
-                let query_service = QueryService::new(client.clone());
-                match query_service
-                    .find_documents(&db_name, &coll_name, None, 0, 20)
-                    .await
-                {
-                    Ok(documents) => {
-                        state.set_documents(documents);
-                        state.set_error(None);
-                    }
-                    Err(e) => {
-                        state.set_error(Some(format!("Failed to reload: {}", e)));
-                    }
+        if let (Some(db_name), Some(coll_name)) = (db_name, coll_name)
+            && let Some(client) = conn_service.get_client()
+        {
+            let query_service = QueryService::new(client.clone());
+            let sort = state.newest_first.clone();
+            let limit = state.page_size as i64;
+            loader.spawn(async move {
+                let result = query_service
+                    .find_documents(&db_name, &coll_name, None, sort, 0, limit)
+                    .await;
+                Loaded::Documents {
+                    db: db_name,
+                    coll: coll_name,
+                    label,
+                    result,
                 }
-            }
+            });
         }
     }
 
@@ -550,7 +5917,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
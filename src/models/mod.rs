@@ -2,7 +2,8 @@ use crossterm::event::KeyCode;
 use mongodb::bson::Document;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseInfo {
@@ -12,6 +13,105 @@ pub struct DatabaseInfo {
     pub empty: bool,
 }
 
+/// The distinct values of a field with how many documents hold each.
+#[derive(Debug, Clone)]
+pub struct DistinctValues {
+    pub field: String,
+    /// Values in extended JSON with their counts.
+    pub values: Vec<(String, u64)>,
+    /// Sum of the counts, for each value's share.
+    pub total: u64,
+    /// Whether values past the listed ones were left out.
+    pub truncated: bool,
+    /// Ordered by value rather than by count.
+    pub by_value: bool,
+    pub selected: usize,
+}
+
+/// How BSON values are written in the document view.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ValueFormat {
+    /// Canonical extended JSON instead of readable forms.
+    pub canonical: bool,
+    /// Minutes east of UTC that dates are shown at.
+    pub utc_offset: i32,
+    pub date_format: DateFormat,
+}
+
+/// How readable dates are written; set with `date_format` in the settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DateFormat {
+    /// ISO 8601 with milliseconds and the offset.
+    #[default]
+    Iso,
+    /// Date and time to the second, e.g. `2024-05-01 14:00:00`.
+    Short,
+    /// Milliseconds since the Unix epoch.
+    Epoch,
+}
+
+impl DateFormat {
+    pub const NAMES: [&str; 3] = ["iso", "short", "epoch"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "iso" => Some(DateFormat::Iso),
+            "short" => Some(DateFormat::Short),
+            "epoch" => Some(DateFormat::Epoch),
+            _ => None,
+        }
+    }
+}
+
+/// The colors the screens are drawn in; set with `theme` in the settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ColorScheme {
+    /// For dark terminal backgrounds.
+    #[default]
+    Dark,
+    /// For light terminal backgrounds.
+    Light,
+    /// Bright colors on dark backgrounds, for low vision or glare.
+    HighContrast,
+    /// No colors; selections show in reverse video.
+    Mono,
+}
+
+impl ColorScheme {
+    pub const NAMES: [&str; 4] = ["dark", "light", "high-contrast", "mono"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" | "default" => Some(ColorScheme::Dark),
+            "light" => Some(ColorScheme::Light),
+            "high-contrast" | "high_contrast" => Some(ColorScheme::HighContrast),
+            "mono" => Some(ColorScheme::Mono),
+            _ => None,
+        }
+    }
+}
+
+/// Cumulative reads and writes a collection has served.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpCounters {
+    pub reads: u64,
+    pub writes: u64,
+}
+
+/// Reads and writes per second a collection served between two samples.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CollectionHeat {
+    pub reads: f64,
+    pub writes: f64,
+}
+
+/// Totals of a database from `dbStats`, loaded after the list is shown.
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseTotals {
+    pub documents: u64,
+    pub indexes: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollectionInfo {
     pub name: String,
@@ -19,6 +119,11 @@ pub struct CollectionInfo {
     pub size: u64,
     pub indexes: Vec<String>,
     pub capped: bool,
+    pub clustered: bool,
+    /// Whether change streams can return documents before and after a change.
+    pub pre_and_post_images: bool,
+    /// Field paths declared encrypted by CSFLE or Queryable Encryption.
+    pub encrypted_fields: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +133,7 @@ pub struct ServerInfo {
     pub port: u16,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct IndexInfo {
     pub name: String,
@@ -35,6 +141,613 @@ pub struct IndexInfo {
     pub unique: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct FieldStats {
+    pub path: String,
+    pub count: usize,
+    pub types: BTreeMap<String, usize>,
+    pub samples: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SchemaAnalysis {
+    pub database: String,
+    pub collection: String,
+    pub sampled: usize,
+    pub fields: Vec<FieldStats>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthStatus {
+    Green,
+    Yellow,
+    Red,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthItem {
+    pub name: String,
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanWarning {
+    pub docs_examined: u64,
+    pub returned: u64,
+    pub suggested_index: Document,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollectionKind {
+    Standard,
+    Capped,
+    TimeSeries,
+    Clustered,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FormField {
+    Name,
+    Kind,
+    CappedSize,
+    CappedMax,
+    TimeField,
+    MetaField,
+    Granularity,
+    ExpireAfter,
+    Validator,
+    CollationLocale,
+    CollationStrength,
+}
+
+/// Inputs of the create collection wizard; only the fields for `kind` apply.
+#[derive(Debug, Clone)]
+pub struct CollectionForm {
+    pub name: String,
+    pub kind: CollectionKind,
+    pub capped_size: String,
+    pub capped_max: String,
+    pub time_field: String,
+    pub meta_field: String,
+    pub granularity: usize,
+    pub expire_after: String,
+    pub validator: String,
+    pub collation_locale: String,
+    pub collation_strength: String,
+    pub focus: usize,
+}
+
+impl Default for CollectionForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            kind: CollectionKind::Standard,
+            capped_size: String::new(),
+            capped_max: String::new(),
+            time_field: "timestamp".to_string(),
+            meta_field: String::new(),
+            granularity: 0,
+            expire_after: String::new(),
+            validator: String::new(),
+            collation_locale: String::new(),
+            collation_strength: String::new(),
+            focus: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileField {
+    Name,
+    Uri,
+    DefaultDatabase,
+    ReadOnly,
+}
+
+/// Inputs of the connection profile editor; `editing` is the index of the
+/// profile being changed, `None` for a new one.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileForm {
+    pub editing: Option<usize>,
+    pub name: String,
+    pub uri: String,
+    pub default_database: String,
+    pub read_only: bool,
+    pub focus: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetupField {
+    Connection,
+    PageSize,
+    ReadOnly,
+    Language,
+}
+
+/// Answers of the first-run setup wizard, one step per field.
+#[derive(Debug, Clone)]
+pub struct SetupForm {
+    /// A URI, or the name of a saved profile.
+    pub connection: String,
+    pub page_size: String,
+    pub read_only: bool,
+    pub language: String,
+    pub focus: usize,
+}
+
+/// Field checkboxes for building a projection from sampled field paths.
+#[derive(Debug, Clone)]
+pub struct ProjectionPicker {
+    pub fields: Vec<String>,
+    pub checked: Vec<bool>,
+    /// Checked fields are left out instead of kept.
+    pub exclude: bool,
+    pub selected: usize,
+}
+
+/// A comparison offered by the query builder.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BuilderOperator {
+    #[default]
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    In,
+    Regex,
+    Exists,
+}
+
+/// One `field operator value` row of the query builder.
+#[derive(Debug, Clone, Default)]
+pub struct BuilderCondition {
+    pub field: String,
+    pub operator: BuilderOperator,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BuilderField {
+    #[default]
+    Field,
+    Operator,
+    Value,
+}
+
+/// The query builder popup: conditions picked from sampled field paths,
+/// combined into a filter for users who would rather not write JSON.
+#[derive(Debug, Clone)]
+pub struct QueryBuilder {
+    /// Field paths sampled from the collection, for completion.
+    pub fields: Vec<String>,
+    pub conditions: Vec<BuilderCondition>,
+    pub selected: usize,
+    pub focus: BuilderField,
+    /// Conditions are combined with `$or` instead of `$and`.
+    pub any: bool,
+}
+
+/// How long one query took, as seen by the driver.
+#[derive(Debug, Clone)]
+pub struct QueryTiming {
+    pub at: mongodb::bson::DateTime,
+    pub namespace: String,
+    pub command: String,
+    /// Filter, sort and pipeline with values replaced by their types.
+    pub shape: String,
+    pub shape_hash: u64,
+    pub duration: Duration,
+    pub failed: bool,
+}
+
+/// Runs of the same query shape against one namespace.
+#[derive(Debug, Clone)]
+pub struct TimingGroup {
+    pub namespace: String,
+    pub command: String,
+    pub shape: String,
+    pub shape_hash: u64,
+    pub durations: Vec<Duration>,
+    pub failures: usize,
+    pub last_run: mongodb::bson::DateTime,
+}
+
+/// One stage of an explained query plan, flattened in tree order.
+#[derive(Debug, Clone)]
+pub struct PlanNode {
+    pub stage: String,
+    pub depth: usize,
+    /// Index name, key pattern, direction or filter, whichever apply.
+    pub detail: Option<String>,
+    pub returned: Option<u64>,
+    pub keys_examined: Option<u64>,
+    pub docs_examined: Option<u64>,
+    pub time_ms: Option<u64>,
+    pub collapsed: bool,
+}
+
+/// The winning plan of the current query as a navigable tree.
+#[derive(Debug, Clone)]
+pub struct PlanTree {
+    pub nodes: Vec<PlanNode>,
+    pub selected: usize,
+    pub returned: u64,
+    pub keys_examined: u64,
+    pub docs_examined: u64,
+    pub time_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PipelineStage {
+    pub operator: String,
+    /// The stage's argument as JSON text, edited in place.
+    pub body: String,
+    pub enabled: bool,
+}
+
+/// The pipeline run on a sample through a stage body still being typed.
+#[derive(Debug, Clone)]
+pub struct DraftPreview {
+    pub body: String,
+    pub result: Result<Vec<Document>, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchOperator {
+    Text,
+    Phrase,
+    Autocomplete,
+    Wildcard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchField {
+    Index,
+    Operator,
+    Path,
+    Query,
+}
+
+/// The Atlas Search builder's inputs.
+#[derive(Debug, Clone)]
+pub struct SearchForm {
+    pub indexes: Vec<String>,
+    pub selected_index: usize,
+    pub operator: SearchOperator,
+    pub path: String,
+    pub query: String,
+    pub focus: SearchField,
+}
+
+impl Default for SearchForm {
+    fn default() -> Self {
+        Self {
+            indexes: Vec::new(),
+            selected_index: 0,
+            operator: SearchOperator::Text,
+            path: "*".to_string(),
+            query: String::new(),
+            focus: SearchField::Query,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub doc: mongodb::bson::Document,
+    pub score: f64,
+    /// Matched terms reported by `searchHighlights`.
+    pub terms: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// An edited document waiting for the user to confirm its diff.
+#[derive(Debug, Clone)]
+pub struct PendingEdit {
+    pub edited: mongodb::bson::Document,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChunkInfo {
+    pub min: mongodb::bson::Document,
+    pub max: mongodb::bson::Document,
+    pub shard: String,
+    pub jumbo: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ZoneRange {
+    pub min: mongodb::bson::Document,
+    pub max: mongodb::bson::Document,
+    pub zone: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShardingStatus {
+    pub namespace: String,
+    pub shard_key: mongodb::bson::Document,
+    pub balancer_mode: String,
+    pub balancer_running: bool,
+    pub shards: Vec<String>,
+    /// Zones each shard belongs to, keyed like `shards`.
+    pub shard_zones: Vec<Vec<String>>,
+    pub chunks: Vec<ChunkInfo>,
+    pub zones: Vec<ZoneRange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShardKeyCandidate {
+    pub fields: Vec<String>,
+    pub hashed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShardKeyAnalysis {
+    pub namespace: String,
+    pub candidate: ShardKeyCandidate,
+    pub sampled: u64,
+    pub distinct: u64,
+    pub missing: u64,
+    pub top_values: Vec<(String, u64)>,
+    pub monotonic_ratio: Option<f64>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplicaSetMember {
+    pub name: String,
+    pub state: String,
+    pub healthy: bool,
+    pub uptime_secs: Option<u64>,
+    pub optime_date: Option<mongodb::bson::DateTime>,
+    pub election_date: Option<mongodb::bson::DateTime>,
+    pub is_self: bool,
+    /// Round trip from the connected member's heartbeats, or of `hello`
+    /// for the connected member itself.
+    pub ping_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplicaSetStatus {
+    pub set_name: String,
+    pub term: Option<i64>,
+    pub members: Vec<ReplicaSetMember>,
+    pub last_election_reason: Option<String>,
+    pub last_election_date: Option<mongodb::bson::DateTime>,
+    pub oplog_window: Option<OplogWindow>,
+}
+
+/// The times of the oldest and newest oplog entries: how long a member may
+/// be offline and still catch up without a full resync.
+#[derive(Debug, Clone, Copy)]
+pub struct OplogWindow {
+    pub first: mongodb::bson::DateTime,
+    pub last: mongodb::bson::DateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElectionEvent {
+    pub term: Option<i64>,
+    pub at: Option<mongodb::bson::DateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventLogEntry {
+    pub at: mongodb::bson::DateTime,
+    pub level: EventLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    Running,
+    Completed(String),
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub kind: String,
+    pub label: String,
+    pub status: TaskStatus,
+    /// Units done so far and the total, if known.
+    pub progress: Option<(u64, Option<u64>)>,
+    pub started: Instant,
+    pub finished: Option<Instant>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Privilege {
+    pub db: Option<String>,
+    pub collection: Option<String>,
+    pub cluster: bool,
+    pub any_resource: bool,
+    pub actions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UserPrivileges {
+    pub users: Vec<String>,
+    pub privileges: Vec<Privilege>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PermissionCheck {
+    pub action: &'static str,
+    pub purpose: &'static str,
+    pub allowed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CountComparison {
+    pub namespace: String,
+    pub left: Option<u64>,
+    pub right: Option<u64>,
+}
+
+/// A write held back by dry run, shown instead of being sent.
+#[derive(Debug, Clone, Default)]
+pub struct WouldExecute {
+    /// The namespace, database or deployment written to.
+    pub target: String,
+    pub operation: String,
+    pub filter: Option<String>,
+    /// The command, update or documents, in extended JSON.
+    pub payload: Option<String>,
+    /// Documents the filter matched when the summary was made.
+    pub matched: Option<u64>,
+}
+
+/// An operation running on the server, from `$currentOp`.
+#[derive(Debug, Clone)]
+pub struct OperationInfo {
+    /// A number, or `shard:number` through mongos; passed back as is to kill.
+    pub opid: mongodb::bson::Bson,
+    pub op: String,
+    pub namespace: String,
+    pub running: Duration,
+    pub client: String,
+    pub app_name: String,
+    pub plan_summary: String,
+    pub waiting_for_lock: bool,
+    /// The command in extended JSON.
+    pub command: String,
+}
+
+/// Storage statistics of a collection, summed over its shards.
+#[derive(Debug, Clone, Default)]
+pub struct CollectionStats {
+    pub namespace: String,
+    pub shards: usize,
+    pub count: u64,
+    /// Uncompressed size of the documents.
+    pub size: u64,
+    pub storage_size: u64,
+    /// Space allocated on disk that new documents can reuse.
+    pub free_storage_size: u64,
+    pub avg_obj_size: u64,
+    pub total_index_size: u64,
+    /// Index names with their sizes, largest first.
+    pub index_sizes: Vec<(String, u64)>,
+    pub capped: bool,
+    pub max: Option<u64>,
+    pub max_size: Option<u64>,
+    /// WiredTiger cache counters by name.
+    pub cache: Vec<(String, u64)>,
+}
+
+/// A role granted to a user or inherited by another role.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleRef {
+    pub role: String,
+    pub db: String,
+}
+
+/// A user defined on a database, from `usersInfo`.
+#[derive(Debug, Clone)]
+pub struct UserInfo {
+    pub user: String,
+    pub db: String,
+    pub roles: Vec<RoleRef>,
+    pub mechanisms: Vec<String>,
+}
+
+/// Actions a role allows on one resource.
+#[derive(Debug, Clone)]
+pub struct RolePrivilege {
+    /// `db.collection`, `db.*`, `*.*` or `cluster`.
+    pub resource: String,
+    pub actions: Vec<String>,
+}
+
+/// A built-in or custom role, from `rolesInfo`.
+#[derive(Debug, Clone)]
+pub struct RoleInfo {
+    pub role: String,
+    pub db: String,
+    pub built_in: bool,
+    pub inherited: Vec<RoleRef>,
+    pub privileges: Vec<RolePrivilege>,
+}
+
+/// Roles to grant a user being created, checked off in a list.
+#[derive(Debug, Clone)]
+pub struct RolePicker {
+    pub user: String,
+    pub password: String,
+    pub roles: Vec<RoleRef>,
+    pub checked: Vec<bool>,
+    pub selected: usize,
+}
+
+/// A live collection checked against a file it was exported to.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceDiff {
+    pub namespace: String,
+    pub path: String,
+    pub added: u64,
+    pub removed: u64,
+    pub changed: u64,
+    pub unchanged: u64,
+    /// Reference documents that could not be parsed or lack an `_id`.
+    pub unreadable: u64,
+    /// The differences, up to a limit; the counts above cover all of them.
+    pub entries: Vec<DocumentDiff>,
+}
+
+impl ReferenceDiff {
+    pub fn differences(&self) -> u64 {
+        self.added + self.removed + self.changed
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DocumentDiff {
+    pub id: String,
+    pub kind: DiffKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum DiffKind {
+    /// In the collection but not in the reference.
+    Added,
+    /// In the reference but gone from the collection.
+    Removed,
+    Changed(Vec<FieldChange>),
+}
+
+#[derive(Debug, Clone)]
+pub struct AtlasProject {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AtlasCluster {
+    pub name: String,
+    pub state: String,
+    pub version: String,
+    pub srv_uri: Option<String>,
+    pub standard_uri: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct QueryParams {
     pub database: String,
@@ -43,8 +756,47 @@ pub struct QueryParams {
     pub skip: u64,
     pub limit: i64,
     pub sort: Option<Document>,
+    pub projection: Option<Document>,
+}
+
+/// What the tail screen follows beyond a single collection.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum WatchScope {
+    /// The open collection.
+    #[default]
+    Collection,
+    /// Every collection of a database.
+    Database(String),
+    /// Every database of the deployment.
+    Deployment,
+}
+
+/// Change events seen on one namespace during a wide watch.
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceActivity {
+    pub inserts: u64,
+    pub updates: u64,
+    pub deletes: u64,
+}
+
+impl NamespaceActivity {
+    pub fn total(&self) -> u64 {
+        self.inserts + self.updates + self.deletes
+    }
+}
+
+/// Where a page of the document view starts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageCursor {
+    /// The first page.
+    Start,
+    /// Right after these sort key values, read with an index seek.
+    After(Document),
+    /// After this many documents, when the sort keys can not be used.
+    Skip(u64),
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct QueryResult {
     pub document: Vec<Document>,
@@ -52,6 +804,7 @@ pub struct QueryResult {
     pub execution_time: Duration,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub default_page_size: usize,
@@ -73,25 +826,47 @@ impl Default for AppConfig {
     }
 }
 
-#[derive(Debug, Clone)]
+/// The color of each role the screens draw in, from a [`ColorScheme`] with
+/// the overrides of the `[colors]` settings table.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Theme {
-    pub primary_color: Color,
-    pub secondary_color: Color,
-    pub highlight_color: Color,
-    pub error_color: Color,
-}
-
-impl Default for Theme {
-    fn default() -> Self {
-        Self {
-            primary_color: Color::Cyan,
-            secondary_color: Color::Gray,
-            highlight_color: Color::Yellow,
-            error_color: Color::Red,
-        }
-    }
+    /// Backgrounds show in reverse video, for schemes without colors.
+    pub reverse: bool,
+    pub text: Color,
+    /// Secondary text such as labels and details.
+    pub dim: Color,
+    /// Hints, footers and whatever should recede.
+    pub muted: Color,
+    /// Borders and titles.
+    pub accent: Color,
+    /// Headings of sections and tables.
+    pub header: Color,
+    /// Keys, counts and values to notice.
+    pub highlight: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// Sorts, modes and other states set by the user.
+    pub special: Color,
+    /// Background of the selected row.
+    pub selection: Color,
+    /// Background of the footer bars.
+    pub bar: Color,
+    /// Text on colored badges and highlighted matches.
+    pub badge_text: Color,
+    /// Background of the search match the cursor is on.
+    pub current_match: Color,
+    pub json_key: Color,
+    pub json_string: Color,
+    pub json_number: Color,
+    pub json_bool: Color,
+    pub json_null: Color,
+    /// ObjectIds, UUIDs and binary data.
+    pub json_id: Color,
+    pub json_date: Color,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct KeyBindings {
     pub quit: Vec<KeyCode>,
@@ -120,3 +895,138 @@ impl Default for KeyBindings {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ImportMode {
+    #[default]
+    Insert,
+    Upsert,
+}
+
+/// The import screen: a file to load into the current collection and a
+/// preview of its first documents.
+#[derive(Debug, Clone, Default)]
+pub struct ImportForm {
+    pub path: String,
+    pub mode: ImportMode,
+    /// Directory entries matching the path after an ambiguous completion.
+    pub completions: Vec<String>,
+    pub preview: Vec<Document>,
+    pub preview_error: Option<String>,
+    /// Path the preview was read from.
+    pub previewed: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BulkUpdateField {
+    #[default]
+    Filter,
+    Update,
+}
+
+/// The bulk update screen: a filter and an update for `updateMany`, and
+/// what they matched when last previewed.
+#[derive(Debug, Clone, Default)]
+pub struct BulkUpdateForm {
+    pub filter: String,
+    pub update: String,
+    pub focus: BulkUpdateField,
+    pub preview: Option<BulkUpdatePreview>,
+    pub scroll: usize,
+}
+
+/// The documents a bulk update would change, counted on the server.
+#[derive(Debug, Clone)]
+pub struct BulkUpdatePreview {
+    /// The filter and update as typed when previewed.
+    pub inputs: (String, String),
+    pub filter: Document,
+    pub update: Document,
+    pub matched: u64,
+    /// The first few matching documents, as they are before the update.
+    pub sample: Vec<Document>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportField {
+    Format,
+    Path,
+    Columns,
+}
+
+/// One form of an identifier in the utilities popup, such as its shell
+/// syntax or the time an ObjectId was created.
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    pub label: String,
+    pub value: String,
+}
+
+/// The `Ctrl+P` fuzzy finder over every database and collection name.
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceFinder {
+    pub input: String,
+    /// Every database, and every collection as `db.collection`.
+    pub namespaces: Vec<String>,
+    /// Indexes into `namespaces` of the matches, best first, each with the
+    /// positions of its matched characters.
+    pub matches: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+    /// The collections are still being listed.
+    pub loading: bool,
+}
+
+/// The ObjectId/UUID utilities popup: fresh identifiers while the input is
+/// empty, otherwise every form of the pasted one.
+#[derive(Debug, Clone, Default)]
+pub struct UtilityPanel {
+    pub input: String,
+    pub conversions: Vec<Conversion>,
+    pub error: Option<String>,
+    pub selected: usize,
+}
+
+/// The export dialog, for the documents matching the query or for whole
+/// collections written one file each into a directory.
+#[derive(Debug, Clone, Default)]
+pub struct ExportDialog {
+    /// Collections of the current database to export; empty exports the
+    /// query's documents.
+    pub collections: Vec<String>,
+    /// Index into the export formats.
+    pub format: usize,
+    /// The file, or the directory of a collection export.
+    pub path: String,
+    /// Comma-separated field paths for the column formats; empty takes
+    /// every field found.
+    pub columns: String,
+    pub focus: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology {
+    Standalone,
+    ReplicaSet,
+    Sharded,
+}
+
+/// Whether a feature of the UI works against the connected deployment.
+#[derive(Debug, Clone)]
+pub struct FeatureSupport {
+    pub feature: &'static str,
+    pub supported: bool,
+    /// What the feature needs, shown when it is missing.
+    pub requirement: &'static str,
+}
+
+/// Versions of the app, driver and server, for the about screen.
+#[derive(Debug, Clone)]
+pub struct Compatibility {
+    pub app_version: String,
+    pub driver_version: String,
+    pub server_version: String,
+    /// Feature compatibility version; unknown when the user may not read it.
+    pub fcv: Option<String>,
+    pub topology: Topology,
+    pub features: Vec<FeatureSupport>,
+}
@@ -0,0 +1,226 @@
+use futures::stream::StreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::{
+    config::StageSnippet,
+    error::AppError,
+    models::PipelineStage,
+    services::{query::QueryService, shell_syntax, tasks::TaskProgress},
+};
+
+pub const PREVIEW_LIMIT: i64 = 20;
+/// Documents the live preview reads from the collection while a stage is
+/// being typed, and how many of the results it shows.
+pub const DRAFT_SAMPLE: i64 = 200;
+pub const DRAFT_LIMIT: i64 = 3;
+
+/// Stages that have to open a pipeline, so the sample limit goes after them.
+const LEADING_STAGES: [&str; 7] = [
+    "$geoNear",
+    "$search",
+    "$searchMeta",
+    "$vectorSearch",
+    "$collStats",
+    "$indexStats",
+    "$documents",
+];
+
+/// Stage operators offered when adding a stage, with a starting body.
+pub const STAGE_TEMPLATES: [(&str, &str); 13] = [
+    ("$match", r#"{"field": "value"}"#),
+    ("$group", r#"{"_id": null, "count": {"$sum": 1}}"#),
+    ("$project", r#"{"_id": 1}"#),
+    ("$sort", r#"{"_id": -1}"#),
+    ("$limit", "10"),
+    ("$skip", "0"),
+    ("$unwind", r#""$field""#),
+    (
+        "$lookup",
+        r#"{"from": "other", "localField": "field", "foreignField": "_id", "as": "joined"}"#,
+    ),
+    ("$addFields", r#"{"field": 1}"#),
+    ("$count", r#""count""#),
+    ("$sortByCount", r#""$field""#),
+    ("$sample", r#"{"size": 10}"#),
+    (
+        "$facet",
+        r#"{"total": [{"$count": "count"}], "page": [{"$skip": 0}, {"$limit": 10}]}"#,
+    ),
+];
+
+impl PipelineStage {
+    pub fn new(operator: &str) -> Self {
+        let operator = if operator.starts_with('$') {
+            operator.to_string()
+        } else {
+            format!("${}", operator)
+        };
+        let body = STAGE_TEMPLATES
+            .iter()
+            .find(|(name, _)| *name == operator)
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_else(|| "{}".to_string());
+        Self {
+            operator,
+            body,
+            enabled: true,
+        }
+    }
+
+    /// A stage from a user snippet, matched by name.
+    pub fn from_snippet(snippet: &StageSnippet) -> Self {
+        let mut stage = Self::new(&snippet.operator);
+        stage.body = snippet.body.clone();
+        stage
+    }
+
+    /// The stage as BSON; bodies are extended JSON of any type, since stages
+    /// like `$limit` and `$unwind` take scalars.
+    pub fn to_document(&self) -> Result<Document, String> {
+        let value: serde_json::Value = serde_json::from_str(&self.body)
+            .map_err(|e| format!("{}: invalid JSON: {}", self.operator, e))?;
+        let body = Bson::try_from(value).map_err(|e| format!("{}: {}", self.operator, e))?;
+        Ok(doc! { self.operator.clone(): body })
+    }
+}
+
+/// A snippet called `input`, otherwise a template for the operator `input`.
+pub fn stage_for(input: &str, snippets: &[StageSnippet]) -> PipelineStage {
+    snippets
+        .iter()
+        .find(|s| s.name == input)
+        .map(PipelineStage::from_snippet)
+        .unwrap_or_else(|| PipelineStage::new(input))
+}
+
+/// Stages from a pipeline in mongosh syntax or JSON, as found in tickets and
+/// Compass exports. A surrounding `db.coll.aggregate(...)` call is ignored.
+pub fn import_pipeline(text: &str) -> Result<Vec<PipelineStage>, String> {
+    let text = text.trim();
+    let text = if text.starts_with('[') || text.starts_with('{') {
+        text
+    } else {
+        match (text.find('['), text.rfind(']')) {
+            (Some(start), Some(end)) if start < end => &text[start..=end],
+            _ => return Err("No pipeline array found".to_string()),
+        }
+    };
+
+    let json = shell_syntax::to_extended_json(text)?;
+    let value: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid pipeline: {}", e))?;
+    let stages = match Bson::try_from(value).map_err(|e| format!("Invalid pipeline: {}", e))? {
+        Bson::Array(stages) => stages,
+        stage @ Bson::Document(_) => vec![stage],
+        _ => return Err("A pipeline is an array of stages".to_string()),
+    };
+
+    stages
+        .into_iter()
+        .enumerate()
+        .map(|(i, stage)| {
+            let stage = match stage {
+                Bson::Document(stage) if stage.len() == 1 => stage,
+                _ => return Err(format!("Stage {} must have exactly one operator", i + 1)),
+            };
+            let (operator, body) = stage.into_iter().next().expect("one key");
+            let body = serde_json::to_string(&body.into_relaxed_extjson())
+                .map_err(|e| format!("{}: {}", operator, e))?;
+            Ok(PipelineStage {
+                operator,
+                body,
+                enabled: true,
+            })
+        })
+        .collect()
+}
+
+/// Enabled stages up to and including `upto`, ready to send.
+pub fn build_pipeline(stages: &[PipelineStage], upto: usize) -> Result<Vec<Document>, String> {
+    stages
+        .iter()
+        .take(upto + 1)
+        .filter(|s| s.enabled)
+        .map(PipelineStage::to_document)
+        .collect()
+}
+
+/// Runs a pipeline and returns the first documents of its output.
+pub async fn preview(
+    query_service: &QueryService,
+    db: &str,
+    collection: &str,
+    mut pipeline: Vec<Document>,
+) -> Result<Vec<Document>, AppError> {
+    pipeline.push(doc! { "$limit": PREVIEW_LIMIT });
+    query_service.aggregate(db, collection, pipeline).await
+}
+
+/// Runs a pipeline over the first few hundred documents only, for the live
+/// preview beside the stage editor; cheap enough to repeat while typing.
+pub async fn draft_preview(
+    query_service: &QueryService,
+    db: &str,
+    collection: &str,
+    mut pipeline: Vec<Document>,
+) -> Result<Vec<Document>, AppError> {
+    let leading = pipeline
+        .first()
+        .and_then(|stage| stage.keys().next())
+        .is_some_and(|op| LEADING_STAGES.contains(&op.as_str()));
+    pipeline.insert(usize::from(leading), doc! { "$limit": DRAFT_SAMPLE });
+    pipeline.push(doc! { "$limit": DRAFT_LIMIT });
+    query_service.aggregate(db, collection, pipeline).await
+}
+
+/// The operator of the stage that writes the pipeline's output to a
+/// collection, `$out` or `$merge`, if there is one.
+pub fn write_stage(pipeline: &[Document]) -> Option<&str> {
+    pipeline
+        .iter()
+        .flat_map(|stage| stage.keys())
+        .find(|op| *op == "$out" || *op == "$merge")
+        .map(String::as_str)
+}
+
+/// Runs the whole pipeline as a background task, reading its output to the
+/// end without keeping it; meant for pipelines that write with `$out` or
+/// `$merge`, or to see how long the full run takes and how much it yields.
+pub async fn run(
+    client: Client,
+    db: String,
+    collection: String,
+    pipeline: Vec<Document>,
+    progress: TaskProgress,
+) -> Result<String, AppError> {
+    let comment = progress.tag_operations(&client);
+    let writes = write_stage(&pipeline).map(str::to_string);
+    let mut cursor = client
+        .database(&db)
+        .collection::<Document>(&collection)
+        .aggregate(pipeline)
+        .comment(comment)
+        .await
+        .map_err(|e| AppError::Query(format!("Aggregation failed: {}", e)))?;
+    let mut returned = 0u64;
+    while let Some(result) = cursor.next().await {
+        result.map_err(|e| AppError::Query(format!("Error reading aggregation result: {}", e)))?;
+        returned += 1;
+        if returned.is_multiple_of(1000) {
+            progress.set(returned, None);
+        }
+    }
+    Ok(match writes {
+        Some(operator) => format!(
+            "Pipeline on {}.{} wrote its output with {}",
+            db, collection, operator
+        ),
+        None => format!(
+            "Pipeline on {}.{} returned {} document(s)",
+            db, collection, returned
+        ),
+    })
+}
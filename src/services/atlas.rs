@@ -0,0 +1,217 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use md5::{Digest, Md5};
+use reqwest::{StatusCode, header};
+use serde::Deserialize;
+
+use crate::{
+    config::AtlasConfig,
+    error::AppError,
+    models::{AtlasCluster, AtlasProject},
+};
+
+const BASE_URL: &str = "https://cloud.mongodb.com";
+const API_VERSION: &str = "application/vnd.atlas.2023-01-01+json";
+
+/// Minimal client for the Atlas Admin API v2, authenticated with a
+/// programmatic API key pair over HTTP Digest.
+pub struct AtlasService {
+    http: reqwest::Client,
+    config: AtlasConfig,
+}
+
+#[derive(Deserialize)]
+struct Paginated<T> {
+    results: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct GroupResponse {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClusterResponse {
+    name: String,
+    #[serde(default)]
+    state_name: Option<String>,
+    #[serde(default)]
+    mongo_db_version: Option<String>,
+    #[serde(default)]
+    connection_strings: Option<ConnectionStrings>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionStrings {
+    #[serde(default)]
+    standard_srv: Option<String>,
+    #[serde(default)]
+    standard: Option<String>,
+}
+
+impl AtlasService {
+    pub fn new(config: AtlasConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    pub async fn list_projects(&self) -> Result<Vec<AtlasProject>, AppError> {
+        let groups: Paginated<GroupResponse> =
+            self.get("/api/atlas/v2/groups?itemsPerPage=500").await?;
+        Ok(groups
+            .results
+            .into_iter()
+            .map(|g| AtlasProject {
+                id: g.id,
+                name: g.name,
+            })
+            .collect())
+    }
+
+    pub async fn list_clusters(&self, project_id: &str) -> Result<Vec<AtlasCluster>, AppError> {
+        let clusters: Paginated<ClusterResponse> = self
+            .get(&format!(
+                "/api/atlas/v2/groups/{}/clusters?itemsPerPage=500",
+                project_id
+            ))
+            .await?;
+        Ok(clusters
+            .results
+            .into_iter()
+            .map(|c| {
+                let strings = c.connection_strings;
+                AtlasCluster {
+                    name: c.name,
+                    state: c.state_name.unwrap_or_default(),
+                    version: c.mongo_db_version.unwrap_or_default(),
+                    srv_uri: strings.as_ref().and_then(|s| s.standard_srv.clone()),
+                    standard_uri: strings.and_then(|s| s.standard),
+                }
+            })
+            .collect())
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T, AppError> {
+        let url = format!("{}{}", BASE_URL, path);
+
+        // the first unauthenticated request returns the digest challenge
+        let challenge = self
+            .http
+            .get(&url)
+            .header(header::ACCEPT, API_VERSION)
+            .send()
+            .await
+            .map_err(|e| AppError::Connection(format!("Atlas request failed: {}", e)))?;
+
+        let response = if challenge.status() == StatusCode::UNAUTHORIZED {
+            let www_authenticate = challenge
+                .headers()
+                .get(header::WWW_AUTHENTICATE)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| {
+                    AppError::Connection("Atlas did not send a digest challenge".into())
+                })?
+                .to_string();
+            let authorization = self.digest_authorization(&www_authenticate, path)?;
+
+            self.http
+                .get(&url)
+                .header(header::ACCEPT, API_VERSION)
+                .header(header::AUTHORIZATION, authorization)
+                .send()
+                .await
+                .map_err(|e| AppError::Connection(format!("Atlas request failed: {}", e)))?
+        } else {
+            challenge
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let detail = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v.get("detail").and_then(|d| d.as_str()).map(str::to_string))
+                .unwrap_or(body);
+            return Err(AppError::Connection(format!(
+                "Atlas API {}: {}",
+                status, detail
+            )));
+        }
+
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| AppError::Connection(format!("Unexpected Atlas response: {}", e)))
+    }
+
+    fn digest_authorization(&self, challenge: &str, uri: &str) -> Result<String, AppError> {
+        let params = parse_challenge(challenge);
+        let realm = params.get("realm").cloned().unwrap_or_default();
+        let nonce = params
+            .get("nonce")
+            .cloned()
+            .ok_or_else(|| AppError::Connection("Digest challenge without nonce".into()))?;
+        let qop = params.get("qop").cloned().unwrap_or_else(|| "auth".into());
+
+        let nc = "00000001";
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let cnonce = md5_hex(&format!("{}:{}", nanos, std::process::id()))[..16].to_string();
+
+        let ha1 = md5_hex(&format!(
+            "{}:{}:{}",
+            self.config.public_key, realm, self.config.private_key
+        ));
+        let ha2 = md5_hex(&format!("GET:{}", uri));
+        let response = md5_hex(&format!(
+            "{}:{}:{}:{}:{}:{}",
+            ha1, nonce, nc, cnonce, qop, ha2
+        ));
+
+        Ok(format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", qop={}, nc={}, cnonce=\"{}\", response=\"{}\", algorithm=MD5",
+            self.config.public_key, realm, nonce, uri, qop, nc, cnonce, response
+        ))
+    }
+}
+
+fn parse_challenge(header: &str) -> HashMap<String, String> {
+    let body = header.trim_start_matches("Digest").trim();
+    let mut params = HashMap::new();
+    let mut rest = body;
+
+    while !rest.is_empty() {
+        let Some((key, after)) = rest.split_once('=') else {
+            break;
+        };
+        let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+        let (value, remaining) = if let Some(quoted) = after.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+        } else {
+            let end = after.find(',').unwrap_or(after.len());
+            (after[..end].trim(), &after[end..])
+        };
+        params.insert(key, value.to_string());
+        rest = remaining.trim_start_matches(',').trim();
+    }
+
+    params
+}
+
+fn md5_hex(input: &str) -> String {
+    Md5::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, SystemTime};
+
+use aws_config::{BehaviorVersion, Region};
+use aws_credential_types::{
+    Credentials,
+    provider::{ProvideCredentials, SharedCredentialsProvider, future},
+};
+use mongodb::options::{AuthMechanism, Credential};
+use tokio::sync::Mutex;
+
+use crate::{config::AwsIamConfig, error::AppError};
+
+/// Credentials are refreshed this long before they expire so a reconnect
+/// never signs with a session token that is about to lapse.
+const REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// Builds a MONGODB-AWS credential backed by the default AWS credential chain
+/// (environment, shared config/credentials profiles, SSO cache, web identity,
+/// container and instance metadata).
+pub async fn credential(config: &AwsIamConfig) -> Result<Credential, AppError> {
+    let mut loader = aws_config::defaults(BehaviorVersion::latest());
+    if let Some(profile) = &config.profile {
+        loader = loader.profile_name(profile);
+    }
+    if let Some(region) = &config.region {
+        loader = loader.region(Region::new(region.clone()));
+    }
+    let sdk_config = loader.load().await;
+
+    let chain = sdk_config
+        .credentials_provider()
+        .ok_or_else(|| AppError::Connection("No AWS credential provider found".to_string()))?;
+    let provider = RefreshingProvider {
+        inner: chain,
+        cached: Mutex::new(None),
+    };
+
+    // resolve once up front so a missing or expired SSO login is reported
+    // clearly instead of as a generic authentication failure
+    provider
+        .provide_credentials()
+        .await
+        .map_err(|e| AppError::Connection(format!("AWS credentials unavailable: {}", e)))?;
+
+    Ok(Credential::builder()
+        .mechanism(AuthMechanism::MongoDbAws)
+        .source("$external".to_string())
+        .aws_credential_provider(SharedCredentialsProvider::new(provider))
+        .build())
+}
+
+#[derive(Debug)]
+struct RefreshingProvider {
+    inner: SharedCredentialsProvider,
+    cached: Mutex<Option<Credentials>>,
+}
+
+impl RefreshingProvider {
+    async fn load(&self) -> aws_credential_types::provider::Result {
+        let mut cached = self.cached.lock().await;
+        if let Some(creds) = cached.as_ref()
+            && !expires_soon(creds)
+        {
+            return Ok(creds.clone());
+        }
+
+        let fresh = self.inner.provide_credentials().await?;
+        *cached = Some(fresh.clone());
+        Ok(fresh)
+    }
+}
+
+impl ProvideCredentials for RefreshingProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.load())
+    }
+}
+
+fn expires_soon(creds: &Credentials) -> bool {
+    match creds.expiry() {
+        Some(expiry) => expiry
+            .duration_since(SystemTime::now())
+            .map(|left| left < REFRESH_MARGIN)
+            .unwrap_or(true),
+        None => false,
+    }
+}
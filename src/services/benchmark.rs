@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+use crate::models::QueryParams;
+use crate::services::query::QueryService;
+use crate::services::tasks::TaskProgress;
+
+pub const DEFAULT_RUNS: usize = 20;
+const MAX_RUNS: usize = 1000;
+
+/// How often to run the query and whether each run reads a different page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchmarkPlan {
+    pub runs: usize,
+    /// Step through the result pages so runs do not keep rereading the
+    /// same documents from the storage engine's cache.
+    pub vary: bool,
+}
+
+impl BenchmarkPlan {
+    /// Parses prompt input such as `20` or `50 vary`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        let runs = match words.next() {
+            Some(word) => word
+                .parse::<usize>()
+                .map_err(|_| format!("Not a run count: {}", word))?,
+            None => DEFAULT_RUNS,
+        };
+        if runs == 0 || runs > MAX_RUNS {
+            return Err(format!("Run count must be between 1 and {}", MAX_RUNS));
+        }
+        let vary = match words.next() {
+            Some("vary") => true,
+            Some(other) => return Err(format!("Unknown option: {}", other)),
+            None => false,
+        };
+        Ok(Self { runs, vary })
+    }
+}
+
+/// Runs the query once to warm up, then `plan.runs` timed times, and
+/// summarizes the latencies.
+pub async fn run(
+    query_service: QueryService,
+    params: QueryParams,
+    plan: BenchmarkPlan,
+    progress: TaskProgress,
+) -> Result<String, AppError> {
+    let page = params.limit.max(1) as u64;
+    let pages = if plan.vary {
+        let count = query_service
+            .count_documents(&params.database, &params.collection, params.filter.clone())
+            .await?;
+        count.div_ceil(page).max(1)
+    } else {
+        1
+    };
+
+    query_service.find(params.clone()).await?;
+
+    let mut durations = Vec::with_capacity(plan.runs);
+    for run in 0..plan.runs {
+        let mut run_params = params.clone();
+        if plan.vary {
+            run_params.skip = params.skip + (run as u64 % pages) * page;
+        }
+        let started = Instant::now();
+        query_service.find(run_params).await?;
+        durations.push(started.elapsed());
+        progress.set(run as u64 + 1, Some(plan.runs as u64));
+    }
+
+    durations.sort();
+    Ok(format!(
+        "Benchmark {}.{}: {} runs{}  min {}  median {}  p95 {}  max {}",
+        params.database,
+        params.collection,
+        plan.runs,
+        if plan.vary { " across pages" } else { "" },
+        format_ms(durations[0]),
+        format_ms(percentile(&durations, 50)),
+        format_ms(percentile(&durations, 95)),
+        format_ms(durations[durations.len() - 1]),
+    ))
+}
+
+// nearest-rank percentile of sorted, non-empty durations
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,90 @@
+use mongodb::bson::{Bson, DateTime, Document};
+
+use crate::models::{DateFormat, ValueFormat};
+
+/// Reads any numeric BSON value as an `f64`.
+pub fn number_as_f64(value: &Bson) -> Option<f64> {
+    match value {
+        Bson::Double(v) => Some(*v),
+        Bson::Int32(v) => Some(*v as f64),
+        Bson::Int64(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+/// The value at a dotted `path` such as `items.0.sku`, descending into
+/// embedded documents and, by index, arrays.
+pub fn value_at<'a>(doc: &'a Document, path: &str) -> Option<&'a Bson> {
+    let mut parts = path.split('.');
+    let mut value = doc.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            Bson::Document(inner) => inner.get(part)?,
+            Bson::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Reads an offset from UTC such as `+02:00`, `-0530`, `+2` or `Z`, in
+/// minutes east of UTC.
+pub fn parse_utc_offset(input: &str) -> Option<i32> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("z") || input.eq_ignore_ascii_case("utc") {
+        return Some(0);
+    }
+    let (sign, rest) = match input.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at_checked(2)?,
+        None => (rest, "0"),
+    };
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours <= 14 && minutes < 60).then_some(sign * (hours * 60 + minutes))
+}
+
+impl ValueFormat {
+    /// A date at the offset in the date format, e.g. `2024-05-01 14:00:00`
+    /// for the short one.
+    pub fn date(self, date: DateTime) -> String {
+        match self.date_format {
+            DateFormat::Iso => format_date(date, self.utc_offset),
+            DateFormat::Short => {
+                let iso = format_date(date, self.utc_offset);
+                match iso.get(..19) {
+                    Some(seconds) => seconds.replacen('T', " ", 1),
+                    None => iso,
+                }
+            }
+            DateFormat::Epoch => date.timestamp_millis().to_string(),
+        }
+    }
+}
+
+/// An ISO 8601 date at `offset` minutes east of UTC, e.g.
+/// `2024-05-01T14:00:00+02:00`; UTC ones end in `Z`.
+pub fn format_date(date: DateTime, offset: i32) -> String {
+    let shifted = DateTime::from_millis(
+        date.timestamp_millis()
+            .saturating_add(offset as i64 * 60_000),
+    );
+    let Ok(text) = shifted.try_to_rfc3339_string() else {
+        return date.timestamp_millis().to_string();
+    };
+    if offset == 0 {
+        return text;
+    }
+    let sign = if offset < 0 { '-' } else { '+' };
+    format!(
+        "{}{}{:02}:{:02}",
+        text.trim_end_matches('Z'),
+        sign,
+        offset.abs() / 60,
+        offset.abs() % 60
+    )
+}
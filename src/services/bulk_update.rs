@@ -0,0 +1,125 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+};
+
+use crate::error::AppError;
+use crate::models::{BulkUpdateField, BulkUpdateForm, BulkUpdatePreview};
+use crate::services::{dry_run, shell_syntax};
+
+/// Matching documents shown in the preview.
+const SAMPLE_SIZE: i64 = 5;
+
+impl BulkUpdateForm {
+    /// A form for the documents of the open query, if one is applied.
+    pub fn with_filter(filter: Option<&Document>) -> Self {
+        Self {
+            filter: filter.map(dry_run::extended_json).unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = match self.focus {
+            BulkUpdateField::Filter => BulkUpdateField::Update,
+            BulkUpdateField::Update => BulkUpdateField::Filter,
+        };
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        match self.focus {
+            BulkUpdateField::Filter => self.filter.push(c),
+            BulkUpdateField::Update => self.update.push(c),
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        match self.focus {
+            BulkUpdateField::Filter => self.filter.pop(),
+            BulkUpdateField::Update => self.update.pop(),
+        };
+    }
+
+    /// Whether the preview was made from the filter and update as they are now.
+    pub fn is_previewed(&self) -> bool {
+        self.preview
+            .as_ref()
+            .is_some_and(|p| p.inputs == (self.filter.clone(), self.update.clone()))
+    }
+
+    /// The filter and update in shell syntax. An empty filter matches every
+    /// document; the update must use operators such as `$set`, since
+    /// `updateMany` does not replace whole documents.
+    pub fn parse(&self) -> Result<(Document, Document), String> {
+        let filter = if self.filter.trim().is_empty() {
+            Document::new()
+        } else {
+            shell_syntax::parse_document(&self.filter)
+                .map_err(|e| format!("Invalid filter: {}", e))?
+        };
+        if self.update.trim().is_empty() {
+            return Err("Enter an update, e.g. {$set: {status: \"archived\"}}".to_string());
+        }
+        let update = shell_syntax::parse_document(&self.update)
+            .map_err(|e| format!("Invalid update: {}", e))?;
+        if let Some(key) = update.keys().find(|k| !k.starts_with('$')) {
+            return Err(format!(
+                "The update must use operators such as $set or $inc, not the field {}",
+                key
+            ));
+        }
+        Ok((filter, update))
+    }
+}
+
+/// Counts the documents `filter` matches and reads the first few of them.
+pub async fn preview(
+    client: &Client,
+    db: &str,
+    collection: &str,
+    inputs: (String, String),
+    filter: Document,
+    update: Document,
+) -> Result<BulkUpdatePreview, AppError> {
+    let collection = client.database(db).collection::<Document>(collection);
+    let matched = collection
+        .count_documents(filter.clone())
+        .await
+        .map_err(|e| AppError::Query(format!("Count failed: {}", e)))?;
+    let sample: Vec<Document> = collection
+        .find(filter.clone())
+        .sort(doc! { "_id": 1 })
+        .limit(SAMPLE_SIZE)
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to read matches: {}", e)))?
+        .try_collect()
+        .await?;
+    Ok(BulkUpdatePreview {
+        inputs,
+        filter,
+        update,
+        matched,
+        sample,
+    })
+}
+
+/// Runs `updateMany` and reports how many documents matched and changed.
+pub async fn apply(
+    client: &Client,
+    db: &str,
+    collection: &str,
+    filter: Document,
+    update: Document,
+) -> Result<String, AppError> {
+    let result = client
+        .database(db)
+        .collection::<Document>(collection)
+        .update_many(filter, update)
+        .await
+        .map_err(|e| AppError::Query(format!("updateMany failed: {}", e)))?;
+    Ok(format!(
+        "Matched {} and modified {} document(s)",
+        result.matched_count, result.modified_count
+    ))
+}
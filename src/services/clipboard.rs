@@ -0,0 +1,76 @@
+use std::env;
+use std::io::{self, Write};
+
+use arboard::Clipboard;
+use mongodb::bson::Bson;
+
+/// Where copied text went.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Copied {
+    /// The clipboard of the machine the app runs on.
+    System,
+    /// The terminal's clipboard, through an OSC 52 escape sequence; over
+    /// SSH this is the clipboard of the machine in front of the user.
+    Terminal,
+}
+
+/// Puts `text` on the clipboard. Over SSH the system clipboard belongs to
+/// the remote machine, so the terminal is asked instead; elsewhere the
+/// terminal is the fallback when there is no system clipboard.
+pub fn copy(text: &str) -> Result<Copied, String> {
+    if env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some() {
+        return osc52(text)
+            .map(|_| Copied::Terminal)
+            .map_err(|e| format!("Failed to reach the terminal's clipboard: {}", e));
+    }
+    match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+        Ok(()) => Ok(Copied::System),
+        Err(e) => osc52(text)
+            .map(|_| Copied::Terminal)
+            .map_err(|_| format!("Clipboard unavailable: {}", e)),
+    }
+}
+
+/// A value as it is pasted: strings without quotes, ObjectIds as their hex
+/// string, anything else as extended JSON, pretty-printed when nested.
+pub fn value_text(value: &Bson, canonical: bool) -> String {
+    match value {
+        Bson::String(s) => s.clone(),
+        Bson::ObjectId(id) if !canonical => id.to_hex(),
+        _ => {
+            let json = if canonical {
+                value.clone().into_canonical_extjson()
+            } else {
+                value.clone().into_relaxed_extjson()
+            };
+            serde_json::to_string_pretty(&json).unwrap_or_else(|_| json.to_string())
+        }
+    }
+}
+
+// terminals that support it put the payload of `ESC ] 52 ; c ; <base64> BEL`
+// on their clipboard; the others ignore it
+fn osc52(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | ((*b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,412 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+    results::CollectionType,
+};
+
+use crate::{
+    error::AppError,
+    models::{CollectionForm, CollectionKind, FormField},
+    services::{shell_syntax, tasks::TaskProgress, throttle::Throttle},
+};
+
+const GRANULARITIES: [&str; 3] = ["seconds", "minutes", "hours"];
+
+/// Documents a duplicate inserts at a time.
+const COPY_BATCH: usize = 500;
+
+impl CollectionKind {
+    pub const ALL: [CollectionKind; 4] = [
+        CollectionKind::Standard,
+        CollectionKind::Capped,
+        CollectionKind::TimeSeries,
+        CollectionKind::Clustered,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CollectionKind::Standard => "standard",
+            CollectionKind::Capped => "capped",
+            CollectionKind::TimeSeries => "time series",
+            CollectionKind::Clustered => "clustered",
+        }
+    }
+}
+
+impl FormField {
+    pub fn label(self) -> &'static str {
+        match self {
+            FormField::Name => "Name",
+            FormField::Kind => "Type",
+            FormField::CappedSize => "Size (bytes)",
+            FormField::CappedMax => "Max documents",
+            FormField::TimeField => "Time field",
+            FormField::MetaField => "Meta field",
+            FormField::Granularity => "Granularity",
+            FormField::ExpireAfter => "Expire after (s)",
+            FormField::Validator => "Validator",
+            FormField::CollationLocale => "Collation locale",
+            FormField::CollationStrength => "Collation strength",
+        }
+    }
+}
+
+impl CollectionForm {
+    /// Fields that apply to the chosen collection type, in display order.
+    pub fn fields(&self) -> Vec<FormField> {
+        let mut fields = vec![FormField::Name, FormField::Kind];
+        match self.kind {
+            CollectionKind::Standard => {}
+            CollectionKind::Capped => {
+                fields.extend([FormField::CappedSize, FormField::CappedMax]);
+            }
+            CollectionKind::TimeSeries => fields.extend([
+                FormField::TimeField,
+                FormField::MetaField,
+                FormField::Granularity,
+                FormField::ExpireAfter,
+            ]),
+            CollectionKind::Clustered => fields.push(FormField::ExpireAfter),
+        }
+        fields.extend([
+            FormField::Validator,
+            FormField::CollationLocale,
+            FormField::CollationStrength,
+        ]);
+        fields
+    }
+
+    pub fn focused_field(&self) -> FormField {
+        let fields = self.fields();
+        fields[self.focus.min(fields.len() - 1)]
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = (self.focus + 1) % self.fields().len();
+    }
+
+    pub fn prev_field(&mut self) {
+        let count = self.fields().len();
+        self.focus = (self.focus + count - 1) % count;
+    }
+
+    pub fn value(&self, field: FormField) -> String {
+        match field {
+            FormField::Name => self.name.clone(),
+            FormField::Kind => self.kind.name().to_string(),
+            FormField::CappedSize => self.capped_size.clone(),
+            FormField::CappedMax => self.capped_max.clone(),
+            FormField::TimeField => self.time_field.clone(),
+            FormField::MetaField => self.meta_field.clone(),
+            FormField::Granularity => GRANULARITIES[self.granularity].to_string(),
+            FormField::ExpireAfter => self.expire_after.clone(),
+            FormField::Validator => self.validator.clone(),
+            FormField::CollationLocale => self.collation_locale.clone(),
+            FormField::CollationStrength => self.collation_strength.clone(),
+        }
+    }
+
+    fn text_mut(&mut self, field: FormField) -> Option<&mut String> {
+        match field {
+            FormField::Name => Some(&mut self.name),
+            FormField::CappedSize => Some(&mut self.capped_size),
+            FormField::CappedMax => Some(&mut self.capped_max),
+            FormField::TimeField => Some(&mut self.time_field),
+            FormField::MetaField => Some(&mut self.meta_field),
+            FormField::ExpireAfter => Some(&mut self.expire_after),
+            FormField::Validator => Some(&mut self.validator),
+            FormField::CollationLocale => Some(&mut self.collation_locale),
+            FormField::CollationStrength => Some(&mut self.collation_strength),
+            FormField::Kind | FormField::Granularity => None,
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if let Some(text) = self.text_mut(self.focused_field()) {
+            text.push(c);
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if let Some(text) = self.text_mut(self.focused_field()) {
+            text.pop();
+        }
+    }
+
+    /// Cycles the type or granularity when one of them has focus.
+    pub fn cycle_option(&mut self, forward: bool) {
+        match self.focused_field() {
+            FormField::Kind => {
+                let all = CollectionKind::ALL;
+                let current = all.iter().position(|k| *k == self.kind).unwrap_or(0);
+                let step = if forward { 1 } else { all.len() - 1 };
+                self.kind = all[(current + step) % all.len()];
+            }
+            FormField::Granularity => {
+                let step = if forward { 1 } else { GRANULARITIES.len() - 1 };
+                self.granularity = (self.granularity + step) % GRANULARITIES.len();
+            }
+            _ => {}
+        }
+    }
+
+    /// The `create` command for the form, or what is wrong with it.
+    pub fn to_command(&self) -> Result<Document, String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Name is required".to_string());
+        }
+
+        let mut command = doc! { "create": name };
+        match self.kind {
+            CollectionKind::Standard => {}
+            CollectionKind::Capped => {
+                command.insert("capped", true);
+                command.insert(
+                    "size",
+                    parse_number(&self.capped_size, "Size")?
+                        .ok_or("Capped collections need a size")?,
+                );
+                if let Some(max) = parse_number(&self.capped_max, "Max documents")? {
+                    command.insert("max", max);
+                }
+            }
+            CollectionKind::TimeSeries => {
+                let time_field = self.time_field.trim();
+                if time_field.is_empty() {
+                    return Err("Time series collections need a time field".to_string());
+                }
+                let mut timeseries = doc! {
+                    "timeField": time_field,
+                    "granularity": GRANULARITIES[self.granularity],
+                };
+                if !self.meta_field.trim().is_empty() {
+                    timeseries.insert("metaField", self.meta_field.trim());
+                }
+                command.insert("timeseries", timeseries);
+                if let Some(seconds) = parse_number(&self.expire_after, "Expire after")? {
+                    command.insert("expireAfterSeconds", seconds);
+                }
+            }
+            CollectionKind::Clustered => {
+                command.insert(
+                    "clusteredIndex",
+                    doc! { "key": { "_id": 1 }, "unique": true },
+                );
+                if let Some(seconds) = parse_number(&self.expire_after, "Expire after")? {
+                    command.insert("expireAfterSeconds", seconds);
+                }
+            }
+        }
+
+        if !self.validator.trim().is_empty() {
+            let validator = shell_syntax::parse_document(&self.validator).map_err(|e| {
+                format!(
+                    "Validator must be an object such as {{$jsonSchema: {{...}}}}: {}",
+                    e
+                )
+            })?;
+            command.insert("validator", validator);
+        }
+
+        if !self.collation_locale.trim().is_empty() {
+            let mut collation = doc! { "locale": self.collation_locale.trim() };
+            if let Some(strength) = parse_number(&self.collation_strength, "Collation strength")? {
+                if !(1..=5).contains(&strength) {
+                    return Err("Collation strength must be 1-5".to_string());
+                }
+                collation.insert("strength", strength);
+            }
+            command.insert("collation", collation);
+        }
+
+        Ok(command)
+    }
+}
+
+fn parse_number(input: &str, label: &str) -> Result<Option<i64>, String> {
+    let input = input.trim().replace('_', "");
+    if input.is_empty() {
+        return Ok(None);
+    }
+    input
+        .parse::<i64>()
+        .map(Some)
+        .map_err(|_| format!("{} must be a whole number", label))
+}
+
+pub async fn create_collection(
+    client: &Client,
+    db: &str,
+    command: Document,
+) -> Result<(), AppError> {
+    client
+        .database(db)
+        .run_command(command)
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to create collection: {}", e)))?;
+    Ok(())
+}
+
+pub async fn drop_collection(client: &Client, db: &str, collection: &str) -> Result<(), AppError> {
+    client
+        .database(db)
+        .collection::<Document>(collection)
+        .drop()
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to drop {}.{}: {}", db, collection, e)))
+}
+
+pub async fn drop_database(client: &Client, db: &str) -> Result<(), AppError> {
+    client
+        .database(db)
+        .drop()
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to drop database {}: {}", db, e)))
+}
+
+/// Renames a collection within its database; an existing collection of the
+/// new name is an error rather than replaced.
+pub async fn rename_collection(
+    client: &Client,
+    db: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), AppError> {
+    client
+        .database("admin")
+        .run_command(doc! {
+            "renameCollection": format!("{}.{}", db, from),
+            "to": format!("{}.{}", db, to),
+            "dropTarget": false,
+        })
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to rename {}: {}", from, e)))?;
+    Ok(())
+}
+
+/// A duplicate as typed in the prompt: the new name, then `indexes` to copy
+/// the secondary indexes too.
+#[derive(Debug, Clone)]
+pub struct ClonePlan {
+    pub name: String,
+    pub indexes: bool,
+}
+
+impl ClonePlan {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        let name = words
+            .next()
+            .ok_or("Enter a name for the copy, then `indexes` to copy them too")?;
+        let indexes = match words.next() {
+            None => false,
+            Some(word) if word.eq_ignore_ascii_case("indexes") => true,
+            Some(word) => return Err(format!("Unexpected '{}'; only `indexes` may follow", word)),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            indexes,
+        })
+    }
+}
+
+/// Copies `source` into a new collection created with the same options
+/// (capped size, validator, collation, time series), inserting the
+/// documents in batches so the task can report progress and keep to the
+/// profile's rate limit.
+pub async fn clone_collection(
+    client: Client,
+    db: String,
+    source: String,
+    plan: ClonePlan,
+    mut throttle: Throttle,
+    progress: TaskProgress,
+) -> Result<String, AppError> {
+    let database = client.database(&db);
+    let spec = database
+        .list_collections()
+        .filter(doc! { "name": &source })
+        .await?
+        .try_next()
+        .await?
+        .ok_or_else(|| AppError::Query(format!("{}.{} no longer exists", db, source)))?;
+    if spec.collection_type == CollectionType::View {
+        return Err(AppError::Query(format!(
+            "{} is a view; only collections can be duplicated",
+            source
+        )));
+    }
+    database
+        .create_collection(&plan.name)
+        .with_options(spec.options)
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to create {}: {}", plan.name, e)))?;
+
+    let from = database.collection::<Document>(&source);
+    let to = database.collection::<Document>(&plan.name);
+    let total = from.estimated_document_count().await.unwrap_or(0);
+    progress.set(0, Some(total));
+    let mut cursor = from.find(Document::new()).await?;
+    let mut batch = Vec::with_capacity(COPY_BATCH);
+    let mut copied = 0u64;
+    loop {
+        let next = cursor.try_next().await?;
+        let done = next.is_none();
+        batch.extend(next);
+        if batch.len() == COPY_BATCH || (done && !batch.is_empty()) {
+            let count = batch.len() as u64;
+            to.insert_many(std::mem::take(&mut batch))
+                .await
+                .map_err(|e| AppError::Query(format!("Copy failed after {}: {}", copied, e)))?;
+            copied += count;
+            progress.set(copied, Some(total.max(copied)));
+            throttle.pace(count).await;
+        }
+        if done {
+            break;
+        }
+    }
+
+    let mut summary = format!(
+        "Copied {} document(s) from {}.{} to {}.{}",
+        copied, db, source, db, plan.name
+    );
+    if plan.indexes {
+        let models: Vec<_> = from
+            .list_indexes()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .filter(|index| index.options.as_ref().and_then(|o| o.name.as_deref()) != Some("_id_"))
+            .collect();
+        let count = models.len();
+        if count > 0 {
+            to.create_indexes(models)
+                .await
+                .map_err(|e| AppError::Query(format!("Documents copied, indexes failed: {}", e)))?;
+        }
+        summary.push_str(&format!(" with {} index(es)", count));
+    }
+    Ok(summary)
+}
+
+/// Turns `changeStreamPreAndPostImages` on or off for a collection.
+pub async fn set_pre_and_post_images(
+    client: &Client,
+    db: &str,
+    collection: &str,
+    enabled: bool,
+) -> Result<(), AppError> {
+    client
+        .database(db)
+        .run_command(doc! {
+            "collMod": collection,
+            "changeStreamPreAndPostImages": { "enabled": enabled },
+        })
+        .await
+        .map_err(|e| AppError::Query(format!("collMod failed: {}", e)))?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use mongodb::{Client, bson::Document};
+
+use crate::{error::AppError, models::CountComparison, services::throttle::Throttle};
+
+const SYSTEM_DATABASES: [&str; 3] = ["admin", "config", "local"];
+
+/// Estimated document counts for every user collection, keyed by namespace.
+pub async fn collection_counts(
+    client: &Client,
+    mut throttle: Throttle,
+) -> Result<BTreeMap<String, u64>, AppError> {
+    let databases = client
+        .list_database_names()
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to list databases: {}", e)))?;
+
+    let mut counts = BTreeMap::new();
+    for db_name in databases {
+        if SYSTEM_DATABASES.contains(&db_name.as_str()) {
+            continue;
+        }
+        let database = client.database(&db_name);
+        let collections = database
+            .list_collection_names()
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to list collections: {}", e)))?;
+
+        for coll_name in collections {
+            if coll_name.starts_with("system.") {
+                continue;
+            }
+            let count = database
+                .collection::<Document>(&coll_name)
+                .estimated_document_count()
+                .await
+                .map_err(|e| {
+                    AppError::Query(format!("Failed to count {}.{}: {}", db_name, coll_name, e))
+                })?;
+            counts.insert(format!("{}.{}", db_name, coll_name), count);
+            throttle.pace(0).await;
+        }
+    }
+
+    Ok(counts)
+}
+
+/// Lines up two sets of counts by namespace; a namespace missing on one side
+/// counts as a mismatch.
+pub fn compare(
+    left: &BTreeMap<String, u64>,
+    right: &BTreeMap<String, u64>,
+) -> Vec<CountComparison> {
+    let namespaces: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    namespaces
+        .into_iter()
+        .map(|namespace| CountComparison {
+            namespace: namespace.clone(),
+            left: left.get(namespace).copied(),
+            right: right.get(namespace).copied(),
+        })
+        .collect()
+}
+
+impl CountComparison {
+    pub fn matches(&self) -> bool {
+        self.left == self.right
+    }
+}
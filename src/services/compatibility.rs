@@ -0,0 +1,128 @@
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+};
+
+use crate::error::AppError;
+use crate::models::{Compatibility, FeatureSupport, Topology};
+
+const MANIFEST: &str = include_str!("../../Cargo.toml");
+
+impl Topology {
+    pub fn name(self) -> &'static str {
+        match self {
+            Topology::Standalone => "standalone",
+            Topology::ReplicaSet => "replica set",
+            Topology::Sharded => "sharded cluster",
+        }
+    }
+}
+
+/// Asks the server what it is and checks each version- or topology-bound
+/// feature of the UI against the answer.
+pub async fn check(
+    client: &Client,
+    host: &str,
+    server_version: &str,
+) -> Result<Compatibility, AppError> {
+    let admin = client.database("admin");
+    let hello = admin
+        .run_command(doc! { "hello": 1 })
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to query the server: {}", e)))?;
+    let topology = topology(&hello);
+
+    // reading the FCV needs a privilege many users lack, so it is optional
+    let fcv = admin
+        .run_command(doc! { "getParameter": 1, "featureCompatibilityVersion": 1 })
+        .await
+        .ok()
+        .and_then(|reply| {
+            reply
+                .get_document("featureCompatibilityVersion")
+                .ok()?
+                .get_str("version")
+                .ok()
+                .map(String::from)
+        });
+
+    Ok(Compatibility {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        driver_version: driver_version(),
+        server_version: server_version.to_string(),
+        fcv,
+        topology,
+        features: features(version(server_version), topology, host),
+    })
+}
+
+fn topology(hello: &Document) -> Topology {
+    if hello.get_str("msg") == Ok("isdbgrid") {
+        Topology::Sharded
+    } else if hello.contains_key("setName") {
+        Topology::ReplicaSet
+    } else {
+        Topology::Standalone
+    }
+}
+
+// major and minor, e.g. (7, 0) for "7.0.5"
+fn version(text: &str) -> (u32, u32) {
+    let mut parts = text.split('.').map(|p| p.parse().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+fn features(version: (u32, u32), topology: Topology, host: &str) -> Vec<FeatureSupport> {
+    let replicated = topology != Topology::Standalone;
+    let atlas = host.ends_with(".mongodb.net");
+    let feature = |feature, supported, requirement| FeatureSupport {
+        feature,
+        supported,
+        requirement,
+    };
+    vec![
+        feature(
+            "Change stream watch (w)",
+            replicated,
+            "a replica set or sharded cluster",
+        ),
+        feature(
+            "Change stream pre/post images (I)",
+            replicated && version >= (6, 0),
+            "MongoDB 6.0+ on a replica set or sharded cluster",
+        ),
+        feature(
+            "Replica set view (S)",
+            topology == Topology::ReplicaSet,
+            "a replica set member",
+        ),
+        feature(
+            "Sharding view (B)",
+            topology == Topology::Sharded,
+            "a mongos of a sharded cluster",
+        ),
+        feature(
+            "Shard key analysis (K)",
+            replicated && version >= (7, 0),
+            "MongoDB 7.0+ on a replica set or sharded cluster",
+        ),
+        feature("Time series collections", version >= (5, 0), "MongoDB 5.0+"),
+        feature("Clustered collections", version >= (5, 3), "MongoDB 5.3+"),
+        feature(
+            "Atlas Search (s)",
+            atlas || version >= (8, 2),
+            "an Atlas cluster or MongoDB 8.2+ with mongot",
+        ),
+    ]
+}
+
+// the declared driver version, read from the manifest built into the binary
+fn driver_version() -> String {
+    MANIFEST
+        .lines()
+        .find(|line| line.trim_start().starts_with("mongodb "))
+        .and_then(|line| line.split("version = \"").nth(1))
+        .and_then(|rest| rest.split('"').next())
+        .map(|version| format!("mongodb {}", version))
+        .unwrap_or_else(|| "mongodb (unknown)".to_string())
+}
@@ -1,12 +1,31 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use mongodb::{Client, options::ClientOptions};
+use mongodb::{
+    Client,
+    event::EventHandler,
+    options::{ClientOptions, ServerAddress, Socks5Proxy},
+};
 
-use crate::{error::AppError, models::ServerInfo};
+use crate::{
+    config::{ConnectionProfile, ProxyConfig, RateLimit},
+    error::AppError,
+    models::ServerInfo,
+    services::{
+        aws, events::EventLog, metrics::NetworkStats, port_forward::PortForward,
+        throttle::Throttle, timings::QueryTimings,
+    },
+};
 
 pub struct ConnectionService {
     client: Option<Client>,
     server_info: Option<ServerInfo>,
+    network_stats: NetworkStats,
+    query_timings: QueryTimings,
+    /// Shared with duplicates so the tunnel lives as long as any of them.
+    tunnel: Option<Arc<PortForward>>,
+    endpoint: Option<(usize, usize)>,
+    rate_limit: Option<RateLimit>,
+    event_log: EventLog,
 }
 
 impl ConnectionService {
@@ -14,10 +33,57 @@ impl ConnectionService {
         Self {
             client: None,
             server_info: None,
+            network_stats: NetworkStats::new(),
+            query_timings: QueryTimings::new(),
+            tunnel: None,
+            endpoint: None,
+            rate_limit: None,
+            event_log: EventLog::new(),
         }
     }
 
-    pub async fn connect(&mut self, uri: &str) -> Result<ServerInfo,AppError> {
+    /// Connects to `uri`, then to each of the profile's fallback URIs in
+    /// order until one answers.
+    pub async fn connect(
+        &mut self,
+        uri: &str,
+        profile: Option<&ConnectionProfile>,
+    ) -> Result<ServerInfo, AppError> {
+        let mut endpoints = vec![uri];
+        if let Some(profile) = profile {
+            endpoints.extend(profile.fallback_uris.iter().map(String::as_str));
+        }
+
+        let total = endpoints.len();
+        let mut last_error = None;
+        for (index, endpoint) in endpoints.into_iter().enumerate() {
+            match self.connect_endpoint(endpoint, profile).await {
+                Ok(server_info) => {
+                    self.endpoint = Some((index, total));
+                    self.rate_limit = profile.and_then(|p| p.rate_limit.clone());
+                    return Ok(server_info);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let error =
+            last_error.unwrap_or_else(|| AppError::Connection("No URI to connect to".into()));
+        if total > 1 {
+            Err(AppError::Connection(format!(
+                "All {} endpoints failed, last: {}",
+                total, error
+            )))
+        } else {
+            Err(error)
+        }
+    }
+
+    async fn connect_endpoint(
+        &mut self,
+        uri: &str,
+        profile: Option<&ConnectionProfile>,
+    ) -> Result<ServerInfo, AppError> {
         // parsing connection string
         let mut client_options = ClientOptions::parse(uri)
             .await
@@ -26,6 +92,39 @@ impl ConnectionService {
         // setting timeout
         client_options.connect_timeout = Some(Duration::from_secs(5));
         client_options.server_selection_timeout = Some(Duration::from_secs(5));
+        let network_stats = self.network_stats.clone();
+        let query_timings = self.query_timings.clone();
+        client_options.command_event_handler = Some(EventHandler::callback(move |event| {
+            network_stats.record(&event);
+            query_timings.record(&event);
+        }));
+        client_options.sdam_event_handler = Some(self.event_log.sdam_handler());
+
+        if let Some(proxy) = profile.and_then(|p| p.proxy.as_ref()) {
+            client_options.socks5_proxy = Some(Self::socks5_proxy(proxy));
+        }
+
+        if let Some(aws_iam) = profile.and_then(|p| p.aws_iam.as_ref()) {
+            client_options.credential = Some(aws::credential(aws_iam).await?);
+        }
+
+        // the previous tunnel goes away with the previous connection
+        self.tunnel = None;
+        let tunnel = match profile.and_then(|p| p.port_forward.as_ref()) {
+            Some(port_forward) => {
+                let tunnel = PortForward::start(port_forward).await?;
+                // cluster-internal member names don't resolve from here, so talk
+                // to the forwarded port directly instead of discovering the set
+                client_options.hosts = vec![ServerAddress::Tcp {
+                    host: "127.0.0.1".to_string(),
+                    port: Some(tunnel.local_port()),
+                }];
+                client_options.direct_connection = Some(true);
+                client_options.repl_set_name = None;
+                Some(Arc::new(tunnel))
+            }
+            None => None,
+        };
 
         let client = Client::with_options(client_options)
             .map_err(|e| AppError::Connection(format!("Failed to create client: {}", e)))?;
@@ -53,19 +152,41 @@ impl ConnectionService {
 
         self.client = Some(client);
         self.server_info = Some(server_info.clone());
+        self.tunnel = tunnel;
 
         Ok(server_info)
     }
+    #[allow(dead_code)]
     pub async fn disconnect(&mut self) -> Result<(),AppError> {
         self.client = None;
         self.server_info = None;
+        self.tunnel = None;
+        self.endpoint = None;
+        self.rate_limit = None;
         Ok(())
     }
 
+    /// A second handle on the same client, for browsing another namespace
+    /// of the cluster side by side; its timings and event log are shared.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            server_info: self.server_info.clone(),
+            network_stats: self.network_stats.clone(),
+            query_timings: self.query_timings.clone(),
+            tunnel: self.tunnel.clone(),
+            endpoint: self.endpoint,
+            rate_limit: self.rate_limit.clone(),
+            event_log: self.event_log.clone(),
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn is_connected(&self) -> bool {
         self.client.is_some()
     }
 
+    #[allow(dead_code)]
     pub async fn test_connection(&self) -> Result<bool,AppError> {
         if let Some(client) = &self.client {
             client
@@ -81,27 +202,55 @@ impl ConnectionService {
     pub fn get_client(&self) -> Option<&Client> {
         self.client.as_ref()
     }
+    #[allow(dead_code)]
     pub fn get_server_info(&self) -> Option<ServerInfo> {
         self.server_info.clone()
     }
+    /// Index of the endpoint in use and how many the profile lists.
+    pub fn endpoint(&self) -> Option<(usize, usize)> {
+        self.endpoint
+    }
+    /// A fresh throttle for one bulk operation on this connection.
+    pub fn throttle(&self) -> Throttle {
+        Throttle::new(self.rate_limit.clone())
+    }
+    pub fn event_log(&self) -> &EventLog {
+        &self.event_log
+    }
+    pub fn network_stats(&self) -> &NetworkStats {
+        &self.network_stats
+    }
+    pub fn query_timings(&self) -> &QueryTimings {
+        &self.query_timings
+    }
+    fn socks5_proxy(proxy: &ProxyConfig) -> Socks5Proxy {
+        let authentication = match (&proxy.username, &proxy.password) {
+            (Some(user), Some(pass)) => Some((user.clone(), pass.clone())),
+            _ => None,
+        };
+        Socks5Proxy::builder()
+            .host(proxy.host.clone())
+            .port(proxy.port)
+            .authentication(authentication)
+            .build()
+    }
     fn parse_host_port(uri: &str) -> (String, u16) {
         if let Some(after_protocol) = uri
             .strip_prefix("mongodb://")
             .or_else(|| uri.strip_prefix("mongodb+srv://"))
+            && let Some(host_part) = after_protocol.split('/').next()
         {
-            if let Some(host_part) = after_protocol.split('/').next() {
-                let host_part = if let Some(at_pos) = host_part.rfind('@') {
-                    &host_part[at_pos + 1..]
-                } else {
-                    host_part
-                };
-
-                if let Some((host, port_str)) = host_part.split_once(':') {
-                    let port = port_str.parse().unwrap_or(27017);
-                    return (host.to_string(), port);
-                } else {
-                    return (host_part.to_string(), 27017);
-                }
+            let host_part = if let Some(at_pos) = host_part.rfind('@') {
+                &host_part[at_pos + 1..]
+            } else {
+                host_part
+            };
+
+            if let Some((host, port_str)) = host_part.split_once(':') {
+                let port = port_str.parse().unwrap_or(27017);
+                return (host.to_string(), port);
+            } else {
+                return (host_part.to_string(), 27017);
             }
         }
         ("localhost".to_string(), 27017)
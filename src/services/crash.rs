@@ -0,0 +1,135 @@
+use std::{
+    backtrace::Backtrace,
+    collections::VecDeque,
+    fmt::Write as _,
+    fs, io,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::{
+    cursor::Show,
+    event::{DisableBracketedPaste, DisableMouseCapture},
+    execute,
+    terminal::{LeaveAlternateScreen, disable_raw_mode},
+};
+use mongodb::bson::DateTime;
+
+use crate::models::EventLevel;
+use crate::services::events::EventLog;
+
+/// Actions kept for the report, oldest dropped first.
+const MAX_ACTIONS: usize = 50;
+
+static ACTIONS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static EVENTS: Mutex<Option<EventLog>> = Mutex::new(None);
+
+// a panic while one of these was held must not stop the report
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Notes a user action for the next crash report.
+pub fn record_action(action: String) {
+    let mut actions = lock(&ACTIONS);
+    actions.push_back(action);
+    if actions.len() > MAX_ACTIONS {
+        actions.pop_front();
+    }
+}
+
+/// Makes `log` the connection events quoted in a crash report.
+pub fn watch_events(log: &EventLog) {
+    *lock(&EVENTS) = Some(log.clone());
+}
+
+/// Replaces the default panic output: the terminal is put back first so
+/// the message is readable, then a report with the backtrace, the last
+/// actions and the connection events is written and its path printed.
+/// Panics in background tasks end the program too, as the screen is gone.
+pub fn install_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        let backtrace = Backtrace::force_capture();
+        match write_report(info, &backtrace) {
+            Ok(path) => {
+                eprintln!("kompass crashed: {}", info);
+                eprintln!("A crash report was written to {}", path.display());
+            }
+            Err(e) => {
+                default_hook(info);
+                eprintln!("Could not write a crash report: {}", e);
+            }
+        }
+        std::process::exit(101);
+    }));
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        Show
+    );
+}
+
+fn report_dir() -> PathBuf {
+    dirs::home_dir()
+        .map(|home| home.join(".mongonaut").join("crashes"))
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+fn write_report(info: &PanicHookInfo, backtrace: &Backtrace) -> io::Result<PathBuf> {
+    let mut report = String::new();
+    let now = DateTime::now();
+    let thread = std::thread::current();
+    let _ = writeln!(
+        report,
+        "kompass {} crashed at {}",
+        env!("CARGO_PKG_VERSION"),
+        now.try_to_rfc3339_string().unwrap_or_default()
+    );
+    let _ = writeln!(report, "{}", info);
+    let _ = writeln!(report, "thread: {}", thread.name().unwrap_or("unnamed"));
+
+    let _ = writeln!(report, "\nLast actions, oldest first:");
+    for action in lock(&ACTIONS).iter() {
+        let _ = writeln!(report, "  {}", action);
+    }
+
+    let _ = writeln!(report, "\nConnection events:");
+    if let Some(log) = lock(&EVENTS).as_ref() {
+        for entry in log.snapshot() {
+            let level = match entry.level {
+                EventLevel::Info => "INFO ",
+                EventLevel::Warn => "WARN ",
+                EventLevel::Error => "ERROR",
+            };
+            let _ = writeln!(
+                report,
+                "  {} {} {}",
+                entry.at.try_to_rfc3339_string().unwrap_or_default(),
+                level,
+                entry.message
+            );
+        }
+    }
+
+    let _ = writeln!(report, "\nBacktrace:\n{}", backtrace);
+
+    let dir = report_dir();
+    fs::create_dir_all(&dir)?;
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let path = dir.join(format!("crash-{}.txt", stamp));
+    fs::write(&path, report)?;
+    Ok(path)
+}
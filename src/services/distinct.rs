@@ -0,0 +1,88 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::error::AppError;
+use crate::models::DistinctValues;
+use crate::services::bson_ext::number_as_f64;
+
+/// Values listed at most; rarer ones are left out.
+const MAX_VALUES: i64 = 1000;
+
+/// The distinct values of `field` among the documents matching `filter`,
+/// most frequent first, each with the number of documents holding it. As
+/// with `distinct`, every element of an array counts on its own; documents
+/// without the field count under null.
+pub async fn distinct_values(
+    client: &Client,
+    db: &str,
+    collection: &str,
+    filter: Option<Document>,
+    field: &str,
+) -> Result<DistinctValues, AppError> {
+    let path = format!("${}", field);
+    let pipeline = vec![
+        doc! { "$match": filter.unwrap_or_default() },
+        doc! { "$unwind": { "path": &path, "preserveNullAndEmptyArrays": true } },
+        doc! { "$group": { "_id": &path, "count": { "$sum": 1 } } },
+        doc! { "$sort": { "count": -1, "_id": 1 } },
+        doc! { "$limit": MAX_VALUES + 1 },
+    ];
+    let mut groups: Vec<Document> = client
+        .database(db)
+        .collection::<Document>(collection)
+        .aggregate(pipeline)
+        .allow_disk_use(true)
+        .await
+        .map_err(|e| AppError::Query(format!("Distinct failed: {}", e)))?
+        .try_collect()
+        .await?;
+    let truncated = groups.len() as i64 > MAX_VALUES;
+    groups.truncate(MAX_VALUES as usize);
+
+    let values: Vec<(String, u64)> = groups
+        .into_iter()
+        .map(|group| {
+            let value = match group.get("_id") {
+                None | Some(Bson::Null) => "null / missing".to_string(),
+                Some(value) => value.clone().into_relaxed_extjson().to_string(),
+            };
+            let count = group.get("count").and_then(number_as_f64).unwrap_or(0.0) as u64;
+            (value, count)
+        })
+        .collect();
+    Ok(DistinctValues {
+        field: field.to_string(),
+        total: values.iter().map(|(_, count)| count).sum(),
+        values,
+        truncated,
+        by_value: false,
+        selected: 0,
+    })
+}
+
+impl DistinctValues {
+    /// Switches between most frequent first and ordered by value.
+    pub fn toggle_sort(&mut self) {
+        self.by_value = !self.by_value;
+        if self.by_value {
+            self.values.sort_by(|a, b| a.0.cmp(&b.0));
+        } else {
+            self.values
+                .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        }
+        self.selected = 0;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.values.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
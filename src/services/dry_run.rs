@@ -0,0 +1,50 @@
+use mongodb::{
+    Client,
+    bson::{Bson, Document},
+};
+
+use crate::models::WouldExecute;
+
+impl WouldExecute {
+    /// A database or admin command, named by its first key.
+    pub fn command(target: &str, command: &Document) -> Self {
+        Self {
+            target: target.to_string(),
+            operation: command.keys().next().cloned().unwrap_or_default(),
+            payload: Some(extended_json(command)),
+            ..Self::default()
+        }
+    }
+
+    /// A write to the documents of a collection that `filter` selects.
+    pub fn write(namespace: &str, operation: &str, filter: &Document, payload: String) -> Self {
+        Self {
+            target: namespace.to_string(),
+            operation: operation.to_string(),
+            filter: Some(extended_json(filter)),
+            payload: Some(payload),
+            ..Self::default()
+        }
+    }
+}
+
+/// How many documents of `db.coll` the write would touch: those matching
+/// `filter`, or all of them by the cheap estimate when there is none.
+pub async fn matched(
+    client: &Client,
+    db: &str,
+    coll: &str,
+    filter: Option<Document>,
+) -> Option<u64> {
+    let collection = client.database(db).collection::<Document>(coll);
+    match filter {
+        Some(filter) => collection.count_documents(filter).await.ok(),
+        None => collection.estimated_document_count().await.ok(),
+    }
+}
+
+pub fn extended_json(document: &Document) -> String {
+    Bson::Document(document.clone())
+        .into_relaxed_extjson()
+        .to_string()
+}
@@ -0,0 +1,105 @@
+use std::{
+    env, fs, io,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use mongodb::bson::{Bson, Document};
+
+use crate::{error::AppError, models::FieldChange};
+
+/// Opens a document as pretty canonical extended JSON in `$VISUAL`/`$EDITOR`
+/// and parses what the user saved, so untouched fields keep their types.
+/// Blocks until the editor exits, so the TUI must be suspended.
+pub fn edit_document(doc: &Document) -> Result<Document, AppError> {
+    let json =
+        serde_json::to_string_pretty(&Bson::Document(doc.clone()).into_canonical_extjson())?;
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let path = env::temp_dir().join(format!(
+        "monjo-kompass-{}-{}.json",
+        std::process::id(),
+        stamp
+    ));
+    fs::write(&path, json)?;
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // editors such as `code --wait` come with their own arguments
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(&path).status();
+
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    let status = status?;
+    if !status.success() {
+        return Err(AppError::Io(io::Error::other(format!(
+            "{} exited with {}",
+            program, status
+        ))));
+    }
+    parse_document(&edited?)
+}
+
+/// Parses extended JSON, so `{"$oid": ...}` and `{"$date": ...}` come back as
+/// their BSON types rather than plain subdocuments.
+pub fn parse_document(json: &str) -> Result<Document, AppError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| AppError::Query(format!("Invalid JSON: {}", e)))?;
+    match Bson::try_from(value) {
+        Ok(Bson::Document(doc)) => Ok(doc),
+        Ok(_) => Err(AppError::Query("Expected a JSON object".to_string())),
+        Err(e) => Err(AppError::Query(format!("Invalid extended JSON: {}", e))),
+    }
+}
+
+/// Field-level differences, descending into subdocuments with dotted paths.
+pub fn diff(before: &Document, after: &Document) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_into("", before, after, &mut changes);
+    changes
+}
+
+fn diff_into(prefix: &str, before: &Document, after: &Document, changes: &mut Vec<FieldChange>) {
+    let path = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+
+    for (key, old) in before {
+        match (old, after.get(key)) {
+            (Bson::Document(old), Some(Bson::Document(new))) => {
+                diff_into(&path(key), old, new, changes);
+            }
+            (old, Some(new)) if old != new => changes.push(FieldChange {
+                path: path(key),
+                before: Some(old.to_string()),
+                after: Some(new.to_string()),
+            }),
+            (old, None) => changes.push(FieldChange {
+                path: path(key),
+                before: Some(old.to_string()),
+                after: None,
+            }),
+            _ => {}
+        }
+    }
+    for (key, new) in after {
+        if !before.contains_key(key) {
+            changes.push(FieldChange {
+                path: path(key),
+                before: None,
+                after: Some(new.to_string()),
+            });
+        }
+    }
+}
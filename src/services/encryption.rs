@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use mongodb::{
+    Database,
+    bson::{Binary, Bson, Document, doc, spec::BinarySubtype},
+};
+
+/// Encrypted field paths per collection of `database`. The driver's typed
+/// collection options leave `encryptedFields` out, so this reads the raw
+/// `listCollections` reply; a failure just means nothing is marked.
+pub async fn declared_by_collection(database: &Database) -> HashMap<String, Vec<String>> {
+    let command = doc! {
+        "listCollections": 1,
+        "filter": { "$or": [
+            { "options.encryptedFields": { "$exists": true } },
+            { "options.validator": { "$exists": true } },
+        ] },
+    };
+    let Ok(reply) = database.run_command(command).await else {
+        return HashMap::new();
+    };
+    let batch = reply
+        .get_document("cursor")
+        .and_then(|cursor| cursor.get_array("firstBatch"))
+        .cloned()
+        .unwrap_or_default();
+    batch
+        .iter()
+        .filter_map(Bson::as_document)
+        .filter_map(|spec| {
+            let name = spec.get_str("name").ok()?;
+            let options = spec.get_document("options").ok()?;
+            let fields = declared_fields(
+                options.get_document("encryptedFields").ok(),
+                options.get_document("validator").ok(),
+            );
+            (!fields.is_empty()).then(|| (name.to_string(), fields))
+        })
+        .collect()
+}
+
+/// Field paths a collection declares encrypted: the `encryptedFields` of
+/// Queryable Encryption, or `encrypt` properties in a CSFLE `$jsonSchema`
+/// validator.
+pub fn declared_fields(
+    encrypted_fields: Option<&Document>,
+    validator: Option<&Document>,
+) -> Vec<String> {
+    let mut fields: Vec<String> = encrypted_fields
+        .and_then(|config| config.get_array("fields").ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|field| field.as_document()?.get_str("path").ok())
+        .map(String::from)
+        .collect();
+    if let Some(schema) = validator.and_then(|v| v.get_document("$jsonSchema").ok()) {
+        schema_fields(schema, "", &mut fields);
+    }
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+// walks nested `properties`, collecting the paths marked `encrypt`
+fn schema_fields(schema: &Document, prefix: &str, fields: &mut Vec<String>) {
+    let Ok(properties) = schema.get_document("properties") else {
+        return;
+    };
+    for (name, property) in properties {
+        let Some(property) = property.as_document() else {
+            continue;
+        };
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        if property.contains_key("encrypt") {
+            fields.push(path);
+        } else {
+            schema_fields(property, &path, fields);
+        }
+    }
+}
+
+/// The encrypted payload in `value`, if it holds ciphertext.
+pub fn ciphertext(value: &Bson) -> Option<&Binary> {
+    match value {
+        Bson::Binary(binary) if binary.subtype == BinarySubtype::Encrypted => Some(binary),
+        _ => None,
+    }
+}
+
+/// What is shown instead of ciphertext. The first byte of the payload
+/// tells the CSFLE algorithms apart; anything else is Queryable Encryption.
+pub fn label(binary: &Binary) -> String {
+    let kind = match binary.bytes.first() {
+        Some(1) => "deterministic",
+        Some(2) => "random",
+        _ => "queryable",
+    };
+    format!("🔒 encrypted ({}, {} bytes)", kind, binary.bytes.len())
+}
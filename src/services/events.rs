@@ -0,0 +1,107 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use mongodb::{
+    ServerType,
+    bson::DateTime,
+    event::{EventHandler, sdam::SdamEvent},
+};
+
+use crate::models::{EventLevel, EventLogEntry};
+
+const MAX_EVENTS: usize = 500;
+
+/// Keeps the most recent topology events reported by the driver's server
+/// discovery and monitoring, for the connection event log.
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    entries: Arc<Mutex<VecDeque<EventLogEntry>>>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sdam_handler(&self) -> EventHandler<SdamEvent> {
+        let log = self.clone();
+        EventHandler::callback(move |event| {
+            if let Some((level, message)) = describe(&event) {
+                log.push(level, message);
+            }
+        })
+    }
+
+    pub fn push(&self, level: EventLevel, message: String) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.push_back(EventLogEntry {
+            at: DateTime::now(),
+            level,
+            message,
+        });
+        if entries.len() > MAX_EVENTS {
+            entries.pop_front();
+        }
+    }
+
+    // also read by the panic hook, where a poisoned lock must not panic again
+    pub fn snapshot(&self) -> Vec<EventLogEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+// heartbeats that succeed and topology-wide diffs are left out; they repeat
+// what the per-server events already say
+fn describe(event: &SdamEvent) -> Option<(EventLevel, String)> {
+    match event {
+        SdamEvent::TopologyOpening(_) => Some((EventLevel::Info, "Topology opened".to_string())),
+        SdamEvent::TopologyClosed(_) => Some((EventLevel::Info, "Topology closed".to_string())),
+        SdamEvent::ServerOpening(e) => Some((
+            EventLevel::Info,
+            format!("Server discovered: {}", e.address),
+        )),
+        SdamEvent::ServerClosed(e) => {
+            Some((EventLevel::Warn, format!("Server removed: {}", e.address)))
+        }
+        SdamEvent::ServerDescriptionChanged(e) => {
+            let previous = e.previous_description.server_type();
+            let new = e.new_description.server_type();
+            if previous == new && e.new_description.error().is_none() {
+                return None;
+            }
+            let mut message = format!("{}: {:?} → {:?}", e.address, previous, new);
+            if let Some(error) = e.new_description.error() {
+                message.push_str(&format!(" ({})", error));
+            }
+            let level = if e.new_description.error().is_some() {
+                EventLevel::Error
+            } else if new == ServerType::RsPrimary {
+                message.push_str(" — new primary");
+                EventLevel::Warn
+            } else if previous == ServerType::RsPrimary {
+                message.push_str(" — primary stepped down");
+                EventLevel::Warn
+            } else {
+                EventLevel::Info
+            };
+            Some((level, message))
+        }
+        SdamEvent::ServerHeartbeatFailed(e) => Some((
+            EventLevel::Error,
+            format!(
+                "Heartbeat to {} failed after {} ms: {}",
+                e.server_address,
+                e.duration.as_millis(),
+                e.failure
+            ),
+        )),
+        _ => None,
+    }
+}
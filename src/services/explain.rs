@@ -0,0 +1,222 @@
+use mongodb::bson::{Bson, Document};
+
+use crate::models::{PlanNode, PlanTree, ScanWarning};
+use crate::services::bson_ext::number_as_f64;
+
+/// Collection scans examining more documents than this are flagged.
+pub const COLLSCAN_WARNING_THRESHOLD: u64 = 1000;
+
+/// Collects stage names from a winning plan, outermost first.
+pub fn plan_stages(plan: &Document) -> Vec<String> {
+    let mut stages = Vec::new();
+    collect_stages(plan, &mut stages);
+    stages
+}
+
+fn collect_stages(plan: &Document, stages: &mut Vec<String>) {
+    if let Ok(stage) = plan.get_str("stage") {
+        stages.push(stage.to_string());
+    }
+    // SBE plans nest the classic tree under queryPlan
+    for key in ["queryPlan", "inputStage"] {
+        if let Ok(inner) = plan.get_document(key) {
+            collect_stages(inner, stages);
+        }
+    }
+    if let Ok(inputs) = plan.get_array("inputStages") {
+        for inner in inputs.iter().filter_map(Bson::as_document) {
+            collect_stages(inner, stages);
+        }
+    }
+}
+
+pub fn scan_warning(explain: &Document, filter: &Document, threshold: u64) -> Option<ScanWarning> {
+    let winning_plan = explain
+        .get_document("queryPlanner")
+        .ok()?
+        .get_document("winningPlan")
+        .ok()?;
+
+    if !plan_stages(winning_plan).iter().any(|s| s == "COLLSCAN") {
+        return None;
+    }
+
+    let stats = explain.get_document("executionStats").ok()?;
+    let examined = stats
+        .get("totalDocsExamined")
+        .and_then(number_as_f64)
+        .unwrap_or(0.0) as u64;
+    let returned = stats
+        .get("nReturned")
+        .and_then(number_as_f64)
+        .unwrap_or(0.0) as u64;
+
+    if examined <= threshold {
+        return None;
+    }
+
+    Some(ScanWarning {
+        docs_examined: examined,
+        returned,
+        suggested_index: suggest_index(filter),
+    })
+}
+
+/// Suggests a compound index over the plain field names used in a filter.
+pub fn suggest_index(filter: &Document) -> Document {
+    let mut index = Document::new();
+    for (key, value) in filter {
+        if key.starts_with('$') {
+            // descend into $and/$or branches for their field names
+            if let Bson::Array(branches) = value {
+                for branch in branches.iter().filter_map(Bson::as_document) {
+                    for (field, _) in suggest_index(branch) {
+                        if !index.contains_key(&field) {
+                            index.insert(field, 1);
+                        }
+                    }
+                }
+            }
+        } else if !index.contains_key(key) {
+            index.insert(key.clone(), 1);
+        }
+    }
+    index
+}
+
+/// The winning plan with per-stage execution stats when the server reports
+/// them, otherwise the bare planner output.
+pub fn plan_tree(explain: &Document) -> PlanTree {
+    let stats = explain.get_document("executionStats").ok();
+    let root = stats
+        .and_then(|s| s.get_document("executionStages").ok())
+        .or_else(|| {
+            explain
+                .get_document("queryPlanner")
+                .and_then(|p| p.get_document("winningPlan"))
+                .ok()
+        });
+
+    let mut nodes = Vec::new();
+    if let Some(root) = root {
+        collect_nodes(root, 0, &mut nodes);
+    }
+    let total = |key: &str| stats.and_then(|s| count(s, key)).unwrap_or(0);
+    PlanTree {
+        nodes,
+        selected: 0,
+        returned: total("nReturned"),
+        keys_examined: total("totalKeysExamined"),
+        docs_examined: total("totalDocsExamined"),
+        time_ms: total("executionTimeMillis"),
+    }
+}
+
+fn collect_nodes(plan: &Document, depth: usize, nodes: &mut Vec<PlanNode>) {
+    if let Ok(inner) = plan.get_document("queryPlan") {
+        return collect_nodes(inner, depth, nodes);
+    }
+    let Ok(stage) = plan.get_str("stage") else {
+        return;
+    };
+    nodes.push(PlanNode {
+        stage: stage.to_string(),
+        depth,
+        detail: stage_detail(plan),
+        returned: count(plan, "nReturned"),
+        keys_examined: count(plan, "keysExamined"),
+        docs_examined: count(plan, "docsExamined"),
+        time_ms: count(plan, "executionTimeMillisEstimate"),
+        collapsed: false,
+    });
+    if let Ok(inner) = plan.get_document("inputStage") {
+        collect_nodes(inner, depth + 1, nodes);
+    }
+    if let Ok(inputs) = plan.get_array("inputStages") {
+        for inner in inputs.iter().filter_map(Bson::as_document) {
+            collect_nodes(inner, depth + 1, nodes);
+        }
+    }
+}
+
+fn stage_detail(plan: &Document) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Ok(name) = plan.get_str("indexName") {
+        parts.push(name.to_string());
+    }
+    if let Ok(pattern) = plan.get_document("keyPattern") {
+        parts.push(pattern.to_string());
+    }
+    if let Ok(direction) = plan.get_str("direction") {
+        parts.push(direction.to_string());
+    }
+    if let Ok(sort) = plan.get_document("sortPattern") {
+        parts.push(format!("sort {}", sort));
+    }
+    if let Ok(filter) = plan.get_document("filter") {
+        parts.push(format!("filter {}", filter));
+    }
+    if let Some(limit) = plan.get("limitAmount").and_then(number_as_f64) {
+        parts.push(format!("limit {}", limit));
+    }
+    (!parts.is_empty()).then(|| parts.join("  "))
+}
+
+fn count(doc: &Document, key: &str) -> Option<u64> {
+    doc.get(key).and_then(number_as_f64).map(|n| n as u64)
+}
+
+impl PlanTree {
+    /// Indexes of nodes not hidden under a collapsed ancestor.
+    pub fn visible(&self) -> Vec<usize> {
+        let mut visible = Vec::new();
+        let mut hidden_below: Option<usize> = None;
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(depth) = hidden_below {
+                if node.depth > depth {
+                    continue;
+                }
+                hidden_below = None;
+            }
+            visible.push(i);
+            if node.collapsed {
+                hidden_below = Some(node.depth);
+            }
+        }
+        visible
+    }
+
+    pub fn has_children(&self, index: usize) -> bool {
+        match (self.nodes.get(index), self.nodes.get(index + 1)) {
+            (Some(node), Some(next)) => next.depth > node.depth,
+            _ => false,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        let visible = self.visible();
+        if let Some(pos) = visible.iter().position(|&i| i == self.selected)
+            && let Some(&next) = visible.get(pos + 1)
+        {
+            self.selected = next;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        let visible = self.visible();
+        if let Some(pos) = visible.iter().position(|&i| i == self.selected)
+            && pos > 0
+        {
+            self.selected = visible[pos - 1];
+        }
+    }
+
+    /// Folds or unfolds the input stages of the selected node.
+    pub fn toggle_collapsed(&mut self) {
+        if self.has_children(self.selected)
+            && let Some(node) = self.nodes.get_mut(self.selected)
+        {
+            node.collapsed = !node.collapsed;
+        }
+    }
+}
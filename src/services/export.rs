@@ -0,0 +1,617 @@
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    path::PathBuf,
+    sync::Mutex as StdMutex,
+};
+
+use futures::stream::{self, StreamExt};
+use mongodb::{
+    Client, Collection, Cursor,
+    bson::{Bson, Document},
+    options::FindOptions,
+};
+use tokio::{fs, io::AsyncWriteExt, sync::Mutex};
+
+use crate::config::QueryLimits;
+use crate::error::AppError;
+use crate::models::{ExportDialog, ExportField, QueryParams};
+use crate::services::paging;
+use crate::services::table;
+use crate::services::tasks::TaskProgress;
+use crate::services::throttle::Throttle;
+
+/// Documents written between progress updates and throttle checks.
+const BATCH_SIZE: u64 = 500;
+
+/// Collections a bulk export writes at the same time.
+const BULK_CONCURRENCY: usize = 4;
+
+/// Called after each batch with the documents written so far and the total,
+/// when it could be counted.
+type Report<'a> = &'a (dyn Fn(u64, Option<u64>) + Send + Sync);
+
+/// Turns exported documents into one file format. The pipeline opens the
+/// file, streams the documents, paces and reports; a format only writes
+/// bytes, so a new one is a type implementing this and an entry in
+/// [`FORMATS`].
+pub trait Exporter: Send {
+    /// Called before the first document. `columns` holds the flattened
+    /// field paths for formats that ask for them, and is empty otherwise.
+    fn begin(&mut self, _out: &mut dyn Write, _columns: &[String]) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn document(&mut self, out: &mut dyn Write, doc: &Document) -> io::Result<()>;
+
+    fn finish(&mut self, _out: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A format offered in the export dialog.
+pub struct ExportFormat {
+    /// Shown in the dialog and used as the file extension.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Documents are flattened to dot-notation columns, which the dialog
+    /// lets you pick.
+    pub columns: bool,
+    create: fn() -> Box<dyn Exporter>,
+}
+
+impl ExportFormat {
+    pub fn exporter(&self) -> Box<dyn Exporter> {
+        (self.create)()
+    }
+}
+
+impl std::fmt::Debug for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// Every export format, in the order the dialog cycles through them.
+pub const FORMATS: &[ExportFormat] = &[
+    ExportFormat {
+        name: "jsonl",
+        description: "one JSON document per line",
+        columns: false,
+        create: || Box::new(JsonLines),
+    },
+    ExportFormat {
+        name: "json",
+        description: "a pretty-printed JSON array",
+        columns: false,
+        create: || Box::new(JsonArray::default()),
+    },
+    ExportFormat {
+        name: "csv",
+        description: "comma-separated columns under a header row",
+        columns: true,
+        create: || Box::new(Delimited::new(',')),
+    },
+    ExportFormat {
+        name: "tsv",
+        description: "tab-separated columns under a header row",
+        columns: true,
+        create: || Box::new(Delimited::new('\t')),
+    },
+    ExportFormat {
+        name: "bson",
+        description: "raw BSON documents back to back, as mongodump writes them",
+        columns: false,
+        create: || Box::new(RawBson),
+    },
+    ExportFormat {
+        name: "md",
+        description: "a Markdown table",
+        columns: true,
+        create: || Box::new(Markdown::default()),
+    },
+];
+
+// canonical extended JSON, so binaries, 64-bit integers and the like come
+// back as the same types on import
+fn extended_json(doc: &Document) -> serde_json::Value {
+    Bson::Document(doc.clone()).into_canonical_extjson()
+}
+
+struct JsonLines;
+
+impl Exporter for JsonLines {
+    fn document(&mut self, out: &mut dyn Write, doc: &Document) -> io::Result<()> {
+        serde_json::to_writer(&mut *out, &extended_json(doc))?;
+        out.write_all(b"\n")
+    }
+}
+
+#[derive(Default)]
+struct JsonArray {
+    written: bool,
+}
+
+impl Exporter for JsonArray {
+    fn begin(&mut self, out: &mut dyn Write, _columns: &[String]) -> io::Result<()> {
+        out.write_all(b"[\n")
+    }
+
+    fn document(&mut self, out: &mut dyn Write, doc: &Document) -> io::Result<()> {
+        if self.written {
+            out.write_all(b",\n")?;
+        }
+        self.written = true;
+        serde_json::to_writer_pretty(&mut *out, &extended_json(doc))?;
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        out.write_all(b"\n]\n")
+    }
+}
+
+struct Delimited {
+    separator: char,
+    columns: Vec<String>,
+}
+
+impl Delimited {
+    fn new(separator: char) -> Self {
+        Self {
+            separator,
+            columns: Vec::new(),
+        }
+    }
+}
+
+impl Exporter for Delimited {
+    fn begin(&mut self, out: &mut dyn Write, columns: &[String]) -> io::Result<()> {
+        self.columns = columns.to_vec();
+        out.write_all(table::delimited_line(columns, self.separator).as_bytes())
+    }
+
+    fn document(&mut self, out: &mut dyn Write, doc: &Document) -> io::Result<()> {
+        let cells: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| table::cell_text(doc, column))
+            .collect();
+        out.write_all(table::delimited_line(&cells, self.separator).as_bytes())
+    }
+}
+
+struct RawBson;
+
+impl Exporter for RawBson {
+    fn document(&mut self, out: &mut dyn Write, doc: &Document) -> io::Result<()> {
+        doc.to_writer(out).map_err(io::Error::other)
+    }
+}
+
+#[derive(Default)]
+struct Markdown {
+    columns: Vec<String>,
+}
+
+impl Markdown {
+    fn row(cells: impl Iterator<Item = String>) -> String {
+        let cells: Vec<String> = cells
+            .map(|cell| cell.replace('|', "\\|").replace(['\n', '\r'], " "))
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    }
+}
+
+impl Exporter for Markdown {
+    fn begin(&mut self, out: &mut dyn Write, columns: &[String]) -> io::Result<()> {
+        self.columns = columns.to_vec();
+        out.write_all(Self::row(columns.iter().cloned()).as_bytes())?;
+        out.write_all(Self::row(columns.iter().map(|_| "---".to_string())).as_bytes())
+    }
+
+    fn document(&mut self, out: &mut dyn Write, doc: &Document) -> io::Result<()> {
+        let cells = self
+            .columns
+            .iter()
+            .map(|column| table::cell_text(doc, column));
+        out.write_all(Self::row(cells).as_bytes())
+    }
+}
+
+/// Where to export to, in which format and, for the column formats, which
+/// columns to write.
+#[derive(Debug, Clone)]
+pub struct ExportTarget {
+    pub path: PathBuf,
+    pub format: &'static ExportFormat,
+    /// Dotted field paths; `None` takes the union over the exported documents.
+    pub columns: Option<Vec<String>>,
+    pub limits: QueryLimits,
+    /// Set by a trailing `!` on the path, to read past the document cap.
+    pub force: bool,
+}
+
+/// Where a collection export writes: a directory and the format of its files.
+#[derive(Debug, Clone)]
+pub struct BulkTarget {
+    pub dir: PathBuf,
+    pub format: &'static ExportFormat,
+    /// The document cap holds for each collection on its own.
+    pub limits: QueryLimits,
+    pub force: bool,
+}
+
+impl BulkTarget {
+    // collection names may hold path separators
+    fn file_for(&self, collection: &str) -> PathBuf {
+        let name = collection.replace(['/', '\\'], "_");
+        self.dir.join(format!("{}.{}", name, self.format.name))
+    }
+}
+
+impl ExportDialog {
+    /// The dialog for the documents matching the query on `namespace`.
+    pub fn for_documents(namespace: &str) -> Self {
+        Self {
+            path: format!("{}.{}", namespace, FORMATS[0].name),
+            ..Self::default()
+        }
+    }
+
+    /// The dialog for exporting `collections` of `database`.
+    pub fn for_collections(database: &str, collections: Vec<String>) -> Self {
+        Self {
+            collections,
+            path: format!("{}-export", database),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_bulk(&self) -> bool {
+        !self.collections.is_empty()
+    }
+
+    pub fn format(&self) -> &'static ExportFormat {
+        &FORMATS[self.format % FORMATS.len()]
+    }
+
+    /// Fields that apply to the chosen format, in display order.
+    pub fn fields(&self) -> Vec<ExportField> {
+        let mut fields = vec![ExportField::Format, ExportField::Path];
+        if self.format().columns && !self.is_bulk() {
+            fields.push(ExportField::Columns);
+        }
+        fields
+    }
+
+    pub fn focused_field(&self) -> ExportField {
+        let fields = self.fields();
+        fields[self.focus.min(fields.len() - 1)]
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = (self.focus + 1) % self.fields().len();
+    }
+
+    pub fn prev_field(&mut self) {
+        let count = self.fields().len();
+        self.focus = (self.focus + count - 1) % count;
+    }
+
+    /// Switches to the next or previous format; a file name ending in the
+    /// old format's extension follows along.
+    pub fn cycle_format(&mut self, forward: bool) {
+        let old = self.format().name;
+        let step = if forward { 1 } else { FORMATS.len() - 1 };
+        self.format = (self.format + step) % FORMATS.len();
+        if !self.is_bulk()
+            && let Some(stem) = self.path.strip_suffix(&format!(".{}", old))
+        {
+            self.path = format!("{}.{}", stem, self.format().name);
+        }
+        self.focus = self.focus.min(self.fields().len() - 1);
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self.focused_field() {
+            ExportField::Path => Some(&mut self.path),
+            ExportField::Columns => Some(&mut self.columns),
+            ExportField::Format => None,
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if let Some(text) = self.text_mut() {
+            text.push(c);
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if let Some(text) = self.text_mut() {
+            text.pop();
+        }
+    }
+
+    // a trailing ! overrides the document cap, as it does for page sizes
+    fn path_and_force(&self) -> (&str, bool) {
+        let path = self.path.trim();
+        match path.strip_suffix('!') {
+            Some(path) => (path.trim_end(), true),
+            None => (path, false),
+        }
+    }
+
+    pub fn document_target(&self, limits: &QueryLimits) -> Result<ExportTarget, String> {
+        let (path, force) = self.path_and_force();
+        if path.is_empty() {
+            return Err("No export path given".to_string());
+        }
+        let columns: Vec<String> = self
+            .columns
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from)
+            .collect();
+        Ok(ExportTarget {
+            path: PathBuf::from(path),
+            format: self.format(),
+            columns: Some(columns).filter(|c| !c.is_empty() && self.format().columns),
+            limits: limits.clone(),
+            force,
+        })
+    }
+
+    pub fn bulk_target(&self, limits: &QueryLimits) -> Result<BulkTarget, String> {
+        let (dir, force) = self.path_and_force();
+        if dir.is_empty() {
+            return Err("No export directory given".to_string());
+        }
+        Ok(BulkTarget {
+            dir: PathBuf::from(dir),
+            format: self.format(),
+            limits: limits.clone(),
+            force,
+        })
+    }
+}
+
+/// Streams every document matching the query's filter, sort and projection
+/// to the target in its format, ignoring the page window.
+pub async fn export_documents(
+    client: Client,
+    params: QueryParams,
+    target: ExportTarget,
+    throttle: Throttle,
+    progress: TaskProgress,
+) -> Result<String, AppError> {
+    let (path, format) = (target.path.clone(), target.format);
+    let throttle = Mutex::new(throttle);
+    let report = |done, total| progress.set(done, total);
+    let comment = progress.tag_operations(&client);
+    let written = write_export(&client, &params, target, &throttle, &report, &comment).await?;
+    progress.set(written, Some(written));
+    Ok(format!(
+        "Exported {} document(s) from {}.{} to {} ({})",
+        written,
+        params.database,
+        params.collection,
+        path.display(),
+        format.name
+    ))
+}
+
+/// Exports whole collections of `database` into one file each under the
+/// target directory, a few at a time. The task's progress sums the
+/// collections; one failing does not stop the others, and the summary
+/// names each failure.
+pub async fn export_collections(
+    client: Client,
+    database: String,
+    collections: Vec<String>,
+    target: BulkTarget,
+    throttle: Throttle,
+    progress: TaskProgress,
+) -> Result<String, AppError> {
+    fs::create_dir_all(&target.dir).await?;
+    // the throttle is shared so the profile's rate limit holds for the job
+    let throttle = Mutex::new(throttle);
+    let slots = StdMutex::new(vec![(0u64, Some(0u64)); collections.len()]);
+    let comment = progress.tag_operations(&client);
+
+    let results: Vec<(String, Result<u64, AppError>)> =
+        stream::iter(collections.into_iter().enumerate())
+            .map(|(i, collection)| {
+                let (client, target, throttle, slots, progress, comment) =
+                    (&client, &target, &throttle, &slots, &progress, &comment);
+                let params = QueryParams {
+                    database: database.clone(),
+                    collection: collection.clone(),
+                    filter: None,
+                    skip: 0,
+                    limit: 0,
+                    sort: None,
+                    projection: None,
+                };
+                async move {
+                    let report = move |done, total| {
+                        let mut slots = slots.lock().unwrap();
+                        slots[i] = (done, total);
+                        let (done, total) =
+                            slots.iter().fold((0, Some(0)), |(d, t), (done, total)| {
+                                (d + done, t.zip(*total).map(|(t, total)| t + total))
+                            });
+                        progress.set(done, total);
+                    };
+                    let export = ExportTarget {
+                        path: target.file_for(&collection),
+                        format: target.format,
+                        columns: None,
+                        limits: target.limits.clone(),
+                        force: target.force,
+                    };
+                    let result =
+                        write_export(client, &params, export, throttle, &report, comment).await;
+                    (collection, result)
+                }
+            })
+            .buffer_unordered(BULK_CONCURRENCY)
+            .collect()
+            .await;
+
+    let mut documents = 0;
+    let mut exported = 0;
+    let mut failures = Vec::new();
+    for (collection, result) in &results {
+        match result {
+            Ok(written) => {
+                documents += written;
+                exported += 1;
+            }
+            Err(e) => failures.push(format!("{} ({})", collection, e)),
+        }
+    }
+    failures.sort();
+    if exported == 0 && !failures.is_empty() {
+        return Err(AppError::Query(format!(
+            "Every collection failed: {}",
+            failures.join("; ")
+        )));
+    }
+
+    let mut summary = format!(
+        "Exported {} collection(s), {} document(s) from {} to {}",
+        exported,
+        documents,
+        database,
+        target.dir.display()
+    );
+    if !failures.is_empty() {
+        summary.push_str(&format!(
+            "; {} failed: {}",
+            failures.len(),
+            failures.join("; ")
+        ));
+    }
+    Ok(summary)
+}
+
+// writes the query's documents to the target and returns how many
+async fn write_export(
+    client: &Client,
+    params: &QueryParams,
+    target: ExportTarget,
+    throttle: &Mutex<Throttle>,
+    report: Report<'_>,
+    comment: &Bson,
+) -> Result<u64, AppError> {
+    let coll = client
+        .database(&params.database)
+        .collection::<Document>(&params.collection);
+    let filter = params.filter.clone().unwrap_or_default();
+    // the server cannot count a $near query, so its export has no total
+    let total = if paging::has_near(&filter) {
+        None
+    } else {
+        Some(
+            coll.count_documents(filter)
+                .comment(comment.clone())
+                .await
+                .map_err(|e| AppError::Query(format!("Failed to count documents: {}", e)))?,
+        )
+    };
+    target
+        .limits
+        .check_operation(total, target.force)
+        .map_err(|e| AppError::InvalidInput(format!("{}; end the path with ! to override", e)))?;
+    report(0, total);
+
+    let path = &target.path;
+    let columns = match target.columns {
+        _ if !target.format.columns => Vec::new(),
+        Some(columns) => columns,
+        None => union_of_paths(&coll, params, total, report, comment).await?,
+    };
+
+    let mut cursor = open_cursor(&coll, params, comment).await?;
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).await?;
+    }
+    let mut file = fs::File::create(path).await?;
+    // formats write into this buffer, which goes to the file once a batch
+    let mut out = Vec::new();
+    let mut exporter = target.format.exporter();
+    exporter.begin(&mut out, &columns)?;
+
+    let mut written = 0u64;
+    while let Some(result) = cursor.next().await {
+        let doc = result.map_err(|e| AppError::Query(format!("Error reading documents: {}", e)))?;
+        exporter.document(&mut out, &doc)?;
+        written += 1;
+        if written.is_multiple_of(BATCH_SIZE) {
+            file.write_all(&out).await?;
+            out.clear();
+            report(written, total.map(|total| total.max(written)));
+            throttle.lock().await.pace(BATCH_SIZE).await;
+        }
+    }
+
+    exporter.finish(&mut out)?;
+    file.write_all(&out).await?;
+    file.flush().await?;
+    report(written, Some(written));
+    Ok(written)
+}
+
+async fn open_cursor(
+    coll: &Collection<Document>,
+    params: &QueryParams,
+    comment: &Bson,
+) -> Result<Cursor<Document>, AppError> {
+    coll.find(params.filter.clone().unwrap_or_default())
+        .with_options(
+            FindOptions::builder()
+                .sort(params.sort.clone())
+                .projection(params.projection.clone())
+                .comment(comment.clone())
+                .build(),
+        )
+        .await
+        .map_err(|e| AppError::Query(format!("Failed to find documents: {}", e)))
+}
+
+// a first pass over the whole export set, so fields that only appear in
+// late documents still get a column
+async fn union_of_paths(
+    coll: &Collection<Document>,
+    params: &QueryParams,
+    total: Option<u64>,
+    report: Report<'_>,
+    comment: &Bson,
+) -> Result<Vec<String>, AppError> {
+    let mut cursor = open_cursor(coll, params, comment).await?;
+    let mut seen = HashSet::new();
+    let mut columns = Vec::new();
+    let mut scanned = 0u64;
+    while let Some(result) = cursor.next().await {
+        let doc = result.map_err(|e| AppError::Query(format!("Error reading documents: {}", e)))?;
+        for path in table::flatten_paths(&doc) {
+            if seen.insert(path.clone()) {
+                columns.push(path);
+            }
+        }
+        scanned += 1;
+        if scanned.is_multiple_of(BATCH_SIZE) {
+            report(scanned, total.map(|total| total.max(scanned)));
+        }
+    }
+    // _id leads, as in the table view
+    if let Some(index) = columns.iter().position(|c| c == "_id") {
+        let id = columns.remove(index);
+        columns.insert(0, id);
+    }
+    Ok(columns)
+}
@@ -0,0 +1,169 @@
+use mongodb::Client;
+
+use crate::error::AppError;
+use crate::models::NamespaceFinder;
+
+/// Extra points for a matched character right after the previous one.
+const RUN_BONUS: i64 = 5;
+/// Extra points for a matched character that starts a word.
+const WORD_BONUS: i64 = 8;
+
+/// Every database name followed by every collection as `db.collection`.
+/// Databases whose collections may not be listed show on their own; when
+/// the databases may not be listed either, `known` ones are used.
+pub async fn list_namespaces(client: &Client, known: Vec<String>) -> Result<Vec<String>, AppError> {
+    let databases = match client.list_database_names().await {
+        Ok(databases) => databases,
+        Err(_) if !known.is_empty() => known,
+        Err(e) => return Err(AppError::Query(format!("Failed to list databases: {}", e))),
+    };
+    let mut namespaces = databases.clone();
+    for db in &databases {
+        if let Ok(mut collections) = client.database(db).list_collection_names().await {
+            collections.sort();
+            namespaces.extend(
+                collections
+                    .into_iter()
+                    .map(|coll| format!("{}.{}", db, coll)),
+            );
+        }
+    }
+    Ok(namespaces)
+}
+
+impl NamespaceFinder {
+    /// A finder over `databases` while their collections are listed.
+    pub fn new(databases: Vec<String>) -> Self {
+        let mut finder = Self {
+            namespaces: databases,
+            loading: true,
+            ..Self::default()
+        };
+        finder.refresh();
+        finder
+    }
+
+    pub fn set_namespaces(&mut self, namespaces: Vec<String>) {
+        self.namespaces = namespaces;
+        self.loading = false;
+        self.refresh();
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.input.push(c);
+        self.refresh();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.input.pop();
+        self.refresh();
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + 1) % self.matches.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = self
+                .selected
+                .checked_sub(1)
+                .unwrap_or(self.matches.len() - 1);
+        }
+    }
+
+    /// The selected database and, for a collection, the collection.
+    pub fn selected(&self) -> Option<(&str, Option<&str>)> {
+        let (index, _) = self.matches.get(self.selected)?;
+        let namespace = self.namespaces[*index].as_str();
+        Some(match namespace.split_once('.') {
+            Some((db, coll)) => (db, Some(coll)),
+            None => (namespace, None),
+        })
+    }
+
+    // best matches first; among equals the shorter name, then list order
+    fn refresh(&mut self) {
+        let mut scored: Vec<(i64, usize, Vec<usize>)> = self
+            .namespaces
+            .iter()
+            .enumerate()
+            .filter_map(|(index, namespace)| {
+                score(&self.input, namespace).map(|(score, positions)| (score, index, positions))
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then(self.namespaces[a.1].len().cmp(&self.namespaces[b.1].len()))
+                .then(a.1.cmp(&b.1))
+        });
+        self.matches = scored
+            .into_iter()
+            .map(|(_, index, positions)| (index, positions))
+            .collect();
+        self.selected = 0;
+    }
+}
+
+/// How well `candidate` matches `pattern`: the pattern's characters must
+/// appear in order, not necessarily together, ignoring case and the
+/// pattern's spaces. Returns the score, higher being better, and the
+/// positions of the matched characters, or None when one is missing.
+pub fn score(pattern: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let Some(first) = pattern.first() else {
+        return Some((0, Vec::new()));
+    };
+    // the greedy match from each place the first character appears, as one
+    // further along may run together better
+    (0..lower.len())
+        .filter(|&start| lower[start] == *first)
+        .filter_map(|start| match_from(&pattern, &chars, &lower, start))
+        .max_by_key(|(score, positions)| (*score, std::cmp::Reverse(positions[0])))
+}
+
+fn match_from(
+    pattern: &[char],
+    chars: &[char],
+    lower: &[char],
+    start: usize,
+) -> Option<(i64, Vec<usize>)> {
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut next = start;
+    for c in pattern {
+        let found = (next..lower.len()).find(|&i| lower[i] == *c)?;
+        positions.push(found);
+        next = found + 1;
+    }
+    let mut score = 0;
+    for (n, &i) in positions.iter().enumerate() {
+        score += 1;
+        if n > 0 && positions[n - 1] + 1 == i {
+            score += RUN_BONUS;
+        }
+        if is_word_start(chars, i) {
+            score += WORD_BONUS;
+        }
+    }
+    // characters skipped between the first and the last match
+    let span = positions[positions.len() - 1] - positions[0] + 1;
+    Some((score - (span - positions.len()) as i64, positions))
+}
+
+// the start of the name, after a separator, or a capital in camelCase
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    i == 0
+        || matches!(chars[i - 1], '.' | '_' | '-' | ' ')
+        || (chars[i].is_uppercase() && chars[i - 1].is_lowercase())
+}
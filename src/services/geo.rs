@@ -0,0 +1,175 @@
+use mongodb::bson::{Bson, Document, doc};
+
+use crate::models::IndexInfo;
+use crate::services::bson_ext::number_as_f64;
+
+const EARTH_RADIUS_METERS: f64 = 6_378_100.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoShape {
+    Near {
+        lng: f64,
+        lat: f64,
+        max_meters: Option<f64>,
+    },
+    Circle {
+        lng: f64,
+        lat: f64,
+        radius_meters: f64,
+    },
+    Box {
+        min_lng: f64,
+        min_lat: f64,
+        max_lng: f64,
+        max_lat: f64,
+    },
+}
+
+impl GeoShape {
+    // parses "near <lng> <lat> [maxMeters]", "circle <lng> <lat> <meters>"
+    // or "box <minLng> <minLat> <maxLng> <maxLat>"
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parts = input.split_whitespace();
+        let kind = parts.next().unwrap_or("").to_lowercase();
+        let numbers = parts
+            .map(|p| {
+                p.trim_end_matches(',')
+                    .parse::<f64>()
+                    .map_err(|_| format!("Not a number: {}", p))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+
+        let shape = match (kind.as_str(), numbers.as_slice()) {
+            ("near", [lng, lat]) => GeoShape::Near {
+                lng: *lng,
+                lat: *lat,
+                max_meters: None,
+            },
+            ("near", [lng, lat, max]) => GeoShape::Near {
+                lng: *lng,
+                lat: *lat,
+                max_meters: Some(*max),
+            },
+            ("circle", [lng, lat, radius]) => GeoShape::Circle {
+                lng: *lng,
+                lat: *lat,
+                radius_meters: *radius,
+            },
+            ("box", [min_lng, min_lat, max_lng, max_lat]) => GeoShape::Box {
+                min_lng: min_lng.min(*max_lng),
+                min_lat: min_lat.min(*max_lat),
+                max_lng: min_lng.max(*max_lng),
+                max_lat: min_lat.max(*max_lat),
+            },
+            _ => {
+                return Err(
+                    "Usage: near <lng> <lat> [m] | circle <lng> <lat> <m> | box <lng1> <lat1> <lng2> <lat2>"
+                        .to_string(),
+                );
+            }
+        };
+
+        shape.validate()?;
+        Ok(shape)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let points: Vec<(f64, f64)> = match self {
+            GeoShape::Near { lng, lat, .. } | GeoShape::Circle { lng, lat, .. } => {
+                vec![(*lng, *lat)]
+            }
+            GeoShape::Box {
+                min_lng,
+                min_lat,
+                max_lng,
+                max_lat,
+            } => vec![(*min_lng, *min_lat), (*max_lng, *max_lat)],
+        };
+
+        for (lng, lat) in points {
+            if !(-180.0..=180.0).contains(&lng) || !(-90.0..=90.0).contains(&lat) {
+                return Err(format!("Coordinates out of range: [{}, {}]", lng, lat));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_filter(&self, field: &str) -> Document {
+        let condition = match self {
+            GeoShape::Near {
+                lng,
+                lat,
+                max_meters,
+            } => {
+                let mut near = doc! {
+                    "$geometry": { "type": "Point", "coordinates": [*lng, *lat] },
+                };
+                if let Some(max) = max_meters {
+                    near.insert("$maxDistance", *max);
+                }
+                doc! { "$near": near }
+            }
+            GeoShape::Circle {
+                lng,
+                lat,
+                radius_meters,
+            } => doc! {
+                "$geoWithin": {
+                    "$centerSphere": [[*lng, *lat], radius_meters / EARTH_RADIUS_METERS]
+                }
+            },
+            GeoShape::Box {
+                min_lng,
+                min_lat,
+                max_lng,
+                max_lat,
+            } => doc! {
+                "$geoWithin": {
+                    "$geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[
+                            [*min_lng, *min_lat],
+                            [*max_lng, *min_lat],
+                            [*max_lng, *max_lat],
+                            [*min_lng, *max_lat],
+                            [*min_lng, *min_lat],
+                        ]]
+                    }
+                }
+            },
+        };
+
+        let mut filter = Document::new();
+        filter.insert(field, condition);
+        filter
+    }
+}
+
+/// Returns the first field covered by a 2dsphere index, if any.
+pub fn find_2dsphere_field(indexes: &[IndexInfo]) -> Option<String> {
+    indexes.iter().find_map(|index| {
+        index
+            .keys
+            .iter()
+            .find(|(_, kind)| matches!(kind, Bson::String(s) if s == "2dsphere"))
+            .map(|(field, _)| field.clone())
+    })
+}
+
+/// Reads a GeoJSON point or legacy `[lng, lat]` pair at a dotted field path.
+pub fn extract_point(doc: &Document, field: &str) -> Option<(f64, f64)> {
+    let mut current = doc.get(field.split('.').next()?)?;
+    for part in field.split('.').skip(1) {
+        current = current.as_document()?.get(part)?;
+    }
+
+    let coords = match current {
+        Bson::Document(geo) => geo.get_array("coordinates").ok()?,
+        Bson::Array(arr) => arr,
+        _ => return None,
+    };
+
+    let lng = number_as_f64(coords.first()?)?;
+    let lat = number_as_f64(coords.get(1)?)?;
+    Some((lng, lat))
+}
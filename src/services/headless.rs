@@ -0,0 +1,114 @@
+use std::io::{self, BufWriter, ErrorKind, Write};
+
+use futures::stream::StreamExt;
+use mongodb::{
+    bson::{Bson, Document},
+    options::FindOptions,
+};
+
+use crate::cli::Args;
+use crate::config::{ProfileStore, Settings};
+use crate::error::AppError;
+use crate::services::{aggregation, connection::ConnectionService, paging, shell_syntax};
+
+/// Runs the query or pipeline of `--exec` and prints each document as a
+/// line of relaxed extended JSON, the way `mongoexport` writes NDJSON.
+/// `--uri` may name a profile, whose tunnels and credentials are used as
+/// in the app; a read-only profile or setting refuses `$out` and `$merge`.
+/// Past the `max_documents` cap, or with no bound at all, it needs
+/// `--force`. Returns how many documents were printed.
+pub async fn exec(args: Args) -> Result<u64, AppError> {
+    let (Some(connection), Some(db), Some(coll)) = (args.uri, args.db, args.coll) else {
+        return Err(AppError::InvalidInput(
+            "--exec needs --uri, --db and --coll".to_string(),
+        ));
+    };
+    let profile = ProfileStore::load()
+        .profiles
+        .into_iter()
+        .find(|p| p.name == connection);
+    let uri = profile.as_ref().map_or(connection, |p| p.uri.clone());
+    let settings = Settings::load();
+    let read_only = settings.read_only || profile.as_ref().is_some_and(|p| p.read_only);
+    let check_cap = |documents| {
+        settings
+            .limits
+            .check_operation(documents, args.force)
+            .map_err(|e| AppError::InvalidInput(format!("{}; pass --force to override", e)))
+    };
+
+    let mut service = ConnectionService::new();
+    service.connect(&uri, profile.as_ref()).await?;
+    let client = service
+        .get_client()
+        .cloned()
+        .ok_or_else(|| AppError::Connection("Not connected".to_string()))?;
+    let collection = client.database(&db).collection::<Document>(&coll);
+
+    let mut cursor = match &args.pipeline {
+        Some(text) => {
+            let stages = aggregation::import_pipeline(text).map_err(AppError::InvalidInput)?;
+            let mut pipeline = aggregation::build_pipeline(&stages, stages.len())
+                .map_err(AppError::InvalidInput)?;
+            let writes = aggregation::write_stage(&pipeline).is_some();
+            if read_only && let Some(operator) = aggregation::write_stage(&pipeline) {
+                return Err(AppError::InvalidInput(format!(
+                    "{} writes, and this connection is read-only",
+                    operator
+                )));
+            }
+            // a pipeline's output cannot be counted up front, so only
+            // --limit bounds it
+            check_cap(args.limit)?;
+            if let Some(limit) = args.limit {
+                // `$out` and `$merge` have to stay the last stage
+                let at = pipeline.len() - usize::from(writes);
+                pipeline.insert(at, mongodb::bson::doc! { "$limit": limit as i64 });
+            }
+            collection.aggregate(pipeline).await?
+        }
+        None => {
+            let query = shell_syntax::parse_query(args.query.as_deref().unwrap_or_default())
+                .map_err(AppError::InvalidInput)?;
+            let filter = query.filter.unwrap_or_default();
+            let documents = match args.limit {
+                // no need to count what is allowed either way
+                limit if args.force => limit,
+                Some(limit) if limit <= settings.limits.max_documents => Some(limit),
+                // the server cannot count a $near query
+                limit if paging::has_near(&filter) => limit,
+                limit => {
+                    let count = collection.count_documents(filter.clone()).await?;
+                    Some(limit.map_or(count, |limit| limit.min(count)))
+                }
+            };
+            check_cap(documents)?;
+            collection
+                .find(filter)
+                .with_options(
+                    FindOptions::builder()
+                        .limit(args.limit.map(|n| n as i64))
+                        .sort(query.sort)
+                        .projection(query.projection)
+                        .build(),
+                )
+                .await?
+        }
+    };
+
+    let mut out = BufWriter::new(io::stdout());
+    let mut printed = 0;
+    while let Some(document) = cursor.next().await {
+        let line = Bson::Document(document?).into_relaxed_extjson();
+        match writeln!(out, "{}", line) {
+            Ok(()) => printed += 1,
+            // the reader of a pipe such as `| head` has had enough
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => return Ok(printed),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    match out.flush() {
+        Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(printed),
+    }
+}
@@ -0,0 +1,227 @@
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::models::{HealthItem, HealthStatus};
+use crate::services::bson_ext::number_as_f64;
+
+pub struct HealthService {
+    client: Client,
+}
+
+impl HealthService {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Runs every check; failures to gather data become yellow items rather
+    /// than errors so a restricted user still gets a partial report.
+    pub async fn run(&self) -> Vec<HealthItem> {
+        vec![
+            self.check_replica_set().await,
+            self.check_connections().await,
+            self.check_disk_usage().await,
+            self.check_startup_warnings().await,
+        ]
+    }
+
+    async fn admin_command(&self, command: Document) -> Result<Document, String> {
+        self.client
+            .database("admin")
+            .run_command(command)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn check_replica_set(&self) -> HealthItem {
+        let name = "Replica set".to_string();
+        match self.admin_command(doc! { "replSetGetStatus": 1 }).await {
+            Ok(status) => {
+                let set_name = status.get_str("set").unwrap_or("unknown");
+                let members = status.get_array("members").cloned().unwrap_or_default();
+
+                let mut primary = None;
+                let mut unhealthy = Vec::new();
+                let mut degraded = Vec::new();
+                for member in members.iter().filter_map(Bson::as_document) {
+                    let host = member.get_str("name").unwrap_or("?").to_string();
+                    let state = member.get_str("stateStr").unwrap_or("UNKNOWN");
+                    let health = member.get("health").and_then(number_as_f64).unwrap_or(0.0);
+                    if health < 1.0 {
+                        unhealthy.push(host);
+                    } else if state == "PRIMARY" {
+                        primary = Some(host);
+                    } else if !matches!(state, "SECONDARY" | "ARBITER") {
+                        degraded.push(format!("{} ({})", host, state));
+                    }
+                }
+
+                let (status, detail) = if !unhealthy.is_empty() || primary.is_none() {
+                    (
+                        HealthStatus::Red,
+                        format!(
+                            "{}: primary {}, unreachable: {}",
+                            set_name,
+                            primary.as_deref().unwrap_or("missing"),
+                            if unhealthy.is_empty() {
+                                "none".to_string()
+                            } else {
+                                unhealthy.join(", ")
+                            }
+                        ),
+                    )
+                } else if !degraded.is_empty() {
+                    (
+                        HealthStatus::Yellow,
+                        format!(
+                            "{}: members not in steady state: {}",
+                            set_name,
+                            degraded.join(", ")
+                        ),
+                    )
+                } else {
+                    (
+                        HealthStatus::Green,
+                        format!(
+                            "{}: {} members, primary {}",
+                            set_name,
+                            members.len(),
+                            primary.unwrap_or_default()
+                        ),
+                    )
+                };
+                HealthItem {
+                    name,
+                    status,
+                    detail,
+                }
+            }
+            Err(e) if e.contains("--replSet") || e.contains("NoReplicationEnabled") => HealthItem {
+                name,
+                status: HealthStatus::Green,
+                detail: "Standalone server (no replica set)".to_string(),
+            },
+            Err(e) => unavailable(name, e),
+        }
+    }
+
+    async fn check_connections(&self) -> HealthItem {
+        let name = "Connections".to_string();
+        match self
+            .admin_command(doc! { "serverStatus": 1, "repl": 0, "metrics": 0, "locks": 0 })
+            .await
+        {
+            Ok(status) => {
+                let connections = status.get_document("connections").ok();
+                let current = connections
+                    .and_then(|c| c.get("current"))
+                    .and_then(number_as_f64)
+                    .unwrap_or(0.0);
+                let available = connections
+                    .and_then(|c| c.get("available"))
+                    .and_then(number_as_f64)
+                    .unwrap_or(0.0);
+                let limit = current + available;
+                let ratio = if limit > 0.0 { current / limit } else { 0.0 };
+
+                HealthItem {
+                    name,
+                    status: threshold(ratio, 0.75, 0.9),
+                    detail: format!("{} of {} in use ({:.0}%)", current, limit, ratio * 100.0),
+                }
+            }
+            Err(e) => unavailable(name, e),
+        }
+    }
+
+    async fn check_disk_usage(&self) -> HealthItem {
+        let name = "Disk usage".to_string();
+        match self.admin_command(doc! { "dbStats": 1 }).await {
+            Ok(stats) => {
+                let used = stats.get("fsUsedSize").and_then(number_as_f64);
+                let total = stats.get("fsTotalSize").and_then(number_as_f64);
+                match (used, total) {
+                    (Some(used), Some(total)) if total > 0.0 => {
+                        let ratio = used / total;
+                        HealthItem {
+                            name,
+                            status: threshold(ratio, 0.8, 0.9),
+                            detail: format!(
+                                "{:.1} GiB of {:.1} GiB used ({:.0}%)",
+                                used / GIB,
+                                total / GIB,
+                                ratio * 100.0
+                            ),
+                        }
+                    }
+                    _ => HealthItem {
+                        name,
+                        status: HealthStatus::Yellow,
+                        detail: "Filesystem size not reported by dbStats".to_string(),
+                    },
+                }
+            }
+            Err(e) => unavailable(name, e),
+        }
+    }
+
+    async fn check_startup_warnings(&self) -> HealthItem {
+        let name = "Startup warnings".to_string();
+        match self
+            .admin_command(doc! { "getLog": "startupWarnings" })
+            .await
+        {
+            Ok(log) => {
+                let lines = log.get_array("log").cloned().unwrap_or_default();
+                if lines.is_empty() {
+                    HealthItem {
+                        name,
+                        status: HealthStatus::Green,
+                        detail: "None".to_string(),
+                    }
+                } else {
+                    let first = lines
+                        .first()
+                        .and_then(Bson::as_str)
+                        .map(summarize_log_line)
+                        .unwrap_or_default();
+                    HealthItem {
+                        name,
+                        status: HealthStatus::Yellow,
+                        detail: format!("{} warning(s), first: {}", lines.len(), first),
+                    }
+                }
+            }
+            Err(e) => unavailable(name, e),
+        }
+    }
+}
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+fn threshold(ratio: f64, warn: f64, critical: f64) -> HealthStatus {
+    if ratio >= critical {
+        HealthStatus::Red
+    } else if ratio >= warn {
+        HealthStatus::Yellow
+    } else {
+        HealthStatus::Green
+    }
+}
+
+fn unavailable(name: String, error: String) -> HealthItem {
+    HealthItem {
+        name,
+        status: HealthStatus::Yellow,
+        detail: format!("Unavailable: {}", error),
+    }
+}
+
+// startup warnings are structured JSON log lines on 4.4+
+fn summarize_log_line(line: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(line)
+        .ok()
+        .and_then(|v| v.get("msg").and_then(|m| m.as_str()).map(str::to_string))
+        .unwrap_or_else(|| line.to_string())
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+};
+
+use crate::error::AppError;
+use crate::models::{CollectionHeat, OpCounters};
+use crate::services::bson_ext::number_as_f64;
+
+/// `top` counters that are reads; the write ones are inserts, updates and
+/// removes.
+const TOP_READS: [&str; 2] = ["queries", "getmore"];
+const TOP_WRITES: [&str; 3] = ["insert", "update", "remove"];
+
+/// Operations per second from which a collection is warm and hot.
+const WARM_RATE: f64 = 10.0;
+const HOT_RATE: f64 = 100.0;
+
+/// Cumulative read and write counts per collection of `db`. `top` covers
+/// every collection in one command but only runs on a mongod; elsewhere,
+/// such as through mongos, each collection's `$collStats` latency stats are
+/// read instead.
+pub async fn op_counters(
+    client: &Client,
+    db: &str,
+    collections: &[String],
+) -> Result<BTreeMap<String, OpCounters>, AppError> {
+    match top(client, db).await {
+        Ok(counters) => Ok(counters),
+        Err(_) => latency_counters(client, db, collections).await,
+    }
+}
+
+async fn top(client: &Client, db: &str) -> Result<BTreeMap<String, OpCounters>, AppError> {
+    let reply = client
+        .database("admin")
+        .run_command(doc! { "top": 1 })
+        .await
+        .map_err(|e| AppError::Query(format!("top failed: {}", e)))?;
+    let prefix = format!("{}.", db);
+    let count = |entry: &Document, keys: &[&str]| -> u64 {
+        keys.iter()
+            .filter_map(|key| entry.get_document(key).ok())
+            .filter_map(|counter| counter.get("count").and_then(number_as_f64))
+            .sum::<f64>() as u64
+    };
+    Ok(reply
+        .get_document("totals")
+        .map_err(|e| AppError::Query(format!("Unexpected top reply: {}", e)))?
+        .iter()
+        .filter_map(|(namespace, entry)| {
+            let collection = namespace.strip_prefix(&prefix)?;
+            let entry = entry.as_document()?;
+            Some((
+                collection.to_string(),
+                OpCounters {
+                    reads: count(entry, &TOP_READS),
+                    writes: count(entry, &TOP_WRITES),
+                },
+            ))
+        })
+        .collect())
+}
+
+async fn latency_counters(
+    client: &Client,
+    db: &str,
+    collections: &[String],
+) -> Result<BTreeMap<String, OpCounters>, AppError> {
+    let mut counters = BTreeMap::new();
+    for collection in collections {
+        let reports: Vec<Document> = client
+            .database(db)
+            .collection::<Document>(collection)
+            .aggregate(vec![doc! { "$collStats": { "latencyStats": {} } }])
+            .await
+            .map_err(|e| AppError::Query(format!("collStats failed: {}", e)))?
+            .try_collect()
+            .await?;
+        let ops = |kind: &str| -> u64 {
+            reports
+                .iter()
+                .filter_map(|report| report.get_document("latencyStats").ok())
+                .filter_map(|stats| stats.get_document(kind).ok())
+                .filter_map(|stats| stats.get("ops").and_then(number_as_f64))
+                .sum::<f64>() as u64
+        };
+        counters.insert(
+            collection.clone(),
+            OpCounters {
+                reads: ops("reads"),
+                writes: ops("writes"),
+            },
+        );
+    }
+    Ok(counters)
+}
+
+/// Rates between two samples taken `secs` apart. Counters that went down,
+/// as after a restart, count as idle.
+pub fn rates(
+    before: &BTreeMap<String, OpCounters>,
+    after: &BTreeMap<String, OpCounters>,
+    secs: f64,
+) -> BTreeMap<String, CollectionHeat> {
+    after
+        .iter()
+        .filter_map(|(collection, now)| {
+            let then = before.get(collection)?;
+            Some((
+                collection.clone(),
+                CollectionHeat {
+                    reads: now.reads.saturating_sub(then.reads) as f64 / secs,
+                    writes: now.writes.saturating_sub(then.writes) as f64 / secs,
+                },
+            ))
+        })
+        .collect()
+}
+
+impl CollectionHeat {
+    pub fn total(&self) -> f64 {
+        self.reads + self.writes
+    }
+
+    /// 0 when idle, 1 when in use, 2 when warm and 3 when hot.
+    pub fn level(&self) -> u8 {
+        match self.total() {
+            rate if rate >= HOT_RATE => 3,
+            rate if rate >= WARM_RATE => 2,
+            rate if rate > 0.0 => 1,
+            _ => 0,
+        }
+    }
+}
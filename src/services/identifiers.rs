@@ -0,0 +1,233 @@
+use mongodb::bson::{Binary, Bson, Uuid, UuidRepresentation, oid::ObjectId, spec::BinarySubtype};
+
+use crate::models::{Conversion, UtilityPanel};
+
+/// The legacy subtype 3 byte orders, as the old drivers wrote them.
+const LEGACY: [(&str, UuidRepresentation); 3] = [
+    ("Java legacy", UuidRepresentation::JavaLegacy),
+    ("C# legacy", UuidRepresentation::CSharpLegacy),
+    ("Python legacy", UuidRepresentation::PythonLegacy),
+];
+
+impl UtilityPanel {
+    pub fn new() -> Self {
+        Self {
+            conversions: generate(),
+            ..Self::default()
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.input.push(c);
+        self.refresh();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.input.pop();
+        self.refresh();
+    }
+
+    /// Clears the input and generates new identifiers.
+    pub fn regenerate(&mut self) {
+        self.input.clear();
+        self.refresh();
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.conversions.is_empty() {
+            self.selected = (self.selected + 1) % self.conversions.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.conversions.is_empty() {
+            self.selected = self
+                .selected
+                .checked_sub(1)
+                .unwrap_or(self.conversions.len() - 1);
+        }
+    }
+
+    pub fn selected_value(&self) -> Option<&str> {
+        self.conversions
+            .get(self.selected)
+            .map(|conversion| conversion.value.as_str())
+    }
+
+    fn refresh(&mut self) {
+        self.selected = 0;
+        match convert(&self.input) {
+            Ok(conversions) => {
+                self.conversions = conversions;
+                self.error = None;
+            }
+            Err(e) => {
+                self.conversions.clear();
+                self.error = Some(e);
+            }
+        }
+    }
+}
+
+/// A new ObjectId and a new random UUID, in the forms they are pasted as.
+pub fn generate() -> Vec<Conversion> {
+    let mut conversions = object_id(ObjectId::new());
+    conversions.extend(uuid(Uuid::new()));
+    conversions
+}
+
+/// Every form of the identifier in `input`: a hex ObjectId, a UUID string,
+/// `BinData(3|4, "...")`, or any of these in shell or extended JSON syntax.
+/// Empty input generates new ones.
+pub fn convert(input: &str) -> Result<Vec<Conversion>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(generate());
+    }
+    if input.starts_with('{') {
+        return from_extended_json(input);
+    }
+    if let Some(binary) = bindata(input) {
+        return from_binary(&binary?);
+    }
+    let value = unwrap(input);
+    if value.len() == 24
+        && let Ok(oid) = ObjectId::parse_str(value)
+    {
+        return Ok(object_id(oid));
+    }
+    if let Ok(parsed) = Uuid::parse_str(value) {
+        return Ok(uuid(parsed));
+    }
+    Err("Not an ObjectId, UUID or BinData value".to_string())
+}
+
+fn object_id(oid: ObjectId) -> Vec<Conversion> {
+    let created = oid.timestamp();
+    vec![
+        conversion("ObjectId", oid.to_hex()),
+        conversion("Shell", format!("ObjectId(\"{}\")", oid.to_hex())),
+        conversion(
+            "Extended JSON",
+            format!("{{\"$oid\": \"{}\"}}", oid.to_hex()),
+        ),
+        conversion(
+            "Created",
+            created
+                .try_to_rfc3339_string()
+                .unwrap_or_else(|_| created.to_string()),
+        ),
+        conversion(
+            "Created (Unix seconds)",
+            (created.timestamp_millis() / 1000).to_string(),
+        ),
+    ]
+}
+
+fn uuid(uuid: Uuid) -> Vec<Conversion> {
+    let standard = Binary::from_uuid(uuid);
+    let mut conversions = vec![
+        conversion("UUID", uuid.to_string()),
+        conversion("Shell", format!("UUID(\"{}\")", uuid)),
+        conversion("Subtype 4", shell_bindata(&standard)),
+        conversion("Extended JSON", extended_json(&standard)),
+    ];
+    for (name, representation) in LEGACY {
+        let legacy = Binary::from_uuid_with_representation(uuid, representation);
+        conversions.push(conversion(
+            &format!("Subtype 3, {}", name),
+            shell_bindata(&legacy),
+        ));
+    }
+    conversions
+}
+
+// a subtype 3 payload does not say which byte order wrote it, so each
+// reading is offered
+fn from_binary(binary: &Binary) -> Result<Vec<Conversion>, String> {
+    match binary.subtype {
+        BinarySubtype::Uuid => binary
+            .to_uuid()
+            .map(uuid)
+            .map_err(|e| format!("Not a UUID: {}", e)),
+        BinarySubtype::UuidOld => {
+            let readings: Vec<Conversion> = LEGACY
+                .iter()
+                .filter_map(|(name, representation)| {
+                    let read = binary.to_uuid_with_representation(*representation).ok()?;
+                    Some(conversion(&format!("As {}", name), read.to_string()))
+                })
+                .collect();
+            if readings.is_empty() {
+                Err("Subtype 3 values hold 16 bytes".to_string())
+            } else {
+                Ok(readings)
+            }
+        }
+        _ => Err("Only subtype 3 and 4 values hold UUIDs".to_string()),
+    }
+}
+
+fn from_extended_json(input: &str) -> Result<Vec<Conversion>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e))?;
+    match Bson::try_from(value) {
+        Ok(Bson::ObjectId(oid)) => Ok(object_id(oid)),
+        Ok(Bson::Binary(binary)) => from_binary(&binary),
+        Ok(_) => Err("Expected $oid, $uuid or $binary".to_string()),
+        Err(e) => Err(format!("Invalid extended JSON: {}", e)),
+    }
+}
+
+// `BinData(4, "...")`; None for anything else
+fn bindata(input: &str) -> Option<Result<Binary, String>> {
+    let args = input.strip_prefix("BinData(")?.strip_suffix(')')?;
+    let (subtype, data) = args.split_once(',')?;
+    let subtype: u8 = match subtype.trim().parse() {
+        Ok(subtype) => subtype,
+        Err(_) => return Some(Err(format!("Invalid subtype: {}", subtype.trim()))),
+    };
+    let data = data.trim().trim_matches(['"', '\'']);
+    Some(
+        Binary::from_base64(data, BinarySubtype::from(subtype))
+            .map_err(|e| format!("Invalid base64: {}", e)),
+    )
+}
+
+// the value inside `ObjectId("...")` or `UUID("...")`
+fn unwrap(input: &str) -> &str {
+    let inner = ["ObjectId(", "UUID("]
+        .iter()
+        .find_map(|prefix| input.strip_prefix(prefix))
+        .and_then(|rest| rest.strip_suffix(')'))
+        .unwrap_or(input);
+    inner.trim().trim_matches(['"', '\''])
+}
+
+fn shell_bindata(binary: &Binary) -> String {
+    format!(
+        "BinData({}, \"{}\")",
+        u8::from(binary.subtype),
+        base64(binary)
+    )
+}
+
+fn extended_json(binary: &Binary) -> String {
+    Bson::Binary(binary.clone())
+        .into_canonical_extjson()
+        .to_string()
+}
+
+fn base64(binary: &Binary) -> String {
+    Bson::Binary(binary.clone()).into_canonical_extjson()["$binary"]["base64"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn conversion(label: &str, value: String) -> Conversion {
+    Conversion {
+        label: label.to_string(),
+        value,
+    }
+}
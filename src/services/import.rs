@@ -0,0 +1,284 @@
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, Read},
+    path::{MAIN_SEPARATOR, Path},
+};
+
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+    error::ErrorKind,
+    options::{InsertManyOptions, ReplaceOptions},
+};
+
+use crate::error::AppError;
+use crate::models::{ImportForm, ImportMode};
+use crate::services::tasks::TaskProgress;
+use crate::services::throttle::Throttle;
+
+/// Records read and sent to the server at a time.
+const BATCH_SIZE: usize = 500;
+pub const PREVIEW_SIZE: usize = 5;
+/// Failure messages quoted in the summary; the rest are only counted.
+const QUOTED_FAILURES: usize = 3;
+
+impl ImportMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            ImportMode::Insert => "insert",
+            ImportMode::Upsert => "upsert by _id",
+        }
+    }
+}
+
+impl ImportForm {
+    pub fn push_char(&mut self, c: char) {
+        self.path.push(c);
+        self.completions.clear();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.path.pop();
+        self.completions.clear();
+    }
+
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            ImportMode::Insert => ImportMode::Upsert,
+            ImportMode::Upsert => ImportMode::Insert,
+        };
+    }
+
+    /// Completes the path as far as the matching directory entries agree
+    /// and lists them when more than one is left.
+    pub fn complete(&mut self) {
+        let (dir, prefix) = match self.path.rfind(MAIN_SEPARATOR) {
+            Some(i) => (&self.path[..=i], &self.path[i + 1..]),
+            None => ("", self.path.as_str()),
+        };
+        let listing = if dir.is_empty() { "." } else { dir };
+        let Ok(entries) = fs::read_dir(listing) else {
+            self.completions.clear();
+            return;
+        };
+
+        let mut matches: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let mut name = entry.file_name().to_string_lossy().into_owned();
+                if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.'))
+                {
+                    return None;
+                }
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    name.push(MAIN_SEPARATOR);
+                }
+                Some(name)
+            })
+            .collect();
+        matches.sort();
+
+        if let Some(common) = common_prefix(&matches) {
+            self.path = format!("{}{}", dir, common);
+        }
+        self.completions = if matches.len() > 1 {
+            matches
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// Whether the preview shown is of the file currently in the path field.
+    pub fn is_previewed(&self) -> bool {
+        self.previewed.as_deref() == Some(self.path.trim())
+    }
+
+    pub fn load_preview(&mut self) {
+        let path = self.path.trim().to_string();
+        match read_documents(Path::new(&path)) {
+            Ok(records) => {
+                let (preview, errors): (Vec<_>, Vec<_>) = records
+                    .take(PREVIEW_SIZE)
+                    .partition(|record| record.is_ok());
+                self.preview = preview.into_iter().flatten().collect();
+                self.preview_error = errors.into_iter().find_map(Result::err);
+            }
+            Err(e) => {
+                self.preview.clear();
+                self.preview_error = Some(e.to_string());
+            }
+        }
+        self.previewed = Some(path);
+    }
+}
+
+fn common_prefix(names: &[String]) -> Option<String> {
+    let mut prefix = names.first()?.as_str();
+    for name in &names[1..] {
+        while !name.starts_with(prefix) {
+            let cut = prefix.char_indices().last().map_or(0, |(i, _)| i);
+            prefix = &prefix[..cut];
+        }
+    }
+    Some(prefix.to_string())
+}
+
+/// Documents from a file holding either a JSON array or one document per
+/// line; values use extended JSON, so `{"$oid": ...}` comes back as an
+/// ObjectId. Lines are read lazily, an array is parsed up front.
+pub fn read_documents(
+    path: &Path,
+) -> Result<Box<dyn Iterator<Item = Result<Document, String>> + Send>, AppError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let starts_with_array = loop {
+        let buffer = reader.fill_buf()?;
+        match buffer.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(i) => break buffer[i] == b'[',
+            None if buffer.is_empty() => break false,
+            None => {
+                let len = buffer.len();
+                reader.consume(len);
+            }
+        }
+    };
+
+    if starts_with_array {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let values: Vec<serde_json::Value> = serde_json::from_str(&text)?;
+        let documents = values
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| to_document(value).map_err(|e| format!("element {}: {}", i + 1, e)));
+        return Ok(Box::new(documents.collect::<Vec<_>>().into_iter()));
+    }
+
+    let documents = reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|(i, line)| {
+            let line = line.map_err(|e| e.to_string())?;
+            serde_json::from_str(&line)
+                .map_err(|e| e.to_string())
+                .and_then(to_document)
+                .map_err(|e| format!("line {}: {}", i + 1, e))
+        });
+    Ok(Box::new(documents))
+}
+
+fn to_document(value: serde_json::Value) -> Result<Document, String> {
+    match Bson::try_from(value).map_err(|e| e.to_string())? {
+        Bson::Document(doc) => Ok(doc),
+        other => Err(format!("expected a document, found {}", other)),
+    }
+}
+
+/// Streams the file into the collection in batches. Documents that fail to
+/// parse or are rejected by the server (validation, duplicate keys) are
+/// counted and summarized instead of stopping the import.
+pub async fn import_documents(
+    client: Client,
+    db: String,
+    collection: String,
+    path: String,
+    mode: ImportMode,
+    mut throttle: Throttle,
+    progress: TaskProgress,
+) -> Result<String, AppError> {
+    let coll = client.database(&db).collection::<Document>(&collection);
+    let mut records = read_documents(Path::new(&path))?;
+    let mut written = 0usize;
+    let mut failures: Vec<String> = Vec::new();
+    let mut processed = 0u64;
+
+    loop {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        // position of each batched document in the file, for error messages
+        let mut numbers = Vec::with_capacity(BATCH_SIZE);
+        let mut read = 0u64;
+        for record in records.by_ref().take(BATCH_SIZE) {
+            read += 1;
+            match record {
+                Ok(doc) => {
+                    batch.push(doc);
+                    numbers.push(processed + read);
+                }
+                Err(e) => failures.push(e),
+            }
+        }
+        if read == 0 {
+            break;
+        }
+        processed += read;
+        let sent = batch.len();
+
+        match mode {
+            ImportMode::Insert if batch.is_empty() => {}
+            ImportMode::Insert => {
+                let options = InsertManyOptions::builder().ordered(false).build();
+                match coll.insert_many(batch).with_options(options).await {
+                    Ok(result) => written += result.inserted_ids.len(),
+                    Err(e) => {
+                        match *e.kind {
+                            ErrorKind::InsertMany(ref error) => {
+                                let errors = error.write_errors.as_deref().unwrap_or_default();
+                                written += sent - errors.len();
+                                failures.extend(errors.iter().map(|w| {
+                                    format!("document {}: {}", numbers[w.index], w.message)
+                                }));
+                            }
+                            _ => {
+                                return Err(AppError::Query(format!("Import failed: {}", e)));
+                            }
+                        }
+                    }
+                }
+            }
+            ImportMode::Upsert => {
+                for (doc, number) in batch.into_iter().zip(numbers) {
+                    let result = match doc.get("_id").cloned() {
+                        Some(id) => coll
+                            .replace_one(doc! { "_id": id }, doc)
+                            .with_options(ReplaceOptions::builder().upsert(true).build())
+                            .await
+                            .map(|_| ()),
+                        None => coll.insert_one(doc).await.map(|_| ()),
+                    };
+                    match result {
+                        Ok(()) => written += 1,
+                        Err(e) if matches!(*e.kind, ErrorKind::Write(_)) => {
+                            failures.push(format!("document {}: {}", number, e));
+                        }
+                        Err(e) => {
+                            return Err(AppError::Query(format!("Import failed: {}", e)));
+                        }
+                    }
+                }
+            }
+        }
+        progress.set(processed, None);
+        throttle.pace(sent as u64).await;
+    }
+
+    let mut summary = format!(
+        "Imported {} document(s) into {}.{} ({})",
+        written,
+        db,
+        collection,
+        mode.name()
+    );
+    if !failures.is_empty() {
+        summary.push_str(&format!(
+            ", {} failed: {}",
+            failures.len(),
+            failures
+                .iter()
+                .take(QUOTED_FAILURES)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ")
+        ));
+    }
+    Ok(summary)
+}
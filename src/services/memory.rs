@@ -0,0 +1,26 @@
+use mongodb::bson::Document;
+
+/// Documents from this size up are flagged in the document list.
+pub const LARGE_DOCUMENT_BYTES: u64 = 1024 * 1024;
+/// Half of the 16 MiB BSON document limit; growth past this is worth a look.
+pub const NEAR_LIMIT_DOCUMENT_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Size of a single document as encoded BSON, the same figure `$bsonSize` reports.
+pub fn document_size(doc: &Document) -> u64 {
+    mongodb::bson::to_vec(doc)
+        .map(|b| b.len() as u64)
+        .unwrap_or(0)
+}
+
+/// Expected size of a page of `page_size` documents of `avg_size` bytes.
+pub fn estimate_page_bytes(avg_size: u64, page_size: usize) -> u64 {
+    avg_size.saturating_mul(page_size as u64)
+}
+
+/// How many documents of `avg_size` bytes fit into `budget` bytes, at least one.
+pub fn documents_within_budget(avg_size: u64, budget: u64) -> usize {
+    if avg_size == 0 {
+        return usize::MAX;
+    }
+    ((budget / avg_size) as usize).max(1)
+}
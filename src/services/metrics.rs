@@ -0,0 +1,92 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use mongodb::event::command::CommandEvent;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetworkSnapshot {
+    pub result_bytes: u64,
+    pub result_batches: u64,
+    pub result_get_mores: u64,
+    pub session_bytes: u64,
+}
+
+/// Byte and round-trip counters fed by the driver's command monitoring.
+///
+/// The "result" counters describe the most recent `find` cursor: they reset
+/// whenever a new `find` starts and accumulate over its `getMore`s.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkStats {
+    inner: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    result_bytes: AtomicU64,
+    result_batches: AtomicU64,
+    result_get_mores: AtomicU64,
+    session_bytes: AtomicU64,
+}
+
+impl NetworkStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, event: &CommandEvent) {
+        let counters = &self.inner;
+        match event {
+            CommandEvent::Started(started) if started.command_name == "find" => {
+                counters.result_bytes.store(0, Ordering::Relaxed);
+                counters.result_batches.store(0, Ordering::Relaxed);
+                counters.result_get_mores.store(0, Ordering::Relaxed);
+            }
+            CommandEvent::Succeeded(succeeded) => {
+                let size = mongodb::bson::to_vec(&succeeded.reply)
+                    .map(|bytes| bytes.len() as u64)
+                    .unwrap_or(0);
+                counters.session_bytes.fetch_add(size, Ordering::Relaxed);
+
+                match succeeded.command_name.as_str() {
+                    "find" => {
+                        counters.result_bytes.fetch_add(size, Ordering::Relaxed);
+                        counters.result_batches.fetch_add(1, Ordering::Relaxed);
+                    }
+                    "getMore" => {
+                        counters.result_bytes.fetch_add(size, Ordering::Relaxed);
+                        counters.result_batches.fetch_add(1, Ordering::Relaxed);
+                        counters.result_get_mores.fetch_add(1, Ordering::Relaxed);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn snapshot(&self) -> NetworkSnapshot {
+        NetworkSnapshot {
+            result_bytes: self.inner.result_bytes.load(Ordering::Relaxed),
+            result_batches: self.inner.result_batches.load(Ordering::Relaxed),
+            result_get_mores: self.inner.result_get_mores.load(Ordering::Relaxed),
+            session_bytes: self.inner.session_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
@@ -1,2 +1,54 @@
 pub mod connection;
-pub mod query;
\ No newline at end of file
+pub mod query;
+pub mod geo;
+pub mod tail;
+pub mod schema;
+pub mod report;
+pub mod health;
+pub mod bson_ext;
+pub mod explain;
+pub mod metrics;
+pub mod aws;
+pub mod atlas;
+pub mod port_forward;
+pub mod compare;
+pub mod permissions;
+pub mod tasks;
+pub mod throttle;
+pub mod memory;
+pub mod events;
+pub mod replset;
+pub mod shard_key;
+pub mod sharding;
+pub mod editor;
+pub mod search;
+pub mod aggregation;
+pub mod collections;
+pub mod projection;
+pub mod profiles;
+pub mod table;
+pub mod shell_syntax;
+pub mod timings;
+pub mod benchmark;
+pub mod export;
+pub mod import;
+pub mod compatibility;
+pub mod setup;
+pub mod crash;
+pub mod paging;
+pub mod replay;
+pub mod encryption;
+pub mod reference;
+pub mod identifiers;
+pub mod placeholders;
+pub mod operations;
+pub mod dry_run;
+pub mod users;
+pub mod stats;
+pub mod bulk_update;
+pub mod heat;
+pub mod distinct;
+pub mod query_builder;
+pub mod clipboard;
+pub mod finder;
+pub mod headless;
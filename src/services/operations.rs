@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::error::AppError;
+use crate::models::OperationInfo;
+use crate::services::bson_ext::number_as_f64;
+
+/// The running operations of every user, longest running first. Idle
+/// connections and the `$currentOp` itself are left out.
+pub async fn current(client: &Client) -> Result<Vec<OperationInfo>, AppError> {
+    let pipeline = vec![
+        doc! { "$currentOp": { "allUsers": true, "idleConnections": false } },
+        doc! { "$match": { "active": true, "op": { "$ne": "none" } } },
+        doc! { "$sort": { "microsecs_running": -1 } },
+    ];
+    let documents: Vec<Document> = client
+        .database("admin")
+        .aggregate(pipeline)
+        .await
+        .map_err(|e| AppError::Query(format!("$currentOp failed: {}", e)))?
+        .try_collect()
+        .await?;
+    Ok(documents
+        .iter()
+        .filter(|document| !is_current_op(document))
+        .filter_map(operation)
+        .collect())
+}
+
+/// Asks the server to kill an operation; it stops at its next interrupt
+/// point, so it may show for a moment longer.
+pub async fn kill(client: &Client, opid: Bson) -> Result<(), AppError> {
+    client
+        .database("admin")
+        .run_command(doc! { "killOp": 1, "op": opid })
+        .await
+        .map_err(|e| AppError::Query(format!("killOp failed: {}", e)))?;
+    Ok(())
+}
+
+/// Kills this user's operations that carry `comment`, including the
+/// `getMore`s of cursors opened with it, and returns how many there were.
+pub async fn kill_tagged(client: &Client, comment: &str) -> Result<usize, AppError> {
+    let pipeline = vec![
+        doc! { "$currentOp": { "allUsers": false, "idleConnections": false } },
+        doc! { "$match": { "$or": [
+            { "command.comment": comment },
+            { "cursor.originatingCommand.comment": comment },
+        ] } },
+    ];
+    let documents: Vec<Document> = client
+        .database("admin")
+        .aggregate(pipeline)
+        .await
+        .map_err(|e| AppError::Query(format!("$currentOp failed: {}", e)))?
+        .try_collect()
+        .await?;
+    for opid in documents.iter().filter_map(|document| document.get("opid")) {
+        kill(client, opid.clone()).await?;
+    }
+    Ok(documents.len())
+}
+
+// this very listing, an aggregate starting with `$currentOp`
+fn is_current_op(document: &Document) -> bool {
+    document
+        .get_document("command")
+        .and_then(|command| command.get_array("pipeline"))
+        .ok()
+        .and_then(|pipeline| pipeline.first())
+        .and_then(Bson::as_document)
+        .is_some_and(|stage| stage.contains_key("$currentOp"))
+}
+
+impl OperationInfo {
+    /// The opid as typed to confirm a kill.
+    pub fn opid_label(&self) -> String {
+        match &self.opid {
+            Bson::String(opid) => opid.clone(),
+            opid => opid.to_string(),
+        }
+    }
+}
+
+fn operation(document: &Document) -> Option<OperationInfo> {
+    let opid = document.get("opid")?.clone();
+    let text = |key: &str| document.get_str(key).unwrap_or_default().to_string();
+    let micros = document
+        .get("microsecs_running")
+        .and_then(number_as_f64)
+        .unwrap_or(0.0);
+    Some(OperationInfo {
+        opid,
+        op: text("op"),
+        namespace: text("ns"),
+        running: Duration::from_micros(micros.max(0.0) as u64),
+        // mongos reports the client under a different key
+        client: document
+            .get_str("client")
+            .or_else(|_| document.get_str("client_s"))
+            .unwrap_or_default()
+            .to_string(),
+        app_name: text("appName"),
+        plan_summary: text("planSummary"),
+        waiting_for_lock: document.get_bool("waitingForLock").unwrap_or(false),
+        command: document
+            .get_document("command")
+            .map(|command| {
+                Bson::Document(command.clone())
+                    .into_relaxed_extjson()
+                    .to_string()
+            })
+            .unwrap_or_default(),
+    })
+}
@@ -0,0 +1,110 @@
+use mongodb::bson::{Bson, Document, doc};
+
+use crate::models::PageCursor;
+use crate::services::bson_ext::number_as_f64;
+use crate::services::table::lookup;
+
+/// The sort pages are read in: the query's sort with `_id` appended, so
+/// every document has one place in the order and a page can start right
+/// after the last one seen.
+pub fn with_tiebreak(sort: Option<&Document>) -> Document {
+    let mut sort = sort.cloned().unwrap_or_default();
+    if !sort.contains_key("_id") {
+        sort.insert("_id", 1);
+    }
+    sort
+}
+
+/// Whether `filter` uses `$near` or `$nearSphere`, whose results come
+/// nearest first in an order of their own that a sort would replace.
+pub fn has_near(filter: &Document) -> bool {
+    filter.iter().any(|(key, value)| {
+        key == "$near"
+            || key == "$nearSphere"
+            || match value {
+                Bson::Document(inner) => has_near(inner),
+                Bson::Array(items) => items
+                    .iter()
+                    .any(|item| matches!(item, Bson::Document(inner) if has_near(inner))),
+                _ => false,
+            }
+    })
+}
+
+/// The sort a page of `filter` is read in: [`with_tiebreak`], except for
+/// `$near` queries, which keep the server's nearest-first order.
+pub fn page_sort(filter: Option<&Document>, sort: Option<&Document>) -> Option<Document> {
+    if filter.is_some_and(has_near) {
+        sort.cloned()
+    } else {
+        Some(with_tiebreak(sort))
+    }
+}
+
+/// Where the page after one ending with `last` starts. Keyset paging is
+/// only used when pages are sorted by `_id` alone: MongoDB compares values
+/// of one type at a time, so past the last value of another key it would
+/// miss nulls, missing fields and values of other types. Other sorts, a
+/// `$near` filter, and a null or array `_id` skip the `skip` documents
+/// before the page instead.
+pub fn cursor_after(
+    last: &Document,
+    filter: Option<&Document>,
+    sort: Option<&Document>,
+    skip: u64,
+) -> PageCursor {
+    let sort = with_tiebreak(sort);
+    if sort.len() != 1 || filter.is_some_and(has_near) {
+        return PageCursor::Skip(skip);
+    }
+    let mut values = Document::new();
+    for (key, direction) in &sort {
+        let value = match lookup(last, key) {
+            Some(Bson::Null | Bson::Undefined | Bson::Array(_)) | None => None,
+            Some(value) => Some(value.clone()),
+        };
+        match (value, number_as_f64(direction)) {
+            (Some(value), Some(_)) => {
+                values.insert(key.clone(), value);
+            }
+            _ => return PageCursor::Skip(skip),
+        }
+    }
+    PageCursor::After(values)
+}
+
+/// `filter` narrowed to the documents sorting after `values`: one branch
+/// per sort key, equal on the keys before it and past it on that key.
+pub fn after_filter(
+    filter: Option<Document>,
+    sort: Option<&Document>,
+    values: &Document,
+) -> Document {
+    let sort = with_tiebreak(sort);
+    let mut branches = Vec::new();
+    let mut equal = Document::new();
+    for (key, direction) in &sort {
+        let Some(value) = values.get(key) else {
+            break;
+        };
+        let op = if number_as_f64(direction).unwrap_or(1.0) < 0.0 {
+            "$lt"
+        } else {
+            "$gt"
+        };
+        let mut branch = equal.clone();
+        branch.insert(key.clone(), doc! { op: value.clone() });
+        branches.push(branch);
+        equal.insert(key.clone(), value.clone());
+    }
+
+    let after = if branches.len() == 1 {
+        branches.remove(0)
+    } else {
+        doc! { "$or": branches }
+    };
+    match filter {
+        Some(filter) if !filter.is_empty() => doc! { "$and": [filter, after] },
+        _ => after,
+    }
+}
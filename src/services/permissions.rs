@@ -0,0 +1,203 @@
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+};
+
+use crate::{
+    error::AppError,
+    models::{PermissionCheck, Privilege, UserPrivileges},
+};
+
+/// Actions checked for a collection, with what in the app depends on them.
+pub const PROBED_ACTIONS: [(&str, &str); 11] = [
+    ("find", "browse, filter, tail, export, schema report"),
+    ("insert", "insert and import documents"),
+    ("update", "edit documents"),
+    ("remove", "delete documents"),
+    ("listIndexes", "index list, geo field detection"),
+    ("createIndex", "create indexes"),
+    ("dropIndex", "drop indexes"),
+    ("collStats", "collection statistics"),
+    ("changeStream", "live tail (falls back to polling)"),
+    ("dropCollection", "drop the collection"),
+    ("renameCollectionSameDB", "rename the collection"),
+];
+
+/// Deployment-wide actions checked from the database list.
+pub const PROBED_CLUSTER_ACTIONS: [(&str, &str); 4] = [
+    (
+        "listDatabases",
+        "list every database, not only those you hold privileges on",
+    ),
+    ("serverStatus", "health check"),
+    ("replSetGetStatus", "replica set view"),
+    (
+        "getParameter",
+        "feature compatibility version on the about screen",
+    ),
+];
+
+/// Actions that never change data, allowed even on read-only profiles.
+const READ_ACTIONS: [&str; 8] = [
+    "find",
+    "listIndexes",
+    "listCollections",
+    "collStats",
+    "dbStats",
+    "changeStream",
+    "viewUser",
+    "viewRole",
+];
+
+pub fn is_read_action(action: &str) -> bool {
+    READ_ACTIONS.contains(&action)
+}
+
+/// Reads the privileges granted to the authenticated users of this
+/// connection; nothing is attempted against the data itself.
+pub async fn fetch(client: &Client) -> Result<UserPrivileges, AppError> {
+    let status = client
+        .database("admin")
+        .run_command(doc! { "connectionStatus": 1, "showPrivileges": true })
+        .await
+        .map_err(|e| AppError::Query(format!("connectionStatus failed: {}", e)))?;
+
+    let auth_info = status.get_document("authInfo").cloned().unwrap_or_default();
+
+    let users = auth_info
+        .get_array("authenticatedUsers")
+        .map(|users| {
+            users
+                .iter()
+                .filter_map(|u| u.as_document())
+                .map(|u| {
+                    format!(
+                        "{}@{}",
+                        u.get_str("user").unwrap_or("?"),
+                        u.get_str("db").unwrap_or("?")
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let privileges = auth_info
+        .get_array("authenticatedUserPrivileges")
+        .map(|privileges| {
+            privileges
+                .iter()
+                .filter_map(|p| p.as_document())
+                .map(parse_privilege)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(UserPrivileges { users, privileges })
+}
+
+fn parse_privilege(privilege: &Document) -> Privilege {
+    let resource = privilege
+        .get_document("resource")
+        .cloned()
+        .unwrap_or_default();
+    Privilege {
+        db: resource.get_str("db").ok().map(str::to_string),
+        collection: resource.get_str("collection").ok().map(str::to_string),
+        cluster: resource.get_bool("cluster").unwrap_or(false),
+        any_resource: resource.get_bool("anyResource").unwrap_or(false),
+        actions: privilege
+            .get_array("actions")
+            .map(|actions| {
+                actions
+                    .iter()
+                    .filter_map(|a| a.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+pub fn probe(privileges: &UserPrivileges, db: &str, collection: &str) -> Vec<PermissionCheck> {
+    PROBED_ACTIONS
+        .iter()
+        .map(|(action, purpose)| PermissionCheck {
+            action,
+            purpose,
+            allowed: privileges.allows(db, collection, action),
+        })
+        .collect()
+}
+
+pub fn probe_cluster(privileges: &UserPrivileges) -> Vec<PermissionCheck> {
+    PROBED_CLUSTER_ACTIONS
+        .iter()
+        .map(|(action, purpose)| PermissionCheck {
+            action,
+            purpose,
+            allowed: privileges.allows_cluster(action),
+        })
+        .collect()
+}
+
+impl UserPrivileges {
+    /// Without an authenticated user the server either runs without access
+    /// control or will reject everything; only the former is usable, so
+    /// treat it as unrestricted.
+    pub fn enforced(&self) -> bool {
+        !self.users.is_empty()
+    }
+
+    pub fn allows_cluster(&self, action: &str) -> bool {
+        if !self.enforced() {
+            return true;
+        }
+        self.privileges
+            .iter()
+            .any(|p| (p.cluster || p.any_resource) && p.actions.iter().any(|a| a == action))
+    }
+
+    /// Databases named in a privilege, i.e. those a user without
+    /// `listDatabases` still sees.
+    pub fn named_databases(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .privileges
+            .iter()
+            .filter_map(|p| p.db.clone())
+            .filter(|db| !db.is_empty())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    pub fn allows(&self, db: &str, collection: &str, action: &str) -> bool {
+        if !self.enforced() {
+            return true;
+        }
+        self.privileges
+            .iter()
+            .any(|p| p.covers(db, collection) && p.actions.iter().any(|a| a == action))
+    }
+}
+
+impl Privilege {
+    // an empty db or collection in a resource means "any"; system collections
+    // are only matched by name
+    fn covers(&self, db: &str, collection: &str) -> bool {
+        if self.any_resource {
+            return true;
+        }
+        if self.cluster {
+            return false;
+        }
+        let db_matches = match self.db.as_deref() {
+            Some("") | None => true,
+            Some(name) => name == db,
+        };
+        let collection_matches = match self.collection.as_deref() {
+            Some("") | None => !collection.starts_with("system."),
+            Some(name) => name == collection,
+        };
+        db_matches && collection_matches
+    }
+}
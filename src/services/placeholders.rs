@@ -0,0 +1,161 @@
+use mongodb::bson::oid::ObjectId;
+
+/// How a value typed for a placeholder is written into the query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueKind {
+    /// An ObjectId, number, date, boolean or string, whichever the value
+    /// looks like.
+    Auto,
+    ObjectId,
+    Date,
+    Number,
+    String,
+}
+
+impl ValueKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "oid" | "objectid" => Some(ValueKind::ObjectId),
+            "date" => Some(ValueKind::Date),
+            "number" | "num" => Some(ValueKind::Number),
+            "string" | "str" => Some(ValueKind::String),
+            _ => None,
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            ValueKind::Auto => "ObjectId, date, number or text",
+            ValueKind::ObjectId => "ObjectId",
+            ValueKind::Date => "date",
+            ValueKind::Number => "number",
+            ValueKind::String => "text",
+        }
+    }
+}
+
+/// A `{{name}}` or `{{name:type}}` in a saved query, standing for a whole
+/// value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placeholder {
+    pub name: String,
+    pub kind: ValueKind,
+}
+
+/// The placeholders of `query` in order of first use; a name used twice is
+/// asked for once.
+pub fn find(query: &str) -> Vec<Placeholder> {
+    let mut found: Vec<Placeholder> = Vec::new();
+    for_each(query, |inner| {
+        if let Some(placeholder) = parse(inner)
+            && !found.iter().any(|p| p.name == placeholder.name)
+        {
+            found.push(placeholder);
+        }
+    });
+    found
+}
+
+/// `query` with each placeholder replaced by its value in shell syntax,
+/// ready for the query parser. `value` returns what was typed for a name.
+pub fn fill<'a>(query: &str, value: impl Fn(&str) -> Option<&'a str>) -> Result<String, String> {
+    let mut out = String::with_capacity(query.len());
+    let mut rest = query;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        let inner = &rest[start + 2..end];
+        let Some(placeholder) = parse(inner) else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        };
+        let typed = value(&placeholder.name)
+            .ok_or_else(|| format!("No value for {{{{{}}}}}", placeholder.name))?;
+        out.push_str(&rest[..start]);
+        out.push_str(
+            &literal(typed, placeholder.kind)
+                .map_err(|e| format!("{{{{{}}}}}: {}", placeholder.name, e))?,
+        );
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// calls `f` with the text between each `{{` and `}}`
+fn for_each(query: &str, mut f: impl FnMut(&str)) {
+    let mut rest = query;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            return;
+        };
+        f(&rest[start + 2..end]);
+        rest = &rest[start + 2..];
+    }
+}
+
+// a name of word characters, optionally followed by `:type`; nested braces
+// such as `{{a: 1}}` are documents, not placeholders
+fn parse(inner: &str) -> Option<Placeholder> {
+    let (name, kind) = match inner.split_once(':') {
+        Some((name, kind)) => (name.trim(), ValueKind::parse(kind.trim())?),
+        None => (inner.trim(), ValueKind::Auto),
+    };
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    valid.then(|| Placeholder {
+        name: name.to_string(),
+        kind,
+    })
+}
+
+/// The shell syntax for a typed value, coerced to `kind`.
+pub fn literal(value: &str, kind: ValueKind) -> Result<String, String> {
+    let value = value.trim();
+    match kind {
+        ValueKind::ObjectId => ObjectId::parse_str(value)
+            .map(|oid| format!("ObjectId(\"{}\")", oid.to_hex()))
+            .map_err(|_| format!("'{}' is not a 24-digit hex ObjectId", value)),
+        ValueKind::Date if looks_like_date(value) => Ok(format!("ISODate(\"{}\")", value)),
+        ValueKind::Date => Err(format!("'{}' is not a date like 2024-05-01", value)),
+        ValueKind::Number => value
+            .parse::<f64>()
+            .map(|_| value.to_string())
+            .map_err(|_| format!("'{}' is not a number", value)),
+        ValueKind::String => Ok(quoted(value)),
+        ValueKind::Auto => Ok(match value {
+            "true" | "false" | "null" => value.to_string(),
+            // already in shell syntax
+            _ if value.starts_with(['"', '\'', '{', '[', '/'])
+                || value.ends_with(')') && value.contains('(') =>
+            {
+                value.to_string()
+            }
+            _ if value.len() == 24 && ObjectId::parse_str(value).is_ok() => {
+                format!("ObjectId(\"{}\")", value)
+            }
+            _ if value.parse::<f64>().is_ok() => value.to_string(),
+            _ if looks_like_date(value) => format!("ISODate(\"{}\")", value),
+            _ => quoted(value),
+        }),
+    }
+}
+
+// `YYYY-MM-DD`, with or without a time after it
+fn looks_like_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10].iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+        && (bytes.len() == 10 || bytes[10] == b'T')
+}
+
+fn quoted(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
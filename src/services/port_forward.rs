@@ -0,0 +1,106 @@
+use std::{process::Stdio, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::{Child, Command},
+    time::timeout,
+};
+
+use crate::{config::PortForwardConfig, error::AppError};
+
+const TUNNEL_READY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A running `kubectl port-forward`; the process is killed when this is dropped.
+pub struct PortForward {
+    // only held, so the process lives as long as the tunnel
+    _child: Child,
+    local_port: u16,
+}
+
+impl PortForward {
+    pub async fn start(config: &PortForwardConfig) -> Result<Self, AppError> {
+        let mut command = Command::new("kubectl");
+        if let Some(context) = &config.context {
+            command.arg("--context").arg(context);
+        }
+        if let Some(namespace) = &config.namespace {
+            command.arg("--namespace").arg(namespace);
+        }
+        // an empty local port lets kubectl pick a free one
+        let local = config.local_port.map(|p| p.to_string()).unwrap_or_default();
+        command
+            .arg("port-forward")
+            .arg(&config.target)
+            .arg(format!("{}:{}", local, config.remote_port))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| AppError::Connection(format!("Failed to run kubectl: {}", e)))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| AppError::Connection("kubectl produced no output".into()))?;
+        let mut lines = BufReader::new(stdout).lines();
+
+        let ready = timeout(TUNNEL_READY_TIMEOUT, async {
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Some(port) = parse_forwarding_line(&line) {
+                    return Some(port);
+                }
+            }
+            None
+        })
+        .await;
+
+        let local_port = match ready {
+            Ok(Some(port)) => port,
+            Ok(None) => {
+                let stderr = match child.stderr.take() {
+                    Some(stderr) => {
+                        let mut lines = BufReader::new(stderr).lines();
+                        let mut out = Vec::new();
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            out.push(line);
+                        }
+                        out.join(" ")
+                    }
+                    None => String::new(),
+                };
+                return Err(AppError::Connection(format!(
+                    "kubectl port-forward exited: {}",
+                    stderr.trim()
+                )));
+            }
+            Err(_) => {
+                return Err(AppError::Connection(format!(
+                    "Timed out waiting for port-forward to {}",
+                    config.target
+                )));
+            }
+        };
+
+        // keep draining stdout so kubectl never blocks on a full pipe
+        tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+
+        Ok(Self {
+            _child: child,
+            local_port,
+        })
+    }
+
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+}
+
+// kubectl prints "Forwarding from 127.0.0.1:54321 -> 27017" once the tunnel is up
+fn parse_forwarding_line(line: &str) -> Option<u16> {
+    let rest = line.strip_prefix("Forwarding from ")?;
+    let (local, _) = rest.split_once(" -> ")?;
+    local.rsplit(':').next()?.parse().ok()
+}
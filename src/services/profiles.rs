@@ -0,0 +1,133 @@
+use crate::config::ConnectionProfile;
+use crate::models::{ProfileField, ProfileForm};
+
+impl ProfileField {
+    pub const ALL: [ProfileField; 4] = [
+        ProfileField::Name,
+        ProfileField::Uri,
+        ProfileField::DefaultDatabase,
+        ProfileField::ReadOnly,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProfileField::Name => "Name",
+            ProfileField::Uri => "URI",
+            ProfileField::DefaultDatabase => "Default database",
+            ProfileField::ReadOnly => "Read-only",
+        }
+    }
+}
+
+impl ProfileForm {
+    pub fn edit(index: usize, profile: &ConnectionProfile) -> Self {
+        Self {
+            editing: Some(index),
+            name: profile.name.clone(),
+            uri: profile.uri.clone(),
+            default_database: profile.default_database.clone().unwrap_or_default(),
+            read_only: profile.read_only,
+            focus: 0,
+        }
+    }
+
+    pub fn focused_field(&self) -> ProfileField {
+        ProfileField::ALL[self.focus.min(ProfileField::ALL.len() - 1)]
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = (self.focus + 1) % ProfileField::ALL.len();
+    }
+
+    pub fn prev_field(&mut self) {
+        let count = ProfileField::ALL.len();
+        self.focus = (self.focus + count - 1) % count;
+    }
+
+    pub fn value(&self, field: ProfileField) -> String {
+        match field {
+            ProfileField::Name => self.name.clone(),
+            ProfileField::Uri => self.uri.clone(),
+            ProfileField::DefaultDatabase => self.default_database.clone(),
+            ProfileField::ReadOnly => if self.read_only { "yes" } else { "no" }.to_string(),
+        }
+    }
+
+    fn text_mut(&mut self, field: ProfileField) -> Option<&mut String> {
+        match field {
+            ProfileField::Name => Some(&mut self.name),
+            ProfileField::Uri => Some(&mut self.uri),
+            ProfileField::DefaultDatabase => Some(&mut self.default_database),
+            ProfileField::ReadOnly => None,
+        }
+    }
+
+    /// Types into the focused field; space flips the read-only switch.
+    pub fn push_char(&mut self, c: char) {
+        match self.text_mut(self.focused_field()) {
+            Some(text) => text.push(c),
+            None if c == ' ' => self.toggle_read_only(),
+            None => {}
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if let Some(text) = self.text_mut(self.focused_field()) {
+            text.pop();
+        }
+    }
+
+    pub fn toggle_read_only(&mut self) {
+        if self.focused_field() == ProfileField::ReadOnly {
+            self.read_only = !self.read_only;
+        }
+    }
+
+    /// Applies the form to `base`, keeping settings the form does not show
+    /// (proxy, tunnels, fallbacks), or builds a new profile.
+    pub fn to_profile(
+        &self,
+        base: Option<ConnectionProfile>,
+        profiles: &[ConnectionProfile],
+    ) -> Result<ConnectionProfile, String> {
+        let name = self.name.trim();
+        let uri = self.uri.trim();
+        if name.is_empty() {
+            return Err("Profile name is required".to_string());
+        }
+        if !uri.starts_with("mongodb://") && !uri.starts_with("mongodb+srv://") {
+            return Err("URI must start with mongodb:// or mongodb+srv://".to_string());
+        }
+        let taken = profiles
+            .iter()
+            .enumerate()
+            .any(|(i, p)| p.name == name && Some(i) != self.editing);
+        if taken {
+            return Err(format!("A profile named {} already exists", name));
+        }
+
+        let default_database = Some(self.default_database.trim())
+            .filter(|db| !db.is_empty())
+            .map(str::to_string);
+        Ok(match base {
+            Some(profile) => ConnectionProfile {
+                name: name.to_string(),
+                uri: uri.to_string(),
+                default_database,
+                read_only: self.read_only,
+                ..profile
+            },
+            None => ConnectionProfile {
+                name: name.to_string(),
+                uri: uri.to_string(),
+                proxy: None,
+                aws_iam: None,
+                port_forward: None,
+                fallback_uris: Vec::new(),
+                rate_limit: None,
+                default_database,
+                read_only: self.read_only,
+            },
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use mongodb::bson::{Bson, Document};
+
+use crate::models::ProjectionPicker;
+use crate::services::schema;
+
+/// Field paths seen in `documents`, in the order the schema analysis sorts them.
+pub fn sampled_fields(documents: &[Document]) -> Vec<String> {
+    schema::analyze("", "", documents)
+        .fields
+        .into_iter()
+        .map(|field| field.path)
+        .collect()
+}
+
+impl ProjectionPicker {
+    /// Starts from `current` so reopening the picker shows the active projection.
+    pub fn new(fields: Vec<String>, current: Option<&Document>) -> Self {
+        let exclude = current
+            .and_then(|p| p.iter().find(|(key, _)| key.as_str() != "_id"))
+            .is_some_and(|(_, value)| is_exclusion(value));
+        let checked = fields
+            .iter()
+            .map(|field| {
+                current
+                    .and_then(|p| p.get(field))
+                    .is_some_and(|value| is_exclusion(value) == exclude)
+            })
+            .collect();
+        Self {
+            fields,
+            checked,
+            exclude,
+            selected: 0,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + 1) % self.fields.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = self
+                .selected
+                .checked_sub(1)
+                .unwrap_or(self.fields.len() - 1);
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(checked) = self.checked.get_mut(self.selected) {
+            *checked = !*checked;
+        }
+    }
+
+    pub fn toggle_mode(&mut self) {
+        self.exclude = !self.exclude;
+    }
+
+    pub fn clear(&mut self) {
+        self.checked.iter_mut().for_each(|c| *c = false);
+    }
+
+    /// The projection for the checked fields, or `None` when nothing is checked.
+    /// Paths under a checked parent are dropped; the server rejects the overlap.
+    pub fn to_projection(&self) -> Option<Document> {
+        let value = if self.exclude { 0 } else { 1 };
+        let checked: Vec<&String> = self
+            .fields
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, checked)| **checked)
+            .map(|(field, _)| field)
+            .collect();
+
+        let mut projection = Document::new();
+        for field in &checked {
+            let covered = checked
+                .iter()
+                .any(|parent| field.starts_with(&format!("{}.", parent)));
+            if !covered {
+                projection.insert(field.as_str(), value);
+            }
+        }
+        (!projection.is_empty()).then_some(projection)
+    }
+}
+
+fn is_exclusion(value: &Bson) -> bool {
+    matches!(
+        value,
+        Bson::Int32(0) | Bson::Int64(0) | Bson::Boolean(false)
+    ) || matches!(value, Bson::Double(v) if *v == 0.0)
+}
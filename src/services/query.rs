@@ -1,8 +1,13 @@
-use mongodb::{Client, bson::Document};
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+    options::FindOptions,
+};
 
 use crate::{
     error::AppError,
-    models::{CollectionInfo, DatabaseInfo},
+    models::{CollectionInfo, DatabaseInfo, IndexInfo, QueryParams},
+    services::{bson_ext::number_as_f64, encryption, paging},
 };
 
 pub struct QueryService {
@@ -32,7 +37,7 @@ impl QueryService {
                 .unwrap_or(0);
             let db_info = DatabaseInfo {
                 name: db.name,
-                size_on_disk: db.size_on_disk as u64,
+                size_on_disk: db.size_on_disk,
                 collection_count,
                 empty: db.empty,
             };
@@ -43,48 +48,165 @@ impl QueryService {
     }
 
     pub async fn list_collections(&self, db: &str) -> Result<Vec<CollectionInfo>, AppError> {
+        use futures::stream::StreamExt;
+
         let database = self.client.database(db);
-        let collections = database
-            .list_collection_names()
+        let mut specs = database
+            .list_collections()
             .await
             .map_err(|e| AppError::Query(format!("Failed to list collections: {}", e)))?;
 
+        let encrypted = encryption::declared_by_collection(&database).await;
         let mut coll_infos = Vec::new();
-        for coll_name in collections {
-            let collection = database.collection::<Document>(&coll_name);
+        while let Some(spec) = specs.next().await {
+            let spec =
+                spec.map_err(|e| AppError::Query(format!("Failed to list collections: {}", e)))?;
+            let collection = database.collection::<Document>(&spec.name);
 
             let doc_count = collection.estimated_document_count().await.unwrap_or(0);
 
             let indexes = collection.list_index_names().await.unwrap_or_default();
 
+            let options = &spec.options;
             let coll_info = CollectionInfo {
-                name: coll_name,
+                name: spec.name.clone(),
                 document_count: doc_count,
                 size: 0,
                 indexes,
-                capped: false,
+                capped: options.capped.unwrap_or(false),
+                clustered: options.clustered_index.is_some(),
+                pre_and_post_images: options
+                    .change_stream_pre_and_post_images
+                    .as_ref()
+                    .is_some_and(|images| images.enabled),
+                encrypted_fields: encrypted.get(&spec.name).cloned().unwrap_or_default(),
             };
             coll_infos.push(coll_info);
         }
         Ok(coll_infos)
     }
 
+    pub async fn list_indexes(
+        &self,
+        db: &str,
+        collection: &str,
+    ) -> Result<Vec<IndexInfo>, AppError> {
+        let coll = self.client.database(db).collection::<Document>(collection);
+
+        let mut cursor = coll
+            .list_indexes()
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to list indexes: {}", e)))?;
+
+        let mut indexes = Vec::new();
+        use futures::stream::StreamExt;
+        while let Some(result) = cursor.next().await {
+            let model =
+                result.map_err(|e| AppError::Query(format!("Error reading index: {}", e)))?;
+            let options = model.options.unwrap_or_default();
+            indexes.push(IndexInfo {
+                name: options.name.unwrap_or_default(),
+                keys: model.keys,
+                unique: options.unique.unwrap_or(false),
+            });
+        }
+
+        Ok(indexes)
+    }
+
     pub async fn find_documents(
         &self,
         db: &str,
         collection: &str,
         filter: Option<Document>,
+        sort: Option<Document>,
         skip: u64,
         limit: i64,
     ) -> Result<Vec<Document>, AppError> {
-        let coll = self.client.database(db).collection::<Document>(collection);
+        self.find(QueryParams {
+            database: db.to_string(),
+            collection: collection.to_string(),
+            filter,
+            skip,
+            limit,
+            sort,
+            projection: None,
+        })
+        .await
+    }
+
+    /// Documents with any field but `_id` matching `text` as a
+    /// case-insensitive regex, for the live filter. The fields are those of
+    /// a sample document; with none to match, the collection reads unfiltered.
+    pub async fn find_in_any_field(
+        &self,
+        db: &str,
+        collection: &str,
+        text: &str,
+        sort: Option<Document>,
+        limit: i64,
+    ) -> Result<Vec<Document>, AppError> {
+        let sample = self
+            .find_documents(db, collection, None, None, 0, 1)
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to analyze fields: {}", e)))?;
+        let Some(sample) = sample.first() else {
+            return Ok(Vec::new());
+        };
+        let conditions: Vec<Document> = sample
+            .keys()
+            .filter(|key| *key != "_id")
+            .map(|key| doc! { key: { "$regex": text, "$options": "i" } })
+            .collect();
+        let filter = (!conditions.is_empty()).then(|| doc! { "$or": conditions });
+        self.find_documents(db, collection, filter, sort, 0, limit)
+            .await
+    }
+
+    /// The sort that lists the newest documents first. ObjectIds start
+    /// with their creation time, so that is `_id` descending when the ids
+    /// are ObjectIds, and otherwise the first date field of a document.
+    pub async fn newest_first_sort(&self, db: &str, collection: &str) -> Document {
+        let field = self
+            .client
+            .database(db)
+            .collection::<Document>(collection)
+            .find_one(doc! {})
+            .await
+            .ok()
+            .flatten()
+            .filter(|document| !matches!(document.get("_id"), Some(Bson::ObjectId(_))))
+            .and_then(|document| {
+                document
+                    .iter()
+                    .find(|(_, value)| matches!(value, Bson::DateTime(_)))
+                    .map(|(key, _)| key.clone())
+            });
+        doc! { field.unwrap_or_else(|| "_id".to_string()): -1 }
+    }
 
-        let filter_doc = filter.unwrap_or_else(|| Document::new());
+    /// Runs a `find` with the sort and projection from `params` applied,
+    /// ties in the sort broken by `_id` so pages keep a fixed order, unless
+    /// a `$near` filter orders them by distance.
+    pub async fn find(&self, params: QueryParams) -> Result<Vec<Document>, AppError> {
+        let coll = self
+            .client
+            .database(&params.database)
+            .collection::<Document>(&params.collection);
+
+        let sort = paging::page_sort(params.filter.as_ref(), params.sort.as_ref());
+        let filter_doc = params.filter.unwrap_or_default();
 
         let mut cursor = coll
             .find(filter_doc)
-            .skip(skip)
-            .limit(limit)
+            .with_options(
+                FindOptions::builder()
+                    .skip(params.skip)
+                    .limit(params.limit)
+                    .sort(sort)
+                    .projection(params.projection)
+                    .build(),
+            )
             .await
             .map_err(|e| AppError::Query(format!("Failed to find documents: {}", e)))?;
 
@@ -94,10 +216,7 @@ impl QueryService {
             match result {
                 Ok(doc) => documents.push(doc),
                 Err(e) => {
-                    return Err(AppError::Query(format!(
-                        "Error reading aggregation result: {}",
-                        e
-                    )));
+                    return Err(AppError::Query(format!("Error reading documents: {}", e)));
                 }
             }
         }
@@ -112,7 +231,7 @@ impl QueryService {
     ) -> Result<u64, AppError> {
         let coll = self.client.database(db).collection::<Document>(collection);
 
-        let filter_doc = filter.unwrap_or_else(|| Document::new());
+        let filter_doc = filter.unwrap_or_default();
 
         let count = coll
             .count_documents(filter_doc)
@@ -122,6 +241,136 @@ impl QueryService {
         Ok(count)
     }
 
+    /// Counts the documents `filter` matches, tagging the count with
+    /// `comment` so it can be found on the server.
+    pub async fn count_matching(
+        &self,
+        db: &str,
+        collection: &str,
+        filter: Document,
+        comment: Bson,
+    ) -> Result<u64, AppError> {
+        self.client
+            .database(db)
+            .collection::<Document>(collection)
+            .count_documents(filter)
+            .comment(comment)
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to count documents: {}", e)))
+    }
+
+    /// The collection's document count from its metadata, which is quick
+    /// but may be off after an unclean shutdown.
+    pub async fn estimated_count(&self, db: &str, collection: &str) -> Result<u64, AppError> {
+        self.client
+            .database(db)
+            .collection::<Document>(collection)
+            .estimated_document_count()
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to count documents: {}", e)))
+    }
+
+    /// Replaces the stored document that has the same `_id` as `doc`.
+    pub async fn replace_document(
+        &self,
+        db: &str,
+        collection: &str,
+        doc: Document,
+    ) -> Result<(), AppError> {
+        let coll = self.client.database(db).collection::<Document>(collection);
+        let id = doc
+            .get("_id")
+            .cloned()
+            .ok_or_else(|| AppError::Query("Document has no _id".to_string()))?;
+
+        let result = coll
+            .replace_one(mongodb::bson::doc! { "_id": id }, doc)
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to replace document: {}", e)))?;
+
+        if result.matched_count == 0 {
+            return Err(AppError::Query(
+                "Document no longer exists; it may have been deleted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn explain_find(
+        &self,
+        db: &str,
+        collection: &str,
+        filter: Document,
+    ) -> Result<Document, AppError> {
+        self.client
+            .database(db)
+            .run_command(mongodb::bson::doc! {
+                "explain": { "find": collection, "filter": filter },
+                "verbosity": "executionStats",
+            })
+            .await
+            .map_err(|e| AppError::Query(format!("Explain failed: {}", e)))
+    }
+
+    /// Explains the find a page load would run, with per-stage execution
+    /// stats.
+    pub async fn explain_query(&self, params: &QueryParams) -> Result<Document, AppError> {
+        let mut find = mongodb::bson::doc! {
+            "find": params.collection.as_str(),
+            "filter": params.filter.clone().unwrap_or_default(),
+            "skip": params.skip as i64,
+            "limit": params.limit,
+            "sort": paging::page_sort(params.filter.as_ref(), params.sort.as_ref())
+                .unwrap_or_default(),
+        };
+        if let Some(projection) = &params.projection {
+            find.insert("projection", projection.clone());
+        }
+        self.client
+            .database(&params.database)
+            .run_command(mongodb::bson::doc! {
+                "explain": find,
+                "verbosity": "executionStats",
+            })
+            .await
+            .map_err(|e| AppError::Query(format!("Explain failed: {}", e)))
+    }
+
+    /// Average document size from `$collStats`, if the server reports one.
+    pub async fn avg_document_size(
+        &self,
+        db: &str,
+        collection: &str,
+    ) -> Result<Option<u64>, AppError> {
+        let stats = self
+            .aggregate(
+                db,
+                collection,
+                vec![mongodb::bson::doc! { "$collStats": { "storageStats": {} } }],
+            )
+            .await?;
+        Ok(stats
+            .first()
+            .and_then(|s| s.get_document("storageStats").ok())
+            .and_then(|s| s.get("avgObjSize"))
+            .and_then(number_as_f64)
+            .map(|size| size as u64))
+    }
+
+    pub async fn sample_documents(
+        &self,
+        db: &str,
+        collection: &str,
+        size: i64,
+    ) -> Result<Vec<Document>, AppError> {
+        self.aggregate(
+            db,
+            collection,
+            vec![mongodb::bson::doc! { "$sample": { "size": size } }],
+        )
+        .await
+    }
+
     pub async fn aggregate(
         &self,
         db: &str,
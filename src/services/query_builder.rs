@@ -0,0 +1,234 @@
+use crate::models::{BuilderCondition, BuilderField, BuilderOperator, QueryBuilder};
+use crate::services::{placeholders, shell_syntax};
+
+/// Suggestions listed under the field being typed.
+const MAX_SUGGESTIONS: usize = 8;
+
+impl BuilderOperator {
+    const ALL: [BuilderOperator; 7] = [
+        BuilderOperator::Eq,
+        BuilderOperator::Ne,
+        BuilderOperator::Gt,
+        BuilderOperator::Lt,
+        BuilderOperator::In,
+        BuilderOperator::Regex,
+        BuilderOperator::Exists,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BuilderOperator::Eq => "=",
+            BuilderOperator::Ne => "!=",
+            BuilderOperator::Gt => ">",
+            BuilderOperator::Lt => "<",
+            BuilderOperator::In => "in",
+            BuilderOperator::Regex => "regex",
+            BuilderOperator::Exists => "exists",
+        }
+    }
+
+    /// What the value should look like, shown while it is empty.
+    pub fn hint(self) -> &'static str {
+        match self {
+            BuilderOperator::In => "a, b, c",
+            BuilderOperator::Regex => "^pattern",
+            BuilderOperator::Exists => "true or false",
+            _ => "value",
+        }
+    }
+
+    fn step(self, forward: bool) -> Self {
+        let index = Self::ALL.iter().position(|o| *o == self).unwrap_or(0);
+        let count = Self::ALL.len();
+        Self::ALL[if forward {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        }]
+    }
+}
+
+impl BuilderCondition {
+    /// The condition in shell syntax, e.g. `{"age": {"$gt": 30}}`. Values
+    /// are typed as in a placeholder: numbers, ObjectIds, dates and
+    /// booleans are recognized, anything else is a string.
+    fn to_shell(&self) -> Result<String, String> {
+        let field = self.field.trim();
+        let value = self.value.trim();
+        let expression = match self.operator {
+            BuilderOperator::Eq => literal(value)?,
+            BuilderOperator::Ne => format!("{{\"$ne\": {}}}", literal(value)?),
+            BuilderOperator::Gt => format!("{{\"$gt\": {}}}", literal(value)?),
+            BuilderOperator::Lt => format!("{{\"$lt\": {}}}", literal(value)?),
+            BuilderOperator::In if value.starts_with('[') => format!("{{\"$in\": {}}}", value),
+            BuilderOperator::In => {
+                let values = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(literal)
+                    .collect::<Result<Vec<_>, _>>()?;
+                format!("{{\"$in\": [{}]}}", values.join(", "))
+            }
+            BuilderOperator::Regex => format!("{{\"$regex\": {}}}", quoted(value)),
+            BuilderOperator::Exists => {
+                let exists = match value.to_ascii_lowercase().as_str() {
+                    "" | "true" | "yes" => true,
+                    "false" | "no" => false,
+                    _ => return Err(format!("exists takes true or false, not '{}'", value)),
+                };
+                format!("{{\"$exists\": {}}}", exists)
+            }
+        };
+        Ok(format!("{{{}: {}}}", quoted(field), expression))
+    }
+}
+
+impl QueryBuilder {
+    /// A builder with one empty condition.
+    pub fn new(fields: Vec<String>) -> Self {
+        Self {
+            fields,
+            conditions: vec![BuilderCondition::default()],
+            selected: 0,
+            focus: BuilderField::Field,
+            any: false,
+        }
+    }
+
+    pub fn condition(&self) -> &BuilderCondition {
+        &self.conditions[self.selected]
+    }
+
+    fn condition_mut(&mut self) -> &mut BuilderCondition {
+        &mut self.conditions[self.selected]
+    }
+
+    /// Sampled fields matching what is typed in the selected condition,
+    /// those starting with it first.
+    pub fn suggestions(&self) -> Vec<&str> {
+        let typed = self.condition().field.trim().to_lowercase();
+        let (mut starting, containing): (Vec<&str>, Vec<&str>) = self
+            .fields
+            .iter()
+            .map(String::as_str)
+            .filter(|f| f.to_lowercase().contains(&typed) && f.to_lowercase() != typed)
+            .partition(|f| f.to_lowercase().starts_with(&typed));
+        starting.extend(containing);
+        starting.truncate(MAX_SUGGESTIONS);
+        starting
+    }
+
+    /// Moves to the next part of the condition; leaving the field takes
+    /// the first suggestion, if any.
+    pub fn next_field(&mut self) {
+        self.focus = match self.focus {
+            BuilderField::Field => {
+                if !self.condition().field.is_empty()
+                    && let Some(first) = self.suggestions().first().map(|f| f.to_string())
+                {
+                    self.condition_mut().field = first;
+                }
+                BuilderField::Operator
+            }
+            BuilderField::Operator => BuilderField::Value,
+            BuilderField::Value => BuilderField::Field,
+        };
+    }
+
+    pub fn prev_field(&mut self) {
+        self.focus = match self.focus {
+            BuilderField::Field => BuilderField::Value,
+            BuilderField::Operator => BuilderField::Field,
+            BuilderField::Value => BuilderField::Operator,
+        };
+    }
+
+    pub fn cycle_operator(&mut self, forward: bool) {
+        let condition = self.condition_mut();
+        condition.operator = condition.operator.step(forward);
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        match self.focus {
+            BuilderField::Field => self.condition_mut().field.push(c),
+            BuilderField::Operator => {}
+            BuilderField::Value => self.condition_mut().value.push(c),
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        match self.focus {
+            BuilderField::Field => self.condition_mut().field.pop(),
+            BuilderField::Operator => None,
+            BuilderField::Value => self.condition_mut().value.pop(),
+        };
+    }
+
+    /// Adds an empty condition below the selected one and selects it.
+    pub fn add_condition(&mut self) {
+        self.selected += 1;
+        self.conditions
+            .insert(self.selected, BuilderCondition::default());
+        self.focus = BuilderField::Field;
+    }
+
+    /// Removes the selected condition; the last one is emptied instead.
+    pub fn remove_condition(&mut self) {
+        if self.conditions.len() == 1 {
+            self.conditions[0] = BuilderCondition::default();
+        } else {
+            self.conditions.remove(self.selected);
+            self.selected = self.selected.min(self.conditions.len() - 1);
+        }
+        self.focus = BuilderField::Field;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.conditions.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn toggle_any(&mut self) {
+        self.any = !self.any;
+    }
+
+    /// The filter in shell syntax, as it would be typed after `/`.
+    /// Conditions without a field are left out; with none left the filter
+    /// matches everything.
+    pub fn filter(&self) -> Result<String, String> {
+        let conditions = self
+            .conditions
+            .iter()
+            .filter(|c| !c.field.trim().is_empty())
+            .map(BuilderCondition::to_shell)
+            .collect::<Result<Vec<_>, _>>()?;
+        let filter = match conditions.as_slice() {
+            [] => "{}".to_string(),
+            [condition] => condition.clone(),
+            _ => format!(
+                "{{\"{}\": [{}]}}",
+                if self.any { "$or" } else { "$and" },
+                conditions.join(", ")
+            ),
+        };
+        shell_syntax::parse_document(&filter)?;
+        Ok(filter)
+    }
+}
+
+fn literal(value: &str) -> Result<String, String> {
+    if value.is_empty() {
+        return Err("Enter a value for every condition".to_string());
+    }
+    placeholders::literal(value, placeholders::ValueKind::Auto)
+}
+
+fn quoted(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
@@ -0,0 +1,109 @@
+use std::{collections::HashMap, path::Path};
+
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document},
+};
+
+use crate::error::AppError;
+use crate::models::{DiffKind, DocumentDiff, ReferenceDiff};
+use crate::services::editor;
+use crate::services::import::read_documents;
+use crate::services::throttle::Throttle;
+
+/// Differences kept for the list; the counts go on past it.
+const LISTED: usize = 1000;
+
+/// Live documents read between two waits on the rate limit.
+const PACED: u64 = 1000;
+
+/// Diffs `db.coll` against a JSON or NDJSON export of it, matching documents
+/// by `_id`. Only reads: the reference is held in memory and each live
+/// document is checked off against it. Both sides go through the same JSON
+/// round trip first, so a relaxed export of an `Int64` still equals the
+/// live value.
+pub async fn diff_against(
+    client: &Client,
+    db: &str,
+    coll: &str,
+    path: &Path,
+    mut throttle: Throttle,
+) -> Result<ReferenceDiff, AppError> {
+    let mut diff = ReferenceDiff {
+        namespace: format!("{}.{}", db, coll),
+        path: path.display().to_string(),
+        ..ReferenceDiff::default()
+    };
+
+    let mut reference: HashMap<String, Document> = HashMap::new();
+    for document in read_documents(path)? {
+        match document.ok().map(normalized).and_then(keyed) {
+            Some((id, document)) => {
+                reference.insert(id, document);
+            }
+            None => diff.unreadable += 1,
+        }
+    }
+
+    let mut cursor = client
+        .database(db)
+        .collection::<Document>(coll)
+        .find(Document::new())
+        .await?;
+    let mut read = 0u64;
+    while let Some(document) = cursor.try_next().await? {
+        read += 1;
+        if read.is_multiple_of(PACED) {
+            throttle.pace(PACED).await;
+        }
+        let Some((id, live)) = keyed(normalized(document)) else {
+            continue;
+        };
+        let kind = match reference.remove(&id) {
+            None => DiffKind::Added,
+            Some(before) => DiffKind::Changed(editor::diff(&before, &live)),
+        };
+        record(&mut diff, id, kind);
+    }
+
+    let mut removed: Vec<String> = reference.into_keys().collect();
+    removed.sort();
+    for id in removed {
+        record(&mut diff, id, DiffKind::Removed);
+    }
+    Ok(diff)
+}
+
+// counts a difference and lists it while there is room
+fn record(diff: &mut ReferenceDiff, id: String, kind: DiffKind) {
+    match &kind {
+        DiffKind::Added => diff.added += 1,
+        DiffKind::Removed => diff.removed += 1,
+        DiffKind::Changed(changes) if changes.is_empty() => {
+            diff.unchanged += 1;
+            return;
+        }
+        DiffKind::Changed(_) => diff.changed += 1,
+    }
+    if diff.entries.len() < LISTED {
+        diff.entries.push(DocumentDiff { id, kind });
+    }
+}
+
+// the document as it reads back from a relaxed extended JSON export
+fn normalized(document: Document) -> Document {
+    serde_json::to_value(&document)
+        .ok()
+        .and_then(|value| Bson::try_from(value).ok())
+        .and_then(|bson| match bson {
+            Bson::Document(document) => Some(document),
+            _ => None,
+        })
+        .unwrap_or(document)
+}
+
+fn keyed(document: Document) -> Option<(String, Document)> {
+    let id = document.get("_id")?.to_string();
+    Some((id, document))
+}
@@ -0,0 +1,286 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use mongodb::{
+    Client, Collection,
+    bson::{Bson, DateTime, Document, doc},
+    error::ErrorKind,
+    options::ReplaceOptions,
+};
+
+use crate::error::AppError;
+use crate::models::NamespaceActivity;
+use crate::services::import::read_documents;
+use crate::services::tasks::TaskProgress;
+
+/// Failure messages quoted in the summary; the rest are only counted.
+const QUOTED_FAILURES: usize = 3;
+
+/// Writes the events of a tail or watch to a file, one canonical extended
+/// JSON document per line, so a replay gets the exact BSON types back.
+#[derive(Debug)]
+pub struct Recorder {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    count: u64,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            path: path.to_path_buf(),
+            count: 0,
+        })
+    }
+
+    /// Appends a batch and flushes it, so the file is usable while the
+    /// recording goes on. A plain tail yields documents rather than change
+    /// events; those are recorded as inserts stamped with the current time.
+    pub fn write(&mut self, documents: &[Document], events: bool) -> io::Result<()> {
+        for document in documents {
+            let event = if events {
+                document.clone()
+            } else {
+                doc! {
+                    "operationType": "insert",
+                    "wallTime": DateTime::now(),
+                    "fullDocument": document.clone(),
+                }
+            };
+            writeln!(
+                self.writer,
+                "{}",
+                Bson::Document(event).into_canonical_extjson()
+            )?;
+            self.count += 1;
+        }
+        self.writer.flush()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// How fast recorded events are applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    /// The recorded gaps between events divided by the factor.
+    Factor(f64),
+    /// One event after the other, without waiting.
+    Max,
+}
+
+impl Speed {
+    fn parse(input: &str) -> Option<Self> {
+        if input.eq_ignore_ascii_case("max") {
+            return Some(Speed::Max);
+        }
+        let factor: f64 = input.strip_suffix(['x', 'X'])?.parse().ok()?;
+        (factor > 0.0 && factor.is_finite()).then_some(Speed::Factor(factor))
+    }
+}
+
+/// A replay as typed in the prompt: `file db[.coll] [speed] [connection]`.
+#[derive(Debug, Clone)]
+pub struct ReplayPlan {
+    pub path: PathBuf,
+    pub database: String,
+    /// Without one, each event goes to the collection it was recorded on.
+    pub collection: Option<String>,
+    pub speed: Speed,
+    /// Profile name or URI of another connection to replay into.
+    pub connection: Option<String>,
+}
+
+impl ReplayPlan {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace().peekable();
+        let usage =
+            "Enter a file and a target: changes.jsonl db.coll [1x|10x|max] [profile or URI]";
+        let path = words.next().ok_or(usage)?;
+        let target = words.next().ok_or(usage)?;
+        let (database, collection) = match target.split_once('.') {
+            Some((db, coll)) if !db.is_empty() && !coll.is_empty() => {
+                (db.to_string(), Some(coll.to_string()))
+            }
+            Some(_) => return Err(format!("Invalid namespace: {}", target)),
+            None => (target.to_string(), None),
+        };
+        let speed = match words.peek().and_then(|word| Speed::parse(word)) {
+            Some(speed) => {
+                words.next();
+                speed
+            }
+            None => Speed::Factor(1.0),
+        };
+        let connection = words.collect::<Vec<_>>().join(" ");
+
+        Ok(Self {
+            path: PathBuf::from(path),
+            database,
+            collection,
+            speed,
+            connection: (!connection.is_empty()).then_some(connection),
+        })
+    }
+
+    /// The target as shown in task labels and summaries.
+    pub fn target(&self) -> String {
+        match &self.collection {
+            Some(collection) => format!("{}.{}", self.database, collection),
+            None => format!("{}.*", self.database),
+        }
+    }
+}
+
+/// Applies the recorded inserts, updates, replaces and deletes to the
+/// plan's target in order, keeping the recorded pacing scaled by the speed.
+/// Events the server rejects are counted and summarized instead of stopping
+/// the replay; other event types such as drops are skipped.
+pub async fn replay(
+    client: Client,
+    plan: ReplayPlan,
+    progress: TaskProgress,
+) -> Result<String, AppError> {
+    let records: Vec<Result<Document, String>> = read_documents(&plan.path)?.collect();
+    let total = records.len() as u64;
+    let database = client.database(&plan.database);
+    let mut applied = NamespaceActivity::default();
+    let mut skipped = 0usize;
+    let mut failures: Vec<String> = Vec::new();
+    let mut previous: Option<DateTime> = None;
+
+    for (index, record) in records.into_iter().enumerate() {
+        progress.set(index as u64, Some(total));
+        let event = match record {
+            Ok(event) => event,
+            Err(e) => {
+                failures.push(e);
+                continue;
+            }
+        };
+
+        if let Speed::Factor(factor) = plan.speed
+            && let Ok(at) = event.get_datetime("wallTime")
+        {
+            if let Some(previous) = previous {
+                let gap = at.timestamp_millis() - previous.timestamp_millis();
+                if gap > 0 {
+                    tokio::time::sleep(Duration::from_secs_f64(gap as f64 / 1000.0 / factor)).await;
+                }
+            }
+            previous = Some(*at);
+        }
+
+        let collection = match &plan.collection {
+            Some(collection) => Some(collection.as_str()),
+            None => event
+                .get_str("ns")
+                .ok()
+                .and_then(|ns| ns.split_once('.'))
+                .map(|(_, collection)| collection),
+        };
+        let Some(collection) = collection else {
+            skipped += 1;
+            continue;
+        };
+
+        match apply(&database.collection(collection), &event).await {
+            Ok(true) => match event.get_str("operationType") {
+                Ok("insert") => applied.inserts += 1,
+                Ok("delete") => applied.deletes += 1,
+                _ => applied.updates += 1,
+            },
+            Ok(false) => skipped += 1,
+            Err(e) if matches!(*e.kind, ErrorKind::Write(_)) => {
+                failures.push(format!("event {}: {}", index + 1, e));
+            }
+            Err(e) => {
+                return Err(AppError::Query(format!("Replay failed: {}", e)));
+            }
+        }
+    }
+    progress.set(total, Some(total));
+
+    let mut summary = format!(
+        "Replayed {} event(s) into {}: {} insert(s), {} update(s), {} delete(s)",
+        applied.total(),
+        plan.target(),
+        applied.inserts,
+        applied.updates,
+        applied.deletes
+    );
+    if skipped > 0 {
+        summary.push_str(&format!(", {} skipped", skipped));
+    }
+    if !failures.is_empty() {
+        summary.push_str(&format!(
+            ", {} failed: {}",
+            failures.len(),
+            failures
+                .iter()
+                .take(QUOTED_FAILURES)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ")
+        ));
+    }
+    Ok(summary)
+}
+
+// applies one event; false when it is not a write or lacks what it needs
+async fn apply(
+    collection: &Collection<Document>,
+    event: &Document,
+) -> Result<bool, mongodb::error::Error> {
+    let key = event.get_document("documentKey").ok();
+    let full = event.get_document("fullDocument").ok();
+    match (event.get_str("operationType"), key, full) {
+        (Ok("insert"), _, Some(full)) => {
+            collection.insert_one(full.clone()).await?;
+        }
+        (Ok("replace"), Some(key), Some(full)) => {
+            collection
+                .replace_one(key.clone(), full.clone())
+                .with_options(ReplaceOptions::builder().upsert(true).build())
+                .await?;
+        }
+        (Ok("update"), Some(key), _) => {
+            let mut update = Document::new();
+            if let Ok(set) = event.get_document("updatedFields")
+                && !set.is_empty()
+            {
+                update.insert("$set", set.clone());
+            }
+            if let Ok(removed) = event.get_array("removedFields")
+                && !removed.is_empty()
+            {
+                let unset: Document = removed
+                    .iter()
+                    .filter_map(Bson::as_str)
+                    .map(|field| (field.to_string(), Bson::String(String::new())))
+                    .collect();
+                update.insert("$unset", unset);
+            }
+            if update.is_empty() {
+                return Ok(false);
+            }
+            collection.update_one(key.clone(), update).await?;
+        }
+        (Ok("delete"), Some(key), _) => {
+            collection.delete_one(key.clone()).await?;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
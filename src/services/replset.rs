@@ -0,0 +1,185 @@
+use std::time::Instant;
+
+use futures::stream::StreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, DateTime, Document, doc},
+    error::ErrorKind,
+};
+
+use crate::{
+    error::AppError,
+    models::{ElectionEvent, OplogWindow, ReplicaSetMember, ReplicaSetStatus},
+    services::bson_ext::number_as_f64,
+};
+
+const ELECTION_HISTORY: i64 = 10;
+
+pub struct ReplicaSetService {
+    client: Client,
+}
+
+impl ReplicaSetService {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Members as the connected one sees them. `replSetGetStatus` has no ping
+    /// for the member it runs on, so that one is timed with `hello`.
+    pub async fn status(&self) -> Result<ReplicaSetStatus, AppError> {
+        let started = Instant::now();
+        let hello = self
+            .client
+            .database("admin")
+            .run_command(doc! { "hello": 1 })
+            .await
+            .map_err(|e| AppError::Query(format!("hello failed: {}", e)))?;
+        let hello_ms = started.elapsed().as_millis() as u64;
+        if !hello.contains_key("setName") {
+            return Err(AppError::Query(
+                "Not a replica set: the server reports no set name".to_string(),
+            ));
+        }
+
+        let status = self
+            .client
+            .database("admin")
+            .run_command(doc! { "replSetGetStatus": 1 })
+            .await
+            .map_err(|e| AppError::Query(format!("replSetGetStatus failed: {}", e)))?;
+
+        let mut members: Vec<ReplicaSetMember> = status
+            .get_array("members")
+            .map(|members| {
+                members
+                    .iter()
+                    .filter_map(Bson::as_document)
+                    .map(parse_member)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for member in members.iter_mut().filter(|m| m.is_self) {
+            member.ping_ms = Some(hello_ms);
+        }
+
+        let metrics = status.get_document("electionCandidateMetrics").ok();
+        Ok(ReplicaSetStatus {
+            set_name: status.get_str("set").unwrap_or("unknown").to_string(),
+            term: status.get("term").and_then(number_as_f64).map(|t| t as i64),
+            members,
+            last_election_reason: metrics
+                .and_then(|m| m.get_str("lastElectionReason").ok())
+                .map(str::to_string),
+            last_election_date: metrics
+                .and_then(|m| m.get_datetime("lastElectionDate").ok().copied()),
+            // the oplog may be unreadable for restricted users
+            oplog_window: self.oplog_window().await.ok().flatten(),
+        })
+    }
+
+    /// The span between the oldest and newest entries of the connected
+    /// member's oplog.
+    async fn oplog_window(&self) -> Result<Option<OplogWindow>, mongodb::error::Error> {
+        let oplog = self
+            .client
+            .database("local")
+            .collection::<Document>("oplog.rs");
+        let entry_time = |entry: Option<Document>| {
+            let ts = entry?.get_timestamp("ts").ok()?;
+            Some(DateTime::from_millis(ts.time as i64 * 1000))
+        };
+        let first = oplog.find_one(doc! {}).sort(doc! { "$natural": 1 }).await?;
+        let last = oplog
+            .find_one(doc! {})
+            .sort(doc! { "$natural": -1 })
+            .await?;
+        Ok(entry_time(first)
+            .zip(entry_time(last))
+            .map(|(first, last)| OplogWindow { first, last }))
+    }
+
+    /// Recent elections, newest first, from the "new primary" no-op entries
+    /// each elected primary writes to the oplog.
+    pub async fn elections(&self) -> Result<Vec<ElectionEvent>, AppError> {
+        let oplog = self
+            .client
+            .database("local")
+            .collection::<Document>("oplog.rs");
+
+        let mut cursor = oplog
+            .find(doc! { "op": "n", "o.msg": "new primary" })
+            .sort(doc! { "$natural": -1 })
+            .limit(ELECTION_HISTORY)
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to read the oplog: {}", e)))?;
+
+        let mut elections = Vec::new();
+        while let Some(entry) = cursor.next().await {
+            let entry = entry.map_err(|e| AppError::Query(format!("Failed to read: {}", e)))?;
+            elections.push(ElectionEvent {
+                term: entry.get("t").and_then(number_as_f64).map(|t| t as i64),
+                at: entry.get_datetime("wall").ok().copied(),
+            });
+        }
+        Ok(elections)
+    }
+
+    /// Asks the primary to step down. The primary drops its connections when
+    /// it does, so a network error after sending counts as success.
+    pub async fn step_down(&self, seconds: i64) -> Result<(), AppError> {
+        match self
+            .client
+            .database("admin")
+            .run_command(doc! { "replSetStepDown": seconds })
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) if matches!(*e.kind, ErrorKind::Io(_)) => Ok(()),
+            Err(e) => Err(AppError::Query(format!("replSetStepDown failed: {}", e))),
+        }
+    }
+}
+
+fn parse_member(member: &Document) -> ReplicaSetMember {
+    ReplicaSetMember {
+        name: member.get_str("name").unwrap_or("?").to_string(),
+        state: member.get_str("stateStr").unwrap_or("UNKNOWN").to_string(),
+        healthy: member.get("health").and_then(number_as_f64).unwrap_or(0.0) >= 1.0,
+        uptime_secs: member
+            .get("uptime")
+            .and_then(number_as_f64)
+            .map(|u| u as u64),
+        optime_date: member.get_datetime("optimeDate").ok().copied(),
+        election_date: member.get_datetime("electionDate").ok().copied(),
+        is_self: member.get_bool("self").unwrap_or(false),
+        ping_ms: member
+            .get("pingMs")
+            .and_then(number_as_f64)
+            .map(|p| p as u64),
+    }
+}
+
+impl OplogWindow {
+    pub fn secs(&self) -> i64 {
+        (self.last.timestamp_millis() - self.first.timestamp_millis()) / 1000
+    }
+}
+
+impl ReplicaSetStatus {
+    pub fn primary(&self) -> Option<&ReplicaSetMember> {
+        self.members.iter().find(|m| m.state == "PRIMARY")
+    }
+
+    /// How far a member's last applied write trails the primary's.
+    pub fn lag_secs(&self, member: &ReplicaSetMember) -> Option<i64> {
+        let primary = self.primary()?.optime_date?;
+        let optime = member.optime_date?;
+        Some((primary.timestamp_millis() - optime.timestamp_millis()) / 1000)
+    }
+}
+
+pub fn format_date(date: DateTime) -> String {
+    date.try_to_rfc3339_string()
+        .map(|s| s.replace('T', " ").chars().take(19).collect())
+        .unwrap_or_else(|_| date.to_string())
+}
@@ -0,0 +1,135 @@
+use std::{fs, path::Path};
+
+use crate::{error::AppError, models::SchemaAnalysis};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                ReportFormat::Html
+            }
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+pub fn write_schema_report(analysis: &SchemaAnalysis, path: &Path) -> Result<(), AppError> {
+    let contents = match ReportFormat::from_path(path) {
+        ReportFormat::Markdown => render_markdown(analysis),
+        ReportFormat::Html => render_html(analysis),
+    };
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn render_markdown(analysis: &SchemaAnalysis) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "# Schema report: `{}.{}`\n\n",
+        analysis.database, analysis.collection
+    ));
+    out.push_str(&format!(
+        "Sampled **{}** documents, found **{}** field paths.\n\n",
+        analysis.sampled,
+        analysis.fields.len()
+    ));
+
+    out.push_str("| Field | Presence | Types | Sample values |\n");
+    out.push_str("|-------|----------|-------|---------------|\n");
+    for field in &analysis.fields {
+        let types = field
+            .type_breakdown()
+            .iter()
+            .map(|(name, pct)| format!("{} ({:.0}%)", name, pct))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let samples = field
+            .samples
+            .iter()
+            .map(|s| format!("`{}`", s.replace('`', "'")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!(
+            "| `{}` | {:.1}% | {} | {} |\n",
+            field.path,
+            field.presence(analysis.sampled),
+            types,
+            samples.replace('|', "\\|")
+        ));
+    }
+
+    out
+}
+
+pub fn render_html(analysis: &SchemaAnalysis) -> String {
+    let mut rows = String::new();
+    for field in &analysis.fields {
+        let types = field
+            .type_breakdown()
+            .iter()
+            .map(|(name, pct)| format!("{} ({:.0}%)", escape_html(name), pct))
+            .collect::<Vec<_>>()
+            .join("<br>");
+        let samples = field
+            .samples
+            .iter()
+            .map(|s| format!("<code>{}</code>", escape_html(s)))
+            .collect::<Vec<_>>()
+            .join("<br>");
+        rows.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{:.1}%</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&field.path),
+            field.presence(analysis.sampled),
+            types,
+            samples
+        ));
+    }
+
+    let namespace = escape_html(&format!("{}.{}", analysis.database, analysis.collection));
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Schema report: {namespace}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}
+th {{ background: #f0f0f0; }}
+</style>
+</head>
+<body>
+<h1>Schema report: <code>{namespace}</code></h1>
+<p>Sampled <strong>{sampled}</strong> documents, found <strong>{count}</strong> field paths.</p>
+<table>
+<tr><th>Field</th><th>Presence</th><th>Types</th><th>Sample values</th></tr>
+{rows}</table>
+</body>
+</html>
+",
+        namespace = namespace,
+        sampled = analysis.sampled,
+        count = analysis.fields.len(),
+        rows = rows
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use mongodb::bson::{Bson, Document};
+
+use crate::models::{FieldStats, SchemaAnalysis};
+
+const MAX_SAMPLES_PER_FIELD: usize = 3;
+const MAX_SAMPLE_LEN: usize = 40;
+
+pub const DEFAULT_SAMPLE_SIZE: i64 = 1000;
+
+pub fn analyze(database: &str, collection: &str, documents: &[Document]) -> SchemaAnalysis {
+    let mut fields: BTreeMap<String, FieldStats> = BTreeMap::new();
+
+    for doc in documents {
+        // a field counts once per document even if it repeats inside arrays
+        let mut seen = Vec::new();
+        walk_document(doc, "", &mut fields, &mut seen);
+    }
+
+    SchemaAnalysis {
+        database: database.to_string(),
+        collection: collection.to_string(),
+        sampled: documents.len(),
+        fields: fields.into_values().collect(),
+    }
+}
+
+fn walk_document(
+    doc: &Document,
+    prefix: &str,
+    fields: &mut BTreeMap<String, FieldStats>,
+    seen: &mut Vec<String>,
+) {
+    for (key, value) in doc {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        record(&path, value, fields, seen);
+    }
+}
+
+fn record(
+    path: &str,
+    value: &Bson,
+    fields: &mut BTreeMap<String, FieldStats>,
+    seen: &mut Vec<String>,
+) {
+    let stats = fields
+        .entry(path.to_string())
+        .or_insert_with(|| FieldStats {
+            path: path.to_string(),
+            count: 0,
+            types: BTreeMap::new(),
+            samples: Vec::new(),
+        });
+
+    if !seen.iter().any(|p| p == path) {
+        stats.count += 1;
+        seen.push(path.to_string());
+    }
+    *stats.types.entry(type_name(value).to_string()).or_insert(0) += 1;
+
+    match value {
+        Bson::Document(inner) => walk_document(inner, path, fields, seen),
+        Bson::Array(items) => {
+            for item in items {
+                if let Bson::Document(inner) = item {
+                    walk_document(inner, path, fields, seen);
+                }
+            }
+        }
+        _ => {
+            if stats.samples.len() < MAX_SAMPLES_PER_FIELD {
+                let sample = sample_value(value);
+                if !stats.samples.contains(&sample) {
+                    stats.samples.push(sample);
+                }
+            }
+        }
+    }
+}
+
+pub fn type_name(value: &Bson) -> &'static str {
+    match value {
+        Bson::Double(_) => "double",
+        Bson::String(_) => "string",
+        Bson::Array(_) => "array",
+        Bson::Document(_) => "object",
+        Bson::Boolean(_) => "bool",
+        Bson::Null => "null",
+        Bson::RegularExpression(_) => "regex",
+        Bson::JavaScriptCode(_) | Bson::JavaScriptCodeWithScope(_) => "javascript",
+        Bson::Int32(_) => "int",
+        Bson::Int64(_) => "long",
+        Bson::Timestamp(_) => "timestamp",
+        Bson::Binary(_) => "binData",
+        Bson::ObjectId(_) => "objectId",
+        Bson::DateTime(_) => "date",
+        Bson::Symbol(_) => "symbol",
+        Bson::Decimal128(_) => "decimal",
+        Bson::Undefined => "undefined",
+        Bson::MaxKey => "maxKey",
+        Bson::MinKey => "minKey",
+        Bson::DbPointer(_) => "dbPointer",
+    }
+}
+
+fn sample_value(value: &Bson) -> String {
+    let text = match value {
+        Bson::String(s) => format!("\"{}\"", s),
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_SAMPLE_LEN {
+        let truncated: String = text.chars().take(MAX_SAMPLE_LEN - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        text
+    }
+}
+
+impl FieldStats {
+    pub fn presence(&self, sampled: usize) -> f64 {
+        if sampled == 0 {
+            0.0
+        } else {
+            self.count as f64 * 100.0 / sampled as f64
+        }
+    }
+
+    /// Type breakdown as percentages of all observed values, most common first.
+    pub fn type_breakdown(&self) -> Vec<(String, f64)> {
+        let total: usize = self.types.values().sum();
+        let mut breakdown: Vec<(String, f64)> = self
+            .types
+            .iter()
+            .map(|(name, n)| (name.clone(), *n as f64 * 100.0 / total.max(1) as f64))
+            .collect();
+        breakdown.sort_by(|a, b| b.1.total_cmp(&a.1));
+        breakdown
+    }
+}
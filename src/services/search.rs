@@ -0,0 +1,232 @@
+use futures::stream::StreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::{
+    error::AppError,
+    models::{SearchField, SearchForm, SearchHit, SearchOperator},
+    services::{bson_ext::number_as_f64, query::QueryService},
+};
+
+pub const RESULT_LIMIT: i64 = 20;
+
+/// Names of the collection's Atlas Search indexes; empty off Atlas.
+pub async fn list_indexes(client: &Client, db: &str, collection: &str) -> Vec<String> {
+    let coll = client.database(db).collection::<Document>(collection);
+    let Ok(mut cursor) = coll.list_search_indexes().await else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    while let Some(Ok(index)) = cursor.next().await {
+        if let Ok(name) = index.get_str("name") {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Builds the `$search` stage for the form. In text mode `+term` must match
+/// and `-term` must not, which turns the query into a compound operator.
+pub fn build_stage(form: &SearchForm) -> Document {
+    let path: Bson = if form.path.trim().is_empty() || form.path.trim() == "*" {
+        doc! { "wildcard": "*" }.into()
+    } else {
+        let paths: Vec<Bson> = form
+            .path
+            .split(',')
+            .map(|p| Bson::String(p.trim().to_string()))
+            .collect();
+        if paths.len() == 1 {
+            paths[0].clone()
+        } else {
+            Bson::Array(paths)
+        }
+    };
+
+    let operator = |name: &str, query: &str| -> Document {
+        let mut body = doc! { "query": query, "path": path.clone() };
+        if form.operator == SearchOperator::Wildcard {
+            body.insert("allowAnalyzedField", true);
+        }
+        doc! { name: body }
+    };
+
+    let mut stage = doc! { "index": form.index_name() };
+    match form.operator {
+        SearchOperator::Text => {
+            let (mut must, mut must_not, mut should) = (Vec::new(), Vec::new(), Vec::new());
+            for term in form.query.split_whitespace() {
+                if let Some(term) = term.strip_prefix('+') {
+                    must.push(operator("text", term));
+                } else if let Some(term) = term.strip_prefix('-') {
+                    must_not.push(operator("text", term));
+                } else {
+                    should.push(term);
+                }
+            }
+
+            if must.is_empty() && must_not.is_empty() {
+                stage.extend(operator("text", form.query.trim()));
+            } else {
+                let mut compound = Document::new();
+                if !should.is_empty() {
+                    compound.insert("should", vec![operator("text", &should.join(" "))]);
+                }
+                if !must.is_empty() {
+                    compound.insert("must", must);
+                }
+                if !must_not.is_empty() {
+                    compound.insert("mustNot", must_not);
+                }
+                stage.insert("compound", compound);
+            }
+        }
+        SearchOperator::Phrase => stage.extend(operator("phrase", form.query.trim())),
+        SearchOperator::Autocomplete => stage.extend(operator("autocomplete", form.query.trim())),
+        SearchOperator::Wildcard => stage.extend(operator("wildcard", form.query.trim())),
+    }
+    // autocomplete does not support highlighting
+    if form.operator != SearchOperator::Autocomplete {
+        stage.insert("highlight", doc! { "path": path });
+    }
+    doc! { "$search": stage }
+}
+
+pub async fn run(
+    query_service: &QueryService,
+    db: &str,
+    collection: &str,
+    stage: Document,
+) -> Result<Vec<SearchHit>, AppError> {
+    let documents = query_service
+        .aggregate(
+            db,
+            collection,
+            vec![
+                stage,
+                doc! { "$limit": RESULT_LIMIT },
+                doc! { "$addFields": {
+                    "_searchScore": { "$meta": "searchScore" },
+                    "_searchHighlights": { "$meta": "searchHighlights" },
+                } },
+            ],
+        )
+        .await?;
+
+    Ok(documents.into_iter().map(into_hit).collect())
+}
+
+/// Splits the score and highlight metadata off a result document.
+fn into_hit(mut doc: Document) -> SearchHit {
+    let score = doc
+        .remove("_searchScore")
+        .as_ref()
+        .and_then(number_as_f64)
+        .unwrap_or(0.0);
+
+    let mut terms: Vec<String> = Vec::new();
+    if let Some(Bson::Array(highlights)) = doc.remove("_searchHighlights") {
+        for highlight in highlights.iter().filter_map(Bson::as_document) {
+            for text in highlight
+                .get_array("texts")
+                .map(|t| t.as_slice())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Bson::as_document)
+            {
+                if text.get_str("type") == Ok("hit")
+                    && let Ok(value) = text.get_str("value")
+                    && !terms.iter().any(|t| t.eq_ignore_ascii_case(value))
+                {
+                    terms.push(value.to_string());
+                }
+            }
+        }
+    }
+
+    SearchHit { doc, score, terms }
+}
+
+impl SearchOperator {
+    pub const ALL: [SearchOperator; 4] = [
+        SearchOperator::Text,
+        SearchOperator::Phrase,
+        SearchOperator::Autocomplete,
+        SearchOperator::Wildcard,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SearchOperator::Text => "text",
+            SearchOperator::Phrase => "phrase",
+            SearchOperator::Autocomplete => "autocomplete",
+            SearchOperator::Wildcard => "wildcard",
+        }
+    }
+}
+
+impl SearchForm {
+    pub fn index_name(&self) -> &str {
+        self.indexes
+            .get(self.selected_index)
+            .map(String::as_str)
+            .unwrap_or("default")
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = match self.focus {
+            SearchField::Index => SearchField::Operator,
+            SearchField::Operator => SearchField::Path,
+            SearchField::Path => SearchField::Query,
+            SearchField::Query => SearchField::Index,
+        };
+    }
+
+    /// Cycles the option under focus; only the index and operator have any.
+    pub fn cycle_option(&mut self, forward: bool) {
+        match self.focus {
+            SearchField::Index if !self.indexes.is_empty() => {
+                let count = self.indexes.len();
+                self.selected_index = if forward {
+                    (self.selected_index + 1) % count
+                } else {
+                    (self.selected_index + count - 1) % count
+                };
+            }
+            SearchField::Operator => {
+                let all = SearchOperator::ALL;
+                let current = all.iter().position(|o| *o == self.operator).unwrap_or(0);
+                let next = if forward {
+                    (current + 1) % all.len()
+                } else {
+                    (current + all.len() - 1) % all.len()
+                };
+                self.operator = all[next];
+            }
+            _ => {}
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        match self.focus {
+            SearchField::Path => self.path.push(c),
+            SearchField::Query => self.query.push(c),
+            _ => {}
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        match self.focus {
+            SearchField::Path => {
+                self.path.pop();
+            }
+            SearchField::Query => {
+                self.query.pop();
+            }
+            _ => {}
+        }
+    }
+}
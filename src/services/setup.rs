@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use crate::config::{ConnectionProfile, DefaultSort, QueryLimits, Settings};
+use crate::i18n;
+use crate::models::{SetupField, SetupForm};
+
+impl SetupField {
+    pub const ALL: [SetupField; 4] = [
+        SetupField::Connection,
+        SetupField::PageSize,
+        SetupField::ReadOnly,
+        SetupField::Language,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SetupField::Connection => "Default connection",
+            SetupField::PageSize => "Page size",
+            SetupField::ReadOnly => "Read-only",
+            SetupField::Language => "Language",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            SetupField::Connection => {
+                "A mongodb:// URI or the name of a saved profile, filled in on the connection screen"
+            }
+            SetupField::PageSize => "Documents shown per page when a collection opens",
+            SetupField::ReadOnly => "Block inserts, edits, imports and admin actions everywhere",
+            SetupField::Language => "Language of the footers and the help screen",
+        }
+    }
+}
+
+impl Default for SetupForm {
+    fn default() -> Self {
+        Self {
+            connection: "mongodb://localhost:27017".to_string(),
+            page_size: "20".to_string(),
+            read_only: false,
+            language: "en".to_string(),
+            focus: 0,
+        }
+    }
+}
+
+impl SetupForm {
+    pub fn focused_field(&self) -> SetupField {
+        SetupField::ALL[self.focus.min(SetupField::ALL.len() - 1)]
+    }
+
+    pub fn is_last_step(&self) -> bool {
+        self.focus + 1 >= SetupField::ALL.len()
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = (self.focus + 1).min(SetupField::ALL.len() - 1);
+    }
+
+    pub fn prev_field(&mut self) {
+        self.focus = self.focus.saturating_sub(1);
+    }
+
+    pub fn value(&self, field: SetupField) -> String {
+        match field {
+            SetupField::Connection => self.connection.clone(),
+            SetupField::PageSize => self.page_size.clone(),
+            SetupField::ReadOnly => if self.read_only { "yes" } else { "no" }.to_string(),
+            SetupField::Language => self.language.clone(),
+        }
+    }
+
+    fn text_mut(&mut self, field: SetupField) -> Option<&mut String> {
+        match field {
+            SetupField::Connection => Some(&mut self.connection),
+            SetupField::PageSize => Some(&mut self.page_size),
+            SetupField::ReadOnly | SetupField::Language => None,
+        }
+    }
+
+    /// Types into the focused field; space flips a choice.
+    pub fn push_char(&mut self, c: char) {
+        match self.text_mut(self.focused_field()) {
+            Some(text) => text.push(c),
+            None if c == ' ' => self.cycle(1),
+            None => {}
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if let Some(text) = self.text_mut(self.focused_field()) {
+            text.pop();
+        }
+    }
+
+    /// Steps the focused choice forwards or backwards.
+    pub fn cycle(&mut self, step: isize) {
+        match self.focused_field() {
+            SetupField::ReadOnly => self.read_only = !self.read_only,
+            SetupField::Language => {
+                let codes = i18n::languages();
+                let current = codes
+                    .iter()
+                    .position(|code| *code == self.language)
+                    .unwrap_or(0);
+                let next = (current as isize + step).rem_euclid(codes.len() as isize);
+                self.language = codes[next as usize].to_string();
+            }
+            SetupField::Connection | SetupField::PageSize => {}
+        }
+    }
+
+    /// The settings file the answers describe. The connection must be a URI
+    /// or an existing profile, and the page size within the configured cap.
+    pub fn to_settings(
+        &self,
+        profiles: &[ConnectionProfile],
+        limits: &QueryLimits,
+    ) -> Result<Settings, String> {
+        let connection = self.connection.trim();
+        let is_uri =
+            connection.starts_with("mongodb://") || connection.starts_with("mongodb+srv://");
+        if !connection.is_empty() && !is_uri && !profiles.iter().any(|p| p.name == connection) {
+            return Err(format!(
+                "{} is neither a mongodb:// URI nor a saved profile",
+                connection
+            ));
+        }
+        let page_size = self
+            .page_size
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("Not a page size: {}", self.page_size.trim()))?;
+        limits.check_page_size(page_size, false)?;
+
+        Ok(Settings {
+            limits: limits.clone(),
+            snippets: Vec::new(),
+            language: Some(self.language.clone()).filter(|code| code != "en"),
+            default_connection: Some(connection.to_string()).filter(|c| !c.is_empty()),
+            page_size: Some(page_size),
+            read_only: self.read_only,
+            dry_run: false,
+            default_sort: DefaultSort::default(),
+            utc_offset: None,
+            date_format: None,
+            theme: None,
+            colors: BTreeMap::new(),
+            confirm_delete: None,
+            split_percent: None,
+            keys: BTreeMap::new(),
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use std::cmp::Ordering;
+
+use futures::stream::StreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::{
+    error::AppError,
+    models::{ShardKeyAnalysis, ShardKeyCandidate},
+    services::bson_ext::number_as_f64,
+};
+
+const SAMPLE_SIZE: i64 = 10_000;
+const RECENT_SAMPLE: i64 = 1000;
+const TOP_VALUES: usize = 5;
+const MIN_CARDINALITY: u64 = 1000;
+const HOT_VALUE_SHARE: f64 = 0.05;
+const MONOTONIC_RATIO: f64 = 0.9;
+
+/// Parses `a, b.c` or `user_id: hashed` into a candidate key.
+pub fn parse_candidate(input: &str) -> Result<ShardKeyCandidate, String> {
+    let mut fields = Vec::new();
+    let mut hashed = false;
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once(':').map(|(f, k)| (f.trim(), k.trim())) {
+            Some((field, "hashed")) => {
+                hashed = true;
+                fields.push(field.to_string());
+            }
+            Some((field, "1")) | Some((field, "-1")) => fields.push(field.to_string()),
+            Some((_, kind)) => return Err(format!("Unsupported shard key type '{}'", kind)),
+            None => fields.push(part.to_string()),
+        }
+    }
+
+    if fields.is_empty() {
+        return Err("Enter at least one field".to_string());
+    }
+    if hashed && fields.len() > 1 {
+        return Err("Only single-field keys can be hashed here".to_string());
+    }
+    Ok(ShardKeyCandidate { fields, hashed })
+}
+
+/// Samples a collection to judge a candidate shard key on cardinality, value
+/// frequency and monotonic growth, the same signals Atlas's advisor reports.
+pub async fn analyze(
+    client: &Client,
+    db: &str,
+    collection: &str,
+    candidate: ShardKeyCandidate,
+) -> Result<ShardKeyAnalysis, AppError> {
+    let coll = client.database(db).collection::<Document>(collection);
+
+    let mut group_id = Document::new();
+    for (i, field) in candidate.fields.iter().enumerate() {
+        group_id.insert(format!("f{}", i), format!("${}", field));
+    }
+
+    let mut cursor = coll
+        .aggregate(vec![
+            doc! { "$sample": { "size": SAMPLE_SIZE } },
+            doc! { "$group": { "_id": group_id, "count": { "$sum": 1 } } },
+            doc! { "$sort": { "count": -1 } },
+        ])
+        .await
+        .map_err(|e| AppError::Query(format!("Shard key sample failed: {}", e)))?;
+
+    let mut sampled = 0;
+    let mut distinct = 0;
+    let mut missing = 0;
+    let mut top_values = Vec::new();
+    while let Some(group) = cursor.next().await {
+        let group = group.map_err(|e| AppError::Query(format!("Failed to read: {}", e)))?;
+        let count = group.get("count").and_then(number_as_f64).unwrap_or(0.0) as u64;
+        let key = group.get_document("_id").cloned().unwrap_or_default();
+
+        sampled += count;
+        distinct += 1;
+        if key.len() < candidate.fields.len() {
+            missing += count;
+        }
+        if top_values.len() < TOP_VALUES {
+            top_values.push((describe_key(&key), count));
+        }
+    }
+
+    // hashing spreads monotonic values, so only range keys need the check
+    let monotonic_ratio = if candidate.hashed {
+        None
+    } else {
+        monotonic_ratio(&coll, &candidate.fields[0]).await?
+    };
+
+    let mut analysis = ShardKeyAnalysis {
+        namespace: format!("{}.{}", db, collection),
+        candidate,
+        sampled,
+        distinct,
+        missing,
+        top_values,
+        monotonic_ratio,
+        warnings: Vec::new(),
+    };
+    analysis.warnings = warnings(&analysis);
+    Ok(analysis)
+}
+
+/// Share of consecutive recent inserts (by `_id`) whose key value grew.
+async fn monotonic_ratio(
+    coll: &mongodb::Collection<Document>,
+    field: &str,
+) -> Result<Option<f64>, AppError> {
+    let mut cursor = coll
+        .aggregate(vec![
+            doc! { "$sort": { "_id": -1 } },
+            doc! { "$limit": RECENT_SAMPLE },
+            doc! { "$project": { "_id": 0, "k": format!("${}", field) } },
+        ])
+        .await
+        .map_err(|e| AppError::Query(format!("Shard key sample failed: {}", e)))?;
+
+    let mut values = Vec::new();
+    while let Some(entry) = cursor.next().await {
+        let entry = entry.map_err(|e| AppError::Query(format!("Failed to read: {}", e)))?;
+        if let Some(value) = entry.get("k") {
+            values.push(value.clone());
+        }
+    }
+    values.reverse();
+
+    let mut compared = 0;
+    let mut increasing = 0;
+    for pair in values.windows(2) {
+        if let Some(order) = compare(&pair[0], &pair[1]) {
+            compared += 1;
+            if order == Ordering::Less {
+                increasing += 1;
+            }
+        }
+    }
+
+    Ok((compared > 0).then(|| increasing as f64 / compared as f64))
+}
+
+fn compare(a: &Bson, b: &Bson) -> Option<Ordering> {
+    match (a, b) {
+        (Bson::ObjectId(a), Bson::ObjectId(b)) => Some(a.cmp(b)),
+        (Bson::DateTime(a), Bson::DateTime(b)) => Some(a.cmp(b)),
+        (Bson::Timestamp(a), Bson::Timestamp(b)) => {
+            Some((a.time, a.increment).cmp(&(b.time, b.increment)))
+        }
+        (Bson::String(a), Bson::String(b)) => Some(a.cmp(b)),
+        _ => number_as_f64(a)?.partial_cmp(&number_as_f64(b)?),
+    }
+}
+
+fn describe_key(key: &Document) -> String {
+    let values: Vec<String> = key.values().map(|v| v.to_string()).collect();
+    if values.is_empty() {
+        "(missing)".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+fn warnings(analysis: &ShardKeyAnalysis) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if analysis.sampled == 0 {
+        warnings.push("Collection is empty; nothing to judge yet".to_string());
+        return warnings;
+    }
+
+    if analysis.distinct < MIN_CARDINALITY && analysis.distinct < analysis.sampled {
+        warnings.push(format!(
+            "Low cardinality: {} distinct values in {} sampled documents limits how many chunks can exist",
+            analysis.distinct, analysis.sampled
+        ));
+    }
+    if let Some((value, count)) = analysis.top_values.first() {
+        let share = *count as f64 / analysis.sampled as f64;
+        if share > HOT_VALUE_SHARE && *count > 1 {
+            warnings.push(format!(
+                "Skewed frequency: {} holds {:.1}% of documents and can form jumbo chunks",
+                value,
+                share * 100.0
+            ));
+        }
+    }
+    if analysis.missing > 0 {
+        warnings.push(format!(
+            "{} sampled documents lack part of the key and would all land in one chunk",
+            analysis.missing
+        ));
+    }
+    if let Some(ratio) = analysis.monotonic_ratio
+        && ratio >= MONOTONIC_RATIO
+    {
+        warnings.push(format!(
+            "Monotonic: {:.0}% of recent inserts increase the key, so writes target one shard; consider hashing it",
+            ratio * 100.0
+        ));
+    }
+    warnings
+}
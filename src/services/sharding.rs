@@ -0,0 +1,275 @@
+use futures::stream::StreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::{
+    error::AppError,
+    models::{ChunkInfo, ShardingStatus, ZoneRange},
+};
+
+pub struct ShardingService {
+    client: Client,
+}
+
+impl ShardingService {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    async fn admin_command(&self, command: Document) -> Result<Document, AppError> {
+        let name = command.keys().next().cloned().unwrap_or_default();
+        self.client
+            .database("admin")
+            .run_command(command)
+            .await
+            .map_err(|e| AppError::Query(format!("{} failed: {}", name, e)))
+    }
+
+    /// Balancer state, shards and the chunk ranges of one collection.
+    pub async fn status(&self, db: &str, collection: &str) -> Result<ShardingStatus, AppError> {
+        let namespace = format!("{}.{}", db, collection);
+        let balancer = self
+            .admin_command(doc! { "balancerStatus": 1 })
+            .await
+            .map_err(|_| {
+                AppError::Query("Not a sharded cluster; connect through mongos".to_string())
+            })?;
+
+        let list = self.admin_command(doc! { "listShards": 1 }).await?;
+        let mut shards = Vec::new();
+        let mut shard_zones = Vec::new();
+        for shard in list
+            .get_array("shards")
+            .map(|s| s.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Bson::as_document)
+        {
+            shards.push(shard.get_str("_id").unwrap_or("?").to_string());
+            shard_zones.push(
+                shard
+                    .get_array("tags")
+                    .map(|tags| {
+                        tags.iter()
+                            .filter_map(Bson::as_str)
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            );
+        }
+
+        let config = self.client.database("config");
+        let entry = config
+            .collection::<Document>("collections")
+            .find_one(doc! { "_id": &namespace })
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to read config.collections: {}", e)))?
+            .filter(|entry| !entry.get_bool("dropped").unwrap_or(false))
+            .ok_or_else(|| AppError::Query(format!("{} is not sharded", namespace)))?;
+
+        // chunks are keyed by collection UUID since 5.0 and by namespace before
+        let filter = match entry.get("uuid") {
+            Some(uuid) => doc! { "uuid": uuid.clone() },
+            None => doc! { "ns": &namespace },
+        };
+        let mut cursor = config
+            .collection::<Document>("chunks")
+            .find(filter)
+            .sort(doc! { "min": 1 })
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to read config.chunks: {}", e)))?;
+
+        let mut chunks = Vec::new();
+        while let Some(chunk) = cursor.next().await {
+            let chunk = chunk.map_err(|e| AppError::Query(format!("Failed to read: {}", e)))?;
+            chunks.push(ChunkInfo {
+                min: chunk.get_document("min").cloned().unwrap_or_default(),
+                max: chunk.get_document("max").cloned().unwrap_or_default(),
+                shard: chunk.get_str("shard").unwrap_or("?").to_string(),
+                jumbo: chunk.get_bool("jumbo").unwrap_or(false),
+            });
+        }
+
+        let mut cursor = config
+            .collection::<Document>("tags")
+            .find(doc! { "ns": &namespace })
+            .sort(doc! { "min": 1 })
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to read config.tags: {}", e)))?;
+
+        let mut zones = Vec::new();
+        while let Some(range) = cursor.next().await {
+            let range = range.map_err(|e| AppError::Query(format!("Failed to read: {}", e)))?;
+            zones.push(ZoneRange {
+                min: range.get_document("min").cloned().unwrap_or_default(),
+                max: range.get_document("max").cloned().unwrap_or_default(),
+                zone: range.get_str("tag").unwrap_or("?").to_string(),
+            });
+        }
+
+        Ok(ShardingStatus {
+            namespace,
+            shard_key: entry.get_document("key").cloned().unwrap_or_default(),
+            balancer_mode: balancer.get_str("mode").unwrap_or("unknown").to_string(),
+            balancer_running: balancer.get_bool("inBalancerRound").unwrap_or(false),
+            shards,
+            shard_zones,
+            chunks,
+            zones,
+        })
+    }
+}
+
+impl ChunkInfo {
+    pub fn range(&self) -> String {
+        format!(
+            "{} → {}",
+            describe_bound(&self.min),
+            describe_bound(&self.max)
+        )
+    }
+}
+
+impl ZoneRange {
+    pub fn range(&self) -> String {
+        format!(
+            "{} → {}",
+            describe_bound(&self.min),
+            describe_bound(&self.max)
+        )
+    }
+
+    /// Parses `ZONE {min} {max}`, where the bounds are extended JSON objects
+    /// over the shard key such as `{"region": {"$minKey": 1}}`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let (zone, bounds) = input
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or("Expected: ZONE {min} {max}")?;
+
+        let mut values =
+            serde_json::Deserializer::from_str(bounds).into_iter::<serde_json::Value>();
+        let mut next_bound = |name: &str| -> Result<Document, String> {
+            let value = values
+                .next()
+                .ok_or(format!("Missing {} bound", name))?
+                .map_err(|e| format!("Invalid {} bound: {}", name, e))?;
+            match Bson::try_from(value) {
+                Ok(Bson::Document(doc)) => Ok(doc),
+                _ => Err(format!("The {} bound must be a JSON object", name)),
+            }
+        };
+        let min = next_bound("min")?;
+        let max = next_bound("max")?;
+
+        Ok(Self {
+            min,
+            max,
+            zone: zone.to_string(),
+        })
+    }
+}
+
+fn describe_bound(bound: &Document) -> String {
+    let values: Vec<String> = bound.values().map(|v| v.to_string()).collect();
+    values.join(", ")
+}
+
+/// A change to the cluster started from the sharding screen.
+pub enum ShardingAction {
+    SetBalancer(bool),
+    Split(ChunkInfo),
+    Move(ChunkInfo, String),
+    AddZoneRange(ZoneRange),
+    RemoveZoneRange(ZoneRange),
+}
+
+impl ShardingAction {
+    /// The admin command that carries out the action on `namespace`.
+    pub fn command(&self, namespace: &str) -> Document {
+        match self {
+            ShardingAction::SetBalancer(true) => doc! { "balancerStart": 1 },
+            ShardingAction::SetBalancer(false) => doc! { "balancerStop": 1 },
+            // the server picks the median point of the chunk's range
+            ShardingAction::Split(chunk) => doc! {
+                "split": namespace,
+                "bounds": [chunk.min.clone(), chunk.max.clone()],
+            },
+            ShardingAction::Move(chunk, to_shard) => doc! {
+                "moveChunk": namespace,
+                "bounds": [chunk.min.clone(), chunk.max.clone()],
+                "to": to_shard,
+            },
+            // a null zone clears the range
+            ShardingAction::AddZoneRange(range) | ShardingAction::RemoveZoneRange(range) => {
+                let zone = match self {
+                    ShardingAction::AddZoneRange(_) => Bson::String(range.zone.clone()),
+                    _ => Bson::Null,
+                };
+                doc! {
+                    "updateZoneKeyRange": namespace,
+                    "min": range.min.clone(),
+                    "max": range.max.clone(),
+                    "zone": zone,
+                }
+            }
+        }
+    }
+
+    /// What happened, for the footer once the command succeeded.
+    fn done(&self) -> String {
+        match self {
+            ShardingAction::SetBalancer(enabled) => {
+                format!("Balancer {}", if *enabled { "started" } else { "stopped" })
+            }
+            ShardingAction::Split(chunk) => format!("Split chunk {}", chunk.range()),
+            ShardingAction::Move(chunk, to_shard) => {
+                format!("Moved chunk {} to {}", chunk.range(), to_shard)
+            }
+            ShardingAction::AddZoneRange(range) => {
+                format!("Assigned {} to zone {}", range.range(), range.zone)
+            }
+            ShardingAction::RemoveZoneRange(range) => {
+                format!("Removed zone range {}", range.range())
+            }
+        }
+    }
+}
+
+impl ShardingService {
+    /// Runs an action and describes what happened for the footer.
+    pub async fn run(&self, namespace: &str, action: ShardingAction) -> Result<String, AppError> {
+        self.admin_command(action.command(namespace)).await?;
+        Ok(action.done())
+    }
+}
+
+impl ShardingStatus {
+    /// A zone range template spanning the whole shard key.
+    pub fn zone_template(&self) -> String {
+        let bound = |marker: &str| {
+            let fields: Vec<String> = self
+                .shard_key
+                .keys()
+                .map(|k| format!("\"{}\": {{\"{}\": 1}}", k, marker))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        };
+        format!("ZONE {} {}", bound("$minKey"), bound("$maxKey"))
+    }
+
+    pub fn balancer_enabled(&self) -> bool {
+        self.balancer_mode == "full"
+    }
+
+    pub fn db_and_collection(&self) -> (String, String) {
+        let (db, collection) = self
+            .namespace
+            .split_once('.')
+            .unwrap_or((&self.namespace, ""));
+        (db.to_string(), collection.to_string())
+    }
+}
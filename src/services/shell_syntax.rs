@@ -0,0 +1,263 @@
+use std::{iter::Peekable, str::Chars};
+
+use mongodb::bson::{Bson, DateTime, Document};
+
+/// Parses a document typed in mongosh syntax or JSON, with constructors and
+/// extended JSON wrappers (`{"$oid": ...}`) turned into their BSON types.
+pub fn parse_document(input: &str) -> Result<Document, String> {
+    let json = to_extended_json(input)?;
+    let value: serde_json::Value = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    match Bson::try_from(value).map_err(|e| e.to_string())? {
+        Bson::Document(doc) => Ok(doc),
+        _ => Err("Expected a document in braces".to_string()),
+    }
+}
+
+/// A query as typed at `/`, taken apart.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryParts {
+    pub filter: Option<Document>,
+    /// `None` keeps the collection's default order.
+    pub sort: Option<Document>,
+    pub projection: Option<Document>,
+}
+
+/// Parses a query in shell syntax, either a plain filter or the combined
+/// `{filter, sort, projection}`. Empty input matches everything.
+pub fn parse_query(input: &str) -> Result<QueryParts, String> {
+    if input.is_empty() {
+        return Ok(QueryParts::default());
+    }
+
+    // mongosh syntax, so filters pasted from the shell work as they are
+    let doc = parse_document(input).map_err(|e| format!("Invalid query: {}", e))?;
+
+    // `{filter, sort, projection}` is the combined form; anything else is a plain filter
+    let combined = !doc.is_empty()
+        && doc
+            .keys()
+            .all(|k| matches!(k.as_str(), "filter" | "sort" | "projection"));
+    if !combined {
+        return Ok(QueryParts {
+            filter: Some(doc),
+            ..QueryParts::default()
+        });
+    }
+
+    let part = |key: &str| match doc.get(key) {
+        None => Ok(None),
+        Some(Bson::Document(d)) if d.is_empty() => Ok(None),
+        Some(Bson::Document(d)) => Ok(Some(d.clone())),
+        Some(_) => Err(format!("'{}' must be a document", key)),
+    };
+    Ok(QueryParts {
+        filter: part("filter")?,
+        sort: part("sort")?,
+        projection: part("projection")?,
+    })
+}
+
+/// Rewrites mongosh syntax (unquoted keys, single quotes, `ObjectId(...)`,
+/// `ISODate(...)`, regex literals, comments, trailing commas) as extended JSON.
+/// Input that is already JSON passes through unchanged.
+pub fn to_extended_json(input: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => out.push_str(&quote(&read_string(&mut chars, c)?)),
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                out.push('\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => last = c,
+                        None => return Err("Unterminated comment".to_string()),
+                    }
+                }
+            }
+            '/' => out.push_str(&read_regex(&mut chars)?),
+            ',' => {
+                // trailing commas are fine in the shell but not in JSON
+                let rest: String = chars.clone().collect();
+                if !matches!(rest.trim_start().chars().next(), Some('}' | ']')) {
+                    out.push(',');
+                }
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                // read whole numbers so exponents are not taken for words
+                out.push(c);
+                let mut last = c;
+                while let Some(&next) = chars.peek() {
+                    let sign = matches!(next, '+' | '-') && matches!(last, 'e' | 'E');
+                    if !(next.is_ascii_alphanumeric() || next == '.' || sign) {
+                        break;
+                    }
+                    out.push(next);
+                    last = next;
+                    chars.next();
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || matches!(next, '_' | '$' | '.') {
+                        word.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                out.push_str(&read_word(&word, &mut chars)?);
+            }
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+fn read_word(word: &str, chars: &mut Peekable<Chars>) -> Result<String, String> {
+    if word == "new" {
+        return Ok(String::new());
+    }
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('(') => {
+            chars.next();
+            let argument = read_argument(chars)?;
+            constructor(word, &argument)
+        }
+        Some(':') => Ok(quote(word)),
+        _ if matches!(word, "true" | "false" | "null") => Ok(word.to_string()),
+        _ => Err(format!("Unexpected '{}'", word)),
+    }
+}
+
+/// Extended JSON for the shell's type constructors.
+fn constructor(name: &str, argument: &str) -> Result<String, String> {
+    let value = match name {
+        "ObjectId" => format!("{{\"$oid\": {}}}", quote(argument)),
+        // no argument is now; a bare number counts milliseconds since the epoch
+        "ISODate" | "Date" if argument.is_empty() => format!(
+            "{{\"$date\": {}}}",
+            quote(&DateTime::now().try_to_rfc3339_string().unwrap_or_default())
+        ),
+        "ISODate" | "Date" if argument.parse::<i64>().is_ok() => {
+            format!("{{\"$date\": {{\"$numberLong\": {}}}}}", quote(argument))
+        }
+        "ISODate" | "Date" => format!("{{\"$date\": {}}}", quote(&iso_date(argument))),
+        "NumberLong" => format!("{{\"$numberLong\": {}}}", quote(argument)),
+        "NumberDecimal" => format!("{{\"$numberDecimal\": {}}}", quote(argument)),
+        "NumberInt" | "Int32" => argument.to_string(),
+        _ => return Err(format!("Unsupported constructor {}()", name)),
+    };
+    Ok(value)
+}
+
+// the shell accepts dates without a time or zone; extended JSON does not
+fn iso_date(text: &str) -> String {
+    if !text.contains('T') {
+        format!("{}T00:00:00Z", text)
+    } else if text.ends_with('Z') || text[text.find('T').unwrap_or(0)..].contains(['+', '-']) {
+        text.to_string()
+    } else {
+        format!("{}Z", text)
+    }
+}
+
+/// The single argument of a constructor call, unquoted.
+fn read_argument(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    skip_whitespace(chars);
+    let argument = match chars.peek() {
+        Some(&q @ ('"' | '\'')) => {
+            chars.next();
+            read_string(chars, q)?
+        }
+        _ => {
+            let mut raw = String::new();
+            while let Some(&c) = chars.peek() {
+                if c == ')' {
+                    break;
+                }
+                raw.push(c);
+                chars.next();
+            }
+            raw.trim().to_string()
+        }
+    };
+    skip_whitespace(chars);
+    match chars.next() {
+        Some(')') => Ok(argument),
+        _ => Err("Expected ')' after constructor argument".to_string()),
+    }
+}
+
+fn read_string(chars: &mut Peekable<Chars>, delimiter: char) -> Result<String, String> {
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some('r') => text.push('\r'),
+                Some(c) => text.push(c),
+                None => break,
+            },
+            Some(c) if c == delimiter => return Ok(text),
+            Some(c) => text.push(c),
+            None => break,
+        }
+    }
+    Err("Unterminated string".to_string())
+}
+
+fn read_regex(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut pattern = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => {
+                pattern.push('\\');
+                if let Some(c) = chars.next() {
+                    pattern.push(c);
+                }
+            }
+            Some('/') => break,
+            Some(c) => pattern.push(c),
+            None => return Err("Unterminated regular expression".to_string()),
+        }
+    }
+    let mut options = String::new();
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_alphabetic() {
+            break;
+        }
+        options.push(c);
+        chars.next();
+    }
+    Ok(format!(
+        "{{\"$regularExpression\": {{\"pattern\": {}, \"options\": {}}}}}",
+        quote(&pattern),
+        quote(&options)
+    ))
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text))
+}
@@ -0,0 +1,115 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+};
+
+use crate::error::AppError;
+use crate::models::{CollectionStats, DatabaseTotals};
+use crate::services::bson_ext::number_as_f64;
+
+/// WiredTiger cache counters shown, in the order they are listed.
+const CACHE_COUNTERS: [&str; 6] = [
+    "bytes currently in the cache",
+    "tracked dirty bytes in the cache",
+    "bytes read into cache",
+    "bytes written from cache",
+    "pages read into cache",
+    "pages written from cache",
+];
+
+/// Storage statistics of a collection from `$collStats`. On a sharded
+/// collection every shard reports its own; sizes and counts are summed.
+pub async fn collection_stats(
+    client: &Client,
+    db: &str,
+    collection: &str,
+) -> Result<CollectionStats, AppError> {
+    let reports: Vec<Document> = client
+        .database(db)
+        .collection::<Document>(collection)
+        .aggregate(vec![doc! { "$collStats": { "storageStats": {} } }])
+        .await
+        .map_err(|e| AppError::Query(format!("collStats failed: {}", e)))?
+        .try_collect()
+        .await?;
+
+    let mut stats = CollectionStats {
+        namespace: format!("{}.{}", db, collection),
+        shards: reports.len(),
+        ..CollectionStats::default()
+    };
+    for storage in reports
+        .iter()
+        .filter_map(|report| report.get_document("storageStats").ok())
+    {
+        stats.count += number(storage, "count");
+        stats.size += number(storage, "size");
+        stats.storage_size += number(storage, "storageSize");
+        stats.free_storage_size += number(storage, "freeStorageSize");
+        stats.total_index_size += number(storage, "totalIndexSize");
+        stats.capped |= storage.get_bool("capped").unwrap_or(false);
+        stats.max = stats
+            .max
+            .or(storage.get("max").and_then(number_as_f64).map(|m| m as u64));
+        stats.max_size = stats.max_size.or(storage
+            .get("maxSize")
+            .and_then(number_as_f64)
+            .map(|m| m as u64));
+
+        if let Ok(sizes) = storage.get_document("indexSizes") {
+            for (name, size) in sizes {
+                let size = number_as_f64(size).unwrap_or(0.0) as u64;
+                match stats.index_sizes.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, total)) => *total += size,
+                    None => stats.index_sizes.push((name.clone(), size)),
+                }
+            }
+        }
+        if let Ok(cache) = storage
+            .get_document("wiredTiger")
+            .and_then(|wt| wt.get_document("cache"))
+        {
+            for counter in CACHE_COUNTERS {
+                let value = cache.get(counter).and_then(number_as_f64).unwrap_or(0.0) as u64;
+                match stats.cache.iter_mut().find(|(name, _)| name == counter) {
+                    Some((_, total)) => *total += value,
+                    None => stats.cache.push((counter.to_string(), value)),
+                }
+            }
+        }
+    }
+    stats.avg_obj_size = stats.size.checked_div(stats.count).unwrap_or(0);
+    stats
+        .index_sizes
+        .sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    Ok(stats)
+}
+
+/// Documents and indexes of every collection of `db`.
+pub async fn database_totals(client: &Client, db: &str) -> Result<DatabaseTotals, AppError> {
+    let stats = client
+        .database(db)
+        .run_command(doc! { "dbStats": 1 })
+        .await
+        .map_err(|e| AppError::Query(format!("dbStats failed: {}", e)))?;
+    Ok(DatabaseTotals {
+        documents: number(&stats, "objects"),
+        indexes: number(&stats, "indexes"),
+    })
+}
+
+fn number(document: &Document, key: &str) -> u64 {
+    document
+        .get(key)
+        .and_then(number_as_f64)
+        .unwrap_or(0.0)
+        .max(0.0) as u64
+}
+
+impl CollectionStats {
+    /// Whether the counter is a byte count rather than a number of pages.
+    pub fn is_bytes(counter: &str) -> bool {
+        counter.starts_with("bytes") || counter.contains(" bytes ")
+    }
+}
@@ -0,0 +1,208 @@
+use std::{cmp::Ordering, fs, path::Path};
+
+use mongodb::bson::{Bson, Document};
+
+use crate::error::AppError;
+use crate::services::bson_ext::number_as_f64;
+
+/// Widest a column grows to fit its values.
+pub const MAX_COLUMN_WIDTH: usize = 30;
+
+/// Documents from the start of the page looked at when inferring columns.
+pub const COLUMN_SAMPLE_SIZE: usize = 100;
+
+/// Top-level fields of a sample of `documents`, `_id` first and the rest by
+/// how many sampled documents have them, ties in first-seen order.
+pub fn infer_columns(documents: &[Document]) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for doc in documents.iter().take(COLUMN_SAMPLE_SIZE) {
+        for key in doc.keys() {
+            match counts.iter_mut().find(|(name, _)| name == key) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((key.clone(), 1)),
+            }
+        }
+    }
+    // sort_by is stable, so equally common fields keep their document order
+    counts.sort_by(|a, b| (b.0 == "_id").cmp(&(a.0 == "_id")).then(b.1.cmp(&a.1)));
+    counts.into_iter().map(|(name, _)| name).collect()
+}
+
+/// Value at a dotted `path`, descending into embedded documents.
+pub fn lookup<'a>(doc: &'a Document, path: &str) -> Option<&'a Bson> {
+    let mut parts = path.split('.');
+    let mut value = doc.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_document()?.get(part)?;
+    }
+    Some(value)
+}
+
+/// A one-line rendering of the cell, empty when the field is missing.
+pub fn cell_text(doc: &Document, column: &str) -> String {
+    match lookup(doc, column) {
+        None => String::new(),
+        Some(Bson::String(s)) => s.replace('\n', " "),
+        Some(Bson::ObjectId(id)) => id.to_hex(),
+        Some(Bson::DateTime(dt)) => dt
+            .try_to_rfc3339_string()
+            .unwrap_or_else(|_| dt.to_string()),
+        Some(value @ (Bson::Document(_) | Bson::Array(_))) => {
+            serde_json::to_string(&value.clone().into_relaxed_extjson())
+                .unwrap_or_else(|_| value.to_string())
+        }
+        Some(value) => value.to_string(),
+    }
+}
+
+/// Column indexes in the order the table draws them: the frozen column, if
+/// present, followed by the rest.
+pub fn display_order(columns: &[String], frozen: Option<&str>) -> Vec<usize> {
+    let pinned = frozen.and_then(|name| columns.iter().position(|c| c == name));
+    pinned
+        .into_iter()
+        .chain((0..columns.len()).filter(|&i| Some(i) != pinned))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("tsv") => ExportFormat::Tsv,
+            Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown") => {
+                ExportFormat::Markdown
+            }
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+/// Writes `documents` as a table of `columns`, formatting cells the way the
+/// table view shows them.
+pub fn write_table(columns: &[&str], documents: &[Document], path: &Path) -> Result<(), AppError> {
+    let rows: Vec<Vec<String>> = documents
+        .iter()
+        .map(|doc| columns.iter().map(|c| cell_text(doc, c)).collect())
+        .collect();
+    let contents = match ExportFormat::from_path(path) {
+        ExportFormat::Csv => render_delimited(columns, &rows, ','),
+        ExportFormat::Tsv => render_delimited(columns, &rows, '\t'),
+        ExportFormat::Markdown => render_markdown(columns, &rows),
+    };
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+fn render_delimited(columns: &[&str], rows: &[Vec<String>], separator: char) -> String {
+    let mut out = delimited_line(columns, separator);
+    for row in rows {
+        out.push_str(&delimited_line(row, separator));
+    }
+    out
+}
+
+/// One CSV or TSV record, newline included.
+pub fn delimited_line<S: AsRef<str>>(cells: &[S], separator: char) -> String {
+    let cells: Vec<String> = cells.iter().map(|c| quote(c.as_ref(), separator)).collect();
+    cells.join(&separator.to_string()) + "\n"
+}
+
+/// Dotted paths of the leaf fields of `doc`: embedded documents are
+/// descended into, arrays and everything else end a path.
+pub fn flatten_paths(doc: &Document) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_paths(doc, "", &mut paths);
+    paths
+}
+
+fn collect_paths(doc: &Document, prefix: &str, paths: &mut Vec<String>) {
+    for (key, value) in doc {
+        let path = format!("{}{}", prefix, key);
+        match value {
+            Bson::Document(inner) if !inner.is_empty() => {
+                collect_paths(inner, &format!("{}.", path), paths);
+            }
+            _ => paths.push(path),
+        }
+    }
+}
+
+// RFC 4180 quoting, only when the cell needs it
+fn quote(cell: &str, separator: char) -> String {
+    if cell.contains(separator) || cell.contains('"') || cell.contains('\n') {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn render_markdown(columns: &[&str], rows: &[Vec<String>]) -> String {
+    let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let escape = |cell: &str| cell.replace('|', "\\|");
+    let mut out = line(columns.iter().map(|c| escape(c)).collect());
+    out.push_str(&line(columns.iter().map(|_| "---".to_string()).collect()));
+    for row in rows {
+        out.push_str(&line(row.iter().map(|c| escape(c)).collect()));
+    }
+    out
+}
+
+/// Orders documents by `column` the way the server sorts mixed types;
+/// missing values sort first, like null.
+pub fn sort_documents(documents: &mut [Document], column: &str, descending: bool) {
+    documents.sort_by(|a, b| {
+        let ordering = compare(lookup(a, column), lookup(b, column));
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn compare(a: Option<&Bson>, b: Option<&Bson>) -> Ordering {
+    let (a, b) = (a.unwrap_or(&Bson::Null), b.unwrap_or(&Bson::Null));
+    type_rank(a).cmp(&type_rank(b)).then_with(|| match (a, b) {
+        (Bson::String(x), Bson::String(y)) => x.cmp(y),
+        (Bson::ObjectId(x), Bson::ObjectId(y)) => x.bytes().cmp(&y.bytes()),
+        (Bson::Boolean(x), Bson::Boolean(y)) => x.cmp(y),
+        (Bson::DateTime(x), Bson::DateTime(y)) => x.cmp(y),
+        _ => match (number_as_f64(a), number_as_f64(b)) {
+            (Some(x), Some(y)) => x.total_cmp(&y),
+            _ => a.to_string().cmp(&b.to_string()),
+        },
+    })
+}
+
+// BSON comparison order across types
+fn type_rank(value: &Bson) -> u8 {
+    match value {
+        Bson::MinKey => 0,
+        Bson::Null | Bson::Undefined => 1,
+        Bson::Double(_) | Bson::Int32(_) | Bson::Int64(_) | Bson::Decimal128(_) => 2,
+        Bson::Symbol(_) | Bson::String(_) => 3,
+        Bson::Document(_) => 4,
+        Bson::Array(_) => 5,
+        Bson::Binary(_) => 6,
+        Bson::ObjectId(_) => 7,
+        Bson::Boolean(_) => 8,
+        Bson::DateTime(_) => 9,
+        Bson::Timestamp(_) => 10,
+        Bson::RegularExpression(_) => 11,
+        Bson::MaxKey => 13,
+        _ => 12,
+    }
+}
@@ -0,0 +1,304 @@
+use std::{future::IntoFuture, time::Duration};
+
+use futures::stream::StreamExt;
+use mongodb::{
+    Client, Collection, Database,
+    bson::{Bson, Document, doc},
+    change_stream::{
+        ChangeStream,
+        event::{ChangeStreamEvent, OperationType, ResumeToken},
+    },
+    options::FullDocumentType,
+};
+
+use crate::error::AppError;
+use crate::models::WatchScope;
+
+const INITIAL_BACKLOG: i64 = 20;
+const POLL_BATCH: i64 = 100;
+
+/// What a change stream is opened on.
+enum Source {
+    Collection(Collection<Document>),
+    Database(Database),
+    Deployment(Client),
+}
+
+/// Follows new documents in a collection, preferring a change stream and
+/// falling back to polling on `_id` for standalone servers.
+pub struct Tailer {
+    source: Source,
+    stream: Option<ChangeStream<ChangeStreamEvent<Document>>>,
+    last_id: Option<Bson>,
+    pipeline: Vec<Document>,
+    /// Every insert, update and delete is reported, not just new documents.
+    watch_all: bool,
+    /// Where a paused change stream picks up again.
+    resume_token: Option<ResumeToken>,
+    paused: bool,
+}
+
+impl Tailer {
+    pub async fn start(
+        client: &Client,
+        db: &str,
+        collection: &str,
+    ) -> Result<(Self, Vec<Document>), AppError> {
+        let coll = client.database(db).collection::<Document>(collection);
+
+        // seed the view with the most recent documents, oldest first
+        let mut cursor = coll
+            .find(Document::new())
+            .sort(doc! { "_id": -1 })
+            .limit(INITIAL_BACKLOG)
+            .await
+            .map_err(|e| AppError::Query(format!("Failed to start tail: {}", e)))?;
+
+        let mut initial = Vec::new();
+        while let Some(result) = cursor.next().await {
+            let doc = result.map_err(|e| AppError::Query(format!("Failed to read: {}", e)))?;
+            initial.push(doc);
+        }
+        initial.reverse();
+
+        let mut tailer = Self {
+            source: Source::Collection(coll),
+            stream: None,
+            last_id: initial.last().and_then(|d| d.get("_id").cloned()),
+            pipeline: vec![doc! { "$match": { "operationType": "insert" } }],
+            watch_all: false,
+            resume_token: None,
+            paused: false,
+        };
+        // change streams need a replica set; standalone servers reject them
+        tailer.stream = tailer.open_stream().await.ok();
+
+        Ok((tailer, initial))
+    }
+
+    /// Follows inserts, updates and deletes through a change stream, keeping
+    /// those whose document matches `filter`. Deletes carry no document and
+    /// always pass.
+    pub async fn watch(
+        client: &Client,
+        db: &str,
+        collection: &str,
+        filter: Option<&Document>,
+    ) -> Result<Self, AppError> {
+        let operations =
+            doc! { "operationType": { "$in": ["insert", "update", "replace", "delete"] } };
+        let stage = match filter.filter(|f| !f.is_empty()) {
+            Some(filter) => doc! { "$and": [operations, {
+                "$or": [{ "operationType": "delete" }, prefix_fields(filter, "fullDocument")],
+            }] },
+            None => operations,
+        };
+
+        let source = Source::Collection(client.database(db).collection::<Document>(collection));
+        Self::watch_source(source, stage).await
+    }
+
+    /// Follows inserts, updates and deletes across a whole database or the
+    /// whole deployment. Events carry their namespace as `ns`.
+    pub async fn watch_scope(client: &Client, scope: &WatchScope) -> Result<Self, AppError> {
+        let source = match scope {
+            WatchScope::Collection => {
+                return Err(AppError::Query(
+                    "A collection watch needs its namespace".to_string(),
+                ));
+            }
+            WatchScope::Database(db) => Source::Database(client.database(db)),
+            WatchScope::Deployment => Source::Deployment(client.clone()),
+        };
+        let operations =
+            doc! { "operationType": { "$in": ["insert", "update", "replace", "delete"] } };
+        Self::watch_source(source, operations).await
+    }
+
+    async fn watch_source(source: Source, stage: Document) -> Result<Self, AppError> {
+        let mut tailer = Self {
+            source,
+            stream: None,
+            last_id: None,
+            pipeline: vec![doc! { "$match": stage }],
+            watch_all: true,
+            resume_token: None,
+            paused: false,
+        };
+        tailer.stream = Some(tailer.open_stream().await.map_err(|e| {
+            AppError::Query(format!(
+                "Change streams need a replica set or sharded cluster: {}",
+                e
+            ))
+        })?);
+        Ok(tailer)
+    }
+
+    // builds the watch up front so the returned future borrows only the
+    // source, not the tailer and its stream, and can run on the loader
+    fn open_stream(
+        &self,
+    ) -> impl Future<
+        Output = Result<ChangeStream<ChangeStreamEvent<Document>>, mongodb::error::Error>,
+    > + Send
+    + '_ {
+        let watch = match &self.source {
+            Source::Collection(collection) => collection.watch(),
+            Source::Database(database) => database.watch(),
+            Source::Deployment(client) => client.watch(),
+        };
+        let mut watch = watch
+            .pipeline(self.pipeline.clone())
+            .max_await_time(Duration::from_millis(50))
+            .resume_after(self.resume_token.clone());
+        if self.watch_all {
+            watch = watch.full_document(FullDocumentType::UpdateLookup);
+        }
+        watch.into_future()
+    }
+
+    /// Whether polls yield change events rather than the new documents.
+    pub fn reports_events(&self) -> bool {
+        self.watch_all
+    }
+
+    pub fn uses_change_stream(&self) -> bool {
+        self.stream.is_some() || self.resume_token.is_some()
+    }
+
+    /// Closes the change stream but keeps its resume token, so `resume`
+    /// replays whatever happened in between.
+    pub fn pause(&mut self) {
+        if let Some(stream) = self.stream.take() {
+            self.resume_token = stream.resume_token().or(self.resume_token.take());
+        }
+        self.paused = true;
+    }
+
+    pub async fn resume(&mut self) -> Result<(), AppError> {
+        if self.paused && self.resume_token.is_some() {
+            self.stream = Some(
+                self.open_stream()
+                    .await
+                    .map_err(|e| AppError::Query(format!("Failed to resume: {}", e)))?,
+            );
+        }
+        self.paused = false;
+        Ok(())
+    }
+
+    pub async fn poll(&mut self) -> Result<Vec<Document>, AppError> {
+        if let Some(stream) = self.stream.as_mut() {
+            let mut docs = Vec::new();
+            while let Some(event) = stream
+                .next_if_any()
+                .await
+                .map_err(|e| AppError::Query(format!("Change stream error: {}", e)))?
+            {
+                if self.watch_all {
+                    let wide = !matches!(self.source, Source::Collection(_));
+                    docs.push(event_document(event, wide));
+                } else if let Some(doc) = event.full_document {
+                    docs.push(doc);
+                }
+            }
+            self.resume_token = stream.resume_token();
+            if !self.watch_all
+                && let Some(id) = docs.last().and_then(|d| d.get("_id"))
+            {
+                self.last_id = Some(id.clone());
+            }
+            return Ok(docs);
+        }
+        let Source::Collection(collection) = &self.source else {
+            return Ok(Vec::new());
+        };
+        if self.watch_all || self.paused {
+            return Ok(Vec::new());
+        }
+
+        let filter = match &self.last_id {
+            Some(id) => doc! { "_id": { "$gt": id.clone() } },
+            None => Document::new(),
+        };
+
+        let mut cursor = collection
+            .find(filter)
+            .sort(doc! { "_id": 1 })
+            .limit(POLL_BATCH)
+            .await
+            .map_err(|e| AppError::Query(format!("Tail poll failed: {}", e)))?;
+
+        let mut docs = Vec::new();
+        while let Some(result) = cursor.next().await {
+            let doc = result.map_err(|e| AppError::Query(format!("Failed to read: {}", e)))?;
+            docs.push(doc);
+        }
+        if let Some(id) = docs.last().and_then(|d| d.get("_id")) {
+            self.last_id = Some(id.clone());
+        }
+        Ok(docs)
+    }
+}
+
+/// A change event trimmed to what the live view shows, with its namespace
+/// when the stream spans more than one collection.
+fn event_document(event: ChangeStreamEvent<Document>, with_ns: bool) -> Document {
+    let mut doc = doc! { "operationType": operation_name(&event.operation_type) };
+    if with_ns && let Some(ns) = event.ns {
+        doc.insert("ns", format!("{}.{}", ns.db, ns.coll.unwrap_or_default()));
+    }
+    if let Some(time) = event.wall_time {
+        doc.insert("wallTime", time);
+    }
+    if let Some(key) = event.document_key {
+        doc.insert("documentKey", key);
+    }
+    if let Some(update) = event.update_description {
+        doc.insert("updatedFields", update.updated_fields);
+        if !update.removed_fields.is_empty() {
+            doc.insert("removedFields", update.removed_fields);
+        }
+    } else if let Some(full) = event.full_document {
+        doc.insert("fullDocument", full);
+    }
+    doc
+}
+
+fn operation_name(operation: &OperationType) -> String {
+    match operation {
+        OperationType::Insert => "insert",
+        OperationType::Update => "update",
+        OperationType::Replace => "replace",
+        OperationType::Delete => "delete",
+        OperationType::Drop => "drop",
+        OperationType::Rename => "rename",
+        OperationType::DropDatabase => "dropDatabase",
+        OperationType::Invalidate => "invalidate",
+        OperationType::Other(name) => name,
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+/// Rewrites a find filter so its field paths point inside `prefix`, keeping
+/// `$and`/`$or`/`$nor` structure.
+fn prefix_fields(filter: &Document, prefix: &str) -> Document {
+    filter
+        .iter()
+        .map(|(key, value)| match (key.as_str(), value) {
+            ("$and" | "$or" | "$nor", Bson::Array(clauses)) => {
+                let clauses: Vec<Bson> = clauses
+                    .iter()
+                    .map(|clause| match clause {
+                        Bson::Document(d) => Bson::Document(prefix_fields(d, prefix)),
+                        other => other.clone(),
+                    })
+                    .collect();
+                (key.clone(), Bson::Array(clauses))
+            }
+            _ if key.starts_with('$') => (key.clone(), value.clone()),
+            _ => (format!("{}.{}", prefix, key), value.clone()),
+        })
+        .collect()
+}
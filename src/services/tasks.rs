@@ -0,0 +1,209 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use mongodb::{Client, bson::Bson};
+use tokio::task::AbortHandle;
+
+use crate::{
+    error::AppError,
+    models::{TaskInfo, TaskStatus},
+    services::operations,
+};
+
+/// Keeps track of long operations running on the tokio runtime so they can be
+/// listed, watched and cancelled from the tasks panel.
+#[derive(Clone, Default)]
+pub struct TaskRegistry {
+    inner: Arc<Mutex<Registry>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    tasks: Vec<Entry>,
+}
+
+struct Entry {
+    info: TaskInfo,
+    abort: Option<AbortHandle>,
+    notified: bool,
+    /// Where the task runs server operations tagged with its comment.
+    server: Option<Client>,
+}
+
+/// Handed to a running task so it can report how far along it is.
+#[derive(Clone)]
+pub struct TaskProgress {
+    id: u64,
+    inner: Arc<Mutex<Registry>>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns `work` in the background. The string it returns on success is
+    /// shown once the task finishes.
+    pub fn spawn<F, Fut>(&self, kind: &str, label: String, work: F) -> u64
+    where
+        F: FnOnce(TaskProgress) -> Fut,
+        Fut: Future<Output = Result<String, AppError>> + Send + 'static,
+    {
+        let id = {
+            let mut registry = self.inner.lock().unwrap();
+            registry.next_id += 1;
+            let id = registry.next_id;
+            registry.tasks.push(Entry {
+                info: TaskInfo {
+                    id,
+                    kind: kind.to_string(),
+                    label,
+                    status: TaskStatus::Running,
+                    progress: None,
+                    started: Instant::now(),
+                    finished: None,
+                },
+                abort: None,
+                notified: false,
+                server: None,
+            });
+            id
+        };
+
+        let progress = TaskProgress {
+            id,
+            inner: Arc::clone(&self.inner),
+        };
+        let future = work(progress);
+        let inner = Arc::clone(&self.inner);
+        let handle = tokio::spawn(async move {
+            let result = future.await;
+            let mut registry = inner.lock().unwrap();
+            if let Some(entry) = registry.tasks.iter_mut().find(|e| e.info.id == id) {
+                entry.info.status = match result {
+                    Ok(message) => TaskStatus::Completed(message),
+                    Err(e) => TaskStatus::Failed(e.to_string()),
+                };
+                entry.info.finished = Some(Instant::now());
+                entry.abort = None;
+                entry.server = None;
+            }
+        });
+
+        let mut registry = self.inner.lock().unwrap();
+        if let Some(entry) = registry.tasks.iter_mut().find(|e| e.info.id == id)
+            && entry.info.status == TaskStatus::Running
+        {
+            entry.abort = Some(handle.abort_handle());
+        }
+        id
+    }
+
+    /// Stops the task. Dropping its cursors closes them on the server; the
+    /// operations it tagged with [`TaskProgress::tag_operations`] are killed
+    /// there as well, so a long count or aggregation does not run on.
+    pub fn cancel(&self, id: u64) {
+        let mut registry = self.inner.lock().unwrap();
+        if let Some(entry) = registry.tasks.iter_mut().find(|e| e.info.id == id)
+            && entry.info.status == TaskStatus::Running
+        {
+            if let Some(abort) = entry.abort.take() {
+                abort.abort();
+            }
+            entry.info.status = TaskStatus::Cancelled;
+            entry.info.finished = Some(Instant::now());
+            if let Some(client) = entry.server.take() {
+                let inner = Arc::clone(&self.inner);
+                tokio::spawn(async move {
+                    if let Err(e) = operations::kill_tagged(&client, &comment(id)).await {
+                        let mut registry = inner.lock().unwrap();
+                        if let Some(entry) = registry.tasks.iter_mut().find(|e| e.info.id == id) {
+                            entry.info.status = TaskStatus::Failed(format!(
+                                "cancelled here, but may still run on the server: {}",
+                                e
+                            ));
+                            entry.notified = false;
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    /// Drops every task that is no longer running.
+    pub fn clear_finished(&self) {
+        let mut registry = self.inner.lock().unwrap();
+        registry
+            .tasks
+            .retain(|e| e.info.status == TaskStatus::Running);
+    }
+
+    pub fn snapshot(&self) -> Vec<TaskInfo> {
+        let registry = self.inner.lock().unwrap();
+        registry.tasks.iter().map(|e| e.info.clone()).collect()
+    }
+
+    /// Tasks that finished since the last call, each reported once.
+    pub fn take_finished(&self) -> Vec<TaskInfo> {
+        let mut registry = self.inner.lock().unwrap();
+        registry
+            .tasks
+            .iter_mut()
+            .filter(|e| e.info.status != TaskStatus::Running && !e.notified)
+            .map(|e| {
+                e.notified = true;
+                e.info.clone()
+            })
+            .collect()
+    }
+}
+
+impl TaskProgress {
+    /// Notes that the task runs operations on `client` and returns the
+    /// `comment` to give them, by which cancelling finds them on the server.
+    pub fn tag_operations(&self, client: &Client) -> Bson {
+        let mut registry = self.inner.lock().unwrap();
+        if let Some(entry) = registry.tasks.iter_mut().find(|e| e.info.id == self.id)
+            && entry.info.status == TaskStatus::Running
+        {
+            entry.server = Some(client.clone());
+        }
+        Bson::String(comment(self.id))
+    }
+
+    pub fn set(&self, done: u64, total: Option<u64>) {
+        let mut registry = self.inner.lock().unwrap();
+        if let Some(entry) = registry.tasks.iter_mut().find(|e| e.info.id == self.id) {
+            entry.info.progress = Some((done, total));
+        }
+    }
+}
+
+// the comment a task's server operations carry
+fn comment(id: u64) -> String {
+    format!(
+        "monjo-kompass task {} of process {}",
+        id,
+        std::process::id()
+    )
+}
+
+impl TaskInfo {
+    pub fn elapsed(&self) -> std::time::Duration {
+        self.finished
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.started)
+    }
+
+    /// Completed fraction, when the task knows its total.
+    pub fn ratio(&self) -> Option<f64> {
+        match self.progress {
+            Some((done, Some(total))) if total > 0 => Some((done as f64 / total as f64).min(1.0)),
+            _ => None,
+        }
+    }
+}
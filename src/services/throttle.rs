@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+use crate::config::RateLimit;
+
+/// Paces bulk operations to a profile's rate limit by sleeping whenever the
+/// work gets ahead of the allowed documents or batches per second.
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    limit: Option<RateLimit>,
+    started: Instant,
+    docs: u64,
+    batches: u64,
+}
+
+impl Throttle {
+    pub fn new(limit: Option<RateLimit>) -> Self {
+        Self {
+            limit,
+            started: Instant::now(),
+            docs: 0,
+            batches: 0,
+        }
+    }
+
+    /// Records one batch of `docs` documents and waits until the running
+    /// totals are back under the limit.
+    pub async fn pace(&mut self, docs: u64) {
+        self.docs += docs;
+        self.batches += 1;
+
+        let Some(limit) = &self.limit else {
+            return;
+        };
+
+        let mut due = Duration::ZERO;
+        if let Some(rate) = limit.docs_per_sec.filter(|r| *r > 0.0) {
+            due = due.max(time_for(self.docs, rate));
+        }
+        if let Some(rate) = limit.batches_per_sec.filter(|r| *r > 0.0) {
+            due = due.max(time_for(self.batches, rate));
+        }
+
+        let elapsed = self.started.elapsed();
+        if due > elapsed {
+            tokio::time::sleep(due - elapsed).await;
+        }
+    }
+}
+
+// how long `count` items take at `rate` per second; a rate so small that
+// this overflows a Duration waits as long as sleep allows
+fn time_for(count: u64, rate: f64) -> Duration {
+    Duration::try_from_secs_f64(count as f64 / rate).unwrap_or(Duration::MAX)
+}
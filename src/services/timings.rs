@@ -0,0 +1,190 @@
+use std::{
+    collections::{HashMap, VecDeque, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use mongodb::{
+    bson::{Bson, DateTime, Document},
+    event::command::CommandEvent,
+};
+
+use crate::models::{QueryTiming, TimingGroup};
+use crate::services::schema;
+
+const MAX_TIMINGS: usize = 500;
+
+/// Commands whose run time says something about a query's plan.
+const TIMED_COMMANDS: [&str; 4] = ["find", "aggregate", "count", "distinct"];
+
+/// Run times of the queries sent on one connection, from the driver's
+/// command monitoring.
+#[derive(Debug, Clone, Default)]
+pub struct QueryTimings {
+    inner: Arc<Mutex<Timings>>,
+}
+
+#[derive(Debug, Default)]
+struct Timings {
+    /// Namespace, command and shape of commands still in flight, by request id.
+    started: HashMap<i32, (String, String, String)>,
+    finished: VecDeque<QueryTiming>,
+}
+
+impl QueryTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, event: &CommandEvent) {
+        let mut timings = self.inner.lock().unwrap();
+        match event {
+            CommandEvent::Started(started)
+                if TIMED_COMMANDS.contains(&started.command_name.as_str()) =>
+            {
+                // database-wide aggregations name no collection
+                let collection = started
+                    .command
+                    .get_str(&started.command_name)
+                    .unwrap_or("(database)");
+                timings.started.insert(
+                    started.request_id,
+                    (
+                        format!("{}.{}", started.db, collection),
+                        started.command_name.clone(),
+                        query_shape(&started.command),
+                    ),
+                );
+            }
+            CommandEvent::Succeeded(succeeded) => {
+                timings.finish(succeeded.request_id, succeeded.duration, false);
+            }
+            CommandEvent::Failed(failed) => {
+                timings.finish(failed.request_id, failed.duration, true);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<QueryTiming> {
+        self.inner
+            .lock()
+            .unwrap()
+            .finished
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().finished.clear();
+    }
+}
+
+impl Timings {
+    fn finish(&mut self, request_id: i32, duration: Duration, failed: bool) {
+        let Some((namespace, command, shape)) = self.started.remove(&request_id) else {
+            return;
+        };
+        self.finished.push_back(QueryTiming {
+            at: DateTime::now(),
+            namespace,
+            command,
+            shape_hash: hash(&shape),
+            shape,
+            duration,
+            failed,
+        });
+        if self.finished.len() > MAX_TIMINGS {
+            self.finished.pop_front();
+        }
+    }
+}
+
+/// The parts of a command that decide its plan, with values replaced by
+/// their types so reruns with other values group together.
+fn query_shape(command: &Document) -> String {
+    ["filter", "query", "key", "sort", "projection", "pipeline"]
+        .iter()
+        .filter_map(|key| {
+            command
+                .get(*key)
+                .map(|value| format!("{} {}", key, shape(value)))
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+fn shape(value: &Bson) -> Bson {
+    match value {
+        Bson::Document(doc) => Bson::Document(
+            doc.iter()
+                .map(|(key, inner)| (key.clone(), shape(inner)))
+                .collect(),
+        ),
+        Bson::Array(items) if items.iter().any(|i| matches!(i, Bson::Document(_))) => {
+            Bson::Array(items.iter().map(shape).collect())
+        }
+        // scalar lists such as $in operands vary in length, not in shape
+        Bson::Array(_) => Bson::String("array".to_string()),
+        other => Bson::String(schema::type_name(other).to_string()),
+    }
+}
+
+fn hash(shape: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    shape.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Timings grouped by namespace, command and query shape, most recently run
+/// first. Durations within a group stay in the order they ran.
+pub fn group(timings: &[QueryTiming]) -> Vec<TimingGroup> {
+    let mut groups: Vec<TimingGroup> = Vec::new();
+    for timing in timings {
+        let existing = groups.iter_mut().find(|g| {
+            g.namespace == timing.namespace
+                && g.command == timing.command
+                && g.shape_hash == timing.shape_hash
+        });
+        let group = match existing {
+            Some(group) => group,
+            None => {
+                groups.push(TimingGroup {
+                    namespace: timing.namespace.clone(),
+                    command: timing.command.clone(),
+                    shape: timing.shape.clone(),
+                    shape_hash: timing.shape_hash,
+                    durations: Vec::new(),
+                    failures: 0,
+                    last_run: timing.at,
+                });
+                groups.last_mut().expect("just pushed")
+            }
+        };
+        group.durations.push(timing.duration);
+        group.last_run = timing.at;
+        if timing.failed {
+            group.failures += 1;
+        }
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.last_run));
+    groups
+}
+
+impl TimingGroup {
+    pub fn min(&self) -> Duration {
+        self.durations.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.durations.clone();
+        sorted.sort();
+        sorted.get(sorted.len() / 2).copied().unwrap_or_default()
+    }
+
+    pub fn last(&self) -> Duration {
+        self.durations.last().copied().unwrap_or_default()
+    }
+}
@@ -0,0 +1,219 @@
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+use crate::error::AppError;
+use crate::models::{RoleInfo, RolePicker, RolePrivilege, RoleRef, UserInfo};
+
+/// The users defined on `db`, by name.
+pub async fn list_users(client: &Client, db: &str) -> Result<Vec<UserInfo>, AppError> {
+    let reply = client
+        .database(db)
+        .run_command(doc! { "usersInfo": 1 })
+        .await
+        .map_err(|e| AppError::Query(format!("usersInfo failed: {}", e)))?;
+    let mut users: Vec<UserInfo> = documents(&reply, "users")
+        .map(|user| UserInfo {
+            user: user.get_str("user").unwrap_or("?").to_string(),
+            db: user.get_str("db").unwrap_or(db).to_string(),
+            roles: role_refs(user, "roles"),
+            mechanisms: strings(user, "mechanisms"),
+        })
+        .collect();
+    users.sort_by(|a, b| a.user.cmp(&b.user));
+    Ok(users)
+}
+
+/// The roles that can be granted on `db`, custom ones first, each with its
+/// privileges and the roles it inherits.
+pub async fn list_roles(client: &Client, db: &str) -> Result<Vec<RoleInfo>, AppError> {
+    let reply = client
+        .database(db)
+        .run_command(doc! {
+            "rolesInfo": 1,
+            "showBuiltinRoles": true,
+            "showPrivileges": true,
+        })
+        .await
+        .map_err(|e| AppError::Query(format!("rolesInfo failed: {}", e)))?;
+    let mut roles: Vec<RoleInfo> = documents(&reply, "roles")
+        .map(|role| RoleInfo {
+            role: role.get_str("role").unwrap_or("?").to_string(),
+            db: role.get_str("db").unwrap_or(db).to_string(),
+            built_in: role.get_bool("isBuiltin").unwrap_or(false),
+            inherited: role_refs(role, "roles"),
+            privileges: documents(role, "privileges")
+                .map(|privilege| RolePrivilege {
+                    resource: privilege
+                        .get_document("resource")
+                        .map(resource_label)
+                        .unwrap_or_default(),
+                    actions: strings(privilege, "actions"),
+                })
+                .collect(),
+        })
+        .collect();
+    roles.sort_by(|a, b| (a.built_in, &a.role).cmp(&(b.built_in, &b.role)));
+    Ok(roles)
+}
+
+/// A change to the users of a database, made from the users screen.
+#[derive(Debug, Clone)]
+pub enum UserChange {
+    Create(RolePicker),
+    ChangePassword { user: String, password: String },
+    Drop(String),
+}
+
+impl UserChange {
+    pub fn command(&self) -> Document {
+        match self {
+            UserChange::Create(picker) => {
+                let roles: Vec<Bson> = picker
+                    .checked_roles()
+                    .map(|r| Bson::Document(doc! { "role": &r.role, "db": &r.db }))
+                    .collect();
+                doc! {
+                    "createUser": &picker.user,
+                    "pwd": &picker.password,
+                    "roles": roles,
+                }
+            }
+            UserChange::ChangePassword { user, password } => {
+                doc! { "updateUser": user, "pwd": password }
+            }
+            UserChange::Drop(user) => doc! { "dropUser": user },
+        }
+    }
+
+    /// The command with its password replaced, for showing instead of sending.
+    pub fn redacted(&self) -> Document {
+        let mut command = self.command();
+        if command.contains_key("pwd") {
+            command.insert("pwd", "********");
+        }
+        command
+    }
+
+    pub async fn apply(self, client: &Client, db: &str) -> Result<String, AppError> {
+        let command = self.command();
+        let name = command.keys().next().cloned().unwrap_or_default();
+        client
+            .database(db)
+            .run_command(command)
+            .await
+            .map_err(|e| AppError::Query(format!("{} failed: {}", name, e)))?;
+        Ok(match self {
+            UserChange::Create(picker) => format!("Created user {}", picker.user),
+            UserChange::ChangePassword { user, .. } => format!("Changed the password of {}", user),
+            UserChange::Drop(user) => format!("Dropped user {}", user),
+        })
+    }
+}
+
+impl RolePicker {
+    /// Nothing is checked at first; a user without roles can only log in.
+    pub fn new(user: String, password: String, roles: &[RoleInfo]) -> Self {
+        let roles: Vec<RoleRef> = roles
+            .iter()
+            .map(|r| RoleRef {
+                role: r.role.clone(),
+                db: r.db.clone(),
+            })
+            .collect();
+        Self {
+            user,
+            password,
+            checked: vec![false; roles.len()],
+            roles,
+            selected: 0,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.roles.is_empty() {
+            self.selected = (self.selected + 1) % self.roles.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.roles.is_empty() {
+            self.selected = self.selected.checked_sub(1).unwrap_or(self.roles.len() - 1);
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(checked) = self.checked.get_mut(self.selected) {
+            *checked = !*checked;
+        }
+    }
+
+    pub fn checked_roles(&self) -> impl Iterator<Item = &RoleRef> {
+        self.roles
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, checked)| **checked)
+            .map(|(role, _)| role)
+    }
+}
+
+impl RoleRef {
+    /// `role` on its own database, `role@db` elsewhere.
+    pub fn label(&self, db: &str) -> String {
+        if self.db == db {
+            self.role.clone()
+        } else {
+            format!("{}@{}", self.role, self.db)
+        }
+    }
+}
+
+fn documents<'a>(document: &'a Document, key: &str) -> impl Iterator<Item = &'a Document> {
+    document
+        .get_array(key)
+        .into_iter()
+        .flatten()
+        .filter_map(Bson::as_document)
+}
+
+fn strings(document: &Document, key: &str) -> Vec<String> {
+    document
+        .get_array(key)
+        .into_iter()
+        .flatten()
+        .filter_map(Bson::as_str)
+        .map(String::from)
+        .collect()
+}
+
+fn role_refs(document: &Document, key: &str) -> Vec<RoleRef> {
+    documents(document, key)
+        .filter_map(|r| {
+            Some(RoleRef {
+                role: r.get_str("role").ok()?.to_string(),
+                db: r.get_str("db").ok()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn resource_label(resource: &Document) -> String {
+    if resource.get_bool("cluster").unwrap_or(false) {
+        return "cluster".to_string();
+    }
+    if resource.get_bool("anyResource").unwrap_or(false) {
+        return "any resource".to_string();
+    }
+    let db = resource.get_str("db").unwrap_or("");
+    let collection = resource.get_str("collection").unwrap_or("");
+    format!(
+        "{}.{}",
+        if db.is_empty() { "*" } else { db },
+        if collection.is_empty() {
+            "*"
+        } else {
+            collection
+        }
+    )
+}
@@ -0,0 +1,99 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{Compatibility, FeatureSupport, Theme};
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Length(6), // Versions
+            Constraint::Min(0),    // Features
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    let header = Paragraph::new(" About and compatibility ")
+        .style(
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(header, chunks[0]);
+
+    if let Some(compatibility) = &state.compatibility {
+        render_versions(f, chunks[1], compatibility, theme);
+        render_features(f, chunks[2], &compatibility.features, theme);
+    }
+    render_footer(f, chunks[3], theme);
+}
+
+fn render_versions(f: &mut Frame, area: Rect, compatibility: &Compatibility, theme: &Theme) {
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<16}", label), Style::default().fg(theme.dim)),
+            Span::styled(value, Style::default().fg(theme.text)),
+        ])
+    };
+    let lines = vec![
+        row("kompass", compatibility.app_version.clone()),
+        row("Driver", compatibility.driver_version.clone()),
+        row("Server", compatibility.server_version.clone()),
+        row(
+            "FCV",
+            compatibility
+                .fcv
+                .clone()
+                .unwrap_or_else(|| "(not permitted to read)".to_string()),
+        ),
+        row("Topology", compatibility.topology.name().to_string()),
+    ];
+
+    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(widget, area);
+}
+
+fn render_features(f: &mut Frame, area: Rect, features: &[FeatureSupport], theme: &Theme) {
+    let items: Vec<ListItem> = features
+        .iter()
+        .map(|feature| {
+            let (mark, color, note) = if feature.supported {
+                ("✓", theme.success, String::new())
+            } else {
+                ("✗", theme.error, format!("needs {}", feature.requirement))
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("  {} ", mark), Style::default().fg(color)),
+                Span::styled(
+                    format!("{:<36}", feature.feature),
+                    Style::default().fg(theme.text),
+                ),
+                Span::styled(note, Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(" Features on this server ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+    f.render_widget(list, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, theme: &Theme) {
+    let footer_text = i18n::hints(" [r] Refresh  [Esc] Back ");
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
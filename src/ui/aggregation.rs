@@ -0,0 +1,224 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use mongodb::bson::Document;
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::Theme;
+use crate::services::aggregation;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Body
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[1]);
+
+    render_header(f, chunks[0], state);
+    render_stages(f, body[0], state);
+    render_preview(f, body[1], state);
+    render_footer(f, chunks[2], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let header = Paragraph::new(format!(
+        " Aggregation on {} ",
+        state.pipeline_namespace.as_deref().unwrap_or("")
+    ))
+    .style(
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_stages(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let items: Vec<ListItem> = state
+        .pipeline
+        .iter()
+        .enumerate()
+        .map(|(i, stage)| {
+            let style = if stage.enabled {
+                Style::default().fg(theme.text)
+            } else {
+                Style::default()
+                    .fg(theme.muted)
+                    .add_modifier(Modifier::CROSSED_OUT)
+            };
+            let marker = if state.preview_stage == Some(i) {
+                "▶"
+            } else {
+                " "
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{}{:>2} ", marker, i + 1),
+                    Style::default().fg(theme.success),
+                ),
+                Span::styled(
+                    format!("{:<13}", stage.operator),
+                    style.add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(stage.body.clone(), style),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(" Stages ({}) ", state.pipeline.len()))
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(theme.background(theme.selection))
+        .highlight_symbol("> ");
+
+    let mut list_state = ListState::default();
+    if !state.pipeline.is_empty() {
+        list_state.select(Some(state.selected_stage_index));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_preview(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    if state.is_editing_stage() {
+        render_draft_preview(f, area, state);
+        return;
+    }
+    let (title, lines) = match state.preview_stage {
+        Some(upto) => {
+            let mut lines = document_lines(&state.aggregation_preview, theme);
+            if lines.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No documents reach this stage",
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            (
+                format!(
+                    " Output after stage {} (first {} documents) ",
+                    upto + 1,
+                    state.aggregation_preview.len()
+                ),
+                lines,
+            )
+        }
+        None => (
+            " Output ".to_string(),
+            vec![Line::from(Span::styled(
+                "Select a stage and press [r] to run the pipeline up to it",
+                Style::default().fg(theme.muted),
+            ))],
+        ),
+    };
+
+    let lines: Vec<Line> = lines.into_iter().skip(state.aggregation_scroll).collect();
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::LEFT)
+            .title(title)
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(widget, area);
+}
+
+// the live preview shown while the selected stage's body is being typed
+fn render_draft_preview(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let stale = state.draft_preview.as_ref().map(|d| &d.body) != state.draft_requested.as_ref();
+    let mut lines = match state.draft_preview.as_ref().map(|d| &d.result) {
+        Some(Ok(documents)) if documents.is_empty() => vec![Line::from(Span::styled(
+            "No sampled documents reach this stage",
+            Style::default().fg(theme.muted),
+        ))],
+        Some(Ok(documents)) => document_lines(documents, theme),
+        Some(Err(e)) => vec![Line::from(Span::styled(
+            e.clone(),
+            Style::default().fg(theme.error),
+        ))],
+        None => vec![Line::from(Span::styled(
+            "Pause typing to preview this stage",
+            Style::default().fg(theme.muted),
+        ))],
+    };
+    if stale && state.draft_requested.is_some() {
+        lines.insert(
+            0,
+            Line::from(Span::styled(
+                format!("{} Running...", state.spinner()),
+                Style::default().fg(theme.highlight),
+            )),
+        );
+    }
+
+    let title = format!(
+        " Live preview after stage {} (first {} results over {} documents) ",
+        state.selected_stage_index + 1,
+        aggregation::DRAFT_LIMIT,
+        aggregation::DRAFT_SAMPLE
+    );
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::LEFT)
+            .title(title)
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(widget, area);
+}
+
+fn document_lines(documents: &[Document], theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for (i, doc) in documents.iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::from(Span::styled(
+                "────",
+                Style::default().fg(theme.muted),
+            )));
+        }
+        let json = serde_json::to_string_pretty(doc).unwrap_or_else(|_| format!("{:?}", doc));
+        lines.extend(json.lines().map(|l| Line::from(l.to_string())));
+    }
+    lines
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let (footer_text, color) = if let Some(error) = &state.error {
+        (format!(" Error: {} ", error), theme.error)
+    } else if state.loading {
+        (format!(" {} Running... ", state.spinner()), theme.highlight)
+    } else if let Some(message) = &state.message {
+        (format!(" {} ", message), theme.success)
+    } else {
+        (
+            i18n::hints(" [a] Add stage  [Enter] Edit  [d] Delete  [Space] Enable/Disable  [J/K] Move  [r] Run to stage  [R] Run all in the background  [PgUp/PgDn] Scroll  [Esc] Back ")
+                .to_string(),
+            theme.muted,
+        )
+    };
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(color));
+
+    f.render_widget(footer, area);
+}
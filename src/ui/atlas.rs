@@ -0,0 +1,159 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::Theme;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Projects / clusters
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    if state.atlas_project.is_some() {
+        render_clusters(f, chunks[1], state);
+    } else {
+        render_projects(f, chunks[1], state);
+    }
+    render_footer(f, chunks[2], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let location = match &state.atlas_project {
+        Some(project) => format!(" Atlas › {} ", project.name),
+        None => " Atlas › Projects ".to_string(),
+    };
+
+    let header = Paragraph::new(Line::from(Span::styled(
+        location,
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )))
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_projects(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let items: Vec<ListItem> = state
+        .atlas_projects
+        .iter()
+        .map(|project| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {:<40}", project.name),
+                    Style::default().fg(theme.text),
+                ),
+                Span::styled(project.id.clone(), Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+
+    render_list(
+        f,
+        area,
+        items,
+        " Projects ",
+        state.selected_atlas_index,
+        theme,
+    );
+}
+
+fn render_clusters(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let items: Vec<ListItem> = state
+        .atlas_clusters
+        .iter()
+        .map(|cluster| {
+            let state_color = match cluster.state.as_str() {
+                "IDLE" => theme.success,
+                "CREATING" | "UPDATING" | "REPAIRING" => theme.highlight,
+                _ => theme.error,
+            };
+            let address = cluster
+                .srv_uri
+                .clone()
+                .or_else(|| cluster.standard_uri.clone())
+                .unwrap_or_else(|| "no connection string yet".to_string());
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {:<24}", cluster.name),
+                    Style::default().fg(theme.text),
+                ),
+                Span::styled(
+                    format!("{:<12}", cluster.state),
+                    Style::default().fg(state_color),
+                ),
+                Span::styled(
+                    format!("{:<10}", cluster.version),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::styled(address, Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+
+    render_list(
+        f,
+        area,
+        items,
+        " Clusters ",
+        state.selected_atlas_index,
+        theme,
+    );
+}
+
+fn render_list(
+    f: &mut Frame,
+    area: Rect,
+    items: Vec<ListItem>,
+    title: &str,
+    selected: usize,
+    theme: &Theme,
+) {
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title.to_string())
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(selected));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let text = if let Some(error) = &state.error {
+        format!(" Error: {}", error)
+    } else if state.atlas_project.is_some() {
+        i18n::hints(" [Enter] Use connection string  [Esc] Projects  [j/k] Navigate ")
+    } else {
+        i18n::hints(" [Enter] Open project  [Esc] Back  [j/k] Navigate ")
+    };
+    let footer = Paragraph::new(text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
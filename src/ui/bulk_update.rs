@@ -0,0 +1,146 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::BulkUpdateField;
+use crate::ui::highlight;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Length(5), // Form
+            Constraint::Min(0),    // Preview
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_form(f, chunks[1], state);
+    render_preview(f, chunks[2], state);
+    render_footer(f, chunks[3], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let namespace = format!(
+        "{}.{}",
+        state.current_database.as_deref().unwrap_or(""),
+        state.current_collection.as_deref().unwrap_or("")
+    );
+    let dry_run = if state.dry_run { "[dry run] " } else { "" };
+    let header = Paragraph::new(format!(" Bulk update of {} {}", namespace, dry_run))
+        .style(
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_form(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let form = &state.bulk_update;
+    let field = |label: &str, value: &str, field: BulkUpdateField| {
+        let (value, style) = if form.focus == field {
+            (format!("{}_", value), Style::default().fg(theme.highlight))
+        } else {
+            (value.to_string(), Style::default().fg(theme.text))
+        };
+        Line::from(vec![
+            Span::styled(format!("  {:<8}", label), Style::default().fg(theme.dim)),
+            Span::styled(value, style),
+        ])
+    };
+
+    let mut lines = vec![
+        field("Filter", &form.filter, BulkUpdateField::Filter),
+        field("Update", &form.update, BulkUpdateField::Update),
+    ];
+    if let Some(error) = &state.error {
+        lines.push(Line::from(Span::styled(
+            format!("  Error: {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+
+    let widget = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(widget, area);
+}
+
+fn render_preview(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let form = &state.bulk_update;
+    let mut lines: Vec<Line> = Vec::new();
+    let preview = form.preview.as_ref().filter(|_| form.is_previewed());
+    match preview {
+        None => lines.push(Line::from(Span::styled(
+            " Press Enter to count the matching documents. An empty filter matches all of them.",
+            Style::default().fg(theme.muted),
+        ))),
+        Some(preview) => {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" {} document(s) match", preview.matched),
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    if preview.matched > 0 {
+                        "; Enter again to update them"
+                    } else {
+                        ""
+                    },
+                    Style::default().fg(theme.muted),
+                ),
+            ]));
+            lines.push(Line::from(""));
+            for doc in &preview.sample {
+                lines.extend(highlight::document_lines(doc, state.value_format, theme));
+                lines.push(Line::from(""));
+            }
+        }
+    }
+
+    let title = match preview {
+        Some(preview) => format!(" First {} match(es) ", preview.sample.len()),
+        None => " Preview ".to_string(),
+    };
+    let scroll = form.scroll.min(lines.len().saturating_sub(1));
+    let widget = Paragraph::new(lines.split_off(scroll))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(Style::default().fg(theme.dim)),
+        );
+    f.render_widget(widget, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let enter = if state.bulk_update.is_previewed() {
+        "[Enter] Update"
+    } else {
+        "[Enter] Preview"
+    };
+    let footer_text = i18n::hints(&format!(
+        " [Tab] Filter/Update  {}  [PgUp/PgDn] Scroll  [Esc] Back ",
+        enter
+    ));
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
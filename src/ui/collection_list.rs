@@ -1,14 +1,22 @@
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
 use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{
+    CollectionForm, CollectionHeat, CollectionStats, FormField, ShardKeyAnalysis, Theme,
+};
+use crate::services::metrics::format_bytes;
+use crate::ui::empty;
+use crate::ui::popup::{self, centered_rect};
 
 pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -20,24 +28,53 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
 
     render_header(f, chunks[0], state);
     render_collection_list(f, chunks[1], state);
-    render_footer(f, chunks[2]);
+    render_footer(f, chunks[2], state);
+
+    if state.show_permissions {
+        render_permissions(f, area, state);
+    }
+    if let Some(analysis) = &state.shard_key_analysis {
+        render_shard_key(f, area, analysis, theme);
+    }
+    if let Some(stats) = &state.collection_stats {
+        render_stats(f, area, stats, state.collection_stats_scroll, theme);
+    }
+    if let Some(form) = &state.collection_form {
+        render_collection_form(f, area, form, state.error.as_deref(), theme);
+    }
 }
 
 fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let title = if let Some(db) = &state.current_database {
-        format!(" Database: {} ", db)
+        let dry_run = if state.dry_run { "[dry run] " } else { "" };
+        format!(" Database: {} {}", db, dry_run)
     } else {
         " No database selected ".to_string()
     };
 
     let header = Paragraph::new(title)
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .style(Style::default().fg(theme.header).add_modifier(Modifier::BOLD))
         .block(Block::default().borders(Borders::BOTTOM));
 
     f.render_widget(header, area);
 }
 
 fn render_collection_list(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let block = Block::default().title(" Collections ").title_style(Style::default().fg(theme.dim));
+    if state.collections.is_empty() && !state.loading {
+        empty::render(
+            f,
+            area,
+            block,
+            "This database has no collections",
+            &["Press n to create one, or r to refresh."],
+            theme,
+        );
+        return;
+    }
+
     let items: Vec<ListItem> = state
         .collections
         .iter()
@@ -48,37 +85,307 @@ fn render_collection_list(f: &mut Frame, area: Rect, state: &AppState) {
             } else {
                 "  "
             };
+            let mark = if state.marked_collections.contains(&coll.name) {
+                "● "
+            } else {
+                ""
+            };
 
-            let content = format!(
+            let mut content = format!(
                 "{}{} ({} documents, {} indexes)",
-                prefix,
+                mark,
                 coll.name,
                 coll.document_count,
                 coll.indexes.len()
             );
+            for (flag, badge) in [
+                (coll.capped, "capped"),
+                (coll.clustered, "clustered"),
+                (coll.pre_and_post_images, "pre/post images"),
+            ] {
+                if flag {
+                    content.push_str(&format!(" [{}]", badge));
+                }
+            }
+            if !coll.encrypted_fields.is_empty() {
+                content.push_str(&format!(" [🔒 {}]", coll.encrypted_fields.join(", ")));
+            }
 
             let style = if i == state.selected_coll_index {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             };
-            ListItem::new(Line::from(Span::styled(content, style)))
+            let heat = state.collection_heat.get(&coll.name);
+            if let Some(heat) = heat.filter(|h| h.level() > 0) {
+                content.push_str(&format!(
+                    " [{:.1} reads/s, {:.1} writes/s]",
+                    heat.reads, heat.writes
+                ));
+            }
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, style),
+                heat_bar(heat, theme),
+                Span::styled(content, style),
+            ]))
         })
         .collect();
     
     let list = List::new(items)
-        .block(Block::default().title(" Collections ").title_style(Style::default().fg(Color::Gray)))
-        .style(Style::default().fg(Color::White));
+        .block(block)
+        .style(Style::default().fg(theme.text));
         
     f.render_widget(list, area);
 }
 
-fn render_footer(f: &mut Frame, area: Rect) {
-    let footer_text = " [q] Quit  [↑/↓] Navigate  [Enter] View Docs  [Back] Go Back  [r] Refresh ";
+// a bar that grows with the collection's reads and writes since the last sample
+fn heat_bar(heat: Option<&CollectionHeat>, theme: &Theme) -> Span<'static> {
+    let (bar, color) = match heat.map_or(0, |h| h.level()) {
+        3 => ("█ ", theme.error),
+        2 => ("▅ ", theme.warning),
+        1 => ("▂ ", theme.highlight),
+        _ => ("  ", theme.muted),
+    };
+    Span::styled(bar, Style::default().fg(color))
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let (footer_text, color) = if let Some(error) = &state.error {
+        (format!(" Error: {} ", error), theme.error)
+    } else if state.loading {
+        (format!(" {} Loading... ", state.spinner()), theme.highlight)
+    } else if let Some(message) = &state.message {
+        (format!(" {} ", message), theme.success)
+    } else {
+        (
+            i18n::hints(" [q] Quit  [↑/↓] Navigate  [Enter] View Docs  [T] New tab  [Back] Go Back  [t] Tail  [W] Watch all  [a] Schema  [R] Report  [n] New  [I] Pre/post images  [P] Permissions  [i] Stats  [K] Shard key  [B] Sharding  [Space] Mark  [X] Export  [p] Replay  [V] Diff file  [m] Rename  [c] Duplicate  [D] Drop  [r] Refresh "),
+            theme.muted,
+        )
+    };
     let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray).bg(Color::Black));
+        .style(theme.background(theme.bar).fg(color));
 
     f.render_widget(footer, area);
 }
+
+fn render_permissions(f: &mut Frame, area: Rect, state: &AppState) {
+    let namespace = format!(
+        "{}.{}",
+        state.current_database.as_deref().unwrap_or(""),
+        state.get_selected_collection().map(|c| c.name.as_str()).unwrap_or("")
+    );
+    popup::render_permissions(f, area, state, &format!(" Permissions on {} ", namespace), Vec::new());
+}
+
+fn render_shard_key(f: &mut Frame, area: Rect, analysis: &ShardKeyAnalysis, theme: &Theme) {
+    let key = if analysis.candidate.hashed {
+        format!("{{ {}: \"hashed\" }}", analysis.candidate.fields[0])
+    } else {
+        format!("{{ {} }}", analysis.candidate.fields.join(": 1, ") + ": 1")
+    };
+
+    let mut text = vec![
+        Line::from(vec![
+            Span::styled(" Key ", Style::default().fg(theme.dim)),
+            Span::styled(key, Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                " {} sampled, {} distinct, {} missing the key",
+                analysis.sampled, analysis.distinct, analysis.missing
+            ),
+            Style::default().fg(theme.dim),
+        )),
+    ];
+    if let Some(ratio) = analysis.monotonic_ratio {
+        text.push(Line::from(Span::styled(
+            format!(" {:.0}% of recent inserts increase the key", ratio * 100.0),
+            Style::default().fg(theme.dim),
+        )));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(" Most frequent values", Style::default().fg(theme.accent))));
+    for (value, count) in &analysis.top_values {
+        text.push(Line::from(vec![
+            Span::styled(format!(" {:>8}  ", count), Style::default().fg(theme.highlight)),
+            Span::styled(value.clone(), Style::default().fg(theme.text)),
+        ]));
+    }
+
+    text.push(Line::from(""));
+    if analysis.warnings.is_empty() {
+        text.push(Line::from(Span::styled(
+            " ✓ No issues found in the sample",
+            Style::default().fg(theme.success),
+        )));
+    }
+    for warning in &analysis.warnings {
+        text.push(Line::from(Span::styled(
+            format!(" ⚠ {}", warning),
+            Style::default().fg(theme.highlight),
+        )));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints(" [Esc] Close"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let popup = centered_rect(90, text.len() as u16 + 2, area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(format!(" Shard key analysis for {} ", analysis.namespace)),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+fn render_stats(f: &mut Frame, area: Rect, stats: &CollectionStats, scroll: usize, theme: &Theme) {
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!(" {:<22}", label), Style::default().fg(theme.dim)),
+            Span::styled(value, Style::default().fg(theme.text)),
+        ])
+    };
+    let heading = |title: &str| {
+        Line::from(Span::styled(format!(" {}", title), Style::default().fg(theme.accent)))
+    };
+
+    let mut lines = vec![
+        row("Documents", stats.count.to_string()),
+        row("Data size", format_bytes(stats.size)),
+        row("Storage size", format_bytes(stats.storage_size)),
+        row("Reusable space", format_bytes(stats.free_storage_size)),
+        row("Average document", format_bytes(stats.avg_obj_size)),
+        row("Total index size", format_bytes(stats.total_index_size)),
+    ];
+    if stats.shards > 1 {
+        lines.push(row("Shards", stats.shards.to_string()));
+    }
+    lines.push(row(
+        "Capped",
+        match (stats.capped, stats.max_size, stats.max) {
+            (false, _, _) => "no".to_string(),
+            (true, size, max) => {
+                let mut limits = Vec::new();
+                if let Some(size) = size {
+                    limits.push(format_bytes(size));
+                }
+                if let Some(max) = max.filter(|&m| m > 0) {
+                    limits.push(format!("{} documents", max));
+                }
+                format!("yes ({})", limits.join(", "))
+            }
+        },
+    ));
+
+    lines.push(Line::from(""));
+    lines.push(heading("Indexes"));
+    for (name, size) in &stats.index_sizes {
+        lines.push(row(name, format_bytes(*size)));
+    }
+
+    if !stats.cache.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(heading("WiredTiger cache"));
+        for (counter, value) in &stats.cache {
+            let value = if CollectionStats::is_bytes(counter) {
+                format_bytes(*value)
+            } else {
+                value.to_string()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {:<36}", counter), Style::default().fg(theme.dim)),
+                Span::styled(value, Style::default().fg(theme.text)),
+            ]));
+        }
+    }
+
+    let scroll = scroll.min(lines.len().saturating_sub(1));
+    let popup = centered_rect(70, (lines.len() as u16 + 2).min(area.height.saturating_sub(4)), area);
+    let widget = Paragraph::new(lines.split_off(scroll)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(format!(" Statistics for {} ", stats.namespace))
+            .title_bottom(
+                Line::from(i18n::hints(" [j/k] Scroll  [r] Refresh  [Esc] Close ")).centered(),
+            ),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+fn render_collection_form(
+    f: &mut Frame,
+    area: Rect,
+    form: &CollectionForm,
+    error: Option<&str>,
+    theme: &Theme,
+) {
+    let focused = form.focused_field();
+    let mut text: Vec<Line> = form
+        .fields()
+        .into_iter()
+        .map(|field| {
+            let is_focused = field == focused;
+            let value = match field {
+                FormField::Kind | FormField::Granularity => format!("◀ {} ▶", form.value(field)),
+                _ => form.value(field),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{}{:<20}", if is_focused { "> " } else { "  " }, field.label()),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::styled(
+                    value,
+                    if is_focused {
+                        Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.text)
+                    },
+                ),
+            ])
+        })
+        .collect();
+
+    text.push(Line::from(""));
+    match form.to_command() {
+        Ok(command) => text.push(Line::from(Span::styled(
+            serde_json::to_string(&command).unwrap_or_else(|_| command.to_string()),
+            Style::default().fg(theme.accent),
+        ))),
+        Err(e) => text.push(Line::from(Span::styled(e, Style::default().fg(theme.muted)))),
+    }
+    if let Some(error) = error {
+        text.push(Line::from(Span::styled(
+            format!("Error: {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints("[Tab/↑/↓] Field  [←/→] Change option  [Enter] Create  [Esc] Cancel"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let popup = centered_rect(80, text.len() as u16 + 4, area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(" New collection "),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
@@ -0,0 +1,152 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Counts
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_counts(f, chunks[1], state);
+    render_footer(f, chunks[2], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let host = state
+        .connection
+        .as_ref()
+        .map(|c| c.server_info.host.clone())
+        .unwrap_or_default();
+    let mismatches = state.comparisons.iter().filter(|c| !c.matches()).count();
+
+    let (badge, badge_color) = if mismatches == 0 {
+        ("all counts match".to_string(), theme.success)
+    } else {
+        (format!("{} mismatches", mismatches), theme.error)
+    };
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" Counts: {} vs {} ", host, state.compare_target),
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" {} ", badge),
+            theme.background(badge_color).fg(theme.badge_text),
+        ),
+    ]))
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_counts(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let width = state
+        .comparisons
+        .iter()
+        .map(|c| c.namespace.len())
+        .max()
+        .unwrap_or(0)
+        .max(9);
+
+    let items: Vec<ListItem> = state
+        .visible_comparisons()
+        .into_iter()
+        .map(|comparison| {
+            let color = if comparison.matches() {
+                theme.text
+            } else {
+                theme.error
+            };
+            let diff = match (comparison.left, comparison.right) {
+                (Some(left), Some(right)) if left != right => {
+                    format!("{:+}", right as i64 - left as i64)
+                }
+                (Some(_), None) => "missing on target".to_string(),
+                (None, Some(_)) => "missing on source".to_string(),
+                _ => String::new(),
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {:<width$}  ", comparison.namespace, width = width),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    format!(
+                        "{:>12}  {:>12}  ",
+                        count_label(comparison.left),
+                        count_label(comparison.right)
+                    ),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::styled(diff, Style::default().fg(theme.error)),
+            ]))
+        })
+        .collect();
+
+    let title = format!(
+        " {:<width$}  {:>12}  {:>12} ",
+        "Namespace",
+        "Source",
+        "Target",
+        width = width
+    );
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selected_compare_index));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let text = if let Some(error) = &state.error {
+        format!(" Error: {}", error)
+    } else {
+        i18n::hints(&format!(
+            " [m] Mismatches only: {}  [r] Re-run  [j/k] Navigate  [Esc] Back  (estimated counts) ",
+            if state.compare_mismatches_only {
+                "on"
+            } else {
+                "off"
+            }
+        ))
+    };
+    let footer = Paragraph::new(text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
+
+fn count_label(count: Option<u64>) -> String {
+    count
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "—".to_string())
+}
@@ -1,21 +1,37 @@
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
 use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{ProfileField, ProfileForm, Theme};
+use crate::ui::input;
+use crate::ui::popup::centered_rect;
 
+/// Rows the logo, input and history need together.
+const CONTENT_HEIGHT: u16 = 30;
+const LOGO_HEIGHT: u16 = 12;
 
 pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    // short terminals drop the logo rather than squeezing the history
+    let fits_logo = area.height > CONTENT_HEIGHT;
+    let content_height = if fits_logo {
+        CONTENT_HEIGHT
+    } else {
+        CONTENT_HEIGHT - LOGO_HEIGHT
+    };
+
     // Vertical layout: Center the content block, with footer at bottom
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(0),         // Top spacer
-            Constraint::Length(30),     // Content block (Logo + Input + History/Instructions)
+            Constraint::Length(content_height), // Content block (Logo + Input + History/Instructions)
             Constraint::Min(0),         // Bottom spacer
             Constraint::Length(1),      // Footer
         ])
@@ -40,25 +56,33 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
     let content_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(12),     // Logo
+            Constraint::Length(if fits_logo { LOGO_HEIGHT } else { 0 }), // Logo
             Constraint::Length(3),      // Input
             Constraint::Length(15),     // History / Instructions
         ])
         .split(content_area);
 
-    render_logo(f, content_chunks[0]);
+    if fits_logo {
+        render_logo(f, content_chunks[0], theme);
+    }
     render_input(f, content_chunks[1], state);
 
-    if state.show_history && !state.connection_history.is_empty() {
+    if state.show_profiles {
+        render_profiles(f, content_chunks[2], state);
+    } else if state.show_history && !state.connection_history.is_empty() {
         render_history(f, content_chunks[2], state);
     } else {
-        render_instructions(f, content_chunks[2]);
+        render_instructions(f, content_chunks[2], theme);
     }
 
     render_footer(f, main_chunks[3], state);
+
+    if let Some(form) = &state.profile_form {
+        render_profile_form(f, area, form, state.error.as_deref(), theme);
+    }
 }
 
-fn render_logo(f: &mut Frame, area: Rect) {
+fn render_logo(f: &mut Frame, area: Rect, theme: &Theme) {
     let logo_text = vec![
         " __  __  ___  _   _     _  ___  ",
         "|  \\/  |/ _ \\| \\ | |   | |/ _ \\ ",
@@ -73,19 +97,19 @@ fn render_logo(f: &mut Frame, area: Rect) {
     ];
 
     let logo = Paragraph::new(logo_text.join("\n"))
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .style(Style::default().fg(theme.header).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center);
 
     f.render_widget(logo, area);
 }
 
-fn render_instructions(f: &mut Frame, area: Rect) {
+fn render_instructions(f: &mut Frame, area: Rect, theme: &Theme) {
     let text = vec![
-        Line::from(Span::styled("Enter MongoDB URI to connect", Style::default().fg(Color::DarkGray))),
+        Line::from(Span::styled(i18n::tr("Enter MongoDB URI to connect"), Style::default().fg(theme.muted))),
         Line::from(Span::raw("")),
         Line::from(vec![
-            Span::styled("Example: ", Style::default().fg(Color::DarkGray)),
-            Span::styled("mongodb://localhost:27017", Style::default().fg(Color::Gray)),
+            Span::styled("Example: ", Style::default().fg(theme.muted)),
+            Span::styled("mongodb://localhost:27017", Style::default().fg(theme.dim)),
         ]),
     ];
     
@@ -95,31 +119,32 @@ fn render_instructions(f: &mut Frame, area: Rect) {
 }
 
 fn render_input(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let input_style = if state.input_mode {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.highlight)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.text)
     };
     
-    let prefix = if state.connection_input.is_empty() {
-        "  > "
-    } else {
-        "  > "
+    let prompt = match state.profile_for_input() {
+        Some(profile) => format!("  [{}] > ", profile.name),
+        None => "  > ".to_string(),
     };
-
-    let input_text = format!("{}{}", prefix, state.connection_input);
+    let mut spans = vec![Span::styled(prompt, input_style)];
+    spans.extend(input::spans(&state.connection_input, input_style, state.input_mode));
     
-    let input = Paragraph::new(input_text)
+    let input = Paragraph::new(Line::from(spans))
         .style(input_style)
         .block(
             Block::default()
                 .borders(Borders::BOTTOM)
-                .border_style(Style::default().fg(if state.input_mode { Color::Yellow } else { Color::DarkGray })),
+                .border_style(Style::default().fg(if state.input_mode { theme.highlight } else { theme.muted })),
         );
     f.render_widget(input, area);
 }
 
 fn render_history(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let items: Vec<ListItem> = state
         .connection_history
         .iter()
@@ -132,9 +157,9 @@ fn render_history(f: &mut Frame, area: Rect, state: &AppState) {
             };
             
             let style = if i == state.selected_history_index {
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Gray)
+                Style::default().fg(theme.dim)
             };
             
             ListItem::new(Line::from(vec![
@@ -146,23 +171,158 @@ fn render_history(f: &mut Frame, area: Rect, state: &AppState) {
 
     let list = List::new(items)
         .block(Block::default().title("Recent Connections").title_alignment(Alignment::Center))
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(theme.text));
+
+    f.render_widget(list, area);
+}
+
+fn render_profiles(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    if state.profiles.is_empty() {
+        let text = vec![
+            Line::from(Span::styled("No profiles configured", Style::default().fg(theme.muted))),
+            Line::from(Span::raw("")),
+            Line::from(Span::styled(
+                "Press [n] to add one, or edit ~/.mongonaut/profiles.json",
+                Style::default().fg(theme.dim),
+            )),
+        ];
+        f.render_widget(Paragraph::new(text).alignment(Alignment::Center), area);
+        return;
+    }
+
+    let items: Vec<ListItem> = state
+        .profiles
+        .iter()
+        .enumerate()
+        .map(|(i, profile)| {
+            let prefix = if i == state.selected_profile_index {
+                "> "
+            } else {
+                "  "
+            };
+
+            let style = if i == state.selected_profile_index {
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.dim)
+            };
+
+            let mut spans = vec![
+                Span::styled(prefix, style),
+                Span::styled(profile.name.clone(), style),
+            ];
+            if profile.read_only {
+                spans.push(Span::styled("  read-only", Style::default().fg(theme.error)));
+            }
+            if let Some(db) = &profile.default_database {
+                spans.push(Span::styled(format!("  → {}", db), Style::default().fg(theme.muted)));
+            }
+            if profile.aws_iam.is_some() {
+                spans.push(Span::styled("  aws-iam", Style::default().fg(theme.muted)));
+            }
+            if let Some(proxy) = &profile.proxy {
+                spans.push(Span::styled(
+                    format!("  via socks5://{}:{}", proxy.host, proxy.port.unwrap_or(1080)),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(i18n::hints("Profiles  [n] New  [e] Edit  [d] Delete"))
+                .title_alignment(Alignment::Center),
+        )
+        .style(Style::default().fg(theme.text));
 
     f.render_widget(list, area);
 }
 
 fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
-    let text = if state.error.is_some() {
-        format!("Error: {}", state.error.as_ref().unwrap())
+    let theme = &state.theme;
+    let text = if let Some(error) = &state.error {
+        format!("Error: {}", error)
     } else if state.loading {
-        "Connecting...".to_string()
+        format!("{} Connecting...", state.spinner())
+    } else if let Some(message) = &state.message {
+        message.clone()
     } else {
-        "[Enter] Connect  [Tab] History  [Esc] Clear  [Ctrl+C] Quit".to_string()
+        i18n::hints(&format!(
+            "[Enter] Connect  [Tab] History  [F3] Profiles  [F4] Atlas  [F2] Health check: {}  [Esc] Clear  [Ctrl+C] Quit",
+            if state.health_check_on_connect { "on" } else { "off" }
+        ))
     };
     
     let footer = Paragraph::new(text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.muted))
         .alignment(Alignment::Center);
 
     f.render_widget(footer, area);
 }
+
+fn render_profile_form(
+    f: &mut Frame,
+    area: Rect,
+    form: &ProfileForm,
+    error: Option<&str>,
+    theme: &Theme,
+) {
+    let focused = form.focused_field();
+    let mut text: Vec<Line> = ProfileField::ALL
+        .into_iter()
+        .map(|field| {
+            let is_focused = field == focused;
+            let value = match field {
+                ProfileField::ReadOnly => format!("◀ {} ▶", form.value(field)),
+                _ => form.value(field),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{}{:<18}", if is_focused { "> " } else { "  " }, field.label()),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::styled(
+                    value,
+                    if is_focused {
+                        Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.text)
+                    },
+                ),
+            ])
+        })
+        .collect();
+
+    if let Some(error) = error {
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!("Error: {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints("[Tab/↑/↓] Field  [←/→/Space] Toggle read-only  [Enter] Save  [Esc] Cancel"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let title = if form.editing.is_some() {
+        " Edit profile "
+    } else {
+        " New profile "
+    };
+    let popup = centered_rect(80, text.len() as u16 + 4, area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(title),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
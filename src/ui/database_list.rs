@@ -1,12 +1,14 @@
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    prelude::style::{Color, Modifier, Style},
+    prelude::style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
 use crate::app::state::AppState;
+use crate::i18n;
+use crate::ui::{empty, popup};
 
 pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
     let chunks = Layout::default()
@@ -20,27 +22,73 @@ pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
 
     render_header(f, chunks[0], state);
     render_database_list(f, chunks[1], state);
-    render_footer(f, chunks[2]);
+    render_footer(f, chunks[2], state);
+
+    if state.show_permissions {
+        render_permissions(f, area, state);
+    }
 }
 
 fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let title = if let Some(conn) = &state.connection {
+        let endpoint = match conn.endpoint {
+            Some((0, total)) if total > 1 => format!("[primary endpoint of {}] ", total),
+            Some((index, total)) if total > 1 => {
+                format!("[fallback endpoint {} of {}] ", index + 1, total)
+            }
+            _ => String::new(),
+        };
+        let rate_limit = state
+            .active_profile
+            .as_ref()
+            .and_then(|p| p.rate_limit.as_ref())
+            .map(|limit| format!("[bulk ops limited to {}] ", limit))
+            .unwrap_or_default();
+        let read_only = if state.active_profile.as_ref().is_some_and(|p| p.read_only) {
+            "[read-only] "
+        } else {
+            ""
+        };
+        let dry_run = if state.dry_run { "[dry run] " } else { "" };
         format!(
-            " Connected to {} (MongoDB {}) ",
-            conn.server_info.host, conn.server_info.version
+            " Connected to {} (MongoDB {}) {}{}{}{}",
+            conn.server_info.host,
+            conn.server_info.version,
+            endpoint,
+            rate_limit,
+            read_only,
+            dry_run
         )
     } else {
         " Not connected ".to_string()
     };
     
     let header = Paragraph::new(title)
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .style(Style::default().fg(theme.header).add_modifier(Modifier::BOLD))
         .block(Block::default().borders(Borders::BOTTOM));
 
     f.render_widget(header, area);
 }
 
 fn render_database_list(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let block = Block::default().title(" Databases ").title_style(Style::default().fg(theme.dim));
+    if state.databases.is_empty() && !state.loading {
+        empty::render(
+            f,
+            area,
+            block,
+            "No databases visible",
+            &[
+                "Your user may lack the listDatabases privilege; without it only databases it holds privileges on are listed.",
+                "Press P to check your privileges, or r to refresh.",
+            ],
+            theme,
+        );
+        return;
+    }
+
     let items: Vec<ListItem> = state
         .databases
         .iter()
@@ -52,17 +100,23 @@ fn render_database_list(f: &mut Frame, area: Rect, state: &AppState) {
                 "  "
             };
 
+            let totals = match state.database_totals.get(&db.name) {
+                Some(totals) => {
+                    format!(", {} documents, {} indexes", totals.documents, totals.indexes)
+                }
+                None => String::new(),
+            };
             let content = format!(
-                "{}{} ({} collections, {} bytes)",
-                prefix, db.name, db.collection_count, db.size_on_disk
+                "{}{} ({} collections, {} bytes{})",
+                prefix, db.name, db.collection_count, db.size_on_disk, totals
             );
 
             let style = if i == state.selected_db_index {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             };
 
             ListItem::new(Line::from(Span::styled(content, style)))
@@ -70,16 +124,44 @@ fn render_database_list(f: &mut Frame, area: Rect, state: &AppState) {
         .collect();
 
     let list = List::new(items)
-        .block(Block::default().title(" Databases ").title_style(Style::default().fg(Color::Gray)))
-        .style(Style::default().fg(Color::White));
+        .block(block)
+        .style(Style::default().fg(theme.text));
 
     f.render_widget(list, area);
 }
 
-fn render_footer(f: &mut Frame, area: Rect) {
-    let footer_text = " [q] Quit  [↑/↓] Navigate  [Enter] Select  [r] Refresh ";
+fn render_permissions(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let databases = state
+        .privileges
+        .as_ref()
+        .map(|p| p.named_databases())
+        .unwrap_or_default();
+    let note = if databases.is_empty() {
+        " No privileges name a database".to_string()
+    } else {
+        format!(" Privileges name: {}", databases.join(", "))
+    };
+    let notes = vec![Line::from(Span::styled(note, Style::default().fg(theme.dim)))];
+    popup::render_permissions(f, area, state, " Deployment permissions ", notes);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let (footer_text, color) = if let Some(error) = &state.error {
+        (format!(" Error: {} ", error), theme.error)
+    } else if state.loading {
+        (format!(" {} Loading... ", state.spinner()), theme.highlight)
+    } else if let Some(message) = &state.message {
+        (format!(" {} ", message), theme.success)
+    } else {
+        (
+            i18n::hints(" [q] Quit  [↑/↓] Navigate  [Enter] Select  [H] Health  [S] Replica set  [O] Operations  [U] Users  [C] Compare counts  [A] About  [P] Permissions  [W] Watch all  [D] Drop  [r] Refresh "),
+            theme.muted,
+        )
+    };
     let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray).bg(Color::Black));
+        .style(theme.background(theme.bar).fg(color));
 
     f.render_widget(footer, area);
 }
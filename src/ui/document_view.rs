@@ -3,94 +3,287 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
 use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{
+    BuilderField, DistinctValues, PendingEdit, ProjectionPicker, QueryBuilder, Theme,
+};
+use crate::services::memory;
+use crate::services::metrics::format_bytes;
+use crate::services::replset;
+use crate::ui::{empty, explain, geo_plot, highlight, input, popup, table_view};
 
 pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
-        .split(area);
+    let theme = &state.theme;
+    let area = if state.tabs.is_empty() {
+        area
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        render_tab_bar(f, chunks[0], state);
+        chunks[1]
+    };
 
-    let left_chunks = Layout::default()
+    if state.table_view {
+        render_table(f, area, state);
+    } else {
+        render_split(f, area, state);
+    }
+
+    if state.show_scan_warning {
+        render_scan_warning(f, area, state);
+    }
+    if let Some(edit) = &state.pending_edit {
+        render_edit_review(f, area, edit, theme);
+    }
+    if let Some(tree) = &state.plan_tree {
+        explain::render(f, area, tree, theme);
+    }
+    if let Some(picker) = &state.projection_picker {
+        render_projection_picker(f, area, picker, theme);
+    }
+    if let Some(values) = &state.distinct_values {
+        render_distinct_values(f, area, values, theme);
+    }
+    if let Some(builder) = &state.query_builder {
+        render_query_builder(f, area, builder, theme);
+    }
+    if state.show_query_history {
+        render_query_history(f, area, state);
+    }
+    if state.show_saved_queries {
+        render_saved_queries(f, area, state);
+    }
+}
+
+fn render_tab_bar(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let mut spans = Vec::new();
+    for (index, label) in state.tab_labels().into_iter().enumerate() {
+        let style = if index == state.active_tab {
+            theme
+                .background(theme.accent)
+                .fg(theme.badge_text)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.dim)
+        };
+        spans.push(Span::styled(format!(" {} {} ", index + 1, label), style));
+        spans.push(Span::raw(" "));
+    }
+    spans.push(Span::styled(
+        i18n::hints("[gt/gT] Switch  [Ctrl+W] Close"),
+        Style::default().fg(theme.muted),
+    ));
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn render_table(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(2), // Header
             Constraint::Length(3), // Filter
-            Constraint::Min(0),    // List
+            Constraint::Min(0),    // Table
             Constraint::Length(1), // Footer
         ])
-        .split(chunks[0]);
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_filter_input(f, chunks[1], state);
+    if let Some(column) = &state.inspected_column {
+        let panes = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[2]);
+        table_view::render(f, panes[0], state);
+        render_cell_inspector(f, panes[1], state, column);
+    } else {
+        table_view::render(f, chunks[2], state);
+    }
+    render_footer(f, chunks[3], state);
+}
+
+fn render_cell_inspector(f: &mut Frame, area: Rect, state: &AppState, column: &str) {
+    let theme = &state.theme;
+    let lines: Vec<Line> = match state.inspected_value() {
+        Some(value) => scrolled(
+            highlight::value_lines(value, state.value_format, theme),
+            state.doc_scroll_offset,
+        ),
+        None => vec![Line::from("(field missing in this document)")],
+    };
 
-    let right_chunks = Layout::default()
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text))
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(format!(" {} ", column))
+                .title_style(Style::default().fg(theme.accent)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_split(f: &mut Frame, area: Rect, state: &AppState) {
+    let rows = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Min(0),    // Content
+            Constraint::Min(0),    // Panes
             Constraint::Length(1), // Footer
         ])
-        .split(chunks[1]);
+        .split(area);
+    render_footer(f, rows[1], state);
+
+    // the inputs live above the list, so typing brings the list back
+    if state.content_maximized && !state.is_typing() {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2), // Header
+                Constraint::Min(0),    // Content
+            ])
+            .split(rows[0]);
+        render_header(f, chunks[0], state);
+        render_content_pane(f, chunks[1], state);
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(state.split_percent),
+            Constraint::Percentage(100 - state.split_percent),
+        ])
+        .split(rows[0]);
+
+    let left_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Length(3), // Filter
+            Constraint::Min(0),    // List
+        ])
+        .split(chunks[0]);
 
     render_header(f, left_chunks[0], state);
     render_filter_input(f, left_chunks[1], state);
     render_document_list(f, left_chunks[2], state);
-    render_footer(f, left_chunks[3]);
-    
-    render_document_content(f, right_chunks[0], state);
-    render_content_footer(f, right_chunks[1]);
+    render_content_pane(f, chunks[1], state);
+}
+
+fn render_content_pane(f: &mut Frame, area: Rect, state: &AppState) {
+    if state.geo_shape.is_some() {
+        let geo_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        geo_plot::render(f, geo_chunks[0], state);
+        render_document_content(f, geo_chunks[1], state);
+    } else {
+        render_document_content(f, area, state);
+    }
 }
 
 fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let title = if let (Some(db), Some(coll)) = (&state.current_database, &state.current_collection)
     {
-        format!(" {}.{} ", db, coll)
+        match state.current_environment() {
+            Some((_, env)) => format!(" [{}] {}.{} ", env.name, db, coll),
+            None => format!(" {}.{} ", db, coll),
+        }
     } else {
         " No collection selected ".to_string()
     };
 
-    let header = Paragraph::new(title)
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    let mut spans = vec![Span::styled(
+        title,
+        Style::default().fg(theme.header).add_modifier(Modifier::BOLD),
+    )];
+    if let Some(sort) = &state.sort {
+        let sort = serde_json::to_string(sort).unwrap_or_else(|_| format!("{}", sort));
+        spans.push(Span::styled(format!("sort {} ", sort), Style::default().fg(theme.special)));
+    }
+    if state.projection.is_some() {
+        spans.push(Span::styled("projected ", Style::default().fg(theme.muted)));
+    }
+    if state.value_format.canonical {
+        spans.push(Span::styled("canonical EJSON ", Style::default().fg(theme.muted)));
+    }
+
+    let header = Paragraph::new(Line::from(spans))
         .block(Block::default().borders(Borders::BOTTOM));
 
     f.render_widget(header, area);
 }
 
 fn render_filter_input(f: &mut Frame, area: Rect, state: &AppState) {
-    let (style, title, text) = if state.query_mode {
+    let theme = &state.theme;
+    let (style, title, text) = if state.geo_mode {
         (
-            Style::default().fg(Color::Magenta),
-            " Query (JSON) ",
+            Style::default().fg(theme.accent),
+            " Geo: near | circle | box ",
+            state.geo_input.as_str(),
+        )
+    } else if state.query_mode {
+        (
+            Style::default().fg(theme.special),
+            " Query (JSON) · Ctrl+R history ",
             state.query_input.as_str(),
         )
     } else if state.filter_mode {
         (
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.highlight),
             " Search ",
             state.filter_input.as_str(),
         )
+    } else if state.geo_shape.is_some() {
+        (
+            Style::default().fg(theme.success),
+            " Active Geo Filter ",
+            state.geo_input.as_str(),
+        )
     } else if state.filter.is_some() {
         (
-            Style::default().fg(Color::Green),
+            Style::default().fg(theme.success),
             " Active Filter ",
             "...", 
         )
     } else {
         (
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
             " Filter ",
             "Press 'f' or '/'",
         )
     };
 
-    let filter_widget = Paragraph::new(text)
+    // the field being typed in shows its cursor
+    let line = if state.geo_mode {
+        Line::from(text)
+    } else if state.query_mode {
+        Line::from(input::spans(&state.query_input, style, true))
+    } else if state.filter_mode {
+        Line::from(input::spans(&state.filter_input, style, true))
+    } else {
+        Line::from(text)
+    };
+
+    let filter_widget = Paragraph::new(line)
         .style(style)
-        .block(Block::default().borders(Borders::BOTTOM).title(title).title_style(Style::default().fg(Color::Gray)));
+        .block(Block::default().borders(Borders::BOTTOM).title(title).title_style(Style::default().fg(theme.dim)));
 
     f.render_widget(filter_widget, area);
 }
 
 fn render_document_list(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let items: Vec<ListItem> = state
         .documents
         .iter()
@@ -115,69 +308,562 @@ fn render_document_list(f: &mut Frame, area: Rect, state: &AppState) {
 
             let style = if i == state.selected_doc_index {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             };
 
-            ListItem::new(Line::from(vec![
-                Span::styled(prefix, style),
-                Span::styled(content, style),
-            ]))
+            let mut spans = vec![Span::styled(prefix, style), Span::styled(content, style)];
+            if state.show_document_sizes
+                && let Some(&size) = state.document_sizes.get(i)
+            {
+                spans.push(Span::styled(
+                    format!("  {}", format_bytes(size)),
+                    Style::default().fg(size_color(size, theme)),
+                ));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     let title = format!(" Documents ({}) ", state.documents.len());
     let list = List::new(items)
-        .block(Block::default().title(title).title_style(Style::default().fg(Color::Gray)))
-        .style(Style::default().fg(Color::White));
+        .block(Block::default().title(title).title_style(Style::default().fg(theme.dim)))
+        .style(Style::default().fg(theme.text));
 
     f.render_widget(list, area);
 }
 
 fn render_document_content(f: &mut Frame, area: Rect, state: &AppState) {
-    let content = if let Some(doc) = state.get_selected_document() {
-        match serde_json::to_string_pretty(&doc) {
-            Ok(json) => json,
-            Err(_) => format!("{:?}", doc),
+    let theme = &state.theme;
+    if state.documents.is_empty() && !state.loading {
+        let block = Block::default()
+            .borders(Borders::LEFT)
+            .title(Span::styled(" Content ", Style::default().fg(theme.dim)));
+        if state.filter.is_some() || state.geo_shape.is_some() || !state.filter_input.is_empty() {
+            empty::render(
+                f,
+                area,
+                block,
+                "No documents match the query",
+                &["Press / to change the query; running an empty query clears it."],
+                theme,
+            );
+        } else {
+            empty::render(
+                f,
+                area,
+                block,
+                "This collection is empty",
+                &["Press I to import a JSON or NDJSON file, or r to refresh."],
+                theme,
+            );
         }
-    } else {
-        "No document selected".to_string()
+        return;
+    }
+
+    let query = state.content_search.as_str();
+    let mut matches = 0;
+    let lines: Vec<Line> = match state.get_selected_document() {
+        Some(doc) => {
+            let lines =
+                highlight::document_tree(doc, state.selected_folds(), state.value_format, theme)
+                    .lines;
+            matches = highlight::find_matches(&lines, query).len();
+            let mut lines = highlight::mark_matches(lines, query, state.content_match, theme);
+            if state.content_focus {
+                let cursor = state.content_cursor.min(lines.len().saturating_sub(1));
+                lines[cursor].style = theme.background(theme.selection);
+            }
+            scrolled(lines, state.doc_scroll_offset)
+        }
+        None => vec![Line::from("No document selected")],
     };
 
-    let lines: Vec<Line> = content
-        .lines()
-        .skip(state.doc_scroll_offset)
-        .map(|line| Line::from(line.to_string()))
-        .collect();
+    let label_style = if state.content_focus {
+        Style::default().fg(theme.header).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.dim)
+    };
+    let mut title = vec![Span::styled(" Content ", label_style)];
+    if let Some(size) = state.selected_document_size() {
+        title.push(Span::styled(
+            format!("{} BSON ", format_bytes(size)),
+            Style::default().fg(size_color(size, theme)),
+        ));
+    }
+    if state.content_search_mode || !query.is_empty() {
+        let style = Style::default().fg(theme.highlight);
+        title.push(Span::styled("/", style));
+        title.extend(input::spans(&state.content_search, style, state.content_search_mode));
+        if !query.is_empty() {
+            title.push(Span::styled(
+                match matches {
+                    0 => "  no matches ".to_string(),
+                    _ => format!("  {}/{} ", state.content_match % matches + 1, matches),
+                },
+                Style::default().fg(theme.muted),
+            ));
+        }
+    }
 
+    let border_style = if state.content_focus {
+        Style::default().fg(theme.accent)
+    } else {
+        Style::default()
+    };
     let paragraph = Paragraph::new(lines)
-        .style(Style::default().fg(Color::White))
+        .style(Style::default().fg(theme.text))
         .block(
             Block::default()
                 .borders(Borders::LEFT)
-                .title(" Content ")
-                .title_style(Style::default().fg(Color::Gray)),
+                .border_style(border_style)
+                .title(Line::from(title)),
         )
         .wrap(Wrap { trim: false });
 
     f.render_widget(paragraph, area);
 }
 
-fn render_footer(f: &mut Frame, area: Rect) {
-    let footer_text = " [q] Quit  [↑/↓] Nav  [f] Filter ";
-    let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray).bg(Color::Black));
+fn size_color(size: u64, theme: &Theme) -> Color {
+    if size >= memory::NEAR_LIMIT_DOCUMENT_BYTES {
+        theme.error
+    } else if size >= memory::LARGE_DOCUMENT_BYTES {
+        theme.highlight
+    } else {
+        theme.muted
+    }
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let mut spans = Vec::new();
+    if state.scan_warning.is_some() {
+        spans.push(Span::styled(
+            " ⚠ COLLSCAN [w] ",
+            theme.background(theme.highlight).fg(theme.badge_text),
+        ));
+    }
+    if let Some(error) = &state.error {
+        spans.push(Span::styled(format!(" Error: {} ", error), Style::default().fg(theme.error)));
+    } else if let Some(message) = &state.message {
+        spans.push(Span::styled(format!(" {} ", message), Style::default().fg(theme.success)));
+    } else if state.content_search_mode {
+        spans.push(Span::raw(i18n::hints(" [Enter] Keep the search  [Esc] Cancel ")));
+    } else if state.content_focus && !state.table_view {
+        spans.push(Span::raw(i18n::hints(" [j/k] Move  [za] Fold  [zM/zR] Fold/unfold all  [c] Canonical JSON  [y/Y] Copy value/_id  [/] Search  [n/N] Next/previous match  [Z] Maximize  [Ctrl+←/→] Resize  [Tab] Back to the list ")));
+    } else if state.inspected_column.is_some() {
+        spans.push(Span::raw(i18n::hints(" [↑/↓] Row  [←/→] Column  [Enter] Inspect column  [PgUp/PgDn] Scroll  [Esc] Close ")));
+    } else if state.table_view {
+        spans.push(Span::raw(i18n::hints(" [←/→] Column  [Enter] Inspect  [F] Pin  [</>] Move  [+/x] Add/remove  [R] Reset  [o] Sort page  [O] Server sort  [d] Distinct  [X] Export  [t] JSON view ")));
+    } else {
+        spans.push(Span::raw(i18n::hints(" [q] Quit  [↑/↓] Nav  [PgUp/PgDn] Scroll  [[/]] Page  [r] Refresh  [f] Filter  [g] Geo  [a] Aggregate  [s] Search  [e] Edit  [p] Project  [P] Plan  [S] Save query  [Q] Queries  [B] Bench  [X] Export  [t] Table  [b] Sizes  [L] Page size  [E] Environment  [d] Distinct  [U] Bulk update  [v] Builder  [Tab] Content  [c] Canonical JSON  [y/Y] Copy document/_id  [Z] Maximize ")));
+    }
+
+    let footer = Paragraph::new(Line::from(spans))
+        .style(theme.background(theme.bar).fg(theme.muted));
 
     f.render_widget(footer, area);
 }
 
-fn render_content_footer(f: &mut Frame, area: Rect) {
-    let footer_text = " [PgUp/PgDn] Scroll  [r] Refresh ";
-    let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray).bg(Color::Black))
-        .block(Block::default().borders(Borders::LEFT)); // Match content border
+fn render_scan_warning(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let Some(warning) = &state.scan_warning else {
+        return;
+    };
 
-    f.render_widget(footer, area);
+    let index = serde_json::to_string(&warning.suggested_index)
+        .unwrap_or_else(|_| format!("{}", warning.suggested_index));
+
+    let text = vec![
+        Line::from(Span::styled(
+            "This filter is not using an index.",
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "The server scanned {} documents to return {}.",
+            warning.docs_examined, warning.returned
+        )),
+        Line::from("Full collection scans get slower as the collection grows"),
+        Line::from("and compete with other workloads for cache."),
+        Line::from(""),
+        Line::from("Suggested index for the filtered fields:"),
+        Line::from(Span::styled(
+            format!(
+                "  db.{}.createIndex({})",
+                state.current_collection.as_deref().unwrap_or("coll"),
+                index
+            ),
+            Style::default().fg(theme.accent),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            i18n::hints("[w/Esc] Close"),
+            Style::default().fg(theme.muted),
+        )),
+    ];
+
+    let popup = popup::centered_rect(70, 13, area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight))
+            .title(" Unindexed query "),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+fn render_edit_review(f: &mut Frame, area: Rect, edit: &PendingEdit, theme: &Theme) {
+    let mut text = vec![
+        Line::from(format!("{} field(s) changed:", edit.changes.len())),
+        Line::from(""),
+    ];
+    for change in &edit.changes {
+        text.push(Line::from(Span::styled(
+            change.path.clone(),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )));
+        if let Some(before) = &change.before {
+            text.push(Line::from(Span::styled(
+                format!("  - {}", before),
+                Style::default().fg(theme.error),
+            )));
+        }
+        if let Some(after) = &change.after {
+            text.push(Line::from(Span::styled(
+                format!("  + {}", after),
+                Style::default().fg(theme.success),
+            )));
+        }
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints("[y/Enter] Replace document  [n/Esc] Discard"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let height = (text.len() as u16 + 2).min(area.height);
+    let popup = popup::centered_rect(80, height, area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(" Review changes "),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+fn render_query_history(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let entries = state.namespace_query_history();
+    let popup = popup::centered_rect(80, area.height.saturating_sub(4).min(20), area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Queries
+            Constraint::Length(1), // Keys
+        ])
+        .margin(1)
+        .split(popup);
+
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{}  ", replset::format_date(entry.run_at)),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::raw(entry.query.clone()),
+            ]))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(state.selected_query_history));
+    let list = List::new(items)
+        .style(Style::default().fg(theme.text))
+        .highlight_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD));
+    let keys = Paragraph::new(i18n::hints("[↑/↓] Select  [Enter] Run  [Tab] Edit  [Esc] Close"))
+        .style(Style::default().fg(theme.muted));
+
+    let title = format!(
+        " Queries on {} ",
+        state.current_namespace().unwrap_or_default()
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.special))
+            .title(title),
+        popup,
+    );
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+    f.render_widget(keys, chunks[1]);
+}
+
+fn render_saved_queries(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let queries = state.namespace_saved_queries();
+    let popup = popup::centered_rect(80, area.height.saturating_sub(4).min(20), area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Queries
+            Constraint::Length(1), // Keys
+        ])
+        .margin(1)
+        .split(popup);
+
+    let width = queries.iter().map(|q| q.name.chars().count()).max().unwrap_or(0);
+    let items: Vec<ListItem> = queries
+        .iter()
+        .map(|saved| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<width$}  ", saved.name, width = width),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(saved.query.clone(), Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(state.selected_saved_query));
+    let list = List::new(items)
+        .style(Style::default().fg(theme.text))
+        .highlight_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD));
+    let keys = Paragraph::new(i18n::hints("[↑/↓] Select  [Enter] Apply  [e] Edit  [d] Delete  [Esc] Close"))
+        .style(Style::default().fg(theme.muted));
+
+    let title = format!(
+        " Saved queries for {} ",
+        state.current_namespace().unwrap_or_default()
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(title),
+        popup,
+    );
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+    f.render_widget(keys, chunks[1]);
+}
+
+fn render_projection_picker(f: &mut Frame, area: Rect, picker: &ProjectionPicker, theme: &Theme) {
+    let popup = popup::centered_rect(60, area.height.saturating_sub(4).min(24), area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Fields
+            Constraint::Length(2), // Projection
+            Constraint::Length(1), // Keys
+        ])
+        .margin(1)
+        .split(popup);
+
+    let mark = if picker.exclude { "x" } else { "✓" };
+    let items: Vec<ListItem> = picker
+        .fields
+        .iter()
+        .zip(&picker.checked)
+        .map(|(field, checked)| {
+            let depth = field.matches('.').count();
+            ListItem::new(format!(
+                "[{}] {}{}",
+                if *checked { mark } else { " " },
+                "  ".repeat(depth),
+                field
+            ))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(picker.selected));
+    let list = List::new(items)
+        .style(Style::default().fg(theme.text))
+        .highlight_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD));
+
+    let projection = picker
+        .to_projection()
+        .map(|p| serde_json::to_string(&p).unwrap_or_else(|_| format!("{}", p)))
+        .unwrap_or_else(|| "all fields".to_string());
+    let preview = Paragraph::new(projection)
+        .style(Style::default().fg(theme.accent))
+        .block(Block::default().borders(Borders::TOP));
+    let keys = Paragraph::new(
+        i18n::hints("[Space] Check  [m] Include/exclude  [c] Clear  [Enter] Apply  [Esc] Cancel"),
+    )
+    .style(Style::default().fg(theme.muted));
+
+    let title = if picker.exclude {
+        " Projection: exclude checked "
+    } else {
+        " Projection: include checked "
+    };
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(title),
+        popup,
+    );
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+    f.render_widget(preview, chunks[1]);
+    f.render_widget(keys, chunks[2]);
+}
+
+fn render_distinct_values(f: &mut Frame, area: Rect, values: &DistinctValues, theme: &Theme) {
+    let popup = popup::centered_rect(70, area.height.saturating_sub(4).min(30), area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Values
+            Constraint::Length(1), // Keys
+        ])
+        .margin(1)
+        .split(popup);
+
+    let items: Vec<ListItem> = values
+        .values
+        .iter()
+        .map(|(value, count)| {
+            let share = *count as f64 / values.total.max(1) as f64 * 100.0;
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:>10} ", count), Style::default().fg(theme.highlight)),
+                Span::styled(format!("{:>5.1}%  ", share), Style::default().fg(theme.muted)),
+                Span::raw(value.clone()),
+            ]))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(values.selected));
+    let list = List::new(items)
+        .style(Style::default().fg(theme.text))
+        .highlight_style(theme.background(theme.selection).add_modifier(Modifier::BOLD));
+    let order = if values.by_value { "[s] By count" } else { "[s] By value" };
+    let keys = Paragraph::new(i18n::hints(&format!("[j/k] Navigate  {}  [Esc] Close", order)))
+        .style(Style::default().fg(theme.muted));
+
+    let shown = if values.truncated {
+        format!("the {} most frequent", values.values.len())
+    } else {
+        values.values.len().to_string()
+    };
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(format!(" Distinct values of {}: {} ", values.field, shown)),
+        popup,
+    );
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+    f.render_widget(keys, chunks[1]);
+}
+
+fn render_query_builder(f: &mut Frame, area: Rect, builder: &QueryBuilder, theme: &Theme) {
+    let popup = popup::centered_rect(70, area.height.saturating_sub(4).min(26), area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Conditions
+            Constraint::Length(5), // Suggestions
+            Constraint::Length(3), // Filter
+            Constraint::Length(2), // Keys
+        ])
+        .margin(1)
+        .split(popup);
+
+    let joiner = if builder.any { "OR " } else { "AND" };
+    let items: Vec<ListItem> = builder
+        .conditions
+        .iter()
+        .enumerate()
+        .map(|(index, condition)| {
+            let focused = |part: BuilderField| {
+                let style = Style::default().fg(theme.text);
+                if index == builder.selected && builder.focus == part {
+                    style.patch(theme.background(theme.selection)).add_modifier(Modifier::BOLD)
+                } else {
+                    style
+                }
+            };
+            let (field, field_style) = if condition.field.is_empty() {
+                ("field".to_string(), focused(BuilderField::Field).fg(theme.muted))
+            } else {
+                (condition.field.clone(), focused(BuilderField::Field).fg(theme.accent))
+            };
+            let (value, value_style) = if condition.value.is_empty() {
+                (
+                    condition.operator.hint().to_string(),
+                    focused(BuilderField::Value).fg(theme.muted),
+                )
+            } else {
+                (condition.value.clone(), focused(BuilderField::Value))
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {} ", if index == 0 { "   " } else { joiner }),
+                    Style::default().fg(theme.special),
+                ),
+                Span::styled(field, field_style),
+                Span::raw(" "),
+                Span::styled(
+                    format!("‹{}›", condition.operator.label()),
+                    focused(BuilderField::Operator).fg(theme.highlight),
+                ),
+                Span::raw(" "),
+                Span::styled(value, value_style),
+            ]))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(builder.selected));
+    let list = List::new(items).highlight_symbol("▶");
+
+    let suggestions = if builder.focus == BuilderField::Field {
+        builder.suggestions().join("  ")
+    } else {
+        String::new()
+    };
+    let suggestions = Paragraph::new(suggestions)
+        .wrap(Wrap { trim: true })
+        .style(Style::default().fg(theme.dim))
+        .block(Block::default().borders(Borders::TOP).title(" Fields "));
+    let filter = match builder.filter() {
+        Ok(filter) => Paragraph::new(filter).style(Style::default().fg(theme.success)),
+        Err(e) => Paragraph::new(e).style(Style::default().fg(theme.error)),
+    }
+    .wrap(Wrap { trim: false })
+    .block(Block::default().borders(Borders::TOP).title(" Filter "));
+    let keys = Paragraph::new(i18n::hints(
+        "[Tab] Next part  [←/→] Operator  [↑/↓] Condition  [Ctrl+N] Add  [Ctrl+X] Remove  [F2] AND/OR  [Enter] Apply  [Esc] Cancel",
+    ))
+    .wrap(Wrap { trim: true })
+    .style(Style::default().fg(theme.muted));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(" Query builder "),
+        popup,
+    );
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+    f.render_widget(suggestions, chunks[1]);
+    f.render_widget(filter, chunks[2]);
+    f.render_widget(keys, chunks[3]);
+}
+
+// keeps at least the last line in view when the offset outgrew the content
+fn scrolled(mut lines: Vec<Line<'static>>, offset: usize) -> Vec<Line<'static>> {
+    let offset = offset.min(lines.len().saturating_sub(1));
+    lines.split_off(offset)
 }
@@ -0,0 +1,57 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::i18n;
+use crate::models::{Theme, WouldExecute};
+use crate::ui::popup::centered_rect;
+
+pub fn render(f: &mut Frame, area: Rect, summary: &WouldExecute, theme: &Theme) {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<10}", label), Style::default().fg(theme.dim)),
+            Span::styled(value, Style::default().fg(theme.text)),
+        ])
+    };
+
+    let mut text = vec![
+        Line::from(Span::styled(
+            "  Dry run is on; nothing was sent. This would execute:",
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        field("Target", summary.target.clone()),
+        field("Operation", summary.operation.clone()),
+    ];
+    if let Some(filter) = &summary.filter {
+        text.push(field("Filter", filter.clone()));
+    }
+    if let Some(payload) = &summary.payload {
+        text.push(field("Payload", payload.clone()));
+    }
+    if let Some(matched) = summary.matched {
+        text.push(field("Matches", format!("{} document(s) now", matched)));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints("  [Enter/Esc] Close  [F6] Dry run off"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let popup = centered_rect(80, (text.len() as u16 + 6).min(area.height), area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight))
+            .title(" Dry run "),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
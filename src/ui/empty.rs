@@ -0,0 +1,40 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+};
+
+use crate::i18n;
+use crate::models::Theme;
+
+/// Guidance drawn in place of a list with nothing in it: what the blank
+/// means, then the keys that get past it.
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    block: Block,
+    headline: &str,
+    hints: &[&str],
+    theme: &Theme,
+) {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {}", i18n::tr(headline)),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )),
+    ];
+    for hint in hints {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", i18n::tr(hint)),
+            Style::default().fg(theme.dim),
+        )));
+    }
+
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(widget, area);
+}
@@ -0,0 +1,102 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{EventLevel, Theme};
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Events
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_events(f, chunks[1], state);
+    render_footer(f, chunks[2], theme);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let errors = state
+        .event_log
+        .iter()
+        .filter(|e| e.level == EventLevel::Error)
+        .count();
+
+    let header = Paragraph::new(format!(
+        " Connection events: {} recorded, {} errors ",
+        state.event_log.len(),
+        errors
+    ))
+    .style(
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_events(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let visible = area.height.saturating_sub(1) as usize;
+    let end = state.event_log.len().saturating_sub(state.event_log_scroll);
+    let start = end.saturating_sub(visible);
+
+    let items: Vec<ListItem> = state.event_log[start..end]
+        .iter()
+        .map(|entry| {
+            let (label, color) = match entry.level {
+                EventLevel::Info => ("INFO ", theme.dim),
+                EventLevel::Warn => ("WARN ", theme.highlight),
+                EventLevel::Error => ("ERROR", theme.error),
+            };
+            // "2024-01-01T12:34:56.789Z" -> "12:34:56.789"
+            let time = entry
+                .at
+                .try_to_rfc3339_string()
+                .ok()
+                .and_then(|s| s.get(11..23).map(str::to_string))
+                .unwrap_or_default();
+
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {} ", time), Style::default().fg(theme.muted)),
+                Span::styled(format!("{} ", label), Style::default().fg(color)),
+                Span::styled(entry.message.clone(), Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let title = if state.event_log_scroll == 0 {
+        " Topology and heartbeat events ".to_string()
+    } else {
+        format!(" Scrolled back {} ", state.event_log_scroll)
+    };
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(title)
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(list, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, theme: &Theme) {
+    let footer_text = i18n::hints(" [j/k] Scroll  [G] Latest  [Esc/Ctrl+E] Back ");
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
@@ -0,0 +1,120 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::i18n;
+use crate::models::{PlanNode, PlanTree, Theme};
+use crate::ui::popup;
+
+/// The explained plan as a tree of stages with their execution counters.
+pub fn render(f: &mut Frame, area: Rect, tree: &PlanTree, theme: &Theme) {
+    let popup = popup::centered_rect(
+        area.width.saturating_sub(8).min(110),
+        area.height.saturating_sub(4).min(30),
+        area,
+    );
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Totals
+            Constraint::Min(0),    // Stages
+            Constraint::Length(2), // Detail
+            Constraint::Length(1), // Keys
+        ])
+        .margin(1)
+        .split(popup);
+
+    let totals = Paragraph::new(format!(
+        "returned {}  keys examined {}  docs examined {}  {} ms",
+        tree.returned, tree.keys_examined, tree.docs_examined, tree.time_ms
+    ))
+    .style(Style::default().fg(theme.dim))
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    let visible = tree.visible();
+    let items: Vec<ListItem> = visible
+        .iter()
+        .map(|&i| node_line(&tree.nodes[i], tree.has_children(i), theme))
+        .map(ListItem::new)
+        .collect();
+    let selected = visible.iter().position(|&i| i == tree.selected);
+    let mut list_state = ListState::default().with_selected(selected);
+    let list = List::new(items).highlight_style(
+        theme
+            .background(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let detail = tree
+        .nodes
+        .get(tree.selected)
+        .and_then(|node| node.detail.clone())
+        .unwrap_or_default();
+    let detail = Paragraph::new(detail)
+        .style(Style::default().fg(theme.accent))
+        .block(Block::default().borders(Borders::TOP));
+    let keys = Paragraph::new(i18n::hints("[↑/↓] Stage  [Enter] Fold  [Esc] Close"))
+        .style(Style::default().fg(theme.muted));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(" Query plan "),
+        popup,
+    );
+    f.render_widget(totals, chunks[0]);
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+    f.render_widget(detail, chunks[2]);
+    f.render_widget(keys, chunks[3]);
+}
+
+fn node_line(node: &PlanNode, has_children: bool, theme: &Theme) -> Line<'static> {
+    let fold = match (has_children, node.collapsed) {
+        (false, _) => "  ",
+        (true, false) => "▾ ",
+        (true, true) => "▸ ",
+    };
+    let mut spans = vec![
+        Span::raw(format!("{}{}", "  ".repeat(node.depth), fold)),
+        Span::styled(
+            node.stage.clone(),
+            Style::default()
+                .fg(stage_color(&node.stage, theme))
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+
+    let metrics: Vec<String> = [
+        ("returned", node.returned),
+        ("keys", node.keys_examined),
+        ("docs", node.docs_examined),
+        ("ms", node.time_ms),
+    ]
+    .iter()
+    .filter_map(|(label, value)| value.map(|v| format!("{} {}", label, v)))
+    .collect();
+    if !metrics.is_empty() {
+        spans.push(Span::styled(
+            format!("  {}", metrics.join("  ")),
+            Style::default().fg(theme.dim),
+        ));
+    }
+    Line::from(spans)
+}
+
+// index use is good, a full scan or an in-memory sort is worth a look
+fn stage_color(stage: &str, theme: &Theme) -> Color {
+    match stage {
+        "IXSCAN" | "EXPRESS_IXSCAN" | "IDHACK" | "EXPRESS_CLUSTERED_IXSCAN" => theme.success,
+        "COLLSCAN" => theme.error,
+        "SORT" | "SORT_KEY_GENERATOR" => theme.highlight,
+        "FETCH" => theme.accent,
+        _ => theme.text,
+    }
+}
@@ -0,0 +1,93 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::i18n;
+use crate::models::{ExportDialog, ExportField, Theme};
+use crate::services::export::FORMATS;
+use crate::ui::popup::centered_rect;
+
+pub fn render(
+    f: &mut Frame,
+    area: Rect,
+    dialog: &ExportDialog,
+    error: Option<&str>,
+    theme: &Theme,
+) {
+    let focused = dialog.focused_field();
+    let format = dialog.format();
+    let mut text: Vec<Line> = dialog
+        .fields()
+        .into_iter()
+        .map(|field| {
+            let is_focused = field == focused;
+            let (label, value) = match field {
+                ExportField::Format => ("Format", format!("◀ {} ▶", format.name)),
+                ExportField::Path if dialog.is_bulk() => ("Directory", dialog.path.clone()),
+                ExportField::Path => ("File", dialog.path.clone()),
+                ExportField::Columns if dialog.columns.is_empty() && !is_focused => {
+                    ("Columns", "every field".to_string())
+                }
+                ExportField::Columns => ("Columns", dialog.columns.clone()),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{}{:<12}", if is_focused { "> " } else { "  " }, label),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::styled(
+                    value,
+                    if is_focused {
+                        Style::default()
+                            .fg(theme.highlight)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.text)
+                    },
+                ),
+            ])
+        })
+        .collect();
+
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        format!("  {}: {}", format.name, format.description),
+        Style::default().fg(theme.accent),
+    )));
+    let names: Vec<&str> = FORMATS.iter().map(|f| f.name).collect();
+    text.push(Line::from(Span::styled(
+        format!("  Formats: {}", names.join(", ")),
+        Style::default().fg(theme.muted),
+    )));
+    if let Some(error) = error {
+        text.push(Line::from(Span::styled(
+            format!("  Error: {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints("  [Tab/↑/↓] Field  [←/→] Format  [Enter] Export  [Esc] Cancel"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let title = if dialog.is_bulk() {
+        format!(" Export {} collection(s) ", dialog.collections.len())
+    } else {
+        " Export matching documents ".to_string()
+    };
+    let popup = centered_rect(80, text.len() as u16 + 2, area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(title),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
@@ -0,0 +1,82 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::i18n;
+use crate::models::{NamespaceFinder, Theme};
+use crate::ui::popup::centered_rect;
+
+pub fn render(f: &mut Frame, area: Rect, finder: &NamespaceFinder, theme: &Theme) {
+    let popup = centered_rect(60, area.height.saturating_sub(4).min(24), area);
+    // the border, the input with the blank line under it, and the loading note
+    let rows = (popup.height as usize).saturating_sub(5).max(1);
+    let first = finder.selected.saturating_sub(rows - 1);
+
+    let mut text = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.dim)),
+            Span::styled(finder.input.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.highlight)),
+            Span::styled(
+                format!("  {}/{}", finder.matches.len(), finder.namespaces.len()),
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    for (i, (index, positions)) in finder.matches.iter().enumerate().skip(first).take(rows) {
+        let namespace = &finder.namespaces[*index];
+        let selected = i == finder.selected;
+        let base = if !namespace.contains('.') {
+            Style::default().fg(theme.accent)
+        } else {
+            Style::default().fg(theme.text)
+        };
+        let base = if selected {
+            base.add_modifier(Modifier::BOLD)
+        } else {
+            base
+        };
+        let mut spans = vec![Span::styled(
+            if selected { "> " } else { "  " },
+            Style::default().fg(theme.highlight),
+        )];
+        for (n, c) in namespace.chars().enumerate() {
+            let style = if positions.contains(&n) {
+                base.fg(theme.highlight)
+            } else {
+                base
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+        text.push(Line::from(spans));
+    }
+    if finder.matches.is_empty() {
+        text.push(Line::from(Span::styled(
+            "  No database or collection matches",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    if finder.loading {
+        text.push(Line::from(Span::styled(
+            "  Listing collections…",
+            Style::default().fg(theme.muted),
+        )));
+    }
+
+    let widget = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(" Go to database or collection ")
+            .title_bottom(i18n::hints(" [↑/↓] Select  [Enter] Open  [Esc] Close ")),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
@@ -0,0 +1,147 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    symbols::Marker,
+    widgets::{
+        Block, Borders,
+        canvas::{Canvas, Map, MapResolution, Points, Rectangle},
+    },
+};
+
+use crate::app::state::AppState;
+use crate::services::geo::{self, GeoShape};
+
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let points: Vec<(f64, f64)> = match &state.geo_field {
+        Some(field) => state
+            .documents
+            .iter()
+            .filter_map(|doc| geo::extract_point(doc, field))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let selected: Vec<(f64, f64)> = match (&state.geo_field, state.get_selected_document()) {
+        (Some(field), Some(doc)) => geo::extract_point(doc, field).into_iter().collect(),
+        _ => Vec::new(),
+    };
+
+    let (x_bounds, y_bounds) = plot_bounds(&points, state.geo_shape.as_ref());
+
+    let title = format!(
+        " Map ({} of {} plotted) ",
+        points.len(),
+        state.documents.len()
+    );
+
+    let canvas = Canvas::default()
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::BOTTOM)
+                .title(title)
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .marker(Marker::Braille)
+        .x_bounds(x_bounds)
+        .y_bounds(y_bounds)
+        .paint(|ctx| {
+            ctx.draw(&Map {
+                color: theme.muted,
+                resolution: MapResolution::High,
+            });
+            ctx.layer();
+
+            if let Some(GeoShape::Box {
+                min_lng,
+                min_lat,
+                max_lng,
+                max_lat,
+            }) = &state.geo_shape
+            {
+                ctx.draw(&Rectangle {
+                    x: *min_lng,
+                    y: *min_lat,
+                    width: max_lng - min_lng,
+                    height: max_lat - min_lat,
+                    color: theme.special,
+                });
+            }
+
+            ctx.draw(&Points {
+                coords: &points,
+                color: theme.accent,
+            });
+            ctx.draw(&Points {
+                coords: &selected,
+                color: theme.highlight,
+            });
+        });
+
+    f.render_widget(canvas, area);
+}
+
+fn plot_bounds(points: &[(f64, f64)], shape: Option<&GeoShape>) -> ([f64; 2], [f64; 2]) {
+    let mut min_x = f64::MAX;
+    let mut max_x = f64::MIN;
+    let mut min_y = f64::MAX;
+    let mut max_y = f64::MIN;
+
+    let mut include = |x: f64, y: f64| {
+        min_x = min_x.min(x);
+        max_x = max_x.max(x);
+        min_y = min_y.min(y);
+        max_y = max_y.max(y);
+    };
+
+    for (x, y) in points {
+        include(*x, *y);
+    }
+
+    match shape {
+        Some(GeoShape::Near {
+            lng,
+            lat,
+            max_meters,
+        }) => {
+            let r = max_meters.map(|m| m / METERS_PER_DEGREE).unwrap_or(0.0);
+            include(lng - r, lat - r);
+            include(lng + r, lat + r);
+        }
+        Some(GeoShape::Circle {
+            lng,
+            lat,
+            radius_meters,
+        }) => {
+            let r = radius_meters / METERS_PER_DEGREE;
+            include(lng - r, lat - r);
+            include(lng + r, lat + r);
+        }
+        Some(GeoShape::Box {
+            min_lng,
+            min_lat,
+            max_lng,
+            max_lat,
+        }) => {
+            include(*min_lng, *min_lat);
+            include(*max_lng, *max_lat);
+        }
+        None => {}
+    }
+
+    if min_x > max_x {
+        return ([-180.0, 180.0], [-90.0, 90.0]);
+    }
+
+    // pad the extent so edge points are not drawn on the border
+    let pad_x = ((max_x - min_x) * 0.1).max(0.01);
+    let pad_y = ((max_y - min_y) * 0.1).max(0.01);
+
+    (
+        [(min_x - pad_x).max(-180.0), (max_x + pad_x).min(180.0)],
+        [(min_y - pad_y).max(-90.0), (max_y + pad_y).min(90.0)],
+    )
+}
@@ -0,0 +1,113 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{HealthStatus, Theme};
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Checks
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_checks(f, chunks[1], state);
+    render_footer(f, chunks[2], theme);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let worst = state
+        .health_items
+        .iter()
+        .map(|item| item.status)
+        .max()
+        .unwrap_or(HealthStatus::Green);
+
+    let host = state
+        .connection
+        .as_ref()
+        .map(|c| c.server_info.host.clone())
+        .unwrap_or_default();
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" Health check for {} ", host),
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" {} ", status_label(worst)),
+            theme
+                .background(status_color(worst, theme))
+                .fg(theme.badge_text),
+        ),
+    ]))
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_checks(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let items: Vec<ListItem> = state
+        .health_items
+        .iter()
+        .map(|item| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" ● {:<6} ", status_label(item.status)),
+                    Style::default().fg(status_color(item.status, theme)),
+                ),
+                Span::styled(
+                    format!("{:<18}", item.name),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(item.detail.clone(), Style::default().fg(theme.dim)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(" Checks ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(list, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, theme: &Theme) {
+    let footer_text = i18n::hints(" [Enter] Continue to databases  [r] Re-run  [q] Quit ");
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
+
+fn status_label(status: HealthStatus) -> &'static str {
+    match status {
+        HealthStatus::Green => "OK",
+        HealthStatus::Yellow => "WARN",
+        HealthStatus::Red => "ALERT",
+    }
+}
+
+fn status_color(status: HealthStatus, theme: &Theme) -> Color {
+    match status {
+        HealthStatus::Green => theme.success,
+        HealthStatus::Yellow => theme.highlight,
+        HealthStatus::Red => theme.error,
+    }
+}
@@ -0,0 +1,96 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::keymap::KEYMAP;
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::ui::popup;
+
+const KEY_COLUMN: usize = 18;
+
+/// Every keybinding from the central keymap, grouped by screen, followed by
+/// the actions that can be rebound in the settings file.
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let mut lines = Vec::new();
+    for section in KEYMAP {
+        lines.push(Line::from(Span::styled(
+            i18n::tr(section.title),
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for (keys, action) in section.bindings {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<width$}", keys, width = KEY_COLUMN),
+                    Style::default().fg(theme.highlight),
+                ),
+                Span::raw(i18n::tr(action)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+    lines.extend(binding_lines(state));
+
+    let scroll = state.help_scroll.min(lines.len().saturating_sub(1));
+    let popup = popup::centered_rect(72, area.height.saturating_sub(4), area);
+    let widget = Paragraph::new(lines.split_off(scroll)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(format!(" {} ", i18n::tr("Keys")))
+            .title_bottom(
+                Line::from(i18n::hints(" [↑/↓/PgUp/PgDn] Scroll  [Esc/?] Close ")).centered(),
+            ),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+// the rebindable actions with the keys they are on now, rebound ones
+// highlighted, and what the conflict check found
+fn binding_lines(state: &AppState) -> Vec<Line<'static>> {
+    let theme = &state.theme;
+    let mut lines = vec![Line::from(Span::styled(
+        i18n::tr("Key bindings ([keys] in config.toml)"),
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    for (action, keys, rebound) in state.bindings.actions() {
+        let keys = keys
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" / ");
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<width$}", keys, width = KEY_COLUMN),
+                Style::default().fg(if rebound {
+                    theme.special
+                } else {
+                    theme.highlight
+                }),
+            ),
+            Span::styled(
+                format!("{:<16}", action.name()),
+                Style::default().fg(theme.muted),
+            ),
+            Span::raw(i18n::tr(action.description()).to_string()),
+        ]));
+    }
+    for problem in &state.bindings.problems {
+        lines.push(Line::from(Span::styled(
+            format!("  ! {}", problem),
+            Style::default().fg(theme.error),
+        )));
+    }
+    lines
+}
@@ -0,0 +1,351 @@
+use std::collections::BTreeSet;
+
+use mongodb::bson::{Bson, DateTime, Document, spec::BinarySubtype};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+use crate::models::{DateFormat, Theme, ValueFormat};
+use crate::services::encryption;
+
+const INDENT: &str = "  ";
+
+/// A document pretty-printed like mongosh, one styled `Line` per output line.
+pub fn document_lines(doc: &Document, format: ValueFormat, theme: &Theme) -> Vec<Line<'static>> {
+    value_lines(&Bson::Document(doc.clone()), format, theme)
+}
+
+/// Any BSON value pretty-printed like mongosh. Unless `format` asks for
+/// canonical extended JSON, ObjectIds show when they were made, binary
+/// UUIDs and timestamps read as such, and dates are at its UTC offset.
+pub fn value_lines(value: &Bson, format: ValueFormat, theme: &Theme) -> Vec<Line<'static>> {
+    let mut writer = Writer {
+        format,
+        theme: *theme,
+        ..Writer::default()
+    };
+    writer.value(value, 0, String::new());
+    writer.finish().lines
+}
+
+/// A document laid out as a foldable tree, line by line.
+#[derive(Default)]
+pub struct DocumentTree {
+    pub lines: Vec<Line<'static>>,
+    /// For each line, the path of the subdocument or array it opens or sits
+    /// in, so folding works from any line; the top level has none.
+    pub folds: Vec<Option<String>>,
+    /// For each line, the path of the field or array item it shows.
+    pub fields: Vec<Option<String>>,
+}
+
+/// A document as a foldable tree: pretty-printed like [`document_lines`]
+/// behind a gutter marking open and folded subdocuments and arrays, with
+/// those whose dotted paths are in `folded` shown on one line.
+pub fn document_tree(
+    doc: &Document,
+    folded: &BTreeSet<String>,
+    format: ValueFormat,
+    theme: &Theme,
+) -> DocumentTree {
+    let mut writer = Writer {
+        format,
+        theme: *theme,
+        folded: Some(folded),
+        ..Writer::default()
+    };
+    writer.value(&Bson::Document(doc.clone()), 0, String::new());
+    writer.finish()
+}
+
+#[derive(Default)]
+struct Writer<'a> {
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    format: ValueFormat,
+    theme: Theme,
+    /// Set when writing a tree, with the paths shown folded.
+    folded: Option<&'a BTreeSet<String>>,
+    /// The fold path of each finished line, and of the one being written.
+    paths: Vec<Option<String>>,
+    current_path: Option<String>,
+    /// The field path of each finished line, and of the one being written.
+    fields: Vec<Option<String>>,
+    current_field: Option<String>,
+    /// Whether the line being written opens a subdocument or array, and
+    /// whether that is folded.
+    current_fold: Option<bool>,
+    folds: Vec<Option<bool>>,
+    /// Paths of the subdocuments and arrays being written, innermost last.
+    open: Vec<Option<String>>,
+}
+
+impl Writer<'_> {
+    fn push(&mut self, text: String, color: Color) {
+        self.current
+            .push(Span::styled(text, Style::default().fg(color)));
+    }
+
+    fn punct(&mut self, text: &'static str) {
+        self.current.push(Span::raw(text));
+    }
+
+    fn newline(&mut self, depth: usize) {
+        self.end_line();
+        if depth > 0 {
+            self.current.push(Span::raw(INDENT.repeat(depth)));
+        }
+    }
+
+    fn end_line(&mut self) {
+        let spans = std::mem::take(&mut self.current);
+        self.lines.push(Line::from(spans));
+        self.paths.push(std::mem::replace(
+            &mut self.current_path,
+            self.open.last().cloned().flatten(),
+        ));
+        self.folds.push(self.current_fold.take());
+        self.fields.push(self.current_field.take());
+    }
+
+    fn finish(mut self) -> DocumentTree {
+        self.end_line();
+        if self.folded.is_some() {
+            for (line, fold) in self.lines.iter_mut().zip(&self.folds) {
+                let gutter = match fold {
+                    Some(true) => "▸ ",
+                    Some(false) => "▾ ",
+                    None => "  ",
+                };
+                line.spans.insert(
+                    0,
+                    Span::styled(gutter, Style::default().fg(self.theme.muted)),
+                );
+            }
+        }
+        DocumentTree {
+            lines: self.lines,
+            folds: self.paths,
+            fields: self.fields,
+        }
+    }
+
+    // starts a subdocument or array; false when it is folded and was
+    // written as a summary instead
+    fn open(&mut self, path: &str, summary: String) -> bool {
+        let Some(folded) = self.folded.filter(|_| !path.is_empty()) else {
+            self.open.push(None);
+            return true;
+        };
+        self.current_path = Some(path.to_string());
+        if folded.contains(path) {
+            self.current_fold = Some(true);
+            self.push(summary, self.theme.muted);
+            return false;
+        }
+        self.current_fold = Some(false);
+        self.open.push(Some(path.to_string()));
+        true
+    }
+
+    fn value(&mut self, value: &Bson, depth: usize, path: String) {
+        let child = |key: &str| {
+            if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", path, key)
+            }
+        };
+        match value {
+            Bson::Document(doc) if doc.is_empty() => self.punct("{}"),
+            Bson::Document(doc) => {
+                if !self.open(&path, format!("{{ … {} field(s) }}", doc.len())) {
+                    return;
+                }
+                self.punct("{");
+                for (i, (key, inner)) in doc.iter().enumerate() {
+                    self.newline(depth + 1);
+                    self.current_field = Some(child(key));
+                    self.push(quoted(key), self.theme.json_key);
+                    self.punct(": ");
+                    self.value(inner, depth + 1, child(key));
+                    if i + 1 < doc.len() {
+                        self.punct(",");
+                    }
+                }
+                self.newline(depth);
+                self.punct("}");
+                self.open.pop();
+            }
+            Bson::Array(items) if items.is_empty() => self.punct("[]"),
+            Bson::Array(items) => {
+                if !self.open(&path, format!("[ … {} item(s) ]", items.len())) {
+                    return;
+                }
+                self.punct("[");
+                for (i, inner) in items.iter().enumerate() {
+                    self.newline(depth + 1);
+                    self.current_field = Some(child(&i.to_string()));
+                    self.value(inner, depth + 1, child(&i.to_string()));
+                    if i + 1 < items.len() {
+                        self.punct(",");
+                    }
+                }
+                self.newline(depth);
+                self.punct("]");
+                self.open.pop();
+            }
+            Bson::String(s) => self.push(quoted(s), self.theme.json_string),
+            Bson::Binary(binary) if encryption::ciphertext(value).is_some() => {
+                self.push(encryption::label(binary), self.theme.special)
+            }
+            other if self.format.canonical => self.push(
+                other.clone().into_canonical_extjson().to_string(),
+                color(other, &self.theme),
+            ),
+            Bson::Int32(_) | Bson::Int64(_) | Bson::Double(_) | Bson::Decimal128(_) => {
+                self.push(value.to_string(), color(value, &self.theme))
+            }
+            Bson::Boolean(b) => self.push(b.to_string(), color(value, &self.theme)),
+            Bson::Null | Bson::Undefined => {
+                self.push("null".to_string(), color(value, &self.theme))
+            }
+            Bson::ObjectId(id) => {
+                self.push(
+                    format!("ObjectId('{}')", id.to_hex()),
+                    color(value, &self.theme),
+                );
+                let created = DateTime::from_millis(id.timestamp().timestamp_millis());
+                self.push(
+                    format!(" /* {} */", self.format.date(created)),
+                    self.theme.muted,
+                );
+            }
+            Bson::DateTime(dt) if self.format.date_format == DateFormat::Epoch => self.push(
+                format!("new Date({})", dt.timestamp_millis()),
+                color(value, &self.theme),
+            ),
+            Bson::DateTime(dt) => self.push(
+                format!("ISODate('{}')", self.format.date(*dt)),
+                color(value, &self.theme),
+            ),
+            Bson::Binary(binary) if binary.subtype == BinarySubtype::Uuid => {
+                match binary.to_uuid() {
+                    Ok(uuid) => self.push(format!("UUID('{}')", uuid), self.theme.json_id),
+                    Err(_) => self.push(binary.to_string(), self.theme.text),
+                }
+            }
+            Bson::Timestamp(ts) => {
+                let at = DateTime::from_millis(ts.time as i64 * 1000);
+                self.push(
+                    format!("Timestamp({}, {})", self.format.date(at), ts.increment),
+                    color(value, &self.theme),
+                )
+            }
+            other => self.push(other.to_string(), self.theme.text),
+        }
+    }
+}
+
+// the color of a value's type, the same in either format
+fn color(value: &Bson, theme: &Theme) -> Color {
+    match value {
+        Bson::Int32(_) | Bson::Int64(_) | Bson::Double(_) | Bson::Decimal128(_) => {
+            theme.json_number
+        }
+        Bson::Boolean(_) => theme.json_bool,
+        Bson::Null | Bson::Undefined => theme.json_null,
+        Bson::ObjectId(_) | Bson::Binary(_) => theme.json_id,
+        Bson::DateTime(_) | Bson::Timestamp(_) => theme.json_date,
+        _ => theme.text,
+    }
+}
+
+/// Where `query` occurs in `lines`, ignoring ASCII case, as the line and
+/// the byte range within its text, top to bottom.
+pub fn find_matches(lines: &[Line], query: &str) -> Vec<(usize, std::ops::Range<usize>)> {
+    let query = query.to_ascii_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        let text = text.to_ascii_lowercase();
+        let mut pos = 0;
+        while let Some(start) = text[pos..].find(&query).map(|i| pos + i) {
+            found.push((index, start..start + query.len()));
+            pos = start + query.len();
+        }
+    }
+    found
+}
+
+/// `lines` with every occurrence of `query` marked, the `current`-th one in
+/// the order of [`find_matches`] standing out from the rest.
+pub fn mark_matches(
+    lines: Vec<Line<'static>>,
+    query: &str,
+    current: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let mut matches = find_matches(&lines, query)
+        .into_iter()
+        .enumerate()
+        .peekable();
+    let mut marked = Vec::with_capacity(lines.len());
+    for (index, line) in lines.into_iter().enumerate() {
+        let mut ranges = Vec::new();
+        while let Some((n, (_, range))) = matches.next_if(|(_, (i, _))| *i == index) {
+            ranges.push((range, n == current));
+        }
+        if ranges.is_empty() {
+            marked.push(line);
+            continue;
+        }
+
+        let mut spans = Vec::new();
+        let mut offset = 0;
+        for span in line.spans {
+            let text = span.content.as_ref();
+            let end = offset + text.len();
+            // cut the span where a match starts or ends inside it
+            let mut cuts = vec![offset, end];
+            for (range, _) in &ranges {
+                cuts.extend(
+                    [range.start, range.end]
+                        .into_iter()
+                        .filter(|c| (offset..end).contains(c)),
+                );
+            }
+            cuts.sort_unstable();
+            cuts.dedup();
+            for piece in cuts.windows(2) {
+                let (from, to) = (piece[0], piece[1]);
+                let style = match ranges.iter().find(|(range, _)| range.contains(&from)) {
+                    Some((_, true)) => span
+                        .style
+                        .fg(theme.badge_text)
+                        .patch(theme.background(theme.current_match)),
+                    Some((_, false)) => span
+                        .style
+                        .fg(theme.badge_text)
+                        .patch(theme.background(theme.highlight)),
+                    None => span.style,
+                };
+                spans.push(Span::styled(
+                    text[from - offset..to - offset].to_string(),
+                    style,
+                ));
+            }
+            offset = end;
+        }
+        marked.push(Line::from(spans));
+    }
+    marked
+}
+
+fn quoted(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text))
+}
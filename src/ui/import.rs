@@ -0,0 +1,146 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::ImportMode;
+use crate::ui::highlight;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Length(6), // Form
+            Constraint::Min(0),    // Preview
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_form(f, chunks[1], state);
+    render_preview(f, chunks[2], state);
+    render_footer(f, chunks[3], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let namespace = format!(
+        "{}.{}",
+        state.current_database.as_deref().unwrap_or(""),
+        state.current_collection.as_deref().unwrap_or("")
+    );
+    let header = Paragraph::new(format!(" Import into {} ", namespace))
+        .style(
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_form(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let form = &state.import_form;
+    let label = |text: &str| Span::styled(format!("  {:<8}", text), Style::default().fg(theme.dim));
+    let mode = |mode: ImportMode| {
+        let style = if form.mode == mode {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        Span::styled(format!(" {} ", mode.name()), style)
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            label("File"),
+            Span::styled(
+                format!("{}_", form.path),
+                Style::default().fg(theme.highlight),
+            ),
+        ]),
+        Line::from(vec![
+            label("Mode"),
+            mode(ImportMode::Insert),
+            mode(ImportMode::Upsert),
+        ]),
+    ];
+    if !form.completions.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", form.completions.join("  ")),
+            Style::default().fg(theme.accent),
+        )));
+    }
+    if let Some(error) = &state.error {
+        lines.push(Line::from(Span::styled(
+            format!("  Error: {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+
+    let widget = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(widget, area);
+}
+
+fn render_preview(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let form = &state.import_form;
+    let mut lines: Vec<Line> = Vec::new();
+    if !form.is_previewed() {
+        lines.push(Line::from(Span::styled(
+            " Press Enter to preview the file. JSON arrays and one document per line are read.",
+            Style::default().fg(theme.muted),
+        )));
+    } else {
+        if let Some(error) = &form.preview_error {
+            lines.push(Line::from(Span::styled(
+                format!(" {}", error),
+                Style::default().fg(theme.error),
+            )));
+        }
+        for doc in &form.preview {
+            lines.extend(highlight::document_lines(doc, state.value_format, theme));
+            lines.push(Line::from(""));
+        }
+    }
+
+    let title = if form.is_previewed() {
+        format!(" First {} document(s) ", form.preview.len())
+    } else {
+        " Preview ".to_string()
+    };
+    let widget = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(title)
+            .title_style(Style::default().fg(theme.dim)),
+    );
+    f.render_widget(widget, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let enter = if state.import_form.is_previewed() {
+        "[Enter] Import"
+    } else {
+        "[Enter] Preview"
+    };
+    let footer_text = i18n::hints(&format!(
+        " [Tab] Complete path  [←/→] Insert/Upsert  {}  [Esc] Back ",
+        enter
+    ));
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
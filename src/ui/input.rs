@@ -0,0 +1,22 @@
+use ratatui::{
+    style::{Modifier, Style},
+    text::Span,
+};
+
+use crate::app::input::TextInput;
+
+/// The field's text in `style`, with the cursor drawn as a reversed cell
+/// while the field has focus.
+pub fn spans(input: &TextInput, style: Style, focused: bool) -> Vec<Span<'static>> {
+    if !focused {
+        return vec![Span::styled(input.as_str().to_string(), style)];
+    }
+    let (before, after) = input.split_at_cursor();
+    let mut rest = after.chars();
+    let under = rest.next().map_or(" ".to_string(), String::from);
+    vec![
+        Span::styled(before.to_string(), style),
+        Span::styled(under, style.add_modifier(Modifier::REVERSED)),
+        Span::styled(rest.as_str().to_string(), style),
+    ]
+}
@@ -1,4 +1,38 @@
 pub mod database_list;
 pub mod collection_list;
 pub mod document_view;
-pub mod connection;
\ No newline at end of file
+pub mod connection;
+pub mod geo_plot;
+pub mod tail;
+pub mod popup;
+pub mod health;
+pub mod atlas;
+pub mod compare;
+pub mod tasks;
+pub mod event_log;
+pub mod replica_set;
+pub mod sharding;
+pub mod search;
+pub mod aggregation;
+pub mod switcher;
+pub mod table_view;pub mod highlight;
+pub mod explain;
+pub mod help;
+pub mod timings;
+pub mod too_small;
+pub mod import;
+pub mod about;
+pub mod schema;
+pub mod setup;
+pub mod empty;
+pub mod input;
+pub mod export;
+pub mod reference_diff;
+pub mod utilities;
+pub mod operations;
+pub mod dry_run;
+pub mod users;
+pub mod bulk_update;
+pub mod finder;
+pub mod theme;
+pub mod status_bar;
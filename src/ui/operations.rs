@@ -0,0 +1,182 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{OperationInfo, Theme};
+use crate::ui::tasks::format_elapsed;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),      // Header
+            Constraint::Percentage(60), // Operations
+            Constraint::Min(0),         // Selected operation
+            Constraint::Length(1),      // Footer
+        ])
+        .split(area);
+
+    let visible = state.visible_operations();
+    render_header(f, chunks[0], state, visible.len());
+    render_operations(
+        f,
+        chunks[1],
+        &visible,
+        state.selected_operation_index,
+        theme,
+    );
+    render_details(f, chunks[2], state.get_selected_operation(), theme);
+    render_footer(f, chunks[3], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState, shown: usize) {
+    let theme = &state.theme;
+    let mut spans = vec![Span::styled(
+        format!(" Operations: {} running ", state.operations.len()),
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if state.operations_min_secs > 0 {
+        spans.push(Span::styled(
+            format!(" {} running ≥ {}s ", shown, state.operations_min_secs),
+            theme.background(theme.highlight).fg(theme.badge_text),
+        ));
+    }
+    if state.operations_paused {
+        spans.push(Span::styled(
+            " PAUSED ",
+            theme.background(theme.dim).fg(theme.badge_text),
+        ));
+    }
+    let header = Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_operations(
+    f: &mut Frame,
+    area: Rect,
+    operations: &[&OperationInfo],
+    selected: usize,
+    theme: &Theme,
+) {
+    let width = operations
+        .iter()
+        .map(|op| op.namespace.len())
+        .max()
+        .unwrap_or(0)
+        .clamp(9, 40);
+
+    let items: Vec<ListItem> = operations
+        .iter()
+        .map(|op| {
+            let color = if op.running.as_secs() >= 10 {
+                theme.error
+            } else if op.running.as_secs() >= 1 {
+                theme.highlight
+            } else {
+                theme.text
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!(" {:>10}  ", op.opid_label()),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::styled(format!("{:<8}", op.op), Style::default().fg(theme.accent)),
+                Span::styled(
+                    format!("{:<width$}  ", op.namespace, width = width),
+                    Style::default().fg(theme.text),
+                ),
+                Span::styled(
+                    format!("{:>8}  ", format_elapsed(op.running)),
+                    Style::default().fg(color),
+                ),
+                Span::styled(op.plan_summary.clone(), Style::default().fg(theme.muted)),
+            ];
+            if op.waiting_for_lock {
+                spans.push(Span::styled(
+                    "  waiting for lock",
+                    Style::default().fg(theme.error),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!(
+        " {:>10}  {:<8}{:<width$}  {:>8}  Plan ",
+        "opid",
+        "Op",
+        "Namespace",
+        "Running",
+        width = width
+    );
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut list_state = ListState::default();
+    if !operations.is_empty() {
+        list_state.select(Some(selected));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_details(f: &mut Frame, area: Rect, operation: Option<&OperationInfo>, theme: &Theme) {
+    let text = match operation {
+        Some(op) => {
+            let label = |label: &str, value: &str| {
+                Line::from(vec![
+                    Span::styled(format!(" {:<10}", label), Style::default().fg(theme.dim)),
+                    Span::styled(value.to_string(), Style::default().fg(theme.text)),
+                ])
+            };
+            vec![
+                label("Client", &op.client),
+                label("App", &op.app_name),
+                label("Command", &op.command),
+            ]
+        }
+        None => vec![Line::from(Span::styled(
+            " No operations match",
+            Style::default().fg(theme.muted),
+        ))],
+    };
+
+    let widget = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::TOP));
+    f.render_widget(widget, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let text = if let Some(error) = &state.error {
+        format!(" Error: {}", error)
+    } else if let Some(message) = &state.message {
+        format!(" {}", message)
+    } else {
+        i18n::hints(
+            " [j/k] Navigate  [K] Kill  [l] Long-running only  [Space] Pause/Resume  [r] Refresh  [Esc] Back ",
+        )
+    };
+    let footer = Paragraph::new(text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
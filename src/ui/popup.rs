@@ -0,0 +1,133 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::app::prompt::Prompt;
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::Theme;
+
+/// Returns a rect of `width` x `height` centered inside `area`, clamped to fit.
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(width),
+            Constraint::Min(0),
+        ])
+        .split(vertical[1])[1]
+}
+
+pub fn render_prompt(f: &mut Frame, area: Rect, prompt: &Prompt, theme: &Theme) {
+    let popup = centered_rect(70, 5, area);
+
+    let text = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.highlight)),
+            Span::styled(
+                if prompt.kind.is_secret() {
+                    "•".repeat(prompt.input.chars().count())
+                } else {
+                    prompt.input.clone()
+                },
+                Style::default().fg(theme.text),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            i18n::hints("[Enter] Confirm  [Esc] Cancel"),
+            Style::default().fg(theme.muted),
+        )),
+    ];
+
+    let widget = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight))
+            .title(format!(" {} ", prompt.title))
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
+
+/// The checked privileges of the signed-in user, with `notes` below them.
+pub fn render_permissions(
+    f: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    title: &str,
+    notes: Vec<Line<'static>>,
+) {
+    let theme = &state.theme;
+    let users = state
+        .privileges
+        .as_ref()
+        .map(|p| p.users.join(", "))
+        .unwrap_or_default();
+
+    let mut text = vec![
+        Line::from(Span::styled(
+            if users.is_empty() {
+                " No authenticated user; access control appears to be off".to_string()
+            } else {
+                format!(" Signed in as {}", users)
+            },
+            Style::default().fg(theme.dim),
+        )),
+        Line::from(""),
+    ];
+    for check in &state.permission_checks {
+        let (mark, color) = if check.allowed {
+            ("✓", theme.success)
+        } else {
+            ("✗", theme.error)
+        };
+        text.push(Line::from(vec![
+            Span::styled(format!(" {} ", mark), Style::default().fg(color)),
+            Span::styled(
+                format!("{:<16}", check.action),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(check.purpose, Style::default().fg(theme.dim)),
+        ]));
+    }
+    if !notes.is_empty() {
+        text.push(Line::from(""));
+        text.extend(notes);
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints(" [Esc] Close"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let popup = centered_rect(80, text.len() as u16 + 2, area);
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(title.to_string()),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
@@ -0,0 +1,160 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{DiffKind, ReferenceDiff, Theme};
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),      // Header
+            Constraint::Percentage(55), // Differences
+            Constraint::Min(0),         // Field changes
+            Constraint::Length(1),      // Footer
+        ])
+        .split(area);
+
+    if let Some(diff) = &state.reference_diff {
+        render_header(f, chunks[0], diff, theme);
+        render_entries(f, chunks[1], diff, state.selected_reference_index, theme);
+        render_changes(f, chunks[2], diff, state.selected_reference_index, theme);
+    }
+    render_footer(f, chunks[3], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, diff: &ReferenceDiff, theme: &Theme) {
+    let (badge, badge_color) = if diff.differences() == 0 {
+        ("matches the reference".to_string(), theme.success)
+    } else {
+        (format!("{} differences", diff.differences()), theme.error)
+    };
+    let mut counts = format!(
+        "  +{} added  -{} removed  ~{} changed  {} unchanged",
+        diff.added, diff.removed, diff.changed, diff.unchanged
+    );
+    if diff.unreadable > 0 {
+        counts.push_str(&format!("  {} unreadable in the file", diff.unreadable));
+    }
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" {} vs {} ", diff.namespace, diff.path),
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" {} ", badge),
+            theme.background(badge_color).fg(theme.badge_text),
+        ),
+        Span::styled(counts, Style::default().fg(theme.dim)),
+    ]))
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_entries(f: &mut Frame, area: Rect, diff: &ReferenceDiff, selected: usize, theme: &Theme) {
+    let items: Vec<ListItem> = diff
+        .entries
+        .iter()
+        .map(|entry| {
+            let (mark, note, color) = match &entry.kind {
+                DiffKind::Added => ("+", "only in the collection".to_string(), theme.success),
+                DiffKind::Removed => ("-", "only in the reference".to_string(), theme.error),
+                DiffKind::Changed(changes) => (
+                    "~",
+                    format!("{} field(s) changed", changes.len()),
+                    theme.highlight,
+                ),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {} ", mark), Style::default().fg(color)),
+                Span::styled(format!("{}  ", entry.id), Style::default().fg(theme.text)),
+                Span::styled(note, Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+
+    let listed = diff.entries.len() as u64;
+    let title = if listed < diff.differences() {
+        format!(" Differences (first {} of {}) ", listed, diff.differences())
+    } else {
+        " Differences ".to_string()
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut list_state = ListState::default();
+    if !diff.entries.is_empty() {
+        list_state.select(Some(selected));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_changes(f: &mut Frame, area: Rect, diff: &ReferenceDiff, selected: usize, theme: &Theme) {
+    let text: Vec<Line> = match diff.entries.get(selected).map(|entry| &entry.kind) {
+        Some(DiffKind::Changed(changes)) => changes
+            .iter()
+            .map(|change| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {}: ", change.path),
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::styled(
+                        change.before.clone().unwrap_or_else(|| "—".to_string()),
+                        Style::default().fg(theme.error),
+                    ),
+                    Span::styled(" → ", Style::default().fg(theme.muted)),
+                    Span::styled(
+                        change.after.clone().unwrap_or_else(|| "—".to_string()),
+                        Style::default().fg(theme.success),
+                    ),
+                ])
+            })
+            .collect(),
+        Some(DiffKind::Added) => vec![Line::from(
+            " Inserted since the export, or missing from the file",
+        )],
+        Some(DiffKind::Removed) => vec![Line::from(" Deleted since the export, or not restored")],
+        None => Vec::new(),
+    };
+
+    let widget = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::TOP)
+            .title(" Reference → collection ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+    f.render_widget(widget, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let text = if let Some(error) = &state.error {
+        format!(" Error: {}", error)
+    } else {
+        i18n::hints(" [j/k] Navigate  [r] Re-run  [Esc] Back  [q] Quit ")
+    };
+    let footer = Paragraph::new(text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::services::replset::format_date;
+use crate::ui::tasks::format_elapsed;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),  // Header
+            Constraint::Min(0),     // Members
+            Constraint::Length(14), // Elections
+            Constraint::Length(1),  // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_members(f, chunks[1], state);
+    render_elections(f, chunks[2], state);
+    render_footer(f, chunks[3], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let title = match &state.replica_set {
+        Some(rs) => format!(
+            " Replica set {}  term {}  oplog window {}  {} ",
+            rs.set_name,
+            rs.term
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "?".to_string()),
+            rs.oplog_window
+                .map(|w| format_elapsed(Duration::from_secs(w.secs().max(0) as u64)))
+                .unwrap_or_else(|| "unknown".to_string()),
+            if state.replica_set_paused {
+                "[paused]"
+            } else {
+                "[refreshing every 5s]"
+            }
+        ),
+        None => " Replica set ".to_string(),
+    };
+
+    let header = Paragraph::new(title)
+        .style(
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_members(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let Some(rs) = &state.replica_set else {
+        return;
+    };
+
+    let items: Vec<ListItem> = rs
+        .members
+        .iter()
+        .map(|member| {
+            let color = match (member.healthy, member.state.as_str()) {
+                (false, _) => theme.error,
+                (true, "PRIMARY") => theme.success,
+                (true, "SECONDARY") | (true, "ARBITER") => theme.text,
+                _ => theme.highlight,
+            };
+            let lag = match rs.lag_secs(member) {
+                Some(lag) if member.state == "SECONDARY" => format!("lag {}s", lag),
+                _ => String::new(),
+            };
+            let uptime = member
+                .uptime_secs
+                .map(|u| format!("up {}h{:02}m", u / 3600, (u % 3600) / 60))
+                .unwrap_or_default();
+            let ping = member
+                .ping_ms
+                .map(|p| format!("ping {}ms", p))
+                .unwrap_or_default();
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {:<32}", member.name),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{:<12}", member.state), Style::default().fg(color)),
+                Span::styled(format!("{:<14}", uptime), Style::default().fg(theme.dim)),
+                Span::styled(format!("{:<14}", ping), Style::default().fg(theme.dim)),
+                Span::styled(format!("{:<10}", lag), Style::default().fg(theme.dim)),
+                Span::styled(
+                    if member.is_self { "(connected)" } else { "" },
+                    Style::default().fg(theme.muted),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(" Members ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(list, area);
+}
+
+fn render_elections(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let mut lines = Vec::new();
+
+    if let Some(rs) = &state.replica_set {
+        if let Some(date) = rs.last_election_date {
+            lines.push(Line::from(vec![
+                Span::styled(" Last election: ", Style::default().fg(theme.dim)),
+                Span::styled(format_date(date), Style::default().fg(theme.text)),
+                Span::styled(
+                    format!(
+                        "  reason: {}",
+                        rs.last_election_reason.as_deref().unwrap_or("unknown")
+                    ),
+                    Style::default().fg(theme.dim),
+                ),
+            ]));
+        }
+        if let Some(primary) = rs.primary()
+            && let Some(date) = primary.election_date
+        {
+            lines.push(Line::from(Span::styled(
+                format!(" {} primary since {}", primary.name, format_date(date)),
+                Style::default().fg(theme.dim),
+            )));
+        }
+        if let Some(window) = rs.oplog_window {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    " Oplog of the connected member: {} to {}",
+                    format_date(window.first),
+                    format_date(window.last)
+                ),
+                Style::default().fg(theme.dim),
+            )));
+        }
+    }
+
+    if state.elections.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No election history readable from local.oplog.rs",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    for election in &state.elections {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "   term {:<6}",
+                    election
+                        .term
+                        .map(|t| t.to_string())
+                        .unwrap_or_else(|| "?".to_string())
+                ),
+                Style::default().fg(theme.highlight),
+            ),
+            Span::styled(
+                election.at.map(format_date).unwrap_or_default(),
+                Style::default().fg(theme.text),
+            ),
+        ]));
+    }
+
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::TOP)
+            .title(" Elections ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(widget, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let (footer_text, color) = if let Some(error) = &state.error {
+        (format!(" Error: {} ", error), theme.error)
+    } else if let Some(message) = &state.message {
+        (format!(" {} ", message), theme.success)
+    } else {
+        (
+            i18n::hints(" [r] Refresh  [Space] Pause/Resume  [s] Step down primary  [Esc] Back "),
+            theme.muted,
+        )
+    };
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(color));
+
+    f.render_widget(footer, area);
+}
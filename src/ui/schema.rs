@@ -0,0 +1,150 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{FieldStats, SchemaAnalysis, Theme};
+
+const BAR_WIDTH: usize = 30;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),      // Header
+            Constraint::Percentage(60), // Fields
+            Constraint::Min(0),         // Selected field
+            Constraint::Length(1),      // Footer
+        ])
+        .split(area);
+
+    if let Some(analysis) = &state.schema {
+        render_header(f, chunks[0], analysis, theme);
+        render_fields(f, chunks[1], state, analysis);
+        if let Some(field) = analysis.fields.get(state.selected_schema_field) {
+            render_field(f, chunks[2], field, analysis.sampled, theme);
+        }
+    }
+    render_footer(f, chunks[3], theme);
+}
+
+fn render_header(f: &mut Frame, area: Rect, analysis: &SchemaAnalysis, theme: &Theme) {
+    let header = Paragraph::new(format!(
+        " Schema of {}.{}: {} fields in {} sampled documents ",
+        analysis.database,
+        analysis.collection,
+        analysis.fields.len(),
+        analysis.sampled
+    ))
+    .style(
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_fields(f: &mut Frame, area: Rect, state: &AppState, analysis: &SchemaAnalysis) {
+    let theme = &state.theme;
+    if analysis.fields.is_empty() {
+        let empty = Paragraph::new(" The sample is empty; the collection has no documents.")
+            .style(Style::default().fg(theme.muted));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = analysis
+        .fields
+        .iter()
+        .map(|field| field_item(field, analysis.sampled, theme))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::BOTTOM))
+        .highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selected_schema_field));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn field_item(field: &FieldStats, sampled: usize, theme: &Theme) -> ListItem<'static> {
+    let types = field
+        .type_breakdown()
+        .iter()
+        .map(|(name, percent)| format!("{} {:.0}%", name, percent))
+        .collect::<Vec<_>>()
+        .join(" · ");
+
+    ListItem::new(Line::from(vec![
+        Span::styled(
+            format!(" {:<36}", field.path),
+            Style::default().fg(theme.text),
+        ),
+        Span::styled(
+            format!("{:>6.1}%  ", field.presence(sampled)),
+            Style::default().fg(theme.highlight),
+        ),
+        Span::styled(types, Style::default().fg(theme.dim)),
+    ]))
+}
+
+// one bar per observed type, then a few example values
+fn render_field(f: &mut Frame, area: Rect, field: &FieldStats, sampled: usize, theme: &Theme) {
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            " {}: in {} of {} documents",
+            field.path, field.count, sampled
+        ),
+        Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+    ))];
+    for (name, percent) in field.type_breakdown() {
+        let filled = (percent / 100.0 * BAR_WIDTH as f64).round() as usize;
+        lines.push(Line::from(vec![
+            Span::styled(format!("   {:<12}", name), Style::default().fg(theme.dim)),
+            Span::styled("█".repeat(filled), Style::default().fg(theme.accent)),
+            Span::styled(
+                "░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)),
+                Style::default().fg(theme.muted),
+            ),
+            Span::styled(
+                format!(" {:>5.1}%", percent),
+                Style::default().fg(theme.highlight),
+            ),
+        ]));
+    }
+    if !field.samples.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            " Examples",
+            Style::default().fg(theme.dim),
+        )));
+        for sample in &field.samples {
+            lines.push(Line::from(Span::styled(
+                format!("   {}", sample),
+                Style::default().fg(theme.success),
+            )));
+        }
+    }
+
+    let widget = Paragraph::new(lines).wrap(Wrap { trim: false });
+    f.render_widget(widget, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, theme: &Theme) {
+    let footer_text = i18n::hints(" [↑↓] Field  [n] Sample size  [r] Re-sample  [Esc] Back ");
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
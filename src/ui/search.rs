@@ -0,0 +1,258 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{SearchField, SearchOperator, Theme};
+use crate::services::search;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Length(8), // Builder
+            Constraint::Min(0),    // Results
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    let builder = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(chunks[1]);
+
+    let results = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(chunks[2]);
+
+    render_header(f, chunks[0], state);
+    render_form(f, builder[0], state);
+    render_stage(f, builder[1], state);
+    render_hits(f, results[0], state);
+    render_hit_document(f, results[1], state);
+    render_footer(f, chunks[3], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let namespace = format!(
+        "{}.{}",
+        state.current_database.as_deref().unwrap_or(""),
+        state.current_collection.as_deref().unwrap_or("")
+    );
+    let header = Paragraph::new(format!(" Atlas Search on {} ", namespace))
+        .style(
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_form(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let form = &state.search_form;
+    let index = if form.indexes.is_empty() {
+        "default (no search indexes listed)".to_string()
+    } else {
+        format!("◀ {} ▶", form.index_name())
+    };
+    let operator = format!("◀ {} ▶", form.operator.name());
+    let query_hint = if form.operator == SearchOperator::Text {
+        "  +must -not"
+    } else {
+        ""
+    };
+
+    let row = |field: SearchField, label: &str, value: String, hint: &str| {
+        let focused = form.focus == field;
+        let style = if focused {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+        Line::from(vec![
+            Span::styled(
+                format!("{}{:<10}", if focused { "> " } else { "  " }, label),
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled(value, style),
+            Span::styled(hint.to_string(), Style::default().fg(theme.muted)),
+        ])
+    };
+
+    let text = vec![
+        row(SearchField::Index, "Index", index, ""),
+        row(SearchField::Operator, "Operator", operator, ""),
+        row(
+            SearchField::Path,
+            "Path",
+            form.path.clone(),
+            "  comma separated, * for all",
+        ),
+        row(SearchField::Query, "Query", form.query.clone(), query_hint),
+    ];
+
+    let widget = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Builder ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(widget, area);
+}
+
+fn render_stage(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let stage = search::build_stage(&state.search_form);
+    let json = serde_json::to_string(&stage).unwrap_or_else(|_| format!("{}", stage));
+
+    let widget = Paragraph::new(json)
+        .style(Style::default().fg(theme.accent))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Stage ")
+                .title_style(Style::default().fg(theme.dim)),
+        );
+
+    f.render_widget(widget, area);
+}
+
+fn render_hits(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let items: Vec<ListItem> = state
+        .search_hits
+        .iter()
+        .map(|hit| {
+            let id = hit
+                .doc
+                .get("_id")
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "?".to_string());
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:>7.3} ", hit.score),
+                    Style::default().fg(theme.success),
+                ),
+                Span::styled(id, Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(" Results ({}) by score ", state.search_hits.len()))
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut list_state = ListState::default();
+    if !state.search_hits.is_empty() {
+        list_state.select(Some(state.selected_hit_index));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_hit_document(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let lines: Vec<Line> = match state.get_selected_hit() {
+        Some(hit) => serde_json::to_string_pretty(&hit.doc)
+            .unwrap_or_else(|_| format!("{:?}", hit.doc))
+            .lines()
+            .skip(state.search_scroll)
+            .map(|line| highlight_terms(line, &hit.terms, theme))
+            .collect(),
+        None => vec![Line::from(Span::styled(
+            "Run a search to see matching documents",
+            Style::default().fg(theme.muted),
+        ))],
+    };
+
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::LEFT)
+            .title(" Document ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(widget, area);
+}
+
+/// Marks every case-insensitive occurrence of the matched terms.
+fn highlight_terms(line: &str, terms: &[String], theme: &Theme) -> Line<'static> {
+    let lower = line.to_ascii_lowercase();
+    let terms: Vec<String> = terms
+        .iter()
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_ascii_lowercase())
+        .collect();
+
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while pos < line.len() {
+        let next = terms
+            .iter()
+            .filter_map(|t| lower[pos..].find(t.as_str()).map(|i| (pos + i, t.len())))
+            .min_by_key(|(start, len)| (*start, usize::MAX - len));
+        match next {
+            Some((start, len)) => {
+                if start > pos {
+                    spans.push(Span::raw(line[pos..start].to_string()));
+                }
+                spans.push(Span::styled(
+                    line[start..start + len].to_string(),
+                    theme.background(theme.highlight).fg(theme.badge_text),
+                ));
+                pos = start + len;
+            }
+            None => {
+                spans.push(Span::raw(line[pos..].to_string()));
+                break;
+            }
+        }
+    }
+    Line::from(spans)
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let (footer_text, color) = if let Some(error) = &state.error {
+        (format!(" Error: {} ", error), theme.error)
+    } else if state.loading {
+        (
+            format!(" {} Searching... ", state.spinner()),
+            theme.highlight,
+        )
+    } else if let Some(message) = &state.message {
+        (format!(" {} ", message), theme.success)
+    } else {
+        (
+            i18n::hints(" [Tab] Next field  [←/→] Change option  [Enter] Search  [↑/↓] Results  [PgUp/PgDn] Scroll  [Esc] Back ")
+                .to_string(),
+            theme.muted,
+        )
+    };
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(color));
+
+    f.render_widget(footer, area);
+}
@@ -0,0 +1,118 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::config::Settings;
+use crate::i18n;
+use crate::models::{SetupField, SetupForm, Theme};
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Steps
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    let header = Paragraph::new(" Welcome to kompass: first-run setup ")
+        .style(
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(header, chunks[0]);
+
+    if let Some(form) = &state.setup_form {
+        render_steps(f, chunks[1], form, state.error.as_deref(), theme);
+    }
+    render_footer(f, chunks[2], state.setup_form.as_ref(), theme);
+}
+
+fn render_steps(f: &mut Frame, area: Rect, form: &SetupForm, error: Option<&str>, theme: &Theme) {
+    let focused = form.focused_field();
+    let path = Settings::config_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "config.toml".to_string());
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(
+                " A few defaults, saved to {}. Edit the file later to change them.",
+                path
+            ),
+            Style::default().fg(theme.dim),
+        )),
+        Line::from(""),
+    ];
+
+    for (i, field) in SetupField::ALL.into_iter().enumerate() {
+        let is_focused = field == focused;
+        let value = match field {
+            SetupField::ReadOnly | SetupField::Language => format!("◀ {} ▶", form.value(field)),
+            _ => form.value(field),
+        };
+        let marker = if i < form.focus {
+            "✓"
+        } else if is_focused {
+            ">"
+        } else {
+            " "
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(" {} {}. {:<20}", marker, i + 1, field.label()),
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled(
+                value,
+                if is_focused {
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                },
+            ),
+        ]));
+        if is_focused {
+            lines.push(Line::from(Span::styled(
+                format!("         {}", field.hint()),
+                Style::default().fg(theme.muted),
+            )));
+        }
+    }
+
+    if let Some(error) = error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(" Error: {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+
+    let widget = Paragraph::new(lines).wrap(Wrap { trim: false });
+    f.render_widget(widget, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, form: Option<&SetupForm>, theme: &Theme) {
+    let enter = if form.is_some_and(SetupForm::is_last_step) {
+        "[Enter] Save"
+    } else {
+        "[Enter] Next"
+    };
+    let footer_text = i18n::hints(&format!(
+        " [Tab/↑/↓] Step  [←/→/Space] Choose  {}  [Esc] Skip ",
+        enter
+    ));
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
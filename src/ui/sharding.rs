@@ -0,0 +1,195 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),      // Header
+            Constraint::Percentage(60), // Chunks
+            Constraint::Min(0),         // Zones
+            Constraint::Length(1),      // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_chunks(f, chunks[1], state);
+    render_zones(f, chunks[2], state);
+    render_footer(f, chunks[3], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let Some(status) = &state.sharding else {
+        return;
+    };
+
+    let (balancer, color) = match (status.balancer_enabled(), status.balancer_running) {
+        (true, true) => (" BALANCING ", theme.success),
+        (true, false) => (" BALANCER ON ", theme.success),
+        (false, _) => (" BALANCER OFF ", theme.highlight),
+    };
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" Sharding {} ", status.namespace),
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(balancer, theme.background(color).fg(theme.badge_text)),
+        Span::styled(
+            format!("  key {}", status.shard_key),
+            Style::default().fg(theme.muted),
+        ),
+    ]))
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_chunks(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let Some(status) = &state.sharding else {
+        return;
+    };
+
+    let items: Vec<ListItem> = status
+        .chunks
+        .iter()
+        .map(|chunk| {
+            let mut spans = vec![
+                Span::styled(
+                    format!(" {:<16}", chunk.shard),
+                    Style::default().fg(theme.highlight),
+                ),
+                Span::styled(chunk.range(), Style::default().fg(theme.text)),
+            ];
+            if chunk.jumbo {
+                spans.push(Span::styled(" jumbo", Style::default().fg(theme.error)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let per_shard: Vec<String> = status
+        .shards
+        .iter()
+        .map(|shard| {
+            let count = status.chunks.iter().filter(|c| &c.shard == shard).count();
+            format!("{}: {}", shard, count)
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(
+                    " Chunks ({})  {} ",
+                    status.chunks.len(),
+                    per_shard.join("  ")
+                ))
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut list_state = ListState::default();
+    if !state.sharding_focus_zones {
+        list_state.select(Some(state.selected_chunk_index));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_zones(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let Some(status) = &state.sharding else {
+        return;
+    };
+
+    let items: Vec<ListItem> = status
+        .zones
+        .iter()
+        .map(|range| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {:<16}", range.zone),
+                    Style::default().fg(theme.special),
+                ),
+                Span::styled(range.range(), Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let shard_zones: Vec<String> = status
+        .shards
+        .iter()
+        .zip(&status.shard_zones)
+        .filter(|(_, zones)| !zones.is_empty())
+        .map(|(shard, zones)| format!("{}: {}", shard, zones.join("/")))
+        .collect();
+    let title = if shard_zones.is_empty() {
+        format!(
+            " Zone ranges ({})  no shard is in a zone ",
+            status.zones.len()
+        )
+    } else {
+        format!(
+            " Zone ranges ({})  {} ",
+            status.zones.len(),
+            shard_zones.join("  ")
+        )
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(title)
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut list_state = ListState::default();
+    if state.sharding_focus_zones {
+        list_state.select(Some(state.selected_zone_index));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let (footer_text, color) = if let Some(error) = &state.error {
+        (format!(" Error: {} ", error), theme.error)
+    } else if state.loading {
+        (format!(" {} Loading... ", state.spinner()), theme.highlight)
+    } else if let Some(message) = &state.message {
+        (format!(" {} ", message), theme.success)
+    } else {
+        (
+            i18n::hints(" [↑/↓] Navigate  [Tab] Chunks/Zones  [s] Split  [m] Move chunk  [z] Add zone range  [d] Remove zone range  [b] Balancer  [r] Refresh  [Esc] Back ")
+                .to_string(),
+            theme.muted,
+        )
+    };
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(color));
+
+    f.render_widget(footer, area);
+}
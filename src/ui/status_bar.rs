@@ -0,0 +1,122 @@
+use mongodb::bson::Bson;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::app::{screen::Screen, state::AppState};
+use crate::i18n;
+use crate::models::TaskStatus;
+use crate::services::metrics::format_bytes;
+
+const FILTER_WIDTH: usize = 40;
+
+/// The line under every screen: the server, the open namespace with its
+/// filter and page, and what is still running in the background.
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let separator = || Span::styled("│", Style::default().fg(theme.muted));
+    let mut spans = Vec::new();
+
+    match &state.connection {
+        Some(connection) => {
+            let host = format!(
+                "{}:{}",
+                connection.server_info.host, connection.server_info.port
+            );
+            let label = match &state.active_profile {
+                Some(profile) => format!(" {} {} ", profile.name, host),
+                None => format!(" {} ", host),
+            };
+            spans.push(Span::styled(label, Style::default().fg(theme.success)));
+        }
+        None => spans.push(Span::styled(
+            format!(" {} ", i18n::tr("Not connected")),
+            Style::default().fg(theme.muted),
+        )),
+    }
+
+    if let Some(db) = &state.current_database {
+        let namespace = match &state.current_collection {
+            Some(coll) => format!("{}.{}", db, coll),
+            None => db.clone(),
+        };
+        spans.push(separator());
+        spans.push(Span::styled(
+            format!(" {} ", namespace),
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if state.current_screen == Screen::DocumentView && state.current_collection.is_some() {
+        if let Some(filter) = state.filter.as_ref().filter(|f| !f.is_empty()) {
+            let text = Bson::Document(filter.clone())
+                .into_relaxed_extjson()
+                .to_string();
+            spans.push(separator());
+            spans.push(Span::styled(
+                format!(" {} ", shorten(&text, FILTER_WIDTH)),
+                Style::default().fg(theme.highlight),
+            ));
+        }
+        let page = state.current_page + 1;
+        let pages = match (state.page_count(), state.total_count) {
+            (Some(pages), Some(total)) => {
+                format!(" page {}/{} · {} docs ", page, pages, total)
+            }
+            _ => format!(" page {} ", page),
+        };
+        spans.push(separator());
+        spans.push(Span::styled(pages, Style::default().fg(theme.text)));
+        spans.push(separator());
+        spans.push(Span::styled(
+            format!(
+                " ↓ {} in {} batch(es), {} getMore  (session {})  mem {} ",
+                format_bytes(state.network.result_bytes),
+                state.network.result_batches,
+                state.network.result_get_mores,
+                format_bytes(state.network.session_bytes),
+                format_bytes(state.result_bytes)
+            ),
+            Style::default().fg(theme.dim),
+        ));
+    }
+
+    let running = state
+        .tasks
+        .iter()
+        .filter(|task| matches!(task.status, TaskStatus::Running))
+        .count();
+    if state.loading || running > 0 {
+        let mut pending = Vec::new();
+        if state.loading {
+            pending.push(i18n::tr("Loading...").to_string());
+        }
+        if running > 0 {
+            pending.push(format!("{} task(s) [Ctrl+B]", running));
+        }
+        spans.push(separator());
+        spans.push(Span::styled(
+            format!(" {} {} ", state.spinner(), pending.join(", ")),
+            Style::default().fg(theme.highlight),
+        ));
+    }
+
+    let bar = Paragraph::new(Line::from(spans)).style(theme.background(theme.bar));
+    f.render_widget(bar, area);
+}
+
+// cuts `text` to `width` characters, marking the cut
+fn shorten(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(width - 1).collect();
+    short.push('…');
+    short
+}
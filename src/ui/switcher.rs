@@ -0,0 +1,59 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::ui::popup;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let entries = std::iter::once(format!("● {}", state.session_label()))
+        .chain(
+            state
+                .session_labels
+                .iter()
+                .map(|label| format!("  {}", label)),
+        )
+        .chain(std::iter::once("+ New connection".to_string()));
+
+    let mut text: Vec<Line> = entries
+        .enumerate()
+        .map(|(i, entry)| {
+            if i == state.selected_session_index {
+                Line::from(Span::styled(
+                    format!("> {}", entry),
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(Span::styled(
+                    format!("  {}", entry),
+                    Style::default().fg(theme.text),
+                ))
+            }
+        })
+        .collect();
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints("[Enter] Switch  [d] Duplicate current  [x] Close connection  [Esc] Cancel"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let height = (text.len() as u16 + 2).min(area.height);
+    let popup = popup::centered_rect(70, height, area);
+    let widget = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(" Connections "),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}
@@ -0,0 +1,166 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+};
+
+use mongodb::bson::Document;
+
+use crate::app::state::AppState;
+use crate::services::bson_ext::number_as_f64;
+use crate::services::encryption;
+use crate::services::table::{self, MAX_COLUMN_WIDTH};
+
+/// Documents as rows and the chosen fields as columns, scrolled so the
+/// selected column stays on screen. The frozen column, if any, is always drawn
+/// first.
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let columns = &state.table_columns;
+    let cells: Vec<Vec<String>> = state
+        .documents
+        .iter()
+        .map(|doc| columns.iter().map(|c| cell_text(doc, c)).collect())
+        .collect();
+
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{}{}", column, sort_marker(state, column)))
+        .collect();
+    let widths: Vec<u16> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            let widest = cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header.chars().count()))
+                .max()
+                .unwrap_or(0);
+            widest.min(MAX_COLUMN_WIDTH) as u16
+        })
+        .collect();
+    let frozen = state
+        .frozen_column
+        .as_ref()
+        .and_then(|name| columns.iter().position(|c| c == name));
+    let scrolling: Vec<usize> = (0..columns.len()).filter(|&i| Some(i) != frozen).collect();
+    let scrolling_widths: Vec<u16> = scrolling.iter().map(|&i| widths[i]).collect();
+    let selected = scrolling
+        .iter()
+        .position(|&i| i == state.selected_column)
+        .unwrap_or(0);
+    let frozen_width = frozen.map_or(0, |i| widths[i] + 1);
+    let visible = visible_columns(
+        &scrolling_widths,
+        selected,
+        area.width.saturating_sub(frozen_width),
+    );
+    let shown: Vec<usize> = frozen
+        .into_iter()
+        .chain(scrolling[visible.clone()].iter().copied())
+        .collect();
+
+    let header = Row::new(shown.iter().map(|&i| {
+        let color = if Some(i) == frozen {
+            theme.accent
+        } else {
+            theme.dim
+        };
+        let style = if i == state.selected_column {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(color).add_modifier(Modifier::BOLD)
+        };
+        Cell::from(headers[i].clone()).style(style)
+    }));
+    let rows = cells.iter().map(|row| {
+        Row::new(shown.iter().map(|&i| {
+            let style = if i == state.selected_column {
+                Style::default().fg(theme.text)
+            } else if Some(i) == frozen {
+                Style::default().fg(theme.accent)
+            } else {
+                Style::default().fg(theme.dim)
+            };
+            Cell::from(row[i].clone()).style(style)
+        }))
+    });
+
+    let pinned = frozen
+        .map(|i| format!(" · pinned {}", columns[i]))
+        .unwrap_or_default();
+    let title = format!(
+        " Documents ({}){} · columns {}-{} of {} ",
+        state.documents.len(),
+        pinned,
+        visible.start + 1,
+        visible.end,
+        scrolling.len()
+    );
+    let widget = Table::new(rows, shown.iter().map(|&i| Constraint::Length(widths[i])))
+        .header(header)
+        .column_spacing(1)
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .row_highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut table_state = TableState::default().with_selected(Some(state.selected_doc_index));
+    f.render_stateful_widget(widget, area, &mut table_state);
+}
+
+/// Columns that fit in `width`, starting as far left as keeps `selected` visible.
+fn visible_columns(widths: &[u16], selected: usize, width: u16) -> std::ops::Range<usize> {
+    if widths.is_empty() {
+        return 0..0;
+    }
+    let selected = selected.min(widths.len() - 1);
+    let mut first = selected;
+    let mut used = widths[selected];
+    while first > 0 && used + 1 + widths[first - 1] <= width {
+        first -= 1;
+        used += 1 + widths[first];
+    }
+    let mut end = selected + 1;
+    while end < widths.len() && used + 1 + widths[end] <= width {
+        used += 1 + widths[end];
+        end += 1;
+    }
+    first..end
+}
+
+fn sort_marker(state: &AppState, column: &str) -> &'static str {
+    if let Some((sorted, descending)) = &state.table_sort
+        && sorted == column
+    {
+        return if *descending { " ▼" } else { " ▲" };
+    }
+    match state
+        .sort
+        .as_ref()
+        .and_then(|s| s.get(column))
+        .and_then(number_as_f64)
+    {
+        Some(dir) if dir < 0.0 => " ▼ (server)",
+        Some(_) => " ▲ (server)",
+        None => "",
+    }
+}
+
+// ciphertext is shown as a lock rather than base64; exports keep the bytes
+fn cell_text(doc: &Document, column: &str) -> String {
+    match table::lookup(doc, column).and_then(encryption::ciphertext) {
+        Some(binary) => encryption::label(binary),
+        None => table::cell_text(doc, column),
+    }
+}
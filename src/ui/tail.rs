@@ -0,0 +1,195 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::WatchScope;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Stream
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    if state.tail_scope == WatchScope::Collection {
+        render_stream(f, chunks[1], state);
+    } else {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+            .split(chunks[1]);
+        render_stream(f, columns[0], state);
+        render_namespaces(f, columns[1], state);
+    }
+    render_footer(f, chunks[2], state);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let namespace = match (
+        &state.tail_scope,
+        &state.current_database,
+        &state.current_collection,
+    ) {
+        (WatchScope::Database(db), _, _) => format!("every collection of {}", db),
+        (WatchScope::Deployment, _, _) => "the whole deployment".to_string(),
+        (_, Some(db), Some(coll)) => format!("{}.{}", db, coll),
+        _ => "unknown".to_string(),
+    };
+
+    let status = if state.tail_paused {
+        Span::styled(
+            " PAUSED ",
+            theme.background(theme.highlight).fg(theme.badge_text),
+        )
+    } else {
+        Span::styled(
+            " LIVE ",
+            theme.background(theme.success).fg(theme.badge_text),
+        )
+    };
+
+    let mut spans = vec![
+        Span::styled(
+            format!(" Tailing {} ", namespace),
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        ),
+        status,
+        Span::styled(
+            format!("  via {}", state.tail_source),
+            Style::default().fg(theme.muted),
+        ),
+    ];
+    if let Some(recorder) = &state.tail_recorder {
+        spans.push(Span::styled(
+            format!(
+                "  ● REC {} ({} events)",
+                recorder.path().display(),
+                recorder.count()
+            ),
+            Style::default().fg(theme.error),
+        ));
+    }
+
+    let header = Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_stream(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let documents = state.visible_tail_documents();
+    let visible = area.height.saturating_sub(1) as usize;
+    let end = documents.len().saturating_sub(state.tail_scroll_offset);
+    let start = end.saturating_sub(visible);
+
+    let items: Vec<ListItem> = documents[start..end]
+        .iter()
+        .map(|doc| {
+            let line = serde_json::to_string(doc).unwrap_or_else(|_| format!("{:?}", doc));
+            // change events from a watch are colored by operation
+            let color = match doc.get_str("operationType") {
+                Ok("insert") => theme.success,
+                Ok("update") | Ok("replace") => theme.highlight,
+                Ok("delete") => theme.error,
+                _ => theme.text,
+            };
+            ListItem::new(Line::from(Span::styled(line, Style::default().fg(color))))
+        })
+        .collect();
+
+    let shown = match &state.tail_namespace {
+        Some(ns) => format!("{} in {}", documents.len(), ns),
+        None => documents.len().to_string(),
+    };
+    let title = if state.is_following_tail() {
+        format!(" Documents ({}) ", shown)
+    } else {
+        format!(
+            " Documents ({}) - scrolled back {} ",
+            shown, state.tail_scroll_offset
+        )
+    };
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(title)
+            .title_style(Style::default().fg(theme.dim)),
+    );
+
+    f.render_widget(list, area);
+}
+
+// event counts per namespace, busiest first, to see what is writing
+fn render_namespaces(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let items: Vec<ListItem> = state
+        .tail_namespaces()
+        .into_iter()
+        .map(|(ns, activity)| {
+            let selected = state.tail_namespace.as_ref() == Some(ns);
+            let style = if selected {
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            ListItem::new(vec![
+                Line::from(Span::styled(format!("{} {}", activity.total(), ns), style)),
+                Line::from(vec![
+                    Span::styled(
+                        format!("  +{}", activity.inserts),
+                        Style::default().fg(theme.success),
+                    ),
+                    Span::styled(
+                        format!(" ~{}", activity.updates),
+                        Style::default().fg(theme.highlight),
+                    ),
+                    Span::styled(
+                        format!(" -{}", activity.deletes),
+                        Style::default().fg(theme.error),
+                    ),
+                ]),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::LEFT)
+            .title(" Namespaces ")
+            .title_style(Style::default().fg(theme.dim)),
+    );
+    f.render_widget(list, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let footer_text = if let Some(error) = &state.error {
+        format!(" Error: {} ", error)
+    } else if state.tail_scope != WatchScope::Collection {
+        i18n::hints(
+            " [Space] Pause/Resume  [↑/↓] Scroll  [G] Follow  [n] Namespace  [c] Clear  [R] Record  [Back] Go Back ",
+        )
+    } else {
+        i18n::hints(
+            " [Space] Pause/Resume  [↑/↓] Scroll  [G] Follow  [c] Clear  [R] Record  [Back] Go Back ",
+        )
+    };
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{TaskInfo, TaskStatus, Theme};
+
+const BAR_WIDTH: usize = 20;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Tasks
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state);
+    render_tasks(f, chunks[1], state);
+    render_footer(f, chunks[2], theme);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let running = state
+        .tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::Running)
+        .count();
+
+    let header = Paragraph::new(format!(
+        " Background tasks: {} running, {} total ",
+        running,
+        state.tasks.len()
+    ))
+    .style(
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_tasks(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    if state.tasks.is_empty() {
+        let empty = Paragraph::new(" Nothing running. Long operations show up here.")
+            .style(Style::default().fg(theme.muted));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = state
+        .tasks
+        .iter()
+        .map(|task| task_item(task, theme))
+        .collect();
+    let list = List::new(items).highlight_style(
+        theme
+            .background(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selected_task_index));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn task_item(task: &TaskInfo, theme: &Theme) -> ListItem<'static> {
+    let (status, color) = match &task.status {
+        TaskStatus::Running => ("running", theme.highlight),
+        TaskStatus::Completed(_) => ("done", theme.success),
+        TaskStatus::Failed(_) => ("failed", theme.error),
+        TaskStatus::Cancelled => ("cancelled", theme.muted),
+    };
+
+    let progress = match (task.ratio(), task.progress) {
+        (Some(ratio), _) => {
+            let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+            format!(
+                "[{}{}] {:>3.0}%",
+                "#".repeat(filled),
+                "-".repeat(BAR_WIDTH - filled),
+                ratio * 100.0
+            )
+        }
+        (None, Some((done, _))) => format!("{} processed", done),
+        (None, None) => String::new(),
+    };
+
+    let detail = match &task.status {
+        TaskStatus::Completed(message) | TaskStatus::Failed(message) => message.clone(),
+        _ => progress,
+    };
+
+    ListItem::new(vec![
+        Line::from(vec![
+            Span::styled(format!(" ● {:<10}", status), Style::default().fg(color)),
+            Span::styled(
+                format!("{:<16}", task.kind),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:>8}  ", format_elapsed(task.elapsed())),
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled(task.label.clone(), Style::default().fg(theme.dim)),
+        ]),
+        Line::from(Span::styled(
+            format!("   {}", detail),
+            Style::default().fg(theme.muted),
+        )),
+    ])
+}
+
+fn render_footer(f: &mut Frame, area: Rect, theme: &Theme) {
+    let footer_text =
+        i18n::hints(" [c] Cancel  [x] Clear finished  [j/k] Navigate  [Esc/Ctrl+B] Back ");
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
+
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}.{}s", secs, elapsed.subsec_millis() / 100)
+    }
+}
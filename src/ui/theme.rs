@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::models::{ColorScheme, Theme};
+
+impl Theme {
+    pub fn preset(scheme: ColorScheme) -> Self {
+        match scheme {
+            ColorScheme::Dark => Self {
+                reverse: false,
+                text: Color::White,
+                dim: Color::Gray,
+                muted: Color::DarkGray,
+                accent: Color::Cyan,
+                header: Color::Cyan,
+                highlight: Color::Yellow,
+                success: Color::Green,
+                warning: Color::LightRed,
+                error: Color::Red,
+                special: Color::Magenta,
+                selection: Color::DarkGray,
+                bar: Color::Black,
+                badge_text: Color::Black,
+                current_match: Color::LightRed,
+                json_key: Color::Cyan,
+                json_string: Color::Green,
+                json_number: Color::Yellow,
+                json_bool: Color::Magenta,
+                json_null: Color::DarkGray,
+                json_id: Color::LightBlue,
+                json_date: Color::LightRed,
+            },
+            ColorScheme::Light => {
+                let (amber, green, purple) = (
+                    Color::Rgb(150, 100, 0),
+                    Color::Rgb(0, 120, 0),
+                    Color::Rgb(150, 0, 150),
+                );
+                Self {
+                    reverse: false,
+                    text: Color::Black,
+                    dim: Color::DarkGray,
+                    muted: Color::Gray,
+                    accent: Color::Blue,
+                    header: Color::Blue,
+                    highlight: amber,
+                    success: green,
+                    warning: Color::LightRed,
+                    error: Color::Red,
+                    special: purple,
+                    selection: Color::Gray,
+                    bar: Color::White,
+                    badge_text: Color::White,
+                    current_match: Color::LightRed,
+                    json_key: Color::Blue,
+                    json_string: green,
+                    json_number: amber,
+                    json_bool: purple,
+                    json_null: Color::Gray,
+                    json_id: Color::Blue,
+                    json_date: Color::LightRed,
+                }
+            }
+            ColorScheme::HighContrast => {
+                let orange = Color::Rgb(255, 165, 0);
+                Self {
+                    reverse: false,
+                    text: Color::White,
+                    dim: Color::White,
+                    muted: Color::Gray,
+                    accent: Color::LightCyan,
+                    header: Color::LightYellow,
+                    highlight: Color::LightYellow,
+                    success: Color::LightGreen,
+                    warning: orange,
+                    error: Color::LightRed,
+                    special: Color::LightMagenta,
+                    selection: Color::Blue,
+                    bar: Color::Black,
+                    badge_text: Color::Black,
+                    current_match: Color::LightRed,
+                    json_key: Color::LightCyan,
+                    json_string: Color::LightGreen,
+                    json_number: Color::LightYellow,
+                    json_bool: Color::LightMagenta,
+                    json_null: Color::Gray,
+                    json_id: Color::LightBlue,
+                    json_date: orange,
+                }
+            }
+            ColorScheme::Mono => Self {
+                reverse: true,
+                text: Color::Reset,
+                dim: Color::Reset,
+                muted: Color::Reset,
+                accent: Color::Reset,
+                header: Color::Reset,
+                highlight: Color::Reset,
+                success: Color::Reset,
+                warning: Color::Reset,
+                error: Color::Reset,
+                special: Color::Reset,
+                selection: Color::Reset,
+                bar: Color::Reset,
+                badge_text: Color::Reset,
+                current_match: Color::Reset,
+                json_key: Color::Reset,
+                json_string: Color::Reset,
+                json_number: Color::Reset,
+                json_bool: Color::Reset,
+                json_null: Color::Reset,
+                json_id: Color::Reset,
+                json_date: Color::Reset,
+            },
+        }
+    }
+
+    /// The scheme's preset with the `[colors]` overrides applied, e.g.
+    /// `selection = "#3a3a3a"` or `error = "light-red"`, and a line for
+    /// each override that could not be applied.
+    pub fn load(scheme: ColorScheme, colors: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut theme = Self::preset(scheme);
+        let problems = colors
+            .iter()
+            .filter_map(|(role, color)| theme.set(role, color).err())
+            .collect();
+        (theme, problems)
+    }
+
+    pub fn set(&mut self, role: &str, color: &str) -> Result<(), String> {
+        let Some(slot) = self.slot(role) else {
+            return Err(format!("[colors] {}: not a role", role));
+        };
+        *slot = color.trim().parse().map_err(|_| {
+            format!(
+                "[colors] {}: '{}' is not a color; use a name such as light-red or #rrggbb",
+                role, color
+            )
+        })?;
+        Ok(())
+    }
+
+    /// A `color` background; schemes without colors mark it in reverse
+    /// video, which is how selections and bars still show.
+    pub fn background(&self, color: Color) -> Style {
+        let style = Style::new().bg(color);
+        if self.reverse {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+
+    // the color a `[colors]` role name sets
+    fn slot(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "text" => &mut self.text,
+            "dim" => &mut self.dim,
+            "muted" => &mut self.muted,
+            "accent" => &mut self.accent,
+            "header" => &mut self.header,
+            "highlight" => &mut self.highlight,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "special" => &mut self.special,
+            "selection" => &mut self.selection,
+            "bar" => &mut self.bar,
+            "badge_text" => &mut self.badge_text,
+            "current_match" => &mut self.current_match,
+            "json_key" => &mut self.json_key,
+            "json_string" => &mut self.json_string,
+            "json_number" => &mut self.json_number,
+            "json_bool" => &mut self.json_bool,
+            "json_null" => &mut self.json_null,
+            "json_id" => &mut self.json_id,
+            "json_date" => &mut self.json_date,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(ColorScheme::Dark)
+    }
+}
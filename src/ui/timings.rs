@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{Theme, TimingGroup};
+
+const BAR_WIDTH: u16 = 3;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let groups = state.timing_groups();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),      // Header
+            Constraint::Percentage(50), // Query shapes
+            Constraint::Min(0),         // Chart
+            Constraint::Length(1),      // Footer
+        ])
+        .split(area);
+
+    render_header(f, chunks[0], state, groups.len());
+    render_groups(f, chunks[1], state, &groups);
+    if let Some(group) = groups.get(state.selected_timing_index) {
+        render_chart(f, chunks[2], group, theme);
+    }
+    render_footer(f, chunks[3], theme);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState, shapes: usize) {
+    let theme = &state.theme;
+    let header = Paragraph::new(format!(
+        " Query timings: {} runs of {} query shapes this session ",
+        state.query_timings.len(),
+        shapes
+    ))
+    .style(
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_groups(f: &mut Frame, area: Rect, state: &AppState, groups: &[TimingGroup]) {
+    let theme = &state.theme;
+    if groups.is_empty() {
+        let empty = Paragraph::new(" No queries yet. Finds, counts and aggregations show up here.")
+            .style(Style::default().fg(theme.muted));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = groups
+        .iter()
+        .map(|group| group_item(group, theme))
+        .collect();
+    let list = List::new(items).highlight_style(
+        theme
+            .background(theme.selection)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selected_timing_index));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn group_item(group: &TimingGroup, theme: &Theme) -> ListItem<'static> {
+    let failures = if group.failures > 0 {
+        format!("  {} failed", group.failures)
+    } else {
+        String::new()
+    };
+
+    ListItem::new(vec![
+        Line::from(vec![
+            Span::styled(
+                format!(" {:<10}", group.command),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:<32}", group.namespace),
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled(
+                format!(
+                    "{:>4} runs  last {}  min {}  median {}",
+                    group.durations.len(),
+                    format_ms(group.last()),
+                    format_ms(group.min()),
+                    format_ms(group.median())
+                ),
+                Style::default().fg(theme.highlight),
+            ),
+            Span::styled(failures, Style::default().fg(theme.error)),
+        ]),
+        Line::from(Span::styled(
+            format!("   {}", group.shape),
+            Style::default().fg(theme.muted),
+        )),
+    ])
+}
+
+// one bar per run, oldest on the left, so a drop after an index change shows
+fn render_chart(f: &mut Frame, area: Rect, group: &TimingGroup, theme: &Theme) {
+    let fits = (area.width.saturating_sub(2) / (BAR_WIDTH + 1)) as usize;
+    let skip = group.durations.len().saturating_sub(fits);
+    let bars: Vec<Bar> = group
+        .durations
+        .iter()
+        .skip(skip)
+        .map(|duration| {
+            Bar::default()
+                .value(duration.as_millis() as u64)
+                .text_value(format!("{}", duration.as_millis()))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(format!(" {} · ms per run ", group.namespace)),
+        )
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(BAR_WIDTH)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(theme.accent))
+        .value_style(theme.background(theme.accent).fg(theme.badge_text));
+
+    f.render_widget(chart, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, theme: &Theme) {
+    let footer_text = i18n::hints(" [j/k] Query shape  [c] Clear  [Esc/Ctrl+T] Back ");
+    let footer = Paragraph::new(footer_text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,48 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect, Size},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+};
+
+use crate::models::Theme;
+
+pub const MIN_WIDTH: u16 = 80;
+pub const MIN_HEIGHT: u16 = 24;
+
+/// Whether the screens can be laid out in a terminal of `size` at all.
+pub fn fits(size: Size) -> bool {
+    size.width >= MIN_WIDTH && size.height >= MIN_HEIGHT
+}
+
+/// Shown instead of the current screen until the terminal is big enough.
+pub fn render(f: &mut Frame, area: Rect, theme: &Theme) {
+    let text = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(format!(
+            "need {}x{}, have {}x{}",
+            MIN_WIDTH, MIN_HEIGHT, area.width, area.height
+        )),
+        Line::from(Span::styled(
+            "Resize the window or press Ctrl+C to quit",
+            Style::default().fg(theme.muted),
+        )),
+    ];
+
+    let top = area.height.saturating_sub(text.len() as u16) / 2;
+    let body = Rect {
+        y: area.y + top,
+        height: area.height - top,
+        ..area
+    };
+    let widget = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(widget, body);
+}
@@ -0,0 +1,317 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::app::state::AppState;
+use crate::i18n;
+use crate::models::{RoleInfo, RolePicker, Theme, UserInfo};
+use crate::ui::popup;
+
+pub fn render(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Users
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[1]);
+
+    render_header(f, chunks[0], state, "Users");
+    render_users(f, body[0], state);
+    render_user(f, body[1], state.get_selected_user(), state);
+    render_footer(
+        f,
+        chunks[2],
+        state,
+        " [j/k] Navigate  [n] New user  [p] Change password  [D] Drop  [R] Roles  [r] Refresh  [Esc] Back ",
+    );
+    if let Some(picker) = &state.role_picker {
+        render_role_picker(f, area, picker, theme);
+    }
+}
+
+pub fn render_roles(f: &mut Frame, area: Rect, state: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Header
+            Constraint::Min(0),    // Roles
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(chunks[1]);
+
+    render_header(f, chunks[0], state, "Roles");
+    render_roles_list(f, body[0], state);
+    render_role(f, body[1], state.get_selected_role(), state);
+    render_footer(
+        f,
+        chunks[2],
+        state,
+        " [j/k] Navigate  [r] Refresh  [Esc] Back to users ",
+    );
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &AppState, what: &str) {
+    let theme = &state.theme;
+    let header = Paragraph::new(format!(
+        " {} of {} ",
+        what,
+        state.users_database.as_deref().unwrap_or("")
+    ))
+    .style(
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )
+    .block(Block::default().borders(Borders::BOTTOM));
+
+    f.render_widget(header, area);
+}
+
+fn render_users(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let items: Vec<ListItem> = state
+        .users
+        .iter()
+        .map(|user| {
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {}", user.user), Style::default().fg(theme.text)),
+                Span::styled(
+                    format!("  {} role(s)", user.roles.len()),
+                    Style::default().fg(theme.muted),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(" Users ({}) ", state.users.len()))
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut list_state = ListState::default();
+    if !state.users.is_empty() {
+        list_state.select(Some(state.selected_user_index));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_user(f: &mut Frame, area: Rect, user: Option<&UserInfo>, state: &AppState) {
+    let theme = &state.theme;
+    let db = state.users_database.as_deref().unwrap_or("");
+    let text = match user {
+        Some(user) => {
+            let mut lines = vec![
+                label("User", &format!("{}@{}", user.user, user.db), theme),
+                label("Auth", &user.mechanisms.join(", "), theme),
+                Line::from(""),
+                Line::from(Span::styled(" Roles", Style::default().fg(theme.dim))),
+            ];
+            if user.roles.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "   none",
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            for role in &user.roles {
+                lines.push(Line::from(Span::styled(
+                    format!("   {}", role.label(db)),
+                    Style::default().fg(theme.success),
+                )));
+            }
+            lines
+        }
+        None => vec![Line::from(Span::styled(
+            " No users on this database; [n] creates one",
+            Style::default().fg(theme.muted),
+        ))],
+    };
+
+    let widget = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::LEFT));
+    f.render_widget(widget, area);
+}
+
+fn render_roles_list(f: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+    let db = state.users_database.as_deref().unwrap_or("");
+    let items: Vec<ListItem> = state
+        .roles
+        .iter()
+        .map(|role| {
+            let kind = if role.built_in { "built-in" } else { "custom" };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {:<28}", format!("{}@{}", role.role, role.db)),
+                    Style::default().fg(if role.db == db { theme.text } else { theme.dim }),
+                ),
+                Span::styled(
+                    kind,
+                    Style::default().fg(if role.built_in {
+                        theme.muted
+                    } else {
+                        theme.highlight
+                    }),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(format!(" Roles ({}) ", state.roles.len()))
+                .title_style(Style::default().fg(theme.dim)),
+        )
+        .highlight_style(
+            theme
+                .background(theme.selection)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut list_state = ListState::default();
+    if !state.roles.is_empty() {
+        list_state.select(Some(state.selected_role_index));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_role(f: &mut Frame, area: Rect, role: Option<&RoleInfo>, state: &AppState) {
+    let theme = &state.theme;
+    let db = state.users_database.as_deref().unwrap_or("");
+    let text = match role {
+        Some(role) => {
+            let inherited: Vec<String> = role.inherited.iter().map(|r| r.label(db)).collect();
+            let mut lines = vec![label(
+                "Inherits",
+                &if inherited.is_empty() {
+                    "nothing".to_string()
+                } else {
+                    inherited.join(", ")
+                },
+                theme,
+            )];
+            lines.push(Line::from(""));
+            if role.privileges.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    " No privileges of its own",
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            for privilege in &role.privileges {
+                lines.push(Line::from(Span::styled(
+                    format!(" {}", privilege.resource),
+                    Style::default()
+                        .fg(theme.header)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                lines.push(Line::from(Span::styled(
+                    format!("   {}", privilege.actions.join(", ")),
+                    Style::default().fg(theme.text),
+                )));
+            }
+            lines
+        }
+        None => vec![Line::from(Span::styled(
+            " No roles readable on this database",
+            Style::default().fg(theme.muted),
+        ))],
+    };
+
+    let widget = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::LEFT));
+    f.render_widget(widget, area);
+}
+
+fn render_role_picker(f: &mut Frame, area: Rect, picker: &RolePicker, theme: &Theme) {
+    let popup = popup::centered_rect(60, area.height.saturating_sub(4).min(24), area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Roles
+            Constraint::Length(1), // Keys
+        ])
+        .margin(1)
+        .split(popup);
+
+    let items: Vec<ListItem> = picker
+        .roles
+        .iter()
+        .zip(&picker.checked)
+        .map(|(role, checked)| {
+            ListItem::new(format!(
+                "[{}] {}@{}",
+                if *checked { "✓" } else { " " },
+                role.role,
+                role.db
+            ))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(picker.selected));
+    let list = List::new(items)
+        .style(Style::default().fg(theme.text))
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        );
+    let keys = Paragraph::new(i18n::hints(
+        "[Space] Check  [Enter] Create user  [Esc] Cancel",
+    ))
+    .style(Style::default().fg(theme.muted));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(format!(" Roles for {} ", picker.user)),
+        popup,
+    );
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+    f.render_widget(keys, chunks[1]);
+}
+
+fn render_footer(f: &mut Frame, area: Rect, state: &AppState, hints: &str) {
+    let theme = &state.theme;
+    let text = if let Some(error) = &state.error {
+        format!(" Error: {}", error)
+    } else if let Some(message) = &state.message {
+        format!(" {}", message)
+    } else {
+        i18n::hints(hints)
+    };
+    let footer = Paragraph::new(text).style(theme.background(theme.bar).fg(theme.muted));
+
+    f.render_widget(footer, area);
+}
+
+fn label(label: &str, value: &str, theme: &Theme) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!(" {:<10}", label), Style::default().fg(theme.dim)),
+        Span::styled(value.to_string(), Style::default().fg(theme.text)),
+    ])
+}
@@ -0,0 +1,79 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::i18n;
+use crate::models::{Theme, UtilityPanel};
+use crate::ui::popup::centered_rect;
+
+pub fn render(f: &mut Frame, area: Rect, panel: &UtilityPanel, theme: &Theme) {
+    let mut text = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.dim)),
+            Span::styled(panel.input.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.highlight)),
+        ]),
+        Line::from(Span::styled(
+            if panel.input.is_empty() {
+                "  Paste an ObjectId, UUID or BinData value; these are new:"
+            } else {
+                ""
+            },
+            Style::default().fg(theme.muted),
+        )),
+    ];
+
+    let width = panel
+        .conversions
+        .iter()
+        .map(|c| c.label.len())
+        .max()
+        .unwrap_or(0);
+    for (i, conversion) in panel.conversions.iter().enumerate() {
+        let value_style = if i == panel.selected {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+        text.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{}{:<width$}  ",
+                    if i == panel.selected { "> " } else { "  " },
+                    conversion.label,
+                    width = width
+                ),
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled(conversion.value.clone(), value_style),
+        ]));
+    }
+    if let Some(error) = &panel.error {
+        text.push(Line::from(Span::styled(
+            format!("  {}", error),
+            Style::default().fg(theme.error),
+        )));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        i18n::hints("  [↑/↓] Select  [Enter] Copy  [Ctrl+N] New  [Esc] Close"),
+        Style::default().fg(theme.muted),
+    )));
+
+    let popup = centered_rect(80, (text.len() as u16 + 2).min(area.height), area);
+    let widget = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(" ObjectId / UUID "),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(widget, popup);
+}